@@ -13,6 +13,8 @@ impl Model {
         for cell in cells {
             self.shift_cell_formula(cell.index, cell.row, cell.column, displace_data);
         }
+        // Cells have moved around, the graph needs to be rebuilt
+        self.invalidate_dependency_graph();
     }
     /// Returns the list of columns in row
     fn get_columns_for_row(
@@ -53,7 +55,7 @@ impl Model {
         let formula_or_value = self
             .cell_formula(sheet, source_row, source_column)?
             .unwrap_or_else(|| source_cell.get_text(&self.workbook.shared_strings, &self.language));
-        self.set_user_input(sheet, target_row, target_column, formula_or_value);
+        self.set_cell_user_input(sheet, target_row, target_column, formula_or_value);
         self.workbook
             .worksheet_mut(sheet)?
             .set_cell_style(target_row, target_column, style);
@@ -66,6 +68,17 @@ impl Model {
         sheet: u32,
         column: i32,
         column_count: i32,
+    ) -> Result<(), String> {
+        self.try_transaction(|model| {
+            model.insert_columns_in_transaction(sheet, column, column_count)
+        })
+    }
+
+    fn insert_columns_in_transaction(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        column_count: i32,
     ) -> Result<(), String> {
         if column_count <= 0 {
             return Err("Cannot add a negative number of cells :)".to_string());
@@ -108,6 +121,17 @@ impl Model {
         sheet: u32,
         column: i32,
         column_count: i32,
+    ) -> Result<(), String> {
+        self.try_transaction(|model| {
+            model.delete_columns_in_transaction(sheet, column, column_count)
+        })
+    }
+
+    fn delete_columns_in_transaction(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        column_count: i32,
     ) -> Result<(), String> {
         if column_count <= 0 {
             return Err("Please use insert columns instead".to_string());
@@ -143,6 +167,18 @@ impl Model {
     }
 
     pub fn insert_rows(&mut self, sheet: u32, row: i32, row_count: i32) -> Result<(), String> {
+        self.try_transaction(|model| {
+            model.save_rows(sheet);
+            model.insert_rows_in_transaction(sheet, row, row_count)
+        })
+    }
+
+    fn insert_rows_in_transaction(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+    ) -> Result<(), String> {
         if row_count <= 0 {
             return Err("Cannot add a negative number of cells :)".to_string());
         }
@@ -200,6 +236,18 @@ impl Model {
     }
 
     pub fn delete_rows(&mut self, sheet: u32, row: i32, row_count: i32) -> Result<(), String> {
+        self.try_transaction(|model| {
+            model.save_rows(sheet);
+            model.delete_rows_in_transaction(sheet, row, row_count)
+        })
+    }
+
+    fn delete_rows_in_transaction(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+    ) -> Result<(), String> {
         if row_count <= 0 {
             return Err("Please use insert rows instead".to_string());
         }
@@ -267,6 +315,15 @@ impl Model {
         sheet: u32,
         column: i32,
         delta: i32,
+    ) -> Result<(), &'static str> {
+        self.try_transaction(|model| model.move_column_action_in_transaction(sheet, column, delta))
+    }
+
+    fn move_column_action_in_transaction(
+        &mut self,
+        sheet: u32,
+        column: i32,
+        delta: i32,
     ) -> Result<(), &'static str> {
         // Check boundaries
         let target_column = column + delta;
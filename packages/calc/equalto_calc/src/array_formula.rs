@@ -0,0 +1,119 @@
+//! Legacy array formulas (Ctrl+Shift+Enter), evaluated once as an array and fitted to the size
+//! of their block of cells.
+
+use crate::{
+    arrays::broadcast_element,
+    calc_result::{CalcResult, CellReference},
+    expressions::{parser::Node, token::Error},
+    model::Model,
+    spill::{spill_cell, SpillArea},
+    types::FormulaValue,
+};
+
+impl Model {
+    /// Returns true if the cell is in the block of a legacy array formula but is not its anchor.
+    /// Those cells belong to the formula and cannot be edited.
+    pub(crate) fn is_in_array_formula_block(&self, sheet: u32, row: i32, column: i32) -> bool {
+        let key = (sheet, row, column);
+        self.spill_anchors_containing(&key)
+            .iter()
+            .any(|&(sheet, anchor_row, anchor_column)| {
+                (anchor_row, anchor_column) != (row, column)
+                    && self.workbook.worksheets[sheet as usize]
+                        .cell(anchor_row, anchor_column)
+                        .is_some_and(|cell| cell.get_array_formula_size().is_some())
+            })
+    }
+
+    /// Evaluates the array formula in `anchor` over its block of `rows` x `columns` cells. The
+    /// values of the block are written in the worksheet and the value of the anchor is returned.
+    pub(crate) fn evaluate_array_formula(
+        &mut self,
+        anchor: CellReference,
+        node: &Node,
+        rows: i32,
+        columns: i32,
+    ) -> CalcResult {
+        let result = match self.evaluate_node_in_context(node, anchor) {
+            CalcResult::Range { left, right } => self.range_to_array(left, right),
+            result => result,
+        };
+        self.clear_spill(anchor);
+        let mut value = CalcResult::EmptyCell;
+        for dr in 0..rows {
+            for dc in 0..columns {
+                let element =
+                    broadcast_element(&result, dr as usize, dc as usize).unwrap_or_else(|| {
+                        CalcResult::new_error(
+                            Error::NA,
+                            anchor,
+                            "Array formula result is smaller than its range".to_string(),
+                        )
+                    });
+                if dr == 0 && dc == 0 {
+                    value = element;
+                    continue;
+                }
+                let row = anchor.row + dr;
+                let column = anchor.column + dc;
+                let s = self.get_cell_style_index(anchor.sheet, row, column);
+                let cell = spill_cell(&element, s, dr, dc);
+                self.workbook.worksheets[anchor.sheet as usize]
+                    .sheet_data
+                    .entry(row)
+                    .or_default()
+                    .insert(column, cell);
+            }
+        }
+        self.spill_areas.insert(
+            (anchor.sheet, anchor.row, anchor.column),
+            SpillArea {
+                rows,
+                columns,
+                blocked: false,
+            },
+        );
+        value
+    }
+
+    /// The value stored in the anchor cell of an array formula for `result`
+    pub(crate) fn get_formula_value(
+        &self,
+        result: &CalcResult,
+        cell_reference: CellReference,
+    ) -> FormulaValue {
+        match result {
+            CalcResult::Number(v) => {
+                if v.is_nan() || v.is_infinite() {
+                    return FormulaValue::Error {
+                        ei: Error::NUM,
+                        o: self
+                            .cell_reference_to_string(&cell_reference)
+                            .unwrap_or_default(),
+                        m: "".to_string(),
+                    };
+                }
+                FormulaValue::Number { v: *v }
+            }
+            CalcResult::String(v) => FormulaValue::String { v: v.clone() },
+            CalcResult::Boolean(v) => FormulaValue::Boolean { v: *v },
+            CalcResult::Error {
+                error,
+                origin,
+                message,
+            } => FormulaValue::Error {
+                ei: error.clone(),
+                o: self.cell_reference_to_string(origin).unwrap_or_default(),
+                m: message.clone(),
+            },
+            CalcResult::EmptyCell | CalcResult::EmptyArg => FormulaValue::Number { v: 0.0 },
+            CalcResult::Range { .. } | CalcResult::Array(_) => FormulaValue::Error {
+                ei: Error::VALUE,
+                o: self
+                    .cell_reference_to_string(&cell_reference)
+                    .unwrap_or_default(),
+                m: "Invalid reference".to_string(),
+            },
+        }
+    }
+}
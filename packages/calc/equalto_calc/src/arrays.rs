@@ -0,0 +1,248 @@
+//! Array values and element-wise operators.
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    constants::{LAST_COLUMN, LAST_ROW},
+    expressions::{parser::Node, token::Error},
+    model::Model,
+};
+
+/// Returns the top left element of the array, the value used when an array is cast to a scalar
+pub(crate) fn array_top_left(array: Vec<Vec<CalcResult>>, cell: CellReference) -> CalcResult {
+    match array
+        .into_iter()
+        .next()
+        .and_then(|row| row.into_iter().next())
+    {
+        Some(value) => value,
+        None => CalcResult::new_error(Error::CALC, cell, "Empty array".to_string()),
+    }
+}
+
+/// (rows, columns) of a value, scalars are 1x1
+fn dimensions(value: &CalcResult) -> (usize, usize) {
+    match value {
+        CalcResult::Array(array) => (array.len(), array.first().map_or(0, |row| row.len())),
+        _ => (1, 1),
+    }
+}
+
+/// Element (row, column) of `value` broadcast to a bigger size
+pub(crate) fn broadcast_element(
+    value: &CalcResult,
+    row: usize,
+    column: usize,
+) -> Option<CalcResult> {
+    match value {
+        CalcResult::Array(array) => {
+            let row = if array.len() == 1 { 0 } else { row };
+            let values = array.get(row)?;
+            let column = if values.len() == 1 { 0 } else { column };
+            values.get(column).cloned()
+        }
+        _ => Some(value.clone()),
+    }
+}
+
+impl Model {
+    /// Evaluates an operand of an operator. Spill references are converted into arrays, and so
+    /// are all ranges inside legacy array formulas.
+    pub(crate) fn evaluate_operand(&mut self, node: &Node, cell: CellReference) -> CalcResult {
+        let result = self.evaluate_node_in_context(node, cell);
+        match (node, result) {
+            (Node::SpillRefKind(_), CalcResult::Range { left, right }) => {
+                self.range_to_array(left, right)
+            }
+            (_, CalcResult::Range { left, right }) if self.array_context => {
+                self.range_to_array(left, right)
+            }
+            (_, result) => result,
+        }
+    }
+
+    /// Evaluates an argument that is expected to be an array, ranges are converted into arrays
+    /// and a scalar is a 1x1 array. Like in array formulas, operators in the argument work
+    /// element-wise on ranges: `FILTER(A1:A9, B1:B9>0)`.
+    pub(crate) fn get_array_argument(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<Vec<Vec<CalcResult>>, CalcResult> {
+        let array_context = std::mem::replace(&mut self.array_context, true);
+        let result = self.evaluate_node_in_context(node, cell);
+        self.array_context = array_context;
+        match result {
+            CalcResult::Range { left, right } => {
+                if left.sheet != right.sheet {
+                    return Err(CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Ranges are in different sheets".to_string(),
+                    ));
+                }
+                match self.range_to_array(left, right) {
+                    CalcResult::Array(array) => Ok(array),
+                    value => Ok(vec![vec![value]]),
+                }
+            }
+            CalcResult::Array(array) => Ok(array),
+            error @ CalcResult::Error { .. } => Err(error),
+            value => Ok(vec![vec![value]]),
+        }
+    }
+
+    /// Array argument where all values must be numbers
+    pub(crate) fn get_number_matrix(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<Vec<Vec<f64>>, CalcResult> {
+        let array = self.get_array_argument(node, cell)?;
+        let mut matrix = Vec::new();
+        for row in array {
+            let mut values = Vec::new();
+            for value in row {
+                match value {
+                    CalcResult::Number(f) => values.push(f),
+                    error @ CalcResult::Error { .. } => return Err(error),
+                    _ => {
+                        return Err(CalcResult::new_error(
+                            Error::VALUE,
+                            cell,
+                            "Expecting numbers".to_string(),
+                        ))
+                    }
+                }
+            }
+            matrix.push(values);
+        }
+        Ok(matrix)
+    }
+
+    /// Returns an array with the values of the cells in the range. Whole rows and columns are
+    /// clipped to the used part of the sheet.
+    pub(crate) fn range_to_array(
+        &mut self,
+        left: CellReference,
+        right: CellReference,
+    ) -> CalcResult {
+        let mut right = right;
+        if let Ok(worksheet) = self.workbook.worksheet(left.sheet) {
+            let dimension = worksheet.dimension();
+            if left.row == 1 && right.row == LAST_ROW {
+                right.row = dimension.max_row;
+            }
+            if left.column == 1 && right.column == LAST_COLUMN {
+                right.column = dimension.max_column;
+            }
+        }
+        let mut array = Vec::new();
+        for row in left.row..=right.row {
+            let mut values = Vec::new();
+            for column in left.column..=right.column {
+                values.push(self.evaluate_cell(CellReference {
+                    sheet: left.sheet,
+                    row,
+                    column,
+                }));
+            }
+            array.push(values);
+        }
+        CalcResult::Array(array)
+    }
+
+    /// Applies the binary operator `op` to two values. If any of them is an array the operator
+    /// is applied element-wise.
+    pub(crate) fn apply_binary<F>(
+        &mut self,
+        left: CalcResult,
+        right: CalcResult,
+        cell: CellReference,
+        op: F,
+    ) -> CalcResult
+    where
+        F: Fn(&mut Model, CalcResult, CalcResult) -> CalcResult,
+    {
+        if !matches!(left, CalcResult::Array(_)) && !matches!(right, CalcResult::Array(_)) {
+            return op(self, left, right);
+        }
+        let (left_rows, left_columns) = dimensions(&left);
+        let (right_rows, right_columns) = dimensions(&right);
+        let rows = if left_rows == 1 {
+            right_rows
+        } else if right_rows == 1 {
+            left_rows
+        } else {
+            left_rows.max(right_rows)
+        };
+        let columns = if left_columns == 1 {
+            right_columns
+        } else if right_columns == 1 {
+            left_columns
+        } else {
+            left_columns.max(right_columns)
+        };
+        let mut array = Vec::with_capacity(rows);
+        for row in 0..rows {
+            let mut values = Vec::with_capacity(columns);
+            for column in 0..columns {
+                let value = match (
+                    broadcast_element(&left, row, column),
+                    broadcast_element(&right, row, column),
+                ) {
+                    (Some(l), Some(r)) => op(self, l, r),
+                    _ => CalcResult::new_error(
+                        Error::NA,
+                        cell,
+                        "Arrays of different sizes".to_string(),
+                    ),
+                };
+                values.push(value);
+            }
+            array.push(values);
+        }
+        CalcResult::Array(array)
+    }
+
+    /// Applies the unary operator `op` to a value, element-wise if it is an array.
+    pub(crate) fn apply_unary<F>(&mut self, value: CalcResult, op: F) -> CalcResult
+    where
+        F: Fn(&mut Model, CalcResult) -> CalcResult,
+    {
+        match value {
+            CalcResult::Array(array) => CalcResult::Array(
+                array
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|v| op(self, v)).collect())
+                    .collect(),
+            ),
+            value => op(self, value),
+        }
+    }
+
+    /// Evaluates an array literal (`{1,2;3,4}`). Elements must be single values.
+    pub(crate) fn evaluate_array_literal(
+        &mut self,
+        rows: &[Vec<Node>],
+        cell: CellReference,
+    ) -> CalcResult {
+        let mut array = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut values = Vec::with_capacity(row.len());
+            for node in row {
+                let value = match self.evaluate_node_in_context(node, cell) {
+                    CalcResult::Range { .. } | CalcResult::Array(_) => CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Arrays can only contain values".to_string(),
+                    ),
+                    CalcResult::EmptyArg => CalcResult::EmptyCell,
+                    value => value,
+                };
+                values.push(value);
+            }
+            array.push(values);
+        }
+        CalcResult::Array(array)
+    }
+}
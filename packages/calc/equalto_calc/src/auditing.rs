@@ -0,0 +1,190 @@
+//! Formula auditing: the precedents and dependents of a cell, to draw trace arrows.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calc_result::Range,
+    dependencies::CellKey,
+    expressions::types::{Area, CellReferenceIndex},
+    model::Model,
+    spill::SpillArea,
+};
+
+/// An arrow from an area to a formula that reads it. The arrow crosses sheets if the sheet of
+/// the precedent is not the sheet of the dependent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TraceArrow {
+    /// The area being read
+    pub precedent: Area,
+    /// The formula cell reading it
+    pub dependent: CellReferenceIndex,
+}
+
+fn range_to_area(range: &Range) -> Area {
+    Area {
+        sheet: range.left.sheet,
+        row: range.left.row,
+        column: range.left.column,
+        width: range.right.column - range.left.column + 1,
+        height: range.right.row - range.left.row + 1,
+    }
+}
+
+fn cell_to_area((sheet, row, column): CellKey) -> Area {
+    Area {
+        sheet,
+        row,
+        column,
+        width: 1,
+        height: 1,
+    }
+}
+
+fn cell_to_reference((sheet, row, column): CellKey) -> CellReferenceIndex {
+    CellReferenceIndex { sheet, row, column }
+}
+
+/// Sorts the arrows by dependent and then by precedent
+fn sort_arrows(arrows: &mut [TraceArrow]) {
+    arrows.sort_by_key(|arrow| {
+        let (dependent, precedent) = (&arrow.dependent, &arrow.precedent);
+        (
+            (dependent.sheet, dependent.row, dependent.column),
+            (precedent.sheet, precedent.row, precedent.column),
+            (precedent.height, precedent.width),
+        )
+    });
+}
+
+impl Model {
+    /// Returns an arrow for every area read by the formula in the cell. With `transitive` the
+    /// formulas in those areas are traced too, and so on.
+    /// Returns an empty list if the cell is not a formula.
+    pub fn get_cell_precedents(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> Result<Vec<TraceArrow>, String> {
+        self.workbook.worksheet(sheet)?;
+        let mut arrows = Vec::new();
+        let mut visited: HashSet<CellKey> = HashSet::new();
+        let mut stack = vec![(sheet, row, column)];
+        while let Some(key) = stack.pop() {
+            if !visited.insert(key) {
+                continue;
+            }
+            let precedents = match self.cell_precedents(key) {
+                Some((precedents, _)) => precedents,
+                None => continue,
+            };
+            for range in &precedents {
+                let arrow = TraceArrow {
+                    precedent: range_to_area(range),
+                    dependent: cell_to_reference(key),
+                };
+                if !arrows.contains(&arrow) {
+                    arrows.push(arrow);
+                }
+                if transitive {
+                    stack.extend(self.formulas_in_range(range));
+                }
+            }
+        }
+        sort_arrows(&mut arrows);
+        Ok(arrows)
+    }
+
+    /// Returns an arrow from the cell to every formula that reads it. With `transitive` the
+    /// dependents of those formulas are traced too, and so on.
+    pub fn get_cell_dependents(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> Result<Vec<TraceArrow>, String> {
+        self.workbook.worksheet(sheet)?;
+        let graph = self.build_dependency_graph();
+        let mut arrows = Vec::new();
+        let mut visited: HashSet<CellKey> = HashSet::new();
+        let mut stack = vec![(sheet, row, column)];
+        while let Some(key) = stack.pop() {
+            if !visited.insert(key) {
+                continue;
+            }
+            // Formulas reading a spilled value depend on the anchor
+            let mut cells = vec![key];
+            cells.extend(self.spilled_cells(key));
+            for cell in cells {
+                for dependent in graph.direct_dependents(&cell) {
+                    let arrow = TraceArrow {
+                        precedent: cell_to_area(cell),
+                        dependent: cell_to_reference(dependent),
+                    };
+                    if !arrows.contains(&arrow) {
+                        arrows.push(arrow);
+                    }
+                    if transitive {
+                        stack.push(dependent);
+                    }
+                }
+            }
+        }
+        sort_arrows(&mut arrows);
+        Ok(arrows)
+    }
+
+    /// The formula cells in a range, with spilled values replaced by their anchors
+    fn formulas_in_range(&self, range: &Range) -> Vec<CellKey> {
+        let sheet = range.left.sheet;
+        let worksheet = match self.workbook.worksheet(sheet) {
+            Ok(worksheet) => worksheet,
+            Err(_) => return Vec::new(),
+        };
+        let cells = if range.left == range.right {
+            vec![(sheet, range.left.row, range.left.column)]
+        } else {
+            self.cells_in_range(range)
+        };
+        cells
+            .into_iter()
+            .filter_map(|(sheet, row, column)| {
+                let cell = worksheet.cell(row, column)?;
+                if cell.has_formula() {
+                    return Some((sheet, row, column));
+                }
+                cell.get_spill_anchor(row, column)
+                    .map(|(r, c)| (sheet, r, c))
+            })
+            .collect()
+    }
+
+    /// The cells whose values are computed by the array formula in `anchor`, other than the
+    /// anchor itself
+    fn spilled_cells(&self, anchor: CellKey) -> Vec<CellKey> {
+        match self.spill_areas.get(&anchor) {
+            Some(area) if area.blocked => return Vec::new(),
+            Some(area) => return area.cells(&anchor),
+            None => {}
+        }
+        let (sheet, row, column) = anchor;
+        self.workbook
+            .worksheet(sheet)
+            .ok()
+            .and_then(|worksheet| worksheet.cell(row, column))
+            .and_then(|cell| cell.get_array_formula_size())
+            .map(|(rows, columns)| {
+                SpillArea {
+                    rows,
+                    columns,
+                    blocked: false,
+                }
+                .cells(&anchor)
+            })
+            .unwrap_or_default()
+    }
+}
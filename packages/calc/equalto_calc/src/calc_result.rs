@@ -31,6 +31,8 @@ pub(crate) enum CalcResult {
     },
     EmptyCell,
     EmptyArg,
+    /// A list of rows of values. All the rows have the same length
+    Array(Vec<Vec<CalcResult>>),
 }
 
 impl CalcResult {
@@ -1,4 +1,5 @@
 use crate::{
+    arrays::array_top_left,
     calc_result::{CalcResult, CellReference, Range},
     expressions::{parser::Node, token::Error},
     implicit_intersection::implicit_intersection,
@@ -15,7 +16,7 @@ impl Model {
         self.cast_to_number(result, cell)
     }
 
-    fn cast_to_number(
+    pub(crate) fn cast_to_number(
         &mut self,
         result: CalcResult,
         cell: CellReference,
@@ -52,6 +53,10 @@ impl Model {
                     }),
                 }
             }
+            CalcResult::Array(array) => {
+                let value = array_top_left(array, cell);
+                self.cast_to_number(value, cell)
+            }
         }
     }
 
@@ -112,6 +117,10 @@ impl Model {
                     }),
                 }
             }
+            CalcResult::Array(array) => {
+                let value = array_top_left(array, cell);
+                self.cast_to_string(value, cell)
+            }
         }
     }
 
@@ -124,7 +133,35 @@ impl Model {
         self.cast_to_bool(result, cell)
     }
 
-    fn cast_to_bool(
+    /// Optional number argument, an omitted or empty argument takes the default value
+    pub(crate) fn get_optional_number(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        default: f64,
+        cell: CellReference,
+    ) -> Result<f64, CalcResult> {
+        match args.get(index) {
+            None | Some(Node::EmptyArgKind) => Ok(default),
+            Some(node) => self.get_number(node, cell),
+        }
+    }
+
+    /// Optional boolean argument, an omitted or empty argument takes the default value
+    pub(crate) fn get_optional_boolean(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        default: bool,
+        cell: CellReference,
+    ) -> Result<bool, CalcResult> {
+        match args.get(index) {
+            None | Some(Node::EmptyArgKind) => Ok(default),
+            Some(node) => self.get_boolean(node, cell),
+        }
+    }
+
+    pub(crate) fn cast_to_bool(
         &mut self,
         result: CalcResult,
         cell: CellReference,
@@ -164,6 +201,10 @@ impl Model {
                     }),
                 }
             }
+            CalcResult::Array(array) => {
+                let value = array_top_left(array, cell);
+                self.cast_to_bool(value, cell)
+            }
         }
     }
 
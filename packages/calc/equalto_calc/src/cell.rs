@@ -75,6 +75,16 @@ impl Cell {
         Cell::CellFormula { f, s }
     }
 
+    /// Creates a new Cell with an unevaluated array formula over a block of `r` = (rows, columns)
+    pub fn new_array_formula(f: i32, r: (i32, i32), s: i32) -> Cell {
+        Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::Unevaluated,
+            s,
+        }
+    }
+
     /// Returns the formula of a cell if any.
     pub fn get_formula(&self) -> Option<i32> {
         match self {
@@ -83,6 +93,7 @@ impl Cell {
             Cell::CellFormulaNumber { f, .. } => Some(*f),
             Cell::CellFormulaString { f, .. } => Some(*f),
             Cell::CellFormulaError { f, .. } => Some(*f),
+            Cell::ArrayFormula { f, .. } => Some(*f),
             _ => None,
         }
     }
@@ -91,6 +102,26 @@ impl Cell {
         self.get_formula().is_some()
     }
 
+    /// Returns the (rows, columns) of the block of a legacy array formula.
+    pub fn get_array_formula_size(&self) -> Option<(i32, i32)> {
+        match self {
+            Cell::ArrayFormula { r, .. } => Some(*r),
+            _ => None,
+        }
+    }
+
+    /// Returns the (row, column) of the array formula that spilled or computed the value of this
+    /// cell, if any. `row` and `column` are the coordinates of the cell itself.
+    pub fn get_spill_anchor(&self, row: i32, column: i32) -> Option<(i32, i32)> {
+        match self {
+            Cell::SpillBoolean { dr, dc, .. }
+            | Cell::SpillNumber { dr, dc, .. }
+            | Cell::SpillString { dr, dc, .. }
+            | Cell::SpillError { dr, dc, .. } => Some((row - dr, column - dc)),
+            _ => None,
+        }
+    }
+
     pub fn set_style(&mut self, style: i32) {
         match self {
             Cell::EmptyCell { s, .. } => *s = style,
@@ -103,6 +134,11 @@ impl Cell {
             Cell::CellFormulaNumber { s, .. } => *s = style,
             Cell::CellFormulaString { s, .. } => *s = style,
             Cell::CellFormulaError { s, .. } => *s = style,
+            Cell::ArrayFormula { s, .. } => *s = style,
+            Cell::SpillBoolean { s, .. } => *s = style,
+            Cell::SpillNumber { s, .. } => *s = style,
+            Cell::SpillString { s, .. } => *s = style,
+            Cell::SpillError { s, .. } => *s = style,
         };
     }
 
@@ -118,6 +154,11 @@ impl Cell {
             Cell::CellFormulaNumber { s, .. } => *s,
             Cell::CellFormulaString { s, .. } => *s,
             Cell::CellFormulaError { s, .. } => *s,
+            Cell::ArrayFormula { s, .. } => *s,
+            Cell::SpillBoolean { s, .. } => *s,
+            Cell::SpillNumber { s, .. } => *s,
+            Cell::SpillString { s, .. } => *s,
+            Cell::SpillError { s, .. } => *s,
         }
     }
 
@@ -134,6 +175,16 @@ impl Cell {
             Cell::CellFormulaNumber { .. } => CellType::Number,
             Cell::CellFormulaString { .. } => CellType::Text,
             Cell::CellFormulaError { .. } => CellType::ErrorValue,
+            Cell::ArrayFormula { v, .. } => match v {
+                FormulaValue::Unevaluated | FormulaValue::Number { .. } => CellType::Number,
+                FormulaValue::Boolean { .. } => CellType::LogicalValue,
+                FormulaValue::String { .. } => CellType::Text,
+                FormulaValue::Error { .. } => CellType::ErrorValue,
+            },
+            Cell::SpillBoolean { .. } => CellType::LogicalValue,
+            Cell::SpillNumber { .. } => CellType::Number,
+            Cell::SpillString { .. } => CellType::Text,
+            Cell::SpillError { .. } => CellType::ErrorValue,
         }
     }
 
@@ -171,6 +222,22 @@ impl Cell {
                 let v = ei.to_localized_error_string(language);
                 CellValue::String(v)
             }
+            Cell::ArrayFormula { v, .. } => match v {
+                FormulaValue::Unevaluated => CellValue::String("#ERROR!".to_string()),
+                FormulaValue::Boolean { v } => CellValue::Boolean(*v),
+                FormulaValue::Number { v } => CellValue::Number(*v),
+                FormulaValue::String { v } => CellValue::String(v.clone()),
+                FormulaValue::Error { ei, .. } => {
+                    CellValue::String(ei.to_localized_error_string(language))
+                }
+            },
+            Cell::SpillBoolean { v, .. } => CellValue::Boolean(*v),
+            Cell::SpillNumber { v, .. } => CellValue::Number(*v),
+            Cell::SpillString { v, .. } => CellValue::String(v.clone()),
+            Cell::SpillError { ei, .. } => {
+                let v = ei.to_localized_error_string(language);
+                CellValue::String(v)
+            }
         }
     }
 
@@ -10,7 +10,14 @@ pub(crate) const ROW_HEIGHT_FACTOR: f64 = 2.0;
 pub(crate) const LAST_COLUMN: i32 = 16_384;
 pub(crate) const LAST_ROW: i32 = 1_048_576;
 
+// Largest number of elements of an array built by a function (SEQUENCE, MAKEARRAY, ...)
+pub(crate) const MAX_ARRAY_SIZE: usize = 1_048_576;
+
 // 693_594 is computed as:
 // NaiveDate::from_ymd(1900, 1, 1).num_days_from_ce() - 2
 // The 2 days offset is because of Excel 1900 bug
 pub(crate) const EXCEL_DATE_BASE: i32 = 693_594;
+
+// 695_056 is NaiveDate::from_ymd(1904, 1, 1).num_days_from_ce()
+// In the 1904 date system (Mac) 1 January 1904 is the day 0
+pub(crate) const EXCEL_1904_DATE_BASE: i32 = 695_056;
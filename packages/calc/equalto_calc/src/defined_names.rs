@@ -0,0 +1,270 @@
+//! Defined names: the API to create, update and delete them and the evaluation of the names
+//! whose formula is not a reference, `TaxRate = 0.21` or `LastRow = COUNTA(Sheet1!$A:$A)`.
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    diffs::Diff,
+    expressions::{
+        lexer::LexerMode,
+        parser::{
+            stringify::{rename_defined_name_in_node, to_rc_format, to_string},
+            Node,
+        },
+        token::Error,
+        types::CellReferenceRC,
+        utils::is_valid_identifier,
+    },
+    model::{Model, ParsedDefinedName},
+    types::DefinedName,
+};
+
+impl Model {
+    /// Returns the defined names of the workbook as `(name, scope, formula)`. Formulas are in
+    /// their display form, without the xlsx prefixes of the functions (`_xlfn.LET`).
+    pub fn get_defined_name_list(&self) -> Vec<(String, Option<u32>, String)> {
+        self.workbook
+            .defined_names
+            .iter()
+            .filter_map(|defined_name| {
+                let scope = match defined_name.sheet_id {
+                    Some(sheet_id) => Some(self.get_sheet_index_by_sheet_id(sheet_id)?),
+                    None => None,
+                };
+                let formula = match self
+                    .parsed_defined_names
+                    .get(&(scope, defined_name.name.to_lowercase()))
+                {
+                    Some(ParsedDefinedName::Formula(node))
+                    | Some(ParsedDefinedName::Lambda(node)) => {
+                        to_string(node, &self.get_defined_name_context(scope))
+                    }
+                    _ => defined_name.formula.clone(),
+                };
+                Some((defined_name.name.clone(), scope, formula))
+            })
+            .collect()
+    }
+
+    /// Adds a defined name. Fails if:
+    ///   * The name is not valid or it already exists in that scope
+    ///   * The formula can't be parsed
+    pub fn new_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        formula: &str,
+    ) -> Result<(), String> {
+        let sheet_id = self.get_scope_sheet_id(scope)?;
+        if !is_valid_identifier(name) {
+            return Err(format!("Invalid defined name: '{}'", name));
+        }
+        if self.get_defined_name_index(name, sheet_id).is_some() {
+            return Err(format!("Defined name already exists: '{}'", name));
+        }
+        let formula = self.check_defined_name_formula(formula, scope)?;
+        self.push_diff(Diff::NewDefinedName {
+            name: name.to_string(),
+            scope,
+            formula: formula.clone(),
+        });
+        self.workbook.defined_names.push(DefinedName {
+            name: name.to_string(),
+            formula,
+            sheet_id,
+        });
+        self.reset_parsed_structures();
+        Ok(())
+    }
+
+    /// Changes the name, scope and formula of a defined name. If the name changes the formulas
+    /// that use it are rewritten with the new name.
+    pub fn update_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        new_name: &str,
+        new_scope: Option<u32>,
+        new_formula: &str,
+    ) -> Result<(), String> {
+        let sheet_id = self.get_scope_sheet_id(scope)?;
+        let new_sheet_id = self.get_scope_sheet_id(new_scope)?;
+        let index = self
+            .get_defined_name_index(name, sheet_id)
+            .ok_or_else(|| format!("Defined name not found: '{}'", name))?;
+        if !is_valid_identifier(new_name) {
+            return Err(format!("Invalid defined name: '{}'", new_name));
+        }
+        if let Some(other_index) = self.get_defined_name_index(new_name, new_sheet_id) {
+            if other_index != index {
+                return Err(format!("Defined name already exists: '{}'", new_name));
+            }
+        }
+        let formula = self.check_defined_name_formula(new_formula, new_scope)?;
+        let defined_name = &self.workbook.defined_names[index];
+        self.push_diff(Diff::UpdateDefinedName {
+            name: defined_name.name.clone(),
+            scope,
+            formula: defined_name.formula.clone(),
+            new_name: new_name.to_string(),
+            new_scope,
+            new_formula: formula.clone(),
+        });
+        if name != new_name {
+            self.rename_defined_name_in_formulas(name, scope, new_name);
+        }
+        let defined_name = &mut self.workbook.defined_names[index];
+        defined_name.name = new_name.to_string();
+        defined_name.sheet_id = new_sheet_id;
+        defined_name.formula = formula;
+        self.reset_parsed_structures();
+        Ok(())
+    }
+
+    /// Deletes a defined name. Formulas using it evaluate to `#NAME?`
+    pub fn delete_defined_name(&mut self, name: &str, scope: Option<u32>) -> Result<(), String> {
+        let sheet_id = self.get_scope_sheet_id(scope)?;
+        let index = self
+            .get_defined_name_index(name, sheet_id)
+            .ok_or_else(|| format!("Defined name not found: '{}'", name))?;
+        let defined_name = &self.workbook.defined_names[index];
+        self.push_diff(Diff::DeleteDefinedName {
+            name: defined_name.name.clone(),
+            scope,
+            formula: defined_name.formula.clone(),
+        });
+        self.workbook.defined_names.remove(index);
+        self.reset_parsed_structures();
+        Ok(())
+    }
+
+    /// The cell in which the formula of a defined name is parsed. Relative references are
+    /// relative to A1 of the sheet of the name (or the first sheet for global names).
+    pub(crate) fn get_defined_name_context(&self, scope: Option<u32>) -> CellReferenceRC {
+        CellReferenceRC {
+            sheet: self
+                .workbook
+                .worksheets
+                .get(scope.unwrap_or(0) as usize)
+                .map(|worksheet| worksheet.get_name())
+                .unwrap_or_default(),
+            row: 1,
+            column: 1,
+        }
+    }
+
+    fn get_scope_sheet_id(&self, scope: Option<u32>) -> Result<Option<u32>, String> {
+        match scope {
+            Some(sheet_index) => match self.workbook.worksheets.get(sheet_index as usize) {
+                Some(worksheet) => Ok(Some(worksheet.sheet_id)),
+                None => Err("Sheet index out of bounds".to_string()),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn get_defined_name_index(&self, name: &str, sheet_id: Option<u32>) -> Option<usize> {
+        let name = name.to_lowercase();
+        self.workbook.defined_names.iter().position(|defined_name| {
+            defined_name.sheet_id == sheet_id && defined_name.name.to_lowercase() == name
+        })
+    }
+
+    /// Returns the formula without the leading '=' if it can be parsed
+    fn check_defined_name_formula(
+        &mut self,
+        formula: &str,
+        scope: Option<u32>,
+    ) -> Result<String, String> {
+        let formula = formula.strip_prefix('=').unwrap_or(formula);
+        let context = self.get_defined_name_context(scope);
+        match self.parser.parse(formula, &Some(context)) {
+            Node::ParseErrorKind { message, .. } => Err(format!("Invalid formula: {}", message)),
+            _ => Ok(formula.to_string()),
+        }
+    }
+
+    /// Rewrites the formulas of the cells and the defined names that use the defined name
+    /// `name` with `new_name`
+    fn rename_defined_name_in_formulas(&mut self, name: &str, scope: Option<u32>, new_name: &str) {
+        // Sheets where `name` is this defined name and not a local name that hides it
+        let sheets: Vec<usize> = self
+            .workbook
+            .worksheets
+            .iter()
+            .enumerate()
+            .filter(|(sheet_index, worksheet)| match scope {
+                Some(scope) => scope as usize == *sheet_index,
+                None => self
+                    .get_defined_name_index(name, Some(worksheet.sheet_id))
+                    .is_none(),
+            })
+            .map(|(sheet_index, _)| sheet_index)
+            .collect();
+        // All internal formulas are R1C1
+        self.parser.set_lexer_mode(LexerMode::R1C1);
+        for &sheet_index in &sheets {
+            let worksheet = &mut self.workbook.worksheets[sheet_index];
+            let cell_reference = &Some(CellReferenceRC {
+                sheet: worksheet.get_name(),
+                row: 1,
+                column: 1,
+            });
+            let mut formulas = Vec::new();
+            for formula in &worksheet.shared_formulas {
+                let mut t = self.parser.parse(formula, cell_reference);
+                if rename_defined_name_in_node(&mut t, name, new_name) {
+                    formulas.push(to_rc_format(&t));
+                } else {
+                    formulas.push(formula.clone());
+                }
+            }
+            worksheet.shared_formulas = formulas;
+        }
+        self.parser.set_lexer_mode(LexerMode::A1);
+
+        for index in 0..self.workbook.defined_names.len() {
+            let defined_name = &self.workbook.defined_names[index];
+            let defined_name_scope = defined_name
+                .sheet_id
+                .and_then(|sheet_id| self.get_sheet_index_by_sheet_id(sheet_id));
+            // Names local to a sheet where `name` is hidden keep their formulas
+            let renamed = match defined_name_scope {
+                Some(sheet_index) => sheets.contains(&(sheet_index as usize)),
+                None => scope.is_none(),
+            };
+            if !renamed {
+                continue;
+            }
+            let formula = defined_name.formula.clone();
+            let context = self.get_defined_name_context(defined_name_scope);
+            let mut t = self.parser.parse(&formula, &Some(context.clone()));
+            if rename_defined_name_in_node(&mut t, name, new_name) {
+                self.workbook.defined_names[index].formula = to_string(&t, &context);
+            }
+        }
+    }
+
+    /// Evaluates `node`, the formula of the defined name `name`, in the context of `cell`
+    pub(crate) fn evaluate_defined_name_formula(
+        &mut self,
+        name: &str,
+        node: &Node,
+        cell: CellReference,
+    ) -> CalcResult {
+        let key = name.to_lowercase();
+        if self.evaluating_names.contains(&key) {
+            return CalcResult::new_error(
+                Error::CIRC,
+                cell,
+                format!("Circular reference in defined name \"{}\"", name),
+            );
+        }
+        self.evaluating_names.push(key);
+        // LET and LAMBDA bindings of the calling formula are not visible in the name
+        let locals = std::mem::take(&mut self.locals);
+        let result = self.evaluate_node_in_context(node, cell);
+        self.locals = locals;
+        self.evaluating_names.pop();
+        result
+    }
+}
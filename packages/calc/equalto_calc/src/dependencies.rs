@@ -0,0 +1,494 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    calc_result::{CellReference, Range},
+    expressions::parser::Node,
+    model::{Model, ParsedDefinedName},
+};
+
+/// (sheet, row, column), the same key used by `Model::cells`
+pub(crate) type CellKey = (u32, i32, i32);
+
+/// Precedents/dependents graph of all the formulas in the workbook.
+///
+/// Single cell references are indexed by cell so that finding the dependents of a cell is a
+/// lookup. Multi-cell ranges (think `SUM(A:A)`) are kept in a per sheet list and scanned,
+/// materializing them cell by cell would be prohibitive.
+///
+/// References only known at evaluation time (INDIRECT, OFFSET, named LAMBDAs, ...) are not
+/// followed, the formulas using them are volatile instead. The same goes for auditing, iterative
+/// calculation and parallel evaluation, which are built on this graph.
+#[derive(Clone, Default)]
+pub(crate) struct DependencyGraph {
+    /// What every formula cell reads
+    precedents: HashMap<CellKey, Vec<Range>>,
+    /// Formula cells reading a given single cell
+    cell_dependents: HashMap<CellKey, HashSet<CellKey>>,
+    /// Formula cells reading a multi-cell range, by sheet
+    range_dependents: HashMap<u32, Vec<(Range, CellKey)>>,
+    /// Formula cells that must be recomputed on every evaluation (RAND, NOW, OFFSET, ...)
+    volatile: HashSet<CellKey>,
+    /// Cells edited since the last incremental evaluation
+    dirty: HashSet<CellKey>,
+}
+
+fn range_contains(range: &Range, key: &CellKey) -> bool {
+    let (sheet, row, column) = *key;
+    range.left.sheet == sheet
+        && range.left.row <= row
+        && row <= range.right.row
+        && range.left.column <= column
+        && column <= range.right.column
+}
+
+impl DependencyGraph {
+    fn add_formula(&mut self, key: CellKey, precedents: Vec<Range>, is_volatile: bool) {
+        for range in &precedents {
+            if range.left == range.right {
+                let precedent = (range.left.sheet, range.left.row, range.left.column);
+                self.cell_dependents
+                    .entry(precedent)
+                    .or_default()
+                    .insert(key);
+            } else {
+                self.range_dependents
+                    .entry(range.left.sheet)
+                    .or_default()
+                    .push((range.clone(), key));
+            }
+        }
+        if is_volatile {
+            self.volatile.insert(key);
+        }
+        self.precedents.insert(key, precedents);
+    }
+
+    fn remove_formula(&mut self, key: &CellKey) {
+        self.volatile.remove(key);
+        let precedents = match self.precedents.remove(key) {
+            Some(p) => p,
+            None => return,
+        };
+        for range in precedents {
+            if range.left == range.right {
+                let precedent = (range.left.sheet, range.left.row, range.left.column);
+                if let Some(dependents) = self.cell_dependents.get_mut(&precedent) {
+                    dependents.remove(key);
+                    if dependents.is_empty() {
+                        self.cell_dependents.remove(&precedent);
+                    }
+                }
+            } else if let Some(ranges) = self.range_dependents.get_mut(&range.left.sheet) {
+                ranges.retain(|(_, dependent)| dependent != key);
+            }
+        }
+    }
+
+    /// What every formula cell reads
+    pub(crate) fn precedents(&self) -> &HashMap<CellKey, Vec<Range>> {
+        &self.precedents
+    }
+
+    /// Returns true if some formula has to be recomputed on every evaluation
+    pub(crate) fn has_volatile(&self) -> bool {
+        !self.volatile.is_empty()
+    }
+
+    /// Formula cells that read `key` directly
+    pub(crate) fn direct_dependents(&self, key: &CellKey) -> Vec<CellKey> {
+        let mut dependents: Vec<CellKey> = Vec::new();
+        if let Some(cells) = self.cell_dependents.get(key) {
+            dependents.extend(cells.iter().copied());
+        }
+        if let Some(ranges) = self.range_dependents.get(&key.0) {
+            for (range, dependent) in ranges {
+                if range_contains(range, key) {
+                    dependents.push(*dependent);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Returns the dirty cells, the volatile cells and every formula that depends on them
+    /// directly or transitively.
+    fn dirty_closure(&self) -> HashSet<CellKey> {
+        let mut closure = HashSet::new();
+        let mut stack: Vec<CellKey> = self.dirty.iter().copied().collect();
+        stack.extend(self.volatile.iter().copied());
+        while let Some(key) = stack.pop() {
+            if closure.insert(key) {
+                stack.extend(self.direct_dependents(&key));
+            }
+        }
+        closure
+    }
+}
+
+impl Model {
+    /// Collects every area the node reads from when evaluated in the context of `cell`.
+    /// Returns true if the node contains a volatile function or a reference that can only be
+    /// resolved at evaluation time.
+    /// `names` are the defined names whose formulas are being walked, to stop on cycles.
+    fn collect_precedents(
+        &self,
+        node: &Node,
+        cell: &CellReference,
+        precedents: &mut Vec<Range>,
+        names: &mut Vec<String>,
+    ) -> bool {
+        match node {
+            Node::ReferenceKind {
+                sheet_index,
+                absolute_row,
+                absolute_column,
+                row,
+                column,
+                ..
+            } => {
+                let reference = CellReference {
+                    sheet: *sheet_index,
+                    row: if *absolute_row { *row } else { *row + cell.row },
+                    column: if *absolute_column {
+                        *column
+                    } else {
+                        *column + cell.column
+                    },
+                };
+                precedents.push(Range {
+                    left: reference,
+                    right: reference,
+                });
+                false
+            }
+            Node::RangeKind {
+                sheet_index,
+                absolute_row1,
+                absolute_column1,
+                row1,
+                column1,
+                absolute_row2,
+                absolute_column2,
+                row2,
+                column2,
+                ..
+            } => {
+                precedents.push(Range {
+                    left: CellReference {
+                        sheet: *sheet_index,
+                        row: if *absolute_row1 {
+                            *row1
+                        } else {
+                            *row1 + cell.row
+                        },
+                        column: if *absolute_column1 {
+                            *column1
+                        } else {
+                            *column1 + cell.column
+                        },
+                    },
+                    right: CellReference {
+                        sheet: *sheet_index,
+                        row: if *absolute_row2 {
+                            *row2
+                        } else {
+                            *row2 + cell.row
+                        },
+                        column: if *absolute_column2 {
+                            *column2
+                        } else {
+                            *column2 + cell.column
+                        },
+                    },
+                });
+                false
+            }
+            Node::OpRangeKind { left, right } => {
+                // A1:B3 where both ends are references is a static range, anything else
+                // (i.e. `A1:INDEX(...)`) is only known at evaluation time.
+                let mut ends = Vec::new();
+                let left_volatile = self.collect_precedents(left, cell, &mut ends, names);
+                let right_volatile = self.collect_precedents(right, cell, &mut ends, names);
+                let is_static = matches!(**left, Node::ReferenceKind { .. })
+                    && matches!(**right, Node::ReferenceKind { .. });
+                if is_static && ends.len() == 2 && ends[0].left.sheet == ends[1].left.sheet {
+                    let (a, b) = (ends[0].left, ends[1].left);
+                    precedents.push(Range {
+                        left: CellReference {
+                            sheet: a.sheet,
+                            row: a.row.min(b.row),
+                            column: a.column.min(b.column),
+                        },
+                        right: CellReference {
+                            sheet: a.sheet,
+                            row: a.row.max(b.row),
+                            column: a.column.max(b.column),
+                        },
+                    });
+                } else {
+                    precedents.extend(ends);
+                }
+                left_volatile || right_volatile || !is_static
+            }
+            Node::VariableKind(defined_name) => {
+                let parsed_defined_name = self
+                    .parsed_defined_names
+                    .get(&(Some(cell.sheet), defined_name.to_lowercase()))
+                    .or_else(|| {
+                        self.parsed_defined_names
+                            .get(&(None, defined_name.to_lowercase()))
+                    });
+                match parsed_defined_name {
+                    Some(ParsedDefinedName::CellReference(reference)) => {
+                        precedents.push(Range {
+                            left: *reference,
+                            right: *reference,
+                        });
+                    }
+                    Some(ParsedDefinedName::RangeReference(range)) => {
+                        precedents.push(range.clone());
+                    }
+                    Some(ParsedDefinedName::Formula(node)) => {
+                        // A name in its own formula is a circular reference
+                        let name = defined_name.to_lowercase();
+                        if names.contains(&name) {
+                            return true;
+                        }
+                        names.push(name);
+                        let is_volatile = self.collect_precedents(node, cell, precedents, names);
+                        names.pop();
+                        return is_volatile;
+                    }
+                    // The references of a named LAMBDA are only known when it is called
+                    Some(ParsedDefinedName::Lambda(_)) => return true,
+                    Some(ParsedDefinedName::InvalidDefinedNameFormula) | None => {}
+                }
+                false
+            }
+            Node::FunctionKind { kind, args } => {
+                let mut is_volatile = kind.is_volatile();
+                for arg in args {
+                    is_volatile |= self.collect_precedents(arg, cell, precedents, names);
+                }
+                is_volatile
+            }
+            Node::UserFunctionKind { name, args } => {
+                let mut is_volatile = self
+                    .user_functions
+                    .get(name)
+                    .is_some_and(|function| function.is_volatile());
+                for arg in args {
+                    is_volatile |= self.collect_precedents(arg, cell, precedents, names);
+                }
+                is_volatile
+            }
+            Node::InvalidFunctionKind { name, args } => {
+                let mut is_volatile = self.get_defined_lambda(name, *cell).is_some();
+                for arg in args {
+                    is_volatile |= self.collect_precedents(arg, cell, precedents, names);
+                }
+                is_volatile
+            }
+            Node::ArrayKind(rows) => {
+                let mut is_volatile = false;
+                for arg in rows.iter().flatten() {
+                    is_volatile |= self.collect_precedents(arg, cell, precedents, names);
+                }
+                is_volatile
+            }
+            Node::SpillRefKind(reference) => {
+                // The size of the spill range is only known at evaluation time
+                self.collect_precedents(reference, cell, precedents, names);
+                true
+            }
+            Node::OpConcatenateKind { left, right }
+            | Node::OpSumKind { left, right, .. }
+            | Node::OpProductKind { left, right, .. }
+            | Node::OpPowerKind { left, right }
+            | Node::CompareKind { left, right, .. } => {
+                let left_volatile = self.collect_precedents(left, cell, precedents, names);
+                let right_volatile = self.collect_precedents(right, cell, precedents, names);
+                left_volatile || right_volatile
+            }
+            Node::UnaryKind { right, .. } => {
+                self.collect_precedents(right, cell, precedents, names)
+            }
+            Node::LambdaCallKind { lambda, args } => {
+                let mut is_volatile = self.collect_precedents(lambda, cell, precedents, names);
+                for arg in args {
+                    is_volatile |= self.collect_precedents(arg, cell, precedents, names);
+                }
+                is_volatile
+            }
+            Node::LocalVariableKind(_) => false,
+            Node::BooleanKind(_)
+            | Node::NumberKind(_)
+            | Node::StringKind(_)
+            | Node::WrongReferenceKind { .. }
+            | Node::WrongRangeKind { .. }
+            | Node::ErrorKind(_)
+            | Node::ParseErrorKind { .. }
+            | Node::EmptyArgKind => false,
+        }
+    }
+
+    /// The existing cells in a range
+    pub(crate) fn cells_in_range(&self, range: &Range) -> Vec<CellKey> {
+        let sheet = range.left.sheet;
+        let worksheet = match self.workbook.worksheet(sheet) {
+            Ok(worksheet) => worksheet,
+            Err(_) => return Vec::new(),
+        };
+        let mut cells = Vec::new();
+        for (row, row_data) in &worksheet.sheet_data {
+            if *row < range.left.row || *row > range.right.row {
+                continue;
+            }
+            for column in row_data.keys() {
+                if range.left.column <= *column && *column <= range.right.column {
+                    cells.push((sheet, *row, *column));
+                }
+            }
+        }
+        cells
+    }
+
+    /// The areas the formula in `key` reads and whether it is volatile, `None` if the cell is
+    /// not a formula
+    pub(crate) fn cell_precedents(&self, key: CellKey) -> Option<(Vec<Range>, bool)> {
+        let (sheet, row, column) = key;
+        let formula_index = self
+            .workbook
+            .worksheet(sheet)
+            .ok()
+            .and_then(|worksheet| worksheet.cell(row, column))
+            .and_then(|cell| cell.get_formula())?;
+        let node = &self.parsed_formulas[sheet as usize][formula_index as usize];
+        let mut precedents = Vec::new();
+        let is_volatile = self.collect_precedents(
+            node,
+            &CellReference { sheet, row, column },
+            &mut precedents,
+            &mut Vec::new(),
+        );
+        Some((precedents, is_volatile))
+    }
+
+    /// Adds the formula in `key` (if any) to the graph
+    fn add_cell_to_graph(&self, graph: &mut DependencyGraph, key: CellKey) {
+        if let Some((precedents, is_volatile)) = self.cell_precedents(key) {
+            graph.add_formula(key, precedents, is_volatile);
+        }
+    }
+
+    /// Builds the dependency graph of all the formulas in the workbook
+    pub(crate) fn build_dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for cell in self.get_all_cells() {
+            self.add_cell_to_graph(&mut graph, (cell.index, cell.row, cell.column));
+        }
+        graph
+    }
+
+    /// Records that the content of a cell has changed so that the next incremental
+    /// evaluation recomputes it and everything that depends on it.
+    pub(crate) fn mark_cell_dirty(&mut self, sheet: u32, row: i32, column: i32) {
+        let key = (sheet, row, column);
+        let anchors = self.spill_anchors_containing(&key);
+        if let Some(graph) = &mut self.dependency_graph {
+            graph.dirty.insert(key);
+            // Editing a cell in a spill range might block or unblock the array formula
+            graph.dirty.extend(anchors);
+        }
+    }
+
+    /// Drops the dependency graph. Needed after structural changes (inserting rows, renaming
+    /// sheets, ...) that move cells around or change the meaning of references.
+    pub(crate) fn invalidate_dependency_graph(&mut self) {
+        self.dependency_graph = None;
+    }
+
+    /// Updates the graph with the edited cells and returns the list of cells that need to be
+    /// recomputed, sorted by sheet, row and column.
+    pub(crate) fn cells_to_recompute(&mut self) -> Vec<CellKey> {
+        let mut graph = match self.dependency_graph.take() {
+            Some(graph) => graph,
+            None => return Vec::new(),
+        };
+        let dirty: Vec<CellKey> = graph.dirty.iter().copied().collect();
+        for key in dirty {
+            graph.remove_formula(&key);
+            self.add_cell_to_graph(&mut graph, key);
+        }
+        let mut cells: Vec<CellKey> = graph.dirty_closure().into_iter().collect();
+        cells.sort_unstable();
+        graph.dirty.clear();
+        self.dependency_graph = Some(graph);
+        cells
+    }
+
+    /// Evaluates only the cells affected by the edits made since the last call.
+    ///
+    /// The first call (or the first call after a structural change of the workbook) performs a
+    /// full evaluation and builds the dependency graph. Subsequent calls recompute the edited
+    /// cells, the volatile formulas (RAND, NOW, OFFSET, INDIRECT, ...) and their transitive
+    /// dependents only. The results are the same as those of [`Model::evaluate`].
+    pub fn evaluate_incremental(&mut self) {
+        if self.workbook.settings.iterative_calculation.enabled {
+            // Circular references are solved over the whole workbook
+            self.invalidate_dependency_graph();
+            self.evaluate();
+            return;
+        }
+        if self.dependency_graph.is_none() {
+            self.evaluate();
+            self.dependency_graph = Some(self.build_dependency_graph());
+            return;
+        }
+        // Anchors of dynamic arrays already recomputed in this call
+        let mut respilled = HashSet::new();
+        loop {
+            let cells = self.cells_to_recompute();
+            let previous_areas = self.spill_areas.clone();
+            for key in &cells {
+                self.cells.remove(key);
+            }
+            for &(sheet, row, column) in &cells {
+                let cell = CellReference { sheet, row, column };
+                if !self.is_formula(cell) {
+                    // It might have been a dynamic array formula
+                    self.clear_spill(cell);
+                }
+                self.evaluate_cell(cell);
+            }
+            // The values spilled by the recomputed array formulas might have changed and with
+            // them the formulas that depend on them.
+            let mut spilled_cells = Vec::new();
+            for key in &cells {
+                if !respilled.insert(*key) {
+                    continue;
+                }
+                for area in [previous_areas.get(key), self.spill_areas.get(key)]
+                    .into_iter()
+                    .flatten()
+                {
+                    spilled_cells.extend(area.cells(key));
+                }
+            }
+            if spilled_cells.is_empty() {
+                break;
+            }
+            for (sheet, row, column) in spilled_cells {
+                self.mark_cell_dirty(sheet, row, column);
+            }
+        }
+    }
+
+    fn is_formula(&self, cell: CellReference) -> bool {
+        self.workbook
+            .worksheet(cell.sheet)
+            .ok()
+            .and_then(|worksheet| worksheet.cell(cell.row, cell.column))
+            .is_some_and(|c| c.has_formula())
+    }
+}
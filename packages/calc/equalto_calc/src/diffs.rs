@@ -8,8 +8,10 @@ use crate::{
         types::{Area, CellReferenceIndex, CellReferenceRC},
     },
     model::Model,
+    types::{Cell, Col, Row, Worksheet},
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged, deny_unknown_fields)]
@@ -25,7 +27,413 @@ pub struct SetCellValue {
     old_value: CellValue,
 }
 
+/// A reversible change to the model.
+/// Applying the old values undoes the change, applying the new values redoes it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Diff {
+    /// Content and style of a cell, `None` if the cell does not exist
+    SetCell {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        old_value: Box<Option<Cell>>,
+        new_value: Box<Option<Cell>>,
+    },
+    /// Heights and styles of the rows of a sheet
+    SetRows {
+        sheet: u32,
+        old_value: Vec<Row>,
+        new_value: Vec<Row>,
+    },
+    /// Widths and styles of the columns of a sheet
+    SetColumns {
+        sheet: u32,
+        old_value: Vec<Col>,
+        new_value: Vec<Col>,
+    },
+    SetSheetColor {
+        sheet: u32,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+    InsertSheet {
+        sheet: u32,
+        worksheet: Box<Worksheet>,
+    },
+    DeleteSheet {
+        sheet: u32,
+        worksheet: Box<Worksheet>,
+    },
+    RenameSheet {
+        sheet: u32,
+        old_name: String,
+        new_name: String,
+    },
+    NewDefinedName {
+        name: String,
+        scope: Option<u32>,
+        formula: String,
+    },
+    DeleteDefinedName {
+        name: String,
+        scope: Option<u32>,
+        formula: String,
+    },
+    UpdateDefinedName {
+        name: String,
+        scope: Option<u32>,
+        formula: String,
+        new_name: String,
+        new_scope: Option<u32>,
+        new_formula: String,
+    },
+}
+
+/// Part of the model whose state is saved before a transaction changes it
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Target {
+    Cell(u32, i32, i32),
+    Rows(u32),
+    Columns(u32),
+}
+
+#[derive(Clone, PartialEq)]
+enum Snapshot {
+    Cell(Option<Cell>),
+    Rows(Vec<Row>),
+    Columns(Vec<Col>),
+}
+
+/// Undo and redo stacks. Each entry is a transaction: the list of diffs of one user action.
+#[derive(Clone, Default)]
+pub(crate) struct History {
+    undo_stack: Vec<Vec<Diff>>,
+    redo_stack: Vec<Vec<Diff>>,
+    /// Diffs of the open transaction
+    pending: Vec<Diff>,
+    /// State of the cells, rows and columns changed by the open transaction before the change
+    snapshots: Vec<(Target, Snapshot)>,
+    saved_targets: HashSet<Target>,
+    /// Number of nested open transactions
+    depth: usize,
+    /// Nothing is recorded while undoing or redoing
+    replaying: bool,
+}
+
 impl Model {
+    /// Opens a transaction. All changes until the matching [`Model::commit_transaction`] are
+    /// undone and redone together. Transactions can be nested.
+    pub fn begin_transaction(&mut self) {
+        self.history.depth += 1;
+    }
+
+    /// Closes a transaction. When the outermost transaction is closed its changes are pushed to
+    /// the undo stack and the redo stack is cleared.
+    pub fn commit_transaction(&mut self) {
+        self.history.depth = self.history.depth.saturating_sub(1);
+        if self.history.depth > 0 || self.history.replaying {
+            return;
+        }
+        self.flush_snapshots();
+        let diffs = std::mem::take(&mut self.history.pending);
+        if !diffs.is_empty() {
+            self.history.undo_stack.push(diffs);
+            self.history.redo_stack.clear();
+        }
+    }
+
+    /// Runs `f` in its own transaction
+    pub(crate) fn transaction<T>(&mut self, f: impl FnOnce(&mut Model) -> T) -> T {
+        self.begin_transaction();
+        let result = f(self);
+        self.commit_transaction();
+        result
+    }
+
+    /// Runs `f` in its own transaction. If `f` fails the transaction is rolled back.
+    pub(crate) fn try_transaction<T, E>(
+        &mut self,
+        f: impl FnOnce(&mut Model) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.begin_transaction();
+        let result = f(self);
+        if result.is_ok() {
+            self.commit_transaction();
+        } else {
+            self.rollback_transaction();
+        }
+        result
+    }
+
+    /// Closes a failed transaction. When the outermost transaction is closed its changes are
+    /// reverted and discarded, leaving nothing to undo.
+    fn rollback_transaction(&mut self) {
+        self.history.depth = self.history.depth.saturating_sub(1);
+        if self.history.depth > 0 || self.history.replaying {
+            return;
+        }
+        self.flush_snapshots();
+        let diffs = std::mem::take(&mut self.history.pending);
+        // Reverting changes that were just made cannot fail
+        let _ = self.replay(diffs.iter().rev(), true);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.history.redo_stack.is_empty()
+    }
+
+    /// Reverts the last transaction.
+    /// Like any other edit, the model needs to be evaluated afterwards.
+    pub fn undo(&mut self) -> Result<(), String> {
+        if self.history.depth > 0 {
+            return Err("Cannot undo with an open transaction".to_string());
+        }
+        let diffs = self.history.undo_stack.pop().ok_or("Nothing to undo")?;
+        let result = self.replay(diffs.iter().rev(), true);
+        self.history.redo_stack.push(diffs);
+        result
+    }
+
+    /// Applies again the last undone transaction.
+    /// Like any other edit, the model needs to be evaluated afterwards.
+    pub fn redo(&mut self) -> Result<(), String> {
+        if self.history.depth > 0 {
+            return Err("Cannot redo with an open transaction".to_string());
+        }
+        let diffs = self.history.redo_stack.pop().ok_or("Nothing to redo")?;
+        let result = self.replay(diffs.iter(), false);
+        self.history.undo_stack.push(diffs);
+        result
+    }
+
+    fn replay<'a>(
+        &mut self,
+        diffs: impl Iterator<Item = &'a Diff>,
+        undo: bool,
+    ) -> Result<(), String> {
+        self.history.replaying = true;
+        let mut result = Ok(());
+        for diff in diffs {
+            result = self.apply_diff(diff, undo);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.history.replaying = false;
+        result
+    }
+
+    fn apply_diff(&mut self, diff: &Diff, undo: bool) -> Result<(), String> {
+        match diff {
+            Diff::SetCell {
+                sheet,
+                row,
+                column,
+                old_value,
+                new_value,
+            } => {
+                let value = if undo { old_value } else { new_value };
+                let sheet_data = &mut self.workbook.worksheet_mut(*sheet)?.sheet_data;
+                match value.as_ref() {
+                    Some(cell) => {
+                        sheet_data
+                            .entry(*row)
+                            .or_default()
+                            .insert(*column, cell.clone());
+                    }
+                    None => {
+                        if let Some(row_data) = sheet_data.get_mut(row) {
+                            row_data.remove(column);
+                            if row_data.is_empty() {
+                                sheet_data.remove(row);
+                            }
+                        }
+                    }
+                }
+                self.mark_cell_dirty(*sheet, *row, *column);
+            }
+            Diff::SetRows {
+                sheet,
+                old_value,
+                new_value,
+            } => {
+                let value = if undo { old_value } else { new_value };
+                self.workbook.worksheet_mut(*sheet)?.rows = value.clone();
+            }
+            Diff::SetColumns {
+                sheet,
+                old_value,
+                new_value,
+            } => {
+                let value = if undo { old_value } else { new_value };
+                self.workbook.worksheet_mut(*sheet)?.cols = value.clone();
+            }
+            Diff::SetSheetColor {
+                sheet,
+                old_value,
+                new_value,
+            } => {
+                let value = if undo { old_value } else { new_value };
+                self.workbook.worksheet_mut(*sheet)?.color = value.clone();
+            }
+            Diff::InsertSheet { sheet, worksheet } | Diff::DeleteSheet { sheet, worksheet } => {
+                let insert = matches!(diff, Diff::InsertSheet { .. }) != undo;
+                if insert {
+                    let worksheets = &mut self.workbook.worksheets;
+                    if *sheet as usize > worksheets.len() {
+                        return Err("Sheet index out of range".to_string());
+                    }
+                    worksheets.insert(*sheet as usize, worksheet.as_ref().clone());
+                } else {
+                    self.workbook.worksheet(*sheet)?;
+                    self.workbook.worksheets.remove(*sheet as usize);
+                }
+                self.reset_parsed_structures();
+            }
+            Diff::RenameSheet {
+                sheet,
+                old_name,
+                new_name,
+            } => {
+                let name = if undo { old_name } else { new_name };
+                self.rename_sheet_by_index(*sheet, name)?;
+            }
+            Diff::NewDefinedName {
+                name,
+                scope,
+                formula,
+            }
+            | Diff::DeleteDefinedName {
+                name,
+                scope,
+                formula,
+            } => {
+                let create = matches!(diff, Diff::NewDefinedName { .. }) != undo;
+                if create {
+                    self.new_defined_name(name, *scope, formula)?;
+                } else {
+                    self.delete_defined_name(name, *scope)?;
+                }
+            }
+            Diff::UpdateDefinedName {
+                name,
+                scope,
+                formula,
+                new_name,
+                new_scope,
+                new_formula,
+            } => {
+                if undo {
+                    self.update_defined_name(new_name, *new_scope, name, *scope, formula)?;
+                } else {
+                    self.update_defined_name(name, *scope, new_name, *new_scope, new_formula)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records `diff` in the open transaction, or in a new one if there is none.
+    /// Must be called before the change is made.
+    pub(crate) fn push_diff(&mut self, diff: Diff) {
+        if self.history.replaying {
+            return;
+        }
+        self.transaction(|model| {
+            // Changes to the list of sheets move the sheet indices of the saved cells
+            model.flush_snapshots();
+            model.history.pending.push(diff);
+        });
+    }
+
+    /// Saves the state of a cell before the open transaction changes it
+    pub(crate) fn save_cell(&mut self, sheet: u32, row: i32, column: i32) {
+        self.save_target(Target::Cell(sheet, row, column));
+    }
+
+    /// Saves the row heights and styles of a sheet before the open transaction changes them
+    pub(crate) fn save_rows(&mut self, sheet: u32) {
+        self.save_target(Target::Rows(sheet));
+    }
+
+    /// Saves the column widths and styles of a sheet before the open transaction changes them
+    pub(crate) fn save_columns(&mut self, sheet: u32) {
+        self.save_target(Target::Columns(sheet));
+    }
+
+    fn save_target(&mut self, target: Target) {
+        if self.history.replaying || self.history.saved_targets.contains(&target) {
+            return;
+        }
+        if let Some(snapshot) = self.snapshot(&target) {
+            self.history.saved_targets.insert(target.clone());
+            self.history.snapshots.push((target, snapshot));
+        }
+    }
+
+    fn snapshot(&self, target: &Target) -> Option<Snapshot> {
+        let snapshot = match *target {
+            Target::Cell(sheet, row, column) => Snapshot::Cell(
+                self.workbook
+                    .worksheet(sheet)
+                    .ok()?
+                    .cell(row, column)
+                    .cloned(),
+            ),
+            Target::Rows(sheet) => {
+                Snapshot::Rows(self.workbook.worksheet(sheet).ok()?.rows.clone())
+            }
+            Target::Columns(sheet) => {
+                Snapshot::Columns(self.workbook.worksheet(sheet).ok()?.cols.clone())
+            }
+        };
+        Some(snapshot)
+    }
+
+    /// Turns the saved states into diffs against the current state
+    fn flush_snapshots(&mut self) {
+        self.history.saved_targets.clear();
+        let snapshots = std::mem::take(&mut self.history.snapshots);
+        for (target, old_value) in snapshots {
+            let new_value = match self.snapshot(&target) {
+                Some(snapshot) => snapshot,
+                None => continue,
+            };
+            if new_value == old_value {
+                continue;
+            }
+            let diff = match (target, old_value, new_value) {
+                (Target::Cell(sheet, row, column), Snapshot::Cell(old), Snapshot::Cell(new)) => {
+                    Diff::SetCell {
+                        sheet,
+                        row,
+                        column,
+                        old_value: Box::new(old),
+                        new_value: Box::new(new),
+                    }
+                }
+                (Target::Rows(sheet), Snapshot::Rows(old), Snapshot::Rows(new)) => Diff::SetRows {
+                    sheet,
+                    old_value: old,
+                    new_value: new,
+                },
+                (Target::Columns(sheet), Snapshot::Columns(old), Snapshot::Columns(new)) => {
+                    Diff::SetColumns {
+                        sheet,
+                        old_value: old,
+                        new_value: new,
+                    }
+                }
+                _ => continue,
+            };
+            self.history.pending.push(diff);
+        }
+    }
+
     pub(crate) fn shift_cell_formula(
         &mut self,
         sheet: u32,
@@ -62,75 +470,77 @@ impl Model {
         source_area: &Area,
         target: &CellReferenceIndex,
     ) -> Result<Vec<SetCellValue>, String> {
-        let mut diff_list: Vec<SetCellValue> = Vec::new();
-        let target_area = &Area {
-            sheet: target.sheet,
-            row: target.row,
-            column: target.column,
-            width: source_area.width,
-            height: source_area.height,
-        };
-        // Walk over every formula
-        let cells = self.get_all_cells();
-        for cell in cells {
-            if let Some(f) = self
-                .workbook
-                .worksheet(cell.index)
-                .expect("Worksheet must exist")
-                .cell(cell.row, cell.column)
-                .expect("Cell must exist")
-                .get_formula()
-            {
-                let sheet = cell.index;
-                let row = cell.row;
-                let column = cell.column;
-
-                // If cell is in the source or target area, skip
-                if ref_is_in_area(sheet, row, column, source_area)
-                    || ref_is_in_area(sheet, row, column, target_area)
+        self.transaction(|model| {
+            let mut diff_list: Vec<SetCellValue> = Vec::new();
+            let target_area = &Area {
+                sheet: target.sheet,
+                row: target.row,
+                column: target.column,
+                width: source_area.width,
+                height: source_area.height,
+            };
+            // Walk over every formula
+            let cells = model.get_all_cells();
+            for cell in cells {
+                if let Some(f) = model
+                    .workbook
+                    .worksheet(cell.index)
+                    .expect("Worksheet must exist")
+                    .cell(cell.row, cell.column)
+                    .expect("Cell must exist")
+                    .get_formula()
                 {
-                    continue;
-                }
+                    let sheet = cell.index;
+                    let row = cell.row;
+                    let column = cell.column;
 
-                // Get the formula
-                // Get a copy of the AST
-                let node = &mut self.parsed_formulas[sheet as usize][f as usize].clone();
-                let cell_reference = CellReferenceRC {
-                    sheet: self.workbook.worksheets[sheet as usize].get_name(),
-                    column: cell.column,
-                    row: cell.row,
-                };
-                let context = CellReferenceIndex { sheet, column, row };
-                let formula = to_string(node, &cell_reference);
-                let target_sheet_name = &self.workbook.worksheets[target.sheet as usize].name;
-                forward_references(
-                    node,
-                    &context,
-                    source_area,
-                    target.sheet,
-                    target_sheet_name,
-                    target.row,
-                    target.column,
-                );
-
-                // If the string representation of the formula has changed update the cell
-                let updated_formula = to_string(node, &cell_reference);
-                if formula != updated_formula {
-                    self.update_cell_with_formula(
-                        sheet,
-                        row,
-                        column,
-                        format!("={updated_formula}"),
-                    )?;
-                    // Update the diff list
-                    diff_list.push(SetCellValue {
-                        cell: CellReferenceIndex { sheet, column, row },
-                        new_value: CellValue::Value(format!("={}", updated_formula)),
-                        old_value: CellValue::Value(format!("={}", formula)),
-                    });
+                    // If cell is in the source or target area, skip
+                    if ref_is_in_area(sheet, row, column, source_area)
+                        || ref_is_in_area(sheet, row, column, target_area)
+                    {
+                        continue;
+                    }
+
+                    // Get the formula
+                    // Get a copy of the AST
+                    let node = &mut model.parsed_formulas[sheet as usize][f as usize].clone();
+                    let cell_reference = CellReferenceRC {
+                        sheet: model.workbook.worksheets[sheet as usize].get_name(),
+                        column: cell.column,
+                        row: cell.row,
+                    };
+                    let context = CellReferenceIndex { sheet, column, row };
+                    let formula = to_string(node, &cell_reference);
+                    let target_sheet_name = &model.workbook.worksheets[target.sheet as usize].name;
+                    forward_references(
+                        node,
+                        &context,
+                        source_area,
+                        target.sheet,
+                        target_sheet_name,
+                        target.row,
+                        target.column,
+                    );
+
+                    // If the string representation of the formula has changed update the cell
+                    let updated_formula = to_string(node, &cell_reference);
+                    if formula != updated_formula {
+                        model.update_cell_with_formula(
+                            sheet,
+                            row,
+                            column,
+                            format!("={updated_formula}"),
+                        )?;
+                        // Update the diff list
+                        diff_list.push(SetCellValue {
+                            cell: CellReferenceIndex { sheet, column, row },
+                            new_value: CellValue::Value(format!("={}", updated_formula)),
+                            old_value: CellValue::Value(format!("={}", formula)),
+                        });
+                    }
                 }
             }
-        }
-        Ok(diff_list)
+            Ok(diff_list)
+        })
     }
 }
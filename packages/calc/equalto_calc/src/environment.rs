@@ -0,0 +1,107 @@
+//! The environment a model is evaluated in: the current time and a source of random numbers,
+//! read by the volatile functions (NOW, TODAY, RAND and RANDBETWEEN).
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use crate::model::Model;
+
+/// Provides the current time and random numbers to a model
+pub trait Environment: Send + Sync {
+    /// Milliseconds since January 1, 1970 00:00:00 UTC
+    fn milliseconds_since_epoch(&self) -> i64;
+
+    /// A random number uniformly distributed in [0, 1)
+    fn random(&self) -> f64;
+}
+
+/// The system clock and random number generator
+pub struct SystemEnvironment;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Environment for SystemEnvironment {
+    fn milliseconds_since_epoch(&self) -> i64 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("problem with system time")
+            .as_millis() as i64
+    }
+
+    fn random(&self) -> f64 {
+        rand::random()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Environment for SystemEnvironment {
+    fn milliseconds_since_epoch(&self) -> i64 {
+        js_sys::Date::now() as i64
+    }
+
+    fn random(&self) -> f64 {
+        js_sys::Math::random()
+    }
+}
+
+/// A clock stopped at a given time and a seeded random number generator. Two environments
+/// created with the same seed produce the same sequence of random numbers.
+pub struct FixedEnvironment {
+    milliseconds: i64,
+    state: AtomicU64,
+}
+
+impl FixedEnvironment {
+    pub fn new(milliseconds: i64, seed: u64) -> FixedEnvironment {
+        FixedEnvironment {
+            milliseconds,
+            state: AtomicU64::new(seed),
+        }
+    }
+}
+
+impl Environment for FixedEnvironment {
+    fn milliseconds_since_epoch(&self) -> i64 {
+        self.milliseconds
+    }
+
+    // SplitMix64, see https://prng.di.unimi.it/splitmix64.c
+    fn random(&self) -> f64 {
+        const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut z = self
+            .state
+            .fetch_add(GAMMA, Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // The 53 most significant bits, as many as fit in the mantissa of a f64
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Model {
+    /// Replaces the environment the model is evaluated in. Volatile functions pick up the new
+    /// environment the next time the model is evaluated.
+    pub fn set_environment(&mut self, environment: impl Environment + 'static) {
+        self.environment = Arc::new(environment);
+    }
+
+    pub fn environment(&self) -> &dyn Environment {
+        self.environment.as_ref()
+    }
+}
+
+/// The environment of new models. Unit tests use a fixed environment:
+/// 8 November 2022 12:13 Berlin time.
+#[cfg(test)]
+pub(crate) fn default_environment() -> FixedEnvironment {
+    FixedEnvironment::new(1667906008578, 0)
+}
+
+#[cfg(not(test))]
+pub(crate) fn default_environment() -> SystemEnvironment {
+    SystemEnvironment
+}
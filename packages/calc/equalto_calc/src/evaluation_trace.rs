@@ -0,0 +1,186 @@
+//! Step by step evaluation of a formula, like the "Evaluate Formula" dialog of Excel.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    expressions::{
+        parser::{stringify::to_string, Node},
+        types::{Area, CellReferenceIndex, CellReferenceRC},
+    },
+    model::Model,
+};
+
+/// The value of a step of the evaluation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StepValue {
+    Number { value: f64 },
+    String { value: String },
+    Boolean { value: bool },
+    Error { error: String, message: String },
+    Range(Area),
+    Array { values: Vec<Vec<StepValue>> },
+    Empty,
+}
+
+impl From<&CalcResult> for StepValue {
+    fn from(result: &CalcResult) -> Self {
+        match result {
+            CalcResult::Number(value) => StepValue::Number { value: *value },
+            CalcResult::String(value) => StepValue::String {
+                value: value.clone(),
+            },
+            CalcResult::Boolean(value) => StepValue::Boolean { value: *value },
+            CalcResult::Error { error, message, .. } => StepValue::Error {
+                error: error.to_string(),
+                message: message.clone(),
+            },
+            CalcResult::Range { left, right } => StepValue::Range(Area {
+                sheet: left.sheet,
+                row: left.row,
+                column: left.column,
+                width: right.column - left.column + 1,
+                height: right.row - left.row + 1,
+            }),
+            CalcResult::Array(rows) => StepValue::Array {
+                values: rows
+                    .iter()
+                    .map(|row| row.iter().map(StepValue::from).collect())
+                    .collect(),
+            },
+            CalcResult::EmptyCell | CalcResult::EmptyArg => StepValue::Empty,
+        }
+    }
+}
+
+/// A node of the formula and its result
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EvaluationStep {
+    /// The node, as written in a formula
+    pub expression: String,
+    pub value: StepValue,
+    /// The argument (starting at 0) whose value was returned by IF, IFS, SWITCH, IFERROR, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<usize>,
+    /// The cell that matched in a VLOOKUP, HLOOKUP, LOOKUP, MATCH or XLOOKUP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookup_match: Option<CellReferenceIndex>,
+    /// The steps evaluated to compute this one, in evaluation order
+    pub children: Vec<EvaluationStep>,
+}
+
+/// Records the evaluation of a cell
+#[derive(Clone)]
+pub(crate) struct EvaluationTracer {
+    cell: CellReference,
+    /// The steps being evaluated, the innermost last
+    stack: Vec<EvaluationStep>,
+    /// The last top level step
+    root: Option<EvaluationStep>,
+}
+
+impl Model {
+    /// Evaluates the formula in the cell again and returns every step of the evaluation.
+    ///
+    /// The model should be evaluated first, referenced cells are not evaluated again. The
+    /// evaluation happens on a copy of the model, which is not modified.
+    pub fn get_evaluation_trace(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+    ) -> Result<EvaluationStep, String> {
+        let has_formula = self
+            .workbook
+            .worksheet(sheet)?
+            .cell(row, column)
+            .is_some_and(|cell| cell.has_formula());
+        if !has_formula {
+            return Err("Cell does not contain a formula".to_string());
+        }
+        let mut model = self.clone();
+        let cell = CellReference { sheet, row, column };
+        model.cells.remove(&(sheet, row, column));
+        model.evaluation_trace = Some(EvaluationTracer {
+            cell,
+            stack: Vec::new(),
+            root: None,
+        });
+        model.evaluate_cell(cell);
+        model
+            .evaluation_trace
+            .take()
+            .and_then(|tracer| tracer.root)
+            .ok_or_else(|| "Formula could not be evaluated".to_string())
+    }
+
+    /// Runs `evaluate` and records the step if the node is evaluated in the context of the
+    /// traced cell
+    pub(crate) fn trace_step(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+        evaluate: impl FnOnce(&mut Model) -> CalcResult,
+    ) -> CalcResult {
+        let is_traced = self
+            .evaluation_trace
+            .as_ref()
+            .is_some_and(|tracer| tracer.cell == cell);
+        if !is_traced {
+            return evaluate(self);
+        }
+        let context = CellReferenceRC {
+            sheet: self.workbook.worksheets[cell.sheet as usize].get_name(),
+            row: cell.row,
+            column: cell.column,
+        };
+        let step = EvaluationStep {
+            expression: to_string(node, &context),
+            value: StepValue::Empty,
+            branch: None,
+            lookup_match: None,
+            children: Vec::new(),
+        };
+        if let Some(tracer) = &mut self.evaluation_trace {
+            tracer.stack.push(step);
+        }
+        let result = evaluate(self);
+        if let Some(tracer) = &mut self.evaluation_trace {
+            if let Some(mut step) = tracer.stack.pop() {
+                step.value = StepValue::from(&result);
+                match tracer.stack.last_mut() {
+                    Some(parent) => parent.children.push(step),
+                    None => tracer.root = Some(step),
+                }
+            }
+        }
+        result
+    }
+
+    /// The step being evaluated in the context of `cell`, if it is traced
+    fn current_step(&mut self, cell: CellReference) -> Option<&mut EvaluationStep> {
+        self.evaluation_trace
+            .as_mut()
+            .filter(|tracer| tracer.cell == cell)
+            .and_then(|tracer| tracer.stack.last_mut())
+    }
+
+    /// Records that the function being evaluated returns the value of its argument `argument`
+    pub(crate) fn trace_branch(&mut self, cell: CellReference, argument: usize) {
+        if let Some(step) = self.current_step(cell) {
+            step.branch = Some(argument);
+        }
+    }
+
+    /// Records the cell that matched in the lookup function being evaluated
+    pub(crate) fn trace_lookup_match(&mut self, cell: CellReference, reference: CellReference) {
+        if let Some(step) = self.current_step(cell) {
+            step.lookup_match = Some(CellReferenceIndex {
+                sheet: reference.sheet,
+                row: reference.row,
+                column: reference.column,
+            });
+        }
+    }
+}
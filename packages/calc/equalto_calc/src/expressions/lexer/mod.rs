@@ -229,7 +229,18 @@ impl Lexer {
                             TokenType::Compare(OpCompare::GreaterThan)
                         }
                     }
-                    '#' => self.consume_error(),
+                    '#' => {
+                        // Right after a reference (`A1#` or `R[0]C[1]#`) it is the spill range
+                        // operator
+                        let previous = self.position.checked_sub(2).map(|i| self.chars[i]);
+                        if previous.is_some_and(|c| {
+                            c.is_alphanumeric() || (c == ']' && self.mode == LexerMode::R1C1)
+                        }) {
+                            TokenType::Hash
+                        } else {
+                            self.consume_error()
+                        }
+                    }
                     '"' => TokenType::String(self.consume_string()),
                     '\'' => self.consume_quoted_sheet_reference(),
                     '0'..='9' => {
@@ -311,6 +322,10 @@ impl Lexer {
                             }
                             if self.mode == LexerMode::A1 {
                                 let parsed_reference = utils::parse_reference_a1(&name_upper);
+                                if parsed_reference.is_some() && peek_char == Some('(') {
+                                    // A function whose name looks like a reference: LOG10(100)
+                                    return TokenType::Ident(name);
+                                }
                                 if parsed_reference.is_some()
                                     || (utils::is_valid_column(name_upper.trim_start_matches('$'))
                                         && next_char_is_colon)
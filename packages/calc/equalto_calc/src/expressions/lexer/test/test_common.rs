@@ -268,6 +268,25 @@ fn test_error_value() {
     assert_eq!(lx.next_token(), EOF);
 }
 
+#[test]
+fn test_spill_reference() {
+    let mut lx = new_lexer("A1#+#N/A", true);
+    assert_eq!(
+        lx.next_token(),
+        Reference {
+            sheet: None,
+            column: 1,
+            row: 1,
+            absolute_column: false,
+            absolute_row: false,
+        }
+    );
+    assert_eq!(lx.next_token(), Hash);
+    assert_eq!(lx.next_token(), Addition(OpSum::Add));
+    assert_eq!(lx.next_token(), Error(Error::NA));
+    assert_eq!(lx.next_token(), EOF);
+}
+
 #[test]
 fn test_error_error() {
     let mut lx = new_lexer("#ERROR!", true);
@@ -377,6 +396,16 @@ fn test_not_a_reference() {
     assert_eq!(lx.next_token(), EOF);
 }
 
+#[test]
+fn test_function_name_like_reference() {
+    let mut lx = new_lexer("LOG10(A1)", true);
+    assert_eq!(lx.next_token(), Ident("LOG10".to_string()));
+    assert_eq!(lx.next_token(), LeftParenthesis);
+    assert!(matches!(lx.next_token(), Reference { .. }));
+    assert_eq!(lx.next_token(), RightParenthesis);
+    assert_eq!(lx.next_token(), EOF);
+}
+
 #[test]
 fn test_reference_r1c1() {
     let mut lx = new_lexer("R1C1", false);
@@ -12,10 +12,13 @@ term    => factor (opFactor factor)*
 factor  => prod (opProd prod)*
 prod    => power ('^' power)*
 power   => (unaryOp)* range '%'*
-range   => primary (':' primary)?
+range   => spill (':' spill)?
+spill   => primary '#'?
 primary => '(' expr ')'
         => number
         => function '(' f_args ')'
+        => 'LAMBDA' '(' f_args ')' ('(' f_args ')')?
+        => local '(' f_args ')'
         => name
         => string
         => '{' a_args '}'
@@ -24,10 +27,12 @@ primary => '(' expr ')'
         => error
 
 f_args  => e (',' e)*
+a_args  => a_row (';' a_row)*
+a_row   => e (',' e)*
 </pre>
 */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::functions::Function;
 use crate::language::get_language;
@@ -87,6 +92,15 @@ fn get_table_column_by_name(table_column_name: &str, table: &Table) -> Option<i3
     None
 }
 
+/// Names of LET and LAMBDA parameters are prefixed with `_xlpm.` in xlsx files
+fn strip_local_prefix(name: &str) -> &str {
+    if name.len() > 6 && name[..6].eq_ignore_ascii_case("_xlpm.") {
+        &name[6..]
+    } else {
+        name
+    }
+}
+
 pub(crate) struct Reference<'a> {
     sheet_name: &'a Option<String>,
     sheet_index: u32,
@@ -169,8 +183,23 @@ pub enum Node {
         name: String,
         args: Vec<Node>,
     },
-    ArrayKind(Vec<Node>),
+    /// A call to a function registered with `Model::register_function`. The name is upper case
+    UserFunctionKind {
+        name: String,
+        args: Vec<Node>,
+    },
+    /// An array literal like `{1,2;3,4}`, a list of rows
+    ArrayKind(Vec<Vec<Node>>),
+    /// The spill range of the reference, `A1#`
+    SpillRefKind(Box<Node>),
     VariableKind(String),
+    /// A name defined by LET or a LAMBDA parameter. Stored without the `_xlpm.` prefix
+    LocalVariableKind(String),
+    /// A call to a LAMBDA, either inline `LAMBDA(x, x+1)(2)` or bound with LET `f(2)`
+    LambdaCallKind {
+        lambda: Box<Node>,
+        args: Vec<Node>,
+    },
     CompareKind {
         kind: OpCompare,
         left: Box<Node>,
@@ -195,6 +224,10 @@ pub struct Parser {
     worksheets: Vec<String>,
     context: Option<CellReferenceRC>,
     tables: HashMap<String, Table>,
+    // Names defined by LET and LAMBDA parameters in scope (lower case)
+    locals: Vec<String>,
+    // Names of the user defined functions (upper case)
+    user_functions: HashSet<String>,
 }
 
 impl Parser {
@@ -210,6 +243,8 @@ impl Parser {
             worksheets,
             context: None,
             tables,
+            locals: Vec::new(),
+            user_functions: HashSet::new(),
         }
     }
     pub fn set_lexer_mode(&mut self, mode: lexer::LexerMode) {
@@ -220,9 +255,15 @@ impl Parser {
         self.worksheets = worksheets;
     }
 
+    /// Sets the names of the user defined functions, in upper case
+    pub fn set_user_functions(&mut self, user_functions: HashSet<String>) {
+        self.user_functions = user_functions;
+    }
+
     pub fn parse(&mut self, formula: &str, context: &Option<CellReferenceRC>) -> Node {
         self.lexer.set_formula(formula);
         self.context = context.clone();
+        self.locals.clear();
         self.parse_expr()
     }
 
@@ -347,11 +388,11 @@ impl Parser {
 
     fn parse_power(&mut self) -> Node {
         let mut next_token = self.lexer.peek_token();
-        let mut sign = 1;
+        let mut minus_count = 0;
         while let TokenType::Addition(op) = next_token {
             self.lexer.advance_token();
             if op == token::OpSum::Minus {
-                sign = -sign;
+                minus_count += 1;
             }
             next_token = self.lexer.peek_token();
         }
@@ -360,7 +401,13 @@ impl Parser {
         if let Node::ParseErrorKind { .. } = t {
             return t;
         }
-        if sign == -1 {
+        // Two minus signs don't cancel out, `--TRUE` casts the value to a number
+        let minus_signs = match minus_count {
+            0 => 0,
+            count if count % 2 == 1 => 1,
+            _ => 2,
+        };
+        for _ in 0..minus_signs {
             t = Node::UnaryKind {
                 kind: token::OpUnary::Minus,
                 right: Box::new(t),
@@ -379,14 +426,14 @@ impl Parser {
     }
 
     fn parse_range(&mut self) -> Node {
-        let t = self.parse_primary();
+        let t = self.parse_spill();
         if let Node::ParseErrorKind { .. } = t {
             return t;
         }
         let next_token = self.lexer.peek_token();
         if next_token == TokenType::Colon {
             self.lexer.advance_token();
-            let p = self.parse_primary();
+            let p = self.parse_spill();
             if let Node::ParseErrorKind { .. } = p {
                 return p;
             }
@@ -398,6 +445,28 @@ impl Parser {
         t
     }
 
+    fn parse_spill(&mut self) -> Node {
+        let t = self.parse_primary();
+        if let Node::ParseErrorKind { .. } = t {
+            return t;
+        }
+        if self.lexer.peek_token() == TokenType::Hash {
+            self.lexer.advance_token();
+            if !matches!(
+                t,
+                Node::ReferenceKind { .. } | Node::WrongReferenceKind { .. }
+            ) {
+                return Node::ParseErrorKind {
+                    formula: self.lexer.get_formula(),
+                    position: self.lexer.get_position() as usize,
+                    message: "Expected a reference before '#'".to_string(),
+                };
+            }
+            return Node::SpillRefKind(Box::new(t));
+        }
+        t
+    }
+
     fn parse_primary(&mut self) -> Node {
         let next_token = self.lexer.next_token();
         match next_token {
@@ -419,21 +488,32 @@ impl Parser {
             TokenType::Number(s) => Node::NumberKind(s),
             TokenType::String(s) => Node::StringKind(s),
             TokenType::LeftBrace => {
-                let t = self.parse_expr();
-                if let Node::ParseErrorKind { .. } = t {
-                    return t;
-                }
-                let mut next_token = self.lexer.peek_token();
-                let mut args: Vec<Node> = vec![t];
-                while next_token == TokenType::Semicolon {
-                    self.lexer.advance_token();
-                    let p = self.parse_expr();
-                    if let Node::ParseErrorKind { .. } = p {
-                        return p;
+                let mut rows: Vec<Vec<Node>> = Vec::new();
+                let mut row: Vec<Node> = Vec::new();
+                loop {
+                    let t = self.parse_expr();
+                    if let Node::ParseErrorKind { .. } = t {
+                        return t;
+                    }
+                    if let Node::ArrayKind(_) = t {
+                        return Node::ParseErrorKind {
+                            formula: self.lexer.get_formula(),
+                            position: self.lexer.get_position() as usize,
+                            message: "Arrays cannot be nested".to_string(),
+                        };
+                    }
+                    row.push(t);
+                    match self.lexer.peek_token() {
+                        TokenType::Comma => {}
+                        TokenType::Semicolon => {
+                            rows.push(row);
+                            row = Vec::new();
+                        }
+                        _ => break,
                     }
-                    next_token = self.lexer.peek_token();
-                    args.push(p);
+                    self.lexer.advance_token();
                 }
+                rows.push(row);
                 if let Err(err) = self.lexer.expect(TokenType::RightBrace) {
                     return Node::ParseErrorKind {
                         formula: self.lexer.get_formula(),
@@ -441,7 +521,14 @@ impl Parser {
                         message: err.message,
                     };
                 }
-                Node::ArrayKind(args)
+                if rows.iter().any(|r| r.len() != rows[0].len()) {
+                    return Node::ParseErrorKind {
+                        formula: self.lexer.get_formula(),
+                        position: self.lexer.get_position() as usize,
+                        message: "All the rows of an array must have the same length".to_string(),
+                    };
+                }
+                Node::ArrayKind(rows)
             }
             TokenType::Reference {
                 sheet,
@@ -568,10 +655,18 @@ impl Parser {
             }
             TokenType::Ident(name) => {
                 let next_token = self.lexer.peek_token();
+                let local_name = self.get_local_name(&name);
                 if next_token == TokenType::LeftParenthesis {
                     // It's a function call "SUM(.."
                     self.lexer.advance_token();
-                    let args = match self.parse_function_args() {
+                    let kind = Function::get_function(&name);
+                    let args = match kind {
+                        Some(Function::Let) | Some(Function::Lambda) if local_name.is_none() => {
+                            self.parse_scoped_args(kind == Some(Function::Let))
+                        }
+                        _ => self.parse_function_args(),
+                    };
+                    let args = match args {
                         Ok(s) => s,
                         Err(e) => return e,
                     };
@@ -582,15 +677,64 @@ impl Parser {
                             message: err.message,
                         };
                     }
-                    if let Some(function_kind) = Function::get_function(&name) {
-                        return Node::FunctionKind {
+                    if let Some(local_name) = local_name {
+                        return Node::LambdaCallKind {
+                            lambda: Box::new(Node::LocalVariableKind(local_name)),
+                            args,
+                        };
+                    }
+                    // xlsx files save `A1#` as `_xlfn.ANCHORARRAY(A1)`
+                    if name.eq_ignore_ascii_case("_xlfn.ANCHORARRAY") {
+                        if let [reference @ (Node::ReferenceKind { .. }
+                        | Node::WrongReferenceKind { .. })] = args.as_slice()
+                        {
+                            return Node::SpillRefKind(Box::new(reference.clone()));
+                        }
+                    }
+                    if let Some(function_kind) = kind {
+                        let function = Node::FunctionKind {
                             kind: function_kind,
                             args,
                         };
+                        if matches!(
+                            function,
+                            Node::FunctionKind {
+                                kind: Function::Lambda,
+                                ..
+                            }
+                        ) && self.lexer.peek_token() == TokenType::LeftParenthesis
+                        {
+                            // Inline call "LAMBDA(x, x+1)(2)"
+                            self.lexer.advance_token();
+                            let args = match self.parse_function_args() {
+                                Ok(s) => s,
+                                Err(e) => return e,
+                            };
+                            if let Err(err) = self.lexer.expect(TokenType::RightParenthesis) {
+                                return Node::ParseErrorKind {
+                                    formula: self.lexer.get_formula(),
+                                    position: err.position,
+                                    message: err.message,
+                                };
+                            }
+                            return Node::LambdaCallKind {
+                                lambda: Box::new(function),
+                                args,
+                            };
+                        }
+                        return function;
+                    } else if self.user_functions.contains(&name.to_uppercase()) {
+                        return Node::UserFunctionKind {
+                            name: name.to_uppercase(),
+                            args,
+                        };
                     } else {
                         return Node::InvalidFunctionKind { name, args };
                     }
                 }
+                if let Some(local_name) = local_name {
+                    return Node::LocalVariableKind(local_name);
+                }
                 Node::VariableKind(name)
             }
             TokenType::Error(kind) => Node::ErrorKind(kind),
@@ -645,6 +789,7 @@ impl Parser {
             | TokenType::Comma
             | TokenType::Bang
             | TokenType::And
+            | TokenType::Hash
             | TokenType::Percent => Node::ParseErrorKind {
                 formula: self.lexer.get_formula(),
                 position: 0,
@@ -839,6 +984,71 @@ impl Parser {
         }
     }
 
+    /// Returns the name without the `_xlpm.` prefix if it is defined by an enclosing LET or LAMBDA
+    fn get_local_name(&self, name: &str) -> Option<String> {
+        let name = strip_local_prefix(name);
+        let lower_case = name.to_lowercase();
+        if self.locals.contains(&lower_case) {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Parses the arguments of LET (`is_let`) or LAMBDA. The names they define are in scope
+    /// for the arguments that follow:
+    ///  * LET(name1, value1, name2, value2, ..., calculation)
+    ///  * LAMBDA(parameter1, parameter2, ..., calculation)
+    fn parse_scoped_args(&mut self, is_let: bool) -> Result<Vec<Node>, Node> {
+        let depth = self.locals.len();
+        let mut args: Vec<Node> = Vec::new();
+        if self.lexer.peek_token() == TokenType::RightParenthesis {
+            return Ok(args);
+        }
+        loop {
+            let position = self.lexer.get_position() as usize;
+            let arg = self.parse_expr();
+            if let Node::ParseErrorKind { .. } = arg {
+                self.locals.truncate(depth);
+                return Err(arg);
+            }
+            if self.lexer.peek_token() != TokenType::Comma {
+                // The calculation
+                args.push(arg);
+                break;
+            }
+            self.lexer.advance_token();
+            let is_name = !is_let || args.len().is_multiple_of(2);
+            if !is_name {
+                // The value of the last name
+                if let Some(Node::LocalVariableKind(name)) = args.last() {
+                    self.locals.push(name.to_lowercase());
+                }
+                args.push(arg);
+                continue;
+            }
+            let name = match arg {
+                Node::VariableKind(name) | Node::LocalVariableKind(name) => {
+                    strip_local_prefix(&name).to_string()
+                }
+                _ => {
+                    self.locals.truncate(depth);
+                    return Err(Node::ParseErrorKind {
+                        formula: self.lexer.get_formula(),
+                        position,
+                        message: "Expected a name".to_string(),
+                    });
+                }
+            };
+            if !is_let {
+                self.locals.push(name.to_lowercase());
+            }
+            args.push(Node::LocalVariableKind(name));
+        }
+        self.locals.truncate(depth);
+        Ok(args)
+    }
+
     fn parse_function_args(&mut self) -> Result<Vec<Node>, Node> {
         let mut args: Vec<Node> = Vec::new();
         let mut next_token = self.lexer.peek_token();
@@ -356,26 +356,39 @@ fn to_string_moved(node: &Node, move_context: &MoveContext) -> String {
             to_string_moved(left, move_context),
             to_string_moved(right, move_context),
         ),
-        InvalidFunctionKind { name, args } => move_function(name, args, move_context),
+        InvalidFunctionKind { name, args } | UserFunctionKind { name, args } => {
+            move_function(name, args, move_context)
+        }
         FunctionKind { kind, args } => {
             let name = &kind.to_string();
             move_function(name, args, move_context)
         }
-        ArrayKind(args) => {
-            // This code is a placeholder. Arrays are not yet implemented
-            let mut first = true;
-            let mut arguments = "".to_string();
-            for el in args {
-                if !first {
-                    arguments = format!("{},{}", arguments, to_string_moved(el, move_context));
-                } else {
-                    first = false;
-                    arguments = to_string_moved(el, move_context);
-                }
+        ArrayKind(rows) => {
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| to_string_moved(el, move_context))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                })
+                .collect();
+            format!("{{{}}}", rows.join(";"))
+        }
+        SpillRefKind(reference) => {
+            let reference = to_string_moved(reference, move_context);
+            if reference == "#REF!" {
+                reference
+            } else {
+                format!("{}#", reference)
             }
-            format!("{{{}}}", arguments)
         }
         VariableKind(value) => value.to_string(),
+        LocalVariableKind(name) => name.to_string(),
+        LambdaCallKind { lambda, args } => {
+            let lambda = to_string_moved(lambda, move_context);
+            move_function(&lambda, args, move_context)
+        }
         CompareKind { kind, left, right } => format!(
             "{}{}{}",
             to_string_moved(left, move_context),
@@ -31,6 +31,9 @@ pub enum DisplaceData {
         column: i32,
         delta: i32,
     },
+    DeletedSheet {
+        sheet: u32,
+    },
     None,
 }
 
@@ -173,6 +176,11 @@ pub(crate) fn stringify_reference(
                         }
                     }
                 }
+                DisplaceData::DeletedSheet { sheet } => {
+                    if sheet_index == *sheet {
+                        return "#REF!".to_string();
+                    }
+                }
                 DisplaceData::None => {}
             }
             if row < 1 {
@@ -318,6 +326,10 @@ fn stringify(
             row2,
             column2,
         } => {
+            if matches!(displace_data, DisplaceData::DeletedSheet { sheet } if sheet == sheet_index)
+            {
+                return "#REF!".to_string();
+            }
             // Note that open ranges SUM(A:A) or SUM(1:1) will be treated as normal ranges in the R1C1 (internal) representation
             // A:A will be R1C[0]:R1048576C[0]
             // So when we are forming the A1 range we need to strip the irrelevant information
@@ -461,7 +473,7 @@ fn stringify(
             stringify(left, context, displace_data, use_original_name),
             stringify(right, context, displace_data, use_original_name)
         ),
-        InvalidFunctionKind { name, args } => {
+        InvalidFunctionKind { name, args } | UserFunctionKind { name, args } => {
             format_function(name, args, context, displace_data, use_original_name)
         }
         FunctionKind { kind, args } => {
@@ -472,31 +484,55 @@ fn stringify(
             };
             format_function(&name, args, context, displace_data, use_original_name)
         }
-        ArrayKind(args) => {
-            let mut first = true;
-            let mut arguments = "".to_string();
-            for el in args {
-                if !first {
-                    arguments = format!(
-                        "{},{}",
-                        arguments,
-                        stringify(el, context, displace_data, use_original_name)
-                    );
-                } else {
-                    first = false;
-                    arguments = stringify(el, context, displace_data, use_original_name);
-                }
+        ArrayKind(rows) => {
+            let rows: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| stringify(el, context, displace_data, use_original_name))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                })
+                .collect();
+            format!("{{{}}}", rows.join(";"))
+        }
+        SpillRefKind(reference) => {
+            let reference = stringify(reference, context, displace_data, use_original_name);
+            if reference == "#REF!" {
+                reference
+            } else if use_original_name {
+                format!("_xlfn.ANCHORARRAY({})", reference)
+            } else {
+                format!("{}#", reference)
             }
-            format!("{{{}}}", arguments)
         }
         VariableKind(value) => value.to_string(),
+        LocalVariableKind(name) => {
+            if use_original_name {
+                format!("_xlpm.{}", name)
+            } else {
+                name.to_string()
+            }
+        }
+        LambdaCallKind { lambda, args } => {
+            let lambda = stringify(lambda, context, displace_data, use_original_name);
+            format_function(&lambda, args, context, displace_data, use_original_name)
+        }
         UnaryKind { kind, right } => match kind {
-            OpUnary::Minus => {
-                format!(
+            OpUnary::Minus => match **right {
+                OpSumKind { .. }
+                | OpProductKind { .. }
+                | OpPowerKind { .. }
+                | OpConcatenateKind { .. }
+                | CompareKind { .. } => format!(
+                    "-({})",
+                    stringify(right, context, displace_data, use_original_name)
+                ),
+                _ => format!(
                     "-{}",
                     stringify(right, context, displace_data, use_original_name)
-                )
-            }
+                ),
+            },
             OpUnary::Percentage => {
                 format!(
                     "{}%",
@@ -582,7 +618,7 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
                 rename_sheet_in_node(arg, sheet_index, new_name);
             }
         }
-        Node::InvalidFunctionKind { name: _, args } => {
+        Node::InvalidFunctionKind { name: _, args } | Node::UserFunctionKind { name: _, args } => {
             for arg in args {
                 rename_sheet_in_node(arg, sheet_index, new_name);
             }
@@ -598,6 +634,15 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
         Node::UnaryKind { kind: _, right } => {
             rename_sheet_in_node(right, sheet_index, new_name);
         }
+        Node::SpillRefKind(reference) => {
+            rename_sheet_in_node(reference, sheet_index, new_name);
+        }
+        Node::LambdaCallKind { lambda, args } => {
+            rename_sheet_in_node(lambda, sheet_index, new_name);
+            for arg in args {
+                rename_sheet_in_node(arg, sheet_index, new_name);
+            }
+        }
 
         // Do nothing
         Node::BooleanKind(_) => {}
@@ -607,6 +652,78 @@ pub(crate) fn rename_sheet_in_node(node: &mut Node, sheet_index: u32, new_name:
         Node::ParseErrorKind { .. } => {}
         Node::ArrayKind(_) => {}
         Node::VariableKind(_) => {}
+        Node::LocalVariableKind(_) => {}
         Node::EmptyArgKind => {}
     }
 }
+
+/// Replaces the uses of the defined name `name` by `new_name`, both as a value and as a named
+/// LAMBDA call. Returns true if the node was changed.
+pub(crate) fn rename_defined_name_in_node(node: &mut Node, name: &str, new_name: &str) -> bool {
+    match node {
+        // Rename
+        Node::VariableKind(variable) => {
+            if variable.to_lowercase() == name.to_lowercase() {
+                *variable = new_name.to_string();
+                return true;
+            }
+            false
+        }
+        Node::InvalidFunctionKind {
+            name: function_name,
+            args,
+        } => {
+            let mut changed = false;
+            if function_name.to_lowercase() == name.to_lowercase() {
+                *function_name = new_name.to_string();
+                changed = true;
+            }
+            for arg in args {
+                changed |= rename_defined_name_in_node(arg, name, new_name);
+            }
+            changed
+        }
+
+        // Go next level
+        Node::OpRangeKind { left, right }
+        | Node::OpConcatenateKind { left, right }
+        | Node::OpSumKind { left, right, .. }
+        | Node::OpProductKind { left, right, .. }
+        | Node::OpPowerKind { left, right }
+        | Node::CompareKind { left, right, .. } => {
+            let left_changed = rename_defined_name_in_node(left, name, new_name);
+            let right_changed = rename_defined_name_in_node(right, name, new_name);
+            left_changed || right_changed
+        }
+        Node::UnaryKind { right, .. } => rename_defined_name_in_node(right, name, new_name),
+        Node::SpillRefKind(reference) => rename_defined_name_in_node(reference, name, new_name),
+        Node::FunctionKind { args, .. } | Node::UserFunctionKind { args, .. } => {
+            let mut changed = false;
+            for arg in args {
+                changed |= rename_defined_name_in_node(arg, name, new_name);
+            }
+            changed
+        }
+        Node::LambdaCallKind { lambda, args } => {
+            let mut changed = rename_defined_name_in_node(lambda, name, new_name);
+            for arg in args {
+                changed |= rename_defined_name_in_node(arg, name, new_name);
+            }
+            changed
+        }
+
+        // Do nothing
+        Node::BooleanKind(_)
+        | Node::NumberKind(_)
+        | Node::StringKind(_)
+        | Node::ReferenceKind { .. }
+        | Node::RangeKind { .. }
+        | Node::WrongReferenceKind { .. }
+        | Node::WrongRangeKind { .. }
+        | Node::ErrorKind(_)
+        | Node::ParseErrorKind { .. }
+        | Node::ArrayKind(_)
+        | Node::LocalVariableKind(_)
+        | Node::EmptyArgKind => false,
+    }
+}
@@ -7,7 +7,7 @@ use super::super::types::CellReferenceRC;
 use super::Parser;
 use super::{
     super::parser::{
-        stringify::{to_rc_format, to_string},
+        stringify::{to_excel_string, to_rc_format, to_string},
         Node,
     },
     stringify::to_string_displaced,
@@ -399,8 +399,44 @@ fn test_parser_excel_xlfn() {
         column: 1,
     };
 
-    let t = parser.parse("_xlfn.CONCAT(C3)", &Some(cell_reference));
+    let t = parser.parse("_xlfn.CONCAT(C3)", &Some(cell_reference.clone()));
     assert_eq!(to_rc_format(&t), "CONCAT(R[2]C[2])");
+
+    // FILTER and SORT have a second prefix
+    let t = parser.parse(
+        "_xlfn._xlws.SORT(_xlfn.UNIQUE(C3:C9))",
+        &Some(cell_reference.clone()),
+    );
+    assert_eq!(to_string(&t, &cell_reference), "SORT(UNIQUE(C3:C9))");
+    assert_eq!(
+        to_excel_string(&t, &cell_reference),
+        "_xlfn._xlws.SORT(_xlfn.UNIQUE(C3:C9))"
+    );
+}
+
+#[test]
+fn test_parser_unary_minus() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = Parser::new(worksheets, HashMap::new());
+
+    // Reference cell is Sheet1!A1
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+
+    // a double negation is kept, it casts the value to a number
+    let t = parser.parse("--(C3>1)", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "--(C3>1)");
+    let t = parser.parse("---C3", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "-C3");
+    let t = parser.parse("+-+-C3", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "--C3");
+    let t = parser.parse("-(1+2)*3", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "-(1+2)*3");
+    let t = parser.parse("-2^2", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "-2^2");
 }
 
 #[test]
@@ -495,3 +531,153 @@ fn test_to_string_displaced_too_high() {
     let t = to_string_displaced(&node, context, &displace_data);
     assert_eq!(t, "#REF!".to_string());
 }
+
+#[test]
+fn test_parser_arrays() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = Parser::new(worksheets, HashMap::new());
+
+    // Reference cell is Sheet1!A1
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    let t = parser.parse("{1,2;3,4}", &Some(cell_reference.clone()));
+    match &t {
+        Node::ArrayKind(rows) => {
+            assert_eq!(rows.len(), 2);
+            assert_eq!(rows[0].len(), 2);
+        }
+        _ => panic!("Expected an array"),
+    }
+    assert_eq!(to_string(&t, &cell_reference), "{1,2;3,4}");
+
+    let t = parser.parse("{1,2;3}", &Some(cell_reference.clone()));
+    match &t {
+        Node::ParseErrorKind { message, .. } => {
+            assert_eq!(
+                message,
+                "All the rows of an array must have the same length"
+            );
+        }
+        _ => panic!("Expected error in formula"),
+    }
+
+    let t = parser.parse("{1,{2}}", &Some(cell_reference));
+    match &t {
+        Node::ParseErrorKind { message, .. } => {
+            assert_eq!(message, "Arrays cannot be nested");
+        }
+        _ => panic!("Expected error in formula"),
+    }
+}
+
+#[test]
+fn test_parser_spill_reference() {
+    let worksheets = vec!["Sheet1".to_string(), "Sheet2".to_string()];
+    let mut parser = Parser::new(worksheets, HashMap::new());
+
+    // Reference cell is Sheet1!A1
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    let t = parser.parse("SUM(B1#)", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "SUM(B1#)");
+    assert_eq!(to_rc_format(&t), "SUM(R[0]C[1]#)");
+    assert_eq!(
+        to_excel_string(&t, &cell_reference),
+        "SUM(_xlfn.ANCHORARRAY(B1))"
+    );
+
+    parser.set_lexer_mode(LexerMode::R1C1);
+    let t = parser.parse("SUM(R[0]C[1]#)", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "SUM(B1#)");
+    parser.set_lexer_mode(LexerMode::A1);
+
+    let t = parser.parse("SUM(_xlfn.ANCHORARRAY(B1))", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "SUM(B1#)");
+
+    let t = parser.parse("Sheet2!$C$3#*2", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "Sheet2!$C$3#*2");
+
+    let t = parser.parse("5#", &Some(cell_reference));
+    match &t {
+        Node::ParseErrorKind { message, .. } => {
+            assert_eq!(message, "Expected a reference before '#'");
+        }
+        _ => panic!("Expected error in formula"),
+    }
+}
+
+#[test]
+fn test_parser_let_and_lambda_scope() {
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = Parser::new(worksheets, HashMap::new());
+    let cell_reference = CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+
+    // `x` is only a local name inside the LET and after its definition
+    let t = parser.parse("LET(x,x,y,x+1,y*2)+x", &Some(cell_reference.clone()));
+    let args = match &t {
+        Node::OpSumKind { left, right, .. } => {
+            assert!(matches!(**right, Node::VariableKind(_)));
+            match &**left {
+                Node::FunctionKind { args, .. } => args.clone(),
+                _ => panic!("Expected LET"),
+            }
+        }
+        _ => panic!("Expected a sum"),
+    };
+    assert!(matches!(args[0], Node::LocalVariableKind(_)));
+    assert!(matches!(args[1], Node::VariableKind(_)));
+    assert!(matches!(args[2], Node::LocalVariableKind(_)));
+    assert_eq!(to_string(&t, &cell_reference), "LET(x,x,y,x+1,y*2)+x");
+    assert_eq!(
+        to_excel_string(&t, &cell_reference),
+        "_xlfn.LET(_xlpm.x,x,_xlpm.y,_xlpm.x+1,_xlpm.y*2)+x"
+    );
+
+    let t = parser.parse("LET(f,LAMBDA(a,a*2),f(3))", &Some(cell_reference.clone()));
+    assert_eq!(to_string(&t, &cell_reference), "LET(f,LAMBDA(a,a*2),f(3))");
+
+    let t = parser.parse(
+        "_xlfn.LAMBDA(_xlpm.a,_xlpm.a)(1)",
+        &Some(cell_reference.clone()),
+    );
+    assert!(matches!(t, Node::LambdaCallKind { .. }));
+    assert_eq!(to_string(&t, &cell_reference), "LAMBDA(a,a)(1)");
+
+    let t = parser.parse("LET(1,2,3)", &Some(cell_reference));
+    match &t {
+        Node::ParseErrorKind { message, .. } => {
+            assert_eq!(message, "Expected a name");
+        }
+        _ => panic!("Expected error in formula"),
+    }
+}
+
+#[test]
+fn test_to_string_displaced_spill_reference() {
+    let context = &CellReferenceRC {
+        sheet: "Sheet1".to_string(),
+        row: 1,
+        column: 1,
+    };
+    let worksheets = vec!["Sheet1".to_string()];
+    let mut parser = Parser::new(worksheets, HashMap::new());
+
+    let node = parser.parse("C3#", &Some(context.clone()));
+    let displace_data = DisplaceData::Column {
+        sheet: 0,
+        column: 1,
+        delta: 2,
+    };
+    let t = to_string_displaced(&node, context, &displace_data);
+    assert_eq!(t, "E3#".to_string());
+}
@@ -212,7 +212,7 @@ pub(crate) fn forward_references(
                 );
             }
         }
-        Node::InvalidFunctionKind { name: _, args } => {
+        Node::InvalidFunctionKind { name: _, args } | Node::UserFunctionKind { name: _, args } => {
             for arg in args {
                 forward_references(
                     arg,
@@ -260,10 +260,44 @@ pub(crate) fn forward_references(
                 target_column,
             );
         }
-        // TODO: Not implemented
+        Node::SpillRefKind(reference) => {
+            forward_references(
+                reference,
+                context,
+                source_area,
+                target_sheet,
+                target_sheet_name,
+                target_row,
+                target_column,
+            );
+        }
+        Node::LambdaCallKind { lambda, args } => {
+            forward_references(
+                lambda,
+                context,
+                source_area,
+                target_sheet,
+                target_sheet_name,
+                target_row,
+                target_column,
+            );
+            for arg in args {
+                forward_references(
+                    arg,
+                    context,
+                    source_area,
+                    target_sheet,
+                    target_sheet_name,
+                    target_row,
+                    target_column,
+                );
+            }
+        }
+        // Array literals only hold constants
         Node::ArrayKind(_) => {}
         // Do nothing. Note: we could do a blanket _ => {}
         Node::VariableKind(_) => {}
+        Node::LocalVariableKind(_) => {}
         Node::ErrorKind(_) => {}
         Node::ParseErrorKind { .. } => {}
         Node::EmptyArgKind => {}
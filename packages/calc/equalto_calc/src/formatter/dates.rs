@@ -2,16 +2,48 @@ use chrono::Datelike;
 use chrono::Duration;
 use chrono::NaiveDate;
 
-use crate::constants::EXCEL_DATE_BASE;
+use crate::constants::{EXCEL_1904_DATE_BASE, EXCEL_DATE_BASE};
 
-pub fn from_excel_date(days: i64) -> NaiveDate {
+/// The serial number of a date is `date.num_days_from_ce()` minus the base of the date system
+pub(crate) fn date_base(date1904: bool) -> i32 {
+    if date1904 {
+        EXCEL_1904_DATE_BASE
+    } else {
+        EXCEL_DATE_BASE
+    }
+}
+
+/// The largest serial number of a date, 31 December 9999
+pub(crate) fn max_serial_number(date1904: bool) -> i32 {
+    2_958_465 + EXCEL_DATE_BASE - date_base(date1904)
+}
+
+/// The smallest serial number of a date: 1 January 1900 or 1 January 1904
+pub(crate) fn min_serial_number(date1904: bool) -> i32 {
+    if date1904 {
+        0
+    } else {
+        1
+    }
+}
+
+pub fn from_excel_date(days: i64, date1904: bool) -> NaiveDate {
+    if date1904 {
+        let dt = NaiveDate::from_ymd_opt(1904, 1, 1).expect("problem with chrono::NaiveDate");
+        return dt + Duration::days(days);
+    }
     let dt = NaiveDate::from_ymd_opt(1900, 1, 1).expect("problem with chrono::NaiveDate");
     dt + Duration::days(days - 2)
 }
 
-pub fn date_to_serial_number(day: u32, month: u32, year: i32) -> Result<i32, String> {
+pub fn date_to_serial_number(
+    day: u32,
+    month: u32,
+    year: i32,
+    date1904: bool,
+) -> Result<i32, String> {
     match NaiveDate::from_ymd_opt(year, month, day) {
-        Some(native_date) => Ok(native_date.num_days_from_ce() - EXCEL_DATE_BASE),
+        Some(native_date) => Ok(native_date.num_days_from_ce() - date_base(date1904)),
         None => Err("Out of range parameters for date".to_string()),
     }
 }
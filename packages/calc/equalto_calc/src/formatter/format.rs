@@ -3,7 +3,7 @@ use chrono::Datelike;
 use crate::{locale::Locale, number_format::to_precision};
 
 use super::{
-    dates::{date_to_serial_number, from_excel_date},
+    dates::{date_to_serial_number, from_excel_date, max_serial_number, min_serial_number},
     parser::{ParsePart, Parser, TextToken},
 };
 
@@ -50,7 +50,18 @@ fn use_group_separator(use_thousands: bool, digit_index: i32, group_sizes: &str)
     false
 }
 
-pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Formatted {
+/// Formats a number, dates in the 1900 date system
+pub fn format_number(value: f64, format: &str, locale: &Locale) -> Formatted {
+    format_number_with_date_system(value, format, locale, false)
+}
+
+/// Formats a number, dates in the 1904 date system if `date1904` is true
+pub fn format_number_with_date_system(
+    value_original: f64,
+    format: &str,
+    locale: &Locale,
+    date1904: bool,
+) -> Formatted {
     let mut parser = Parser::new(format);
     parser.parse();
     let parts = parser.parts;
@@ -154,15 +165,46 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
         ParsePart::Date(p) => {
             let tokens = &p.tokens;
             let mut text = "".to_string();
-            if !(1.0..=2_958_465.0).contains(&value) {
-                // 2_958_465 is 31 December 9999
+            // Times can be formatted from 0, dates from the first day of the date system
+            let min_value = if tokens.iter().any(|token| token.is_date()) {
+                min_serial_number(date1904) as f64
+            } else {
+                0.0
+            };
+            let max_value = max_serial_number(date1904) as f64 + 1.0;
+            if value < min_value || value >= max_value {
                 return Formatted {
                     text: "#VALUE!".to_owned(),
                     color: None,
                     error: Some("Date negative or too long".to_owned()),
                 };
             }
-            let date = from_excel_date(value as i64);
+            // The value is rounded to the precision of the seconds displayed
+            let fraction_digits = tokens
+                .iter()
+                .map(|token| match token {
+                    TextToken::SecondFraction(digits) => *digits,
+                    _ => 0,
+                })
+                .max()
+                .unwrap_or(0);
+            let ticks_per_second = 10_i64.pow(fraction_digits as u32);
+            let ticks = (value * 86_400.0 * ticks_per_second as f64).round() as i64;
+            let total_seconds = ticks / ticks_per_second;
+            let seconds_fraction = ticks % ticks_per_second;
+            let seconds_of_day = total_seconds % 86_400;
+            let date = from_excel_date(total_seconds / 86_400, date1904);
+            let hour = seconds_of_day / 3600;
+            let minute = (seconds_of_day / 60) % 60;
+            let second = seconds_of_day % 60;
+            let is_twelve_hour_clock = tokens
+                .iter()
+                .any(|token| matches!(token, TextToken::AmPm(..)));
+            let clock_hour = if is_twelve_hour_clock {
+                (hour + 11) % 12 + 1
+            } else {
+                hour
+            };
             for token in tokens {
                 match token {
                     TextToken::Literal(c) => {
@@ -235,6 +277,49 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                     TextToken::Year => {
                         text = format!("{}{}", text, date.year());
                     }
+                    TextToken::Hour => {
+                        text = format!("{}{}", text, clock_hour);
+                    }
+                    TextToken::HourPadded => {
+                        text = format!("{}{:02}", text, clock_hour);
+                    }
+                    TextToken::Minute => {
+                        text = format!("{}{}", text, minute);
+                    }
+                    TextToken::MinutePadded => {
+                        text = format!("{}{:02}", text, minute);
+                    }
+                    TextToken::Second => {
+                        text = format!("{}{}", text, second);
+                    }
+                    TextToken::SecondPadded => {
+                        text = format!("{}{:02}", text, second);
+                    }
+                    TextToken::SecondFraction(digits) => {
+                        // The fraction is rounded to the largest number of digits in the format
+                        let fraction =
+                            seconds_fraction / 10_i64.pow((fraction_digits - digits) as u32);
+                        text = format!(
+                            "{}{}{:0width$}",
+                            text,
+                            locale.numbers.symbols.decimal,
+                            fraction,
+                            width = *digits as usize
+                        );
+                    }
+                    TextToken::AmPm(am, pm) => {
+                        let marker = if hour < 12 { am } else { pm };
+                        text = format!("{}{}", text, marker);
+                    }
+                    TextToken::ElapsedHours(digits) => {
+                        text = format!("{}{:0width$}", text, total_seconds / 3600, width = *digits);
+                    }
+                    TextToken::ElapsedMinutes(digits) => {
+                        text = format!("{}{:0width$}", text, total_seconds / 60, width = *digits);
+                    }
+                    TextToken::ElapsedSeconds(digits) => {
+                        text = format!("{}{:0width$}", text, total_seconds, width = *digits);
+                    }
                 }
             }
             Formatted {
@@ -418,6 +503,17 @@ pub fn format_number(value_original: f64, format: &str, locale: &Locale) -> Form
                     TextToken::MonthLetter => {}
                     TextToken::YearShort => {}
                     TextToken::Year => {}
+                    TextToken::Hour => {}
+                    TextToken::HourPadded => {}
+                    TextToken::Minute => {}
+                    TextToken::MinutePadded => {}
+                    TextToken::Second => {}
+                    TextToken::SecondPadded => {}
+                    TextToken::SecondFraction(_) => {}
+                    TextToken::AmPm(..) => {}
+                    TextToken::ElapsedHours(_) => {}
+                    TextToken::ElapsedMinutes(_) => {}
+                    TextToken::ElapsedSeconds(_) => {}
                 }
             }
             Formatted {
@@ -533,7 +629,7 @@ fn parse_year(year_str: &str) -> Result<(i32, String), String> {
 // NOTE 1: The separator has to be the same
 // NOTE 2: In some engines "2/3" is implemented ad "2/March of the present year"
 // NOTE 3: I did not implement the "short date"
-fn parse_date(value: &str) -> Result<(i32, String), String> {
+fn parse_date(value: &str, date1904: bool) -> Result<(i32, String), String> {
     let separator = if value.contains('/') {
         '/'
     } else if value.contains('-') {
@@ -564,7 +660,7 @@ fn parse_date(value: &str) -> Result<(i32, String), String> {
     let (day, day_format) = parse_day(day_str)?;
     let (month, month_format) = parse_month(month_str)?;
     let (year, year_format) = parse_year(year_str)?;
-    let serial_number = match date_to_serial_number(day, month, year) {
+    let serial_number = match date_to_serial_number(day, month, year, date1904) {
         Ok(n) => n,
         Err(_) => return Err("Not a valid date".to_string()),
     };
@@ -581,14 +677,128 @@ fn parse_date(value: &str) -> Result<(i32, String), String> {
     }
 }
 
+// Check if it is a time of the day. The grammar is:
+//
+// time -> hours [separator minutes [separator seconds]] [am_pm]
+// separator -> the time separator of the locale
+// seconds -> number [decimal_separator digits]
+// am_pm -> "AM" | "PM" | "A" | "P" (in any case, optionally after a space)
+//
+// The minutes are only optional with AM or PM. Without them hours larger than 23 are elapsed
+// time: "25:30" is 1 day and 1.5 hours.
+// Returns the fraction of a day and the format.
+pub(crate) fn parse_time(value: &str, locale: &Locale) -> Result<(f64, String), String> {
+    let error = || "Not a valid time".to_string();
+    let upper = value.trim().to_uppercase();
+    let (time, is_pm) =
+        if let Some(time) = upper.strip_suffix("AM").or_else(|| upper.strip_suffix('A')) {
+            (time.trim_end(), Some(false))
+        } else if let Some(time) = upper.strip_suffix("PM").or_else(|| upper.strip_suffix('P')) {
+            (time.trim_end(), Some(true))
+        } else {
+            (upper.as_str(), None)
+        };
+    let symbols = &locale.numbers.symbols;
+    let parts: Vec<&str> = time.split(symbols.time_separator.as_str()).collect();
+    if parts.len() > 3 || (parts.len() == 1 && is_pm.is_none()) {
+        return Err(error());
+    }
+    let is_number =
+        |s: &str| !s.is_empty() && s.len() <= 2 && s.bytes().all(|b| b.is_ascii_digit());
+    let hours_str = parts[0];
+    if hours_str.is_empty() || hours_str.len() > 4 || !hours_str.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(error());
+    }
+    let mut hours = hours_str.parse::<f64>().map_err(|_| error())?;
+    let minutes = match parts.get(1) {
+        Some(m) if is_number(m) => m.parse::<f64>().map_err(|_| error())?,
+        Some(_) => return Err(error()),
+        None => 0.0,
+    };
+    let (seconds, has_seconds) = match parts.get(2) {
+        Some(s) => {
+            let (whole, fraction) = match s.split_once(symbols.decimal.as_str()) {
+                Some((whole, fraction)) => (whole, fraction),
+                None => (*s, ""),
+            };
+            if !is_number(whole) || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(error());
+            }
+            let seconds = format!("{whole}.{fraction}0")
+                .parse::<f64>()
+                .map_err(|_| error())?;
+            (seconds, true)
+        }
+        None => (0.0, false),
+    };
+    if minutes >= 60.0 || seconds >= 60.0 {
+        return Err(error());
+    }
+    let hours_format = if is_pm.is_none() && hours >= 24.0 {
+        "[h]"
+    } else {
+        "h"
+    };
+    let mut format = if has_seconds {
+        format!("{hours_format}:mm:ss")
+    } else {
+        format!("{hours_format}:mm")
+    };
+    if let Some(is_pm) = is_pm {
+        if hours > 12.0 {
+            return Err(error());
+        }
+        if hours == 12.0 {
+            hours = 0.0;
+        }
+        if is_pm {
+            hours += 12.0;
+        }
+        format.push_str(" AM/PM");
+    }
+    Ok((
+        (hours * 3600.0 + minutes * 60.0 + seconds) / 86_400.0,
+        format,
+    ))
+}
+
+// Check if it is a date, optionally followed by a time after a space: "2023-04-01 08:30"
+// Returns the serial number and the format.
+pub(crate) fn parse_date_time(
+    value: &str,
+    locale: &Locale,
+    date1904: bool,
+) -> Result<(f64, String), String> {
+    let value = value.trim();
+    if let Some((date, time)) = value.split_once(' ') {
+        if let (Ok((serial_number, date_format)), Ok((time, time_format))) =
+            (parse_date(date, date1904), parse_time(time, locale))
+        {
+            if time < 1.0 {
+                return Ok((
+                    serial_number as f64 + time,
+                    format!("{date_format} {time_format}"),
+                ));
+            }
+        }
+    }
+    let (serial_number, format) = parse_date(value, date1904)?;
+    Ok((serial_number as f64, format))
+}
+
 /// Parses a formatted number, returning the numeric value together with the format
 /// Uses heuristics to guess the format string
 /// "$ 123,345.678" => (123345.678, "$#,##0.00")
 /// "30.34%" => (0.3034, "0.00%")
 /// 100€ => (100, "100€")
+/// "13:45" => (0.572916667, "h:mm")
+/// Dates are parsed in the 1904 date system if `date1904` is true
 pub(crate) fn parse_formatted_number(
     value: &str,
     currencies: &[&str],
+    locale: &Locale,
+    date1904: bool,
 ) -> Result<(f64, Option<String>), String> {
     let value = value.trim();
     let scientific_format = "0.00E+00";
@@ -642,8 +852,12 @@ pub(crate) fn parse_formatted_number(
         }
     }
 
-    if let Ok((serial_number, format)) = parse_date(value) {
-        return Ok((serial_number as f64, Some(format)));
+    if let Ok((time, format)) = parse_time(value, locale) {
+        return Ok((time, Some(format)));
+    }
+
+    if let Ok((serial_number, format)) = parse_date_time(value, locale, date1904) {
+        return Ok((serial_number, Some(format)));
     }
 
     // Lastly we check if it is a number
@@ -4,6 +4,8 @@ pub struct Lexer {
     chars: Vec<char>,
     error_message: String,
     error_position: usize,
+    // true if the last date or time token was an hour, 'm' then means minutes
+    after_hour: bool,
 }
 
 #[derive(PartialEq, Debug)]
@@ -37,7 +39,17 @@ pub enum Token {
     MonthLetter,    // mmmmm
     YearShort,      // y or yy
     Year,           // yyy+
-    // TODO: Hours Minutes and Seconds
+    // Times
+    Hour,                  // h
+    HourPadded,            // hh+
+    Minute,                // m after an hour or before a second
+    MinutePadded,          // mm after an hour or before a second
+    Second,                // s
+    SecondPadded,          // ss+
+    AmPm(String, String),  // AM/PM or A/P, with the case of the format
+    ElapsedHours(usize),   // [h], [hh], ... (number of digits)
+    ElapsedMinutes(usize), // [m], [mm], ...
+    ElapsedSeconds(usize), // [s], [ss], ...
     ILLEGAL,
     EOF,
 }
@@ -69,6 +81,22 @@ impl Token {
             || self == &Token::YearShort
             || self == &Token::Year
     }
+
+    pub fn is_time(&self) -> bool {
+        matches!(
+            self,
+            Token::Hour
+                | Token::HourPadded
+                | Token::Minute
+                | Token::MinutePadded
+                | Token::Second
+                | Token::SecondPadded
+                | Token::AmPm(..)
+                | Token::ElapsedHours(_)
+                | Token::ElapsedMinutes(_)
+                | Token::ElapsedSeconds(_)
+        )
+    }
 }
 
 impl Lexer {
@@ -81,6 +109,7 @@ impl Lexer {
             len,
             error_message: "".to_string(),
             error_position: 0,
+            after_hour: false,
         }
     }
 
@@ -243,14 +272,91 @@ impl Lexer {
         None
     }
 
+    // [h], [mm], [ss], ... Returns None if this is not an elapsed time
+    fn consume_elapsed_time(&mut self) -> Option<Token> {
+        let mut position = self.position;
+        let first = self.chars[position].to_ascii_lowercase();
+        if first != 'h' && first != 'm' && first != 's' {
+            return None;
+        }
+        let mut count = 0;
+        while position < self.len && self.chars[position].to_ascii_lowercase() == first {
+            count += 1;
+            position += 1;
+        }
+        if position >= self.len || self.chars[position] != ']' {
+            return None;
+        }
+        self.position = position + 1;
+        Some(match first {
+            'h' => Token::ElapsedHours(count),
+            'm' => Token::ElapsedMinutes(count),
+            _ => Token::ElapsedSeconds(count),
+        })
+    }
+
+    // AM/PM or A/P, the first letter has already been read
+    fn consume_am_pm(&mut self) -> Option<Token> {
+        let start = self.position - 1;
+        let text = |len: usize| -> Option<String> {
+            self.chars
+                .get(start..start + len)
+                .map(|chars| chars.iter().collect())
+        };
+        if let Some(am_pm) = text(5) {
+            if am_pm.to_uppercase() == "AM/PM" {
+                self.position = start + 5;
+                return Some(Token::AmPm(
+                    am_pm[0..2].to_string(),
+                    am_pm[3..5].to_string(),
+                ));
+            }
+        }
+        if let Some(a_p) = text(3) {
+            if a_p.to_uppercase() == "A/P" {
+                self.position = start + 3;
+                return Some(Token::AmPm(a_p[0..1].to_string(), a_p[2..3].to_string()));
+            }
+        }
+        None
+    }
+
+    // Whether the next date or time token is a second, i.e. the 'm' in "mm:ss" are minutes
+    fn is_second_next(&self) -> bool {
+        for &c in &self.chars[self.position..] {
+            if c == 's' || c == 'S' || c == '[' {
+                return true;
+            }
+            if c.is_ascii_alphabetic() || c == ';' || c == '"' {
+                return false;
+            }
+        }
+        false
+    }
+
     pub fn peek_token(&mut self) -> Token {
         let position = self.position;
+        let after_hour = self.after_hour;
         let token = self.next_token();
         self.position = position;
+        self.after_hour = after_hour;
         token
     }
 
     pub fn next_token(&mut self) -> Token {
+        let token = self.read_token();
+        if token.is_date() || token.is_time() {
+            self.after_hour = matches!(
+                token,
+                Token::Hour | Token::HourPadded | Token::ElapsedHours(_)
+            );
+        } else if token == Token::Separator {
+            self.after_hour = false;
+        }
+        token
+    }
+
+    fn read_token(&mut self) -> Token {
         let ch = self.read_next_char();
         match ch {
             Some(x) => match x {
@@ -266,7 +372,9 @@ impl Lexer {
                 '%' => Token::Percent,
                 '[' => {
                     if let Some(c) = self.peek_char() {
-                        if c == '<' || c == '>' || c == '=' {
+                        if let Some(token) = self.consume_elapsed_time() {
+                            token
+                        } else if c == '<' || c == '>' || c == '=' {
                             // Condition
                             if let Some((cmp, value)) = self.consume_condition() {
                                 Token::Condition(cmp, value)
@@ -353,7 +461,10 @@ impl Lexer {
                         m += 1;
                         self.read_next_char();
                     }
+                    let is_minute = self.after_hour || self.is_second_next();
                     match m {
+                        1 if is_minute => Token::Minute,
+                        2 if is_minute => Token::MinutePadded,
                         1 => Token::Month,
                         2 => Token::MonthPadded,
                         3 => Token::MonthNameShort,
@@ -374,6 +485,38 @@ impl Lexer {
                         Token::Year
                     }
                 }
+                'h' | 'H' => {
+                    let mut h = 1;
+                    while let Some('h' | 'H') = self.peek_char() {
+                        h += 1;
+                        self.read_next_char();
+                    }
+                    if h == 1 {
+                        Token::Hour
+                    } else {
+                        Token::HourPadded
+                    }
+                }
+                's' | 'S' => {
+                    let mut s = 1;
+                    while let Some('s' | 'S') = self.peek_char() {
+                        s += 1;
+                        self.read_next_char();
+                    }
+                    if s == 1 {
+                        Token::Second
+                    } else {
+                        Token::SecondPadded
+                    }
+                }
+                'a' | 'A' => {
+                    if let Some(token) = self.consume_am_pm() {
+                        token
+                    } else {
+                        self.set_error(&format!("Unexpected character: {}", x));
+                        Token::ILLEGAL
+                    }
+                }
                 'g' | 'G' => {
                     for c in "eneral".chars() {
                         let cc = self.read_next_char();
@@ -96,6 +96,11 @@ mod test;
 // mmmmm First letter of the month
 // y or yy 2-digit year
 // yyy+ 4 digit year
+// h hour, hh padded hour
+// m or mm minutes if right after an hour or before the seconds, months otherwise
+// s seconds, ss padded seconds, ss.0 to ss.000 with fractions of a second
+// AM/PM or A/P 12-hour clock
+// [h], [m] or [s] elapsed time: hours, minutes or seconds in total (i.e. [h]:mm of 1.5 is 36:00)
 
 // References
 // ==========
@@ -27,7 +27,39 @@ pub enum TextToken {
     MonthLetter,
     YearShort,
     Year,
+    // Times
+    Hour,
+    HourPadded,
+    Minute,
+    MinutePadded,
+    Second,
+    SecondPadded,
+    SecondFraction(i32), // .0, .00 or .000 after the seconds (number of digits)
+    AmPm(String, String),
+    ElapsedHours(usize),
+    ElapsedMinutes(usize),
+    ElapsedSeconds(usize),
 }
+
+impl TextToken {
+    pub fn is_date(&self) -> bool {
+        matches!(
+            self,
+            TextToken::Day
+                | TextToken::DayPadded
+                | TextToken::DayNameShort
+                | TextToken::DayName
+                | TextToken::Month
+                | TextToken::MonthPadded
+                | TextToken::MonthNameShort
+                | TextToken::MonthName
+                | TextToken::MonthLetter
+                | TextToken::YearShort
+                | TextToken::Year
+        )
+    }
+}
+
 pub struct NumberPart {
     pub color: Option<i32>,
     pub condition: Option<(Compare, f64)>,
@@ -42,6 +74,7 @@ pub struct NumberPart {
     pub exponent_digit_count: i32,
 }
 
+/// A format with dates and or times
 pub struct DatePart {
     pub color: Option<i32>,
     pub tokens: Vec<TextToken>,
@@ -116,7 +149,7 @@ impl Parser {
         let mut index = 0;
 
         while token != Token::EOF && token != Token::Separator {
-            let next_token = self.lexer.next_token();
+            let mut next_token = self.lexer.next_token();
             let token_is_digit = token.is_digit();
             is_number = is_number || token_is_digit;
             let next_token_is_digit = next_token.is_digit();
@@ -153,7 +186,22 @@ impl Parser {
                     percent += 1;
                 }
                 Token::Period => {
-                    if !found_decimal_dot {
+                    if matches!(
+                        tokens.last(),
+                        Some(TextToken::Second | TextToken::SecondPadded)
+                    ) && next_token == Token::Zero
+                    {
+                        // Fractions of a second, up to three digits
+                        let mut digits = 0;
+                        while next_token == Token::Zero {
+                            digits += 1;
+                            next_token = self.lexer.next_token();
+                        }
+                        if digits > 3 {
+                            return ParsePart::Error(ErrorPart {});
+                        }
+                        tokens.push(TextToken::SecondFraction(digits));
+                    } else if !found_decimal_dot {
                         tokens.push(TextToken::Period);
                         found_decimal_dot = true;
                         if number == 'i' {
@@ -250,6 +298,46 @@ impl Parser {
                     is_date = true;
                     tokens.push(TextToken::Year);
                 }
+                Token::Hour => {
+                    is_date = true;
+                    tokens.push(TextToken::Hour);
+                }
+                Token::HourPadded => {
+                    is_date = true;
+                    tokens.push(TextToken::HourPadded);
+                }
+                Token::Minute => {
+                    is_date = true;
+                    tokens.push(TextToken::Minute);
+                }
+                Token::MinutePadded => {
+                    is_date = true;
+                    tokens.push(TextToken::MinutePadded);
+                }
+                Token::Second => {
+                    is_date = true;
+                    tokens.push(TextToken::Second);
+                }
+                Token::SecondPadded => {
+                    is_date = true;
+                    tokens.push(TextToken::SecondPadded);
+                }
+                Token::AmPm(am, pm) => {
+                    is_date = true;
+                    tokens.push(TextToken::AmPm(am, pm));
+                }
+                Token::ElapsedHours(digits) => {
+                    is_date = true;
+                    tokens.push(TextToken::ElapsedHours(digits));
+                }
+                Token::ElapsedMinutes(digits) => {
+                    is_date = true;
+                    tokens.push(TextToken::ElapsedMinutes(digits));
+                }
+                Token::ElapsedSeconds(digits) => {
+                    is_date = true;
+                    tokens.push(TextToken::ElapsedSeconds(digits));
+                }
                 Token::Scientific => {
                     if !is_scientific {
                         index = 0;
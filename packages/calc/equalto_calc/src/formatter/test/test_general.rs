@@ -194,3 +194,56 @@ fn test_date() {
         "Sat-September-12"
     );
 }
+
+#[test]
+fn test_time() {
+    let locale = get_default_locale();
+    // 13:45
+    let time = 49500.0 / 86400.0;
+    assert_eq!(format_number(time, "h:mm", locale).text, "13:45");
+    assert_eq!(format_number(time, "hh:mm:ss", locale).text, "13:45:00");
+    assert_eq!(format_number(time, "h:mm AM/PM", locale).text, "1:45 PM");
+    assert_eq!(format_number(time, "h:mm am/pm", locale).text, "1:45 pm");
+    assert_eq!(format_number(time, "h A/P", locale).text, "1 P");
+    assert_eq!(format_number(0.0, "h:mm AM/PM", locale).text, "12:00 AM");
+    assert_eq!(format_number(0.5, "h:mm AM/PM", locale).text, "12:00 PM");
+    assert_eq!(
+        format_number(1.5 / 86400.0, "mm:ss.0", locale).text,
+        "00:01.5"
+    );
+    assert_eq!(format_number(-0.5, "h:mm", locale).text, "#VALUE!");
+
+    // Times are rounded to the second
+    assert_eq!(
+        format_number(59.6 / 86400.0, "h:mm:ss", locale).text,
+        "0:01:00"
+    );
+}
+
+#[test]
+fn test_elapsed_time() {
+    let locale = get_default_locale();
+    assert_eq!(format_number(1.5, "[h]:mm", locale).text, "36:00");
+    assert_eq!(format_number(1.5, "[hh]:mm:ss", locale).text, "36:00:00");
+    assert_eq!(format_number(0.0625, "[mm]:ss", locale).text, "90:00");
+    assert_eq!(format_number(0.001, "[s]", locale).text, "86");
+    assert_eq!(format_number(0.001, "[sss]", locale).text, "086");
+}
+
+#[test]
+fn test_date_and_time() {
+    let locale = get_default_locale();
+    assert_eq!(
+        format_number(41181.75, "dd/mm/yyyy hh:mm", locale).text,
+        "29/09/2012 18:00"
+    );
+    // 'm' after hours are minutes, otherwise months
+    assert_eq!(
+        format_number(41181.75, "m/d/yyyy h:mm", locale).text,
+        "9/29/2012 18:00"
+    );
+    assert_eq!(
+        format_number(41181.75, "dd/mm/yyyy", locale).text,
+        "29/09/2012"
+    );
+}
@@ -1,6 +1,12 @@
 #![allow(clippy::unwrap_used)]
 
-use crate::formatter::format::parse_formatted_number as parse;
+use crate::formatter::format::parse_formatted_number;
+use crate::locale::get_locale;
+
+fn parse(value: &str, currencies: &[&str]) -> Result<(f64, Option<String>), String> {
+    let locale = get_locale("en").unwrap();
+    parse_formatted_number(value, currencies, locale, false)
+}
 
 const PARSE_ERROR_MSG: &str = "Could not parse number";
 
@@ -204,3 +210,66 @@ fn iso_dates() {
         Err(PARSE_ERROR_MSG.to_string())
     );
 }
+
+#[test]
+fn times() {
+    assert_eq!(
+        parse("13:45", &["$"]),
+        Ok((49500.0 / 86400.0, Some("h:mm".to_string())))
+    );
+    assert_eq!(
+        parse("13:45:30", &["$"]),
+        Ok((49530.0 / 86400.0, Some("h:mm:ss".to_string())))
+    );
+    assert_eq!(
+        parse("1:30:15.5", &["$"]),
+        Ok((5415.5 / 86400.0, Some("h:mm:ss".to_string())))
+    );
+    assert_eq!(
+        parse("1:45 PM", &["$"]),
+        Ok((49500.0 / 86400.0, Some("h:mm AM/PM".to_string())))
+    );
+    assert_eq!(
+        parse("1:45pm", &["$"]),
+        Ok((49500.0 / 86400.0, Some("h:mm AM/PM".to_string())))
+    );
+    assert_eq!(
+        parse("12:00 AM", &["$"]),
+        Ok((0.0, Some("h:mm AM/PM".to_string())))
+    );
+    assert_eq!(
+        parse("12 p", &["$"]),
+        Ok((0.5, Some("h:mm AM/PM".to_string())))
+    );
+    // Elapsed time
+    assert_eq!(
+        parse("25:30", &["$"]),
+        Ok((91800.0 / 86400.0, Some("[h]:mm".to_string())))
+    );
+    assert_eq!(parse("13", &["$"]), Ok((13.0, None)));
+    assert_eq!(parse("13:60", &["$"]), Err(PARSE_ERROR_MSG.to_string()));
+    assert_eq!(parse("13 PM", &["$"]), Err(PARSE_ERROR_MSG.to_string()));
+}
+
+#[test]
+fn dates_and_times() {
+    assert_eq!(
+        parse("2023-04-01 08:30", &["$"]),
+        Ok((
+            45017.0 + 30600.0 / 86400.0,
+            Some("yyyy-mm-dd h:mm".to_string())
+        ))
+    );
+    assert_eq!(
+        parse("01/04/2023 8:30:15 PM", &["$"]),
+        Ok((
+            45017.0 + 73815.0 / 86400.0,
+            Some("dd/mm/yyyy h:mm:ss AM/PM".to_string())
+        ))
+    );
+    // Elapsed times are not allowed after a date
+    assert_eq!(
+        parse("2023-04-01 25:30", &["$"]),
+        Err(PARSE_ERROR_MSG.to_string())
+    );
+}
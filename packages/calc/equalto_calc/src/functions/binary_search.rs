@@ -1,9 +1,6 @@
 use std::cmp::Ordering;
 
-use crate::{
-    calc_result::{CalcResult, CellReference},
-    model::Model,
-};
+use crate::calc_result::CalcResult;
 
 use super::util::compare_values;
 
@@ -140,71 +137,31 @@ pub(crate) fn binary_search_descending_or_greater<T: Ord>(target: &T, array: &[T
     Some((n - r - 1) as i32)
 }
 
-impl Model {
-    /// Returns an array with the list of cell values in the range
-    pub(crate) fn prepare_array(
-        &mut self,
-        left: &CellReference,
-        right: &CellReference,
-        is_row_vector: bool,
-    ) -> Vec<CalcResult> {
-        let n = if is_row_vector {
-            right.row - left.row
-        } else {
-            right.column - left.column
-        } + 1;
-        let mut result = vec![];
-        for index in 0..n {
-            let row;
-            let column;
-            if is_row_vector {
-                row = left.row + index;
-                column = left.column;
-            } else {
-                column = left.column + index;
-                row = left.row;
+/// Old style binary search. Used in HLOOKUP, etc
+/// Returns the index of the match or of the largest value smaller than target, -2 if target is
+/// smaller than all the values.
+pub(crate) fn binary_search(target: &CalcResult, array: &[CalcResult]) -> i32 {
+    // We apply binary search leftmost for value in the range
+    let mut l = 0;
+    let mut r = array.len();
+    while l < r {
+        let m = (l + r) / 2;
+        match compare_values(&array[m], target) {
+            -1 => {
+                l = m + 1;
             }
-            let value = self.evaluate_cell(CellReference {
-                sheet: left.sheet,
-                row,
-                column,
-            });
-            result.push(value);
-        }
-        result
-    }
-
-    /// Old style binary search. Used in HLOOKUP, etc
-    pub(crate) fn binary_search(
-        &mut self,
-        target: &CalcResult,
-        left: &CellReference,
-        right: &CellReference,
-        is_row_vector: bool,
-    ) -> i32 {
-        let array = self.prepare_array(left, right, is_row_vector);
-        // We apply binary search leftmost for value in the range
-        let mut l = 0;
-        let mut r = array.len();
-        while l < r {
-            let m = (l + r) / 2;
-            match compare_values(&array[m], target) {
-                -1 => {
-                    l = m + 1;
-                }
-                1 => {
-                    r = m;
-                }
-                _ => {
-                    return m as i32;
-                }
+            1 => {
+                r = m;
+            }
+            _ => {
+                return m as i32;
             }
         }
-        // If target is less than the minimum return #N/A
-        if l == 0 {
-            return -2;
-        }
-        // Now l points to the leftmost element
-        (l - 1) as i32
     }
+    // If target is less than the minimum return #N/A
+    if l == 0 {
+        return -2;
+    }
+    // Now l points to the leftmost element
+    (l - 1) as i32
 }
@@ -1,20 +1,28 @@
+use std::collections::HashSet;
+
 use chrono::Datelike;
+use chrono::Duration;
 use chrono::Months;
+use chrono::NaiveDate;
 use chrono::NaiveDateTime;
 use chrono::TimeZone;
 use chrono::Timelike;
 
-use crate::formatter::dates::date_to_serial_number;
-use crate::model::get_milliseconds_since_epoch;
+use crate::formatter::dates::{
+    date_base, date_to_serial_number, max_serial_number, min_serial_number,
+};
+use crate::formatter::format::{parse_date_time, parse_time};
 use crate::{
     calc_result::{CalcResult, CellReference},
-    constants::EXCEL_DATE_BASE,
+    constants::{LAST_COLUMN, LAST_ROW},
     expressions::parser::Node,
     expressions::token::Error,
     formatter::dates::from_excel_date,
     model::Model,
 };
 
+use super::day_count::{days_360_european, days_360_us, year_fraction, DayCountBasis};
+
 impl Model {
     pub(crate) fn fn_day(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
         let args_count = args.len();
@@ -35,7 +43,7 @@ impl Model {
             }
             Err(s) => return s,
         };
-        let date = from_excel_date(serial_number);
+        let date = from_excel_date(serial_number, self.workbook.settings.date1904);
         let day = date.day() as f64;
         CalcResult::Number(day)
     }
@@ -59,7 +67,7 @@ impl Model {
             }
             Err(s) => return s,
         };
-        let date = from_excel_date(serial_number);
+        let date = from_excel_date(serial_number, self.workbook.settings.date1904);
         let month = date.month() as f64;
         CalcResult::Number(month)
     }
@@ -95,9 +103,11 @@ impl Model {
         let months_abs = months.unsigned_abs();
 
         let native_date = if months > 0 {
-            from_excel_date(serial_number) + Months::new(months_abs)
+            from_excel_date(serial_number, self.workbook.settings.date1904)
+                + Months::new(months_abs)
         } else {
-            from_excel_date(serial_number) - Months::new(months_abs)
+            from_excel_date(serial_number, self.workbook.settings.date1904)
+                - Months::new(months_abs)
         };
 
         // Instead of calculating the end of month we compute the first day of the following month
@@ -108,7 +118,7 @@ impl Model {
             month = 1;
             year += 1;
         }
-        match date_to_serial_number(1, month, year) {
+        match date_to_serial_number(1, month, year, self.workbook.settings.date1904) {
             Ok(serial_number) => CalcResult::Number(serial_number as f64 - 1.0),
             Err(message) => CalcResult::Error {
                 error: Error::NUM,
@@ -166,7 +176,18 @@ impl Model {
             }
             Err(s) => return s,
         };
-        match date_to_serial_number(day, month, year) {
+        let date1904 = self.workbook.settings.date1904;
+        match date_to_serial_number(day, month, year, date1904) {
+            Ok(serial_number)
+                if serial_number < min_serial_number(date1904)
+                    || serial_number > max_serial_number(date1904) =>
+            {
+                CalcResult::Error {
+                    error: Error::NUM,
+                    origin: cell,
+                    message: "Out of range parameters for date".to_string(),
+                }
+            }
             Ok(serial_number) => CalcResult::Number(serial_number as f64),
             Err(message) => CalcResult::Error {
                 error: Error::NUM,
@@ -195,7 +216,7 @@ impl Model {
             }
             Err(s) => return s,
         };
-        let date = from_excel_date(serial_number);
+        let date = from_excel_date(serial_number, self.workbook.settings.date1904);
         let year = date.year() as f64;
         CalcResult::Number(year)
     }
@@ -233,12 +254,15 @@ impl Model {
         let months_abs = months.unsigned_abs();
 
         let native_date = if months > 0 {
-            from_excel_date(serial_number) + Months::new(months_abs)
+            from_excel_date(serial_number, self.workbook.settings.date1904)
+                + Months::new(months_abs)
         } else {
-            from_excel_date(serial_number) - Months::new(months_abs)
+            from_excel_date(serial_number, self.workbook.settings.date1904)
+                - Months::new(months_abs)
         };
 
-        let serial_number = native_date.num_days_from_ce() - EXCEL_DATE_BASE;
+        let serial_number =
+            native_date.num_days_from_ce() - date_base(self.workbook.settings.date1904);
         if serial_number < 0 {
             return CalcResult::Error {
                 error: Error::NUM,
@@ -259,7 +283,7 @@ impl Model {
             };
         }
         // milliseconds since January 1, 1970 00:00:00 UTC.
-        let milliseconds = get_milliseconds_since_epoch();
+        let milliseconds = self.environment.milliseconds_since_epoch();
         let seconds = milliseconds / 1000;
         let dt = match NaiveDateTime::from_timestamp_opt(seconds, 0) {
             Some(dt) => dt,
@@ -272,12 +296,10 @@ impl Model {
             }
         };
         let local_time = self.tz.from_utc_datetime(&dt);
-        // 693_594 is computed as:
-        // NaiveDate::from_ymd(1900, 1, 1).num_days_from_ce() - 2
-        // The 2 days offset is because of Excel 1900 bug
-        let days_from_1900 = local_time.num_days_from_ce() - 693_594;
+        let date_base = date_base(self.workbook.settings.date1904);
+        let serial_number = local_time.num_days_from_ce() - date_base;
 
-        CalcResult::Number(days_from_1900 as f64)
+        CalcResult::Number(serial_number as f64)
     }
 
     pub(crate) fn fn_now(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
@@ -290,7 +312,7 @@ impl Model {
             };
         }
         // milliseconds since January 1, 1970 00:00:00 UTC.
-        let milliseconds = get_milliseconds_since_epoch();
+        let milliseconds = self.environment.milliseconds_since_epoch();
         let seconds = milliseconds / 1000;
         let dt = match NaiveDateTime::from_timestamp_opt(seconds, 0) {
             Some(dt) => dt,
@@ -303,12 +325,627 @@ impl Model {
             }
         };
         let local_time = self.tz.from_utc_datetime(&dt);
-        // 693_594 is computed as:
-        // NaiveDate::from_ymd(1900, 1, 1).num_days_from_ce() - 2
-        // The 2 days offset is because of Excel 1900 bug
-        let days_from_1900 = local_time.num_days_from_ce() - 693_594;
+        let date_base = date_base(self.workbook.settings.date1904);
+        let serial_number = local_time.num_days_from_ce() - date_base;
         let days = (local_time.num_seconds_from_midnight() as f64) / (60.0 * 60.0 * 24.0);
 
-        CalcResult::Number(days_from_1900 as f64 + days.fract())
+        CalcResult::Number(serial_number as f64 + days.fract())
     }
+
+    /// Evaluates a date argument to a serial number, which must be a valid date
+    fn get_serial_number(&mut self, node: &Node, cell: CellReference) -> Result<i64, CalcResult> {
+        let serial_number = self.get_number(node, cell)?.floor();
+        let date1904 = self.workbook.settings.date1904;
+        if serial_number < 0.0 || serial_number > max_serial_number(date1904) as f64 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Invalid date".to_string(),
+            ));
+        }
+        Ok(serial_number as i64)
+    }
+
+    /// Day of the week of a serial number, starting with Monday = 0
+    fn day_of_the_week(&self, serial_number: i64) -> usize {
+        from_excel_date(serial_number, self.workbook.settings.date1904)
+            .weekday()
+            .num_days_from_monday() as usize
+    }
+
+    /// The weekend argument of NETWORKDAYS.INTL and WORKDAY.INTL, as a list of flags starting
+    /// on Monday. It is either a number (1 to 7 for two days, 11 to 17 for a single day) or a
+    /// string of seven zeros and ones, where a one is a non working day.
+    fn get_weekend(&mut self, node: &Node, cell: CellReference) -> Result<[bool; 7], CalcResult> {
+        let mut weekend = [false; 7];
+        match self.evaluate_node_in_context(node, cell) {
+            CalcResult::EmptyArg | CalcResult::EmptyCell => {
+                // Saturday and Sunday
+                weekend[5] = true;
+                weekend[6] = true;
+            }
+            CalcResult::String(mask) => {
+                if mask.len() != 7
+                    || mask.chars().any(|c| c != '0' && c != '1')
+                    || mask == "1111111"
+                {
+                    return Err(CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Invalid weekend".to_string(),
+                    ));
+                }
+                for (index, c) in mask.chars().enumerate() {
+                    weekend[index] = c == '1';
+                }
+            }
+            result => match self.cast_to_number(result, cell)?.trunc() as i64 {
+                code @ 1..=7 => {
+                    weekend[((code + 4) % 7) as usize] = true;
+                    weekend[((code + 5) % 7) as usize] = true;
+                }
+                code @ 11..=17 => {
+                    weekend[((code - 5) % 7) as usize] = true;
+                }
+                _ => {
+                    return Err(CalcResult::new_error(
+                        Error::NUM,
+                        cell,
+                        "Invalid weekend".to_string(),
+                    ))
+                }
+            },
+        }
+        Ok(weekend)
+    }
+
+    /// The holidays argument of the workday functions: a date, a range or an array of dates.
+    /// Empty cells are ignored.
+    fn get_holidays(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<HashSet<i64>, CalcResult> {
+        let values = match self.evaluate_node_in_context(node, cell) {
+            CalcResult::EmptyArg => return Ok(HashSet::new()),
+            CalcResult::Range { left, right } => {
+                if left.sheet != right.sheet {
+                    return Err(CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Ranges are in different sheets".to_string(),
+                    ));
+                }
+                let mut right = right;
+                // Whole rows and columns are clipped to the used part of the sheet
+                if let Ok(worksheet) = self.workbook.worksheet(left.sheet) {
+                    let dimension = worksheet.dimension();
+                    if left.row == 1 && right.row == LAST_ROW {
+                        right.row = dimension.max_row;
+                    }
+                    if left.column == 1 && right.column == LAST_COLUMN {
+                        right.column = dimension.max_column;
+                    }
+                }
+                let mut values = Vec::new();
+                for row in left.row..=right.row {
+                    for column in left.column..=right.column {
+                        values.push(self.evaluate_cell(CellReference {
+                            sheet: left.sheet,
+                            row,
+                            column,
+                        }));
+                    }
+                }
+                values
+            }
+            CalcResult::Array(rows) => rows.into_iter().flatten().collect(),
+            value => vec![value],
+        };
+        let mut holidays = HashSet::new();
+        for value in values {
+            let holiday = match value {
+                CalcResult::EmptyCell | CalcResult::EmptyArg => continue,
+                CalcResult::Number(value) => value.floor(),
+                error @ CalcResult::Error { .. } => return Err(error),
+                _ => {
+                    return Err(CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Invalid holiday".to_string(),
+                    ))
+                }
+            };
+            if holiday < 0.0 {
+                return Err(CalcResult::new_error(
+                    Error::NUM,
+                    cell,
+                    "Invalid holiday".to_string(),
+                ));
+            }
+            holidays.insert(holiday as i64);
+        }
+        Ok(holidays)
+    }
+
+    /// Number of working days between two dates, both included, with `start <= end`
+    fn count_working_days(
+        &self,
+        start: i64,
+        end: i64,
+        weekend: &[bool; 7],
+        holidays: &HashSet<i64>,
+    ) -> i64 {
+        let is_working_day = |serial_number: i64| !weekend[self.day_of_the_week(serial_number)];
+        // Every week has the same number of working days
+        let weeks = (end - start + 1) / 7;
+        let working_days_per_week = weekend.iter().filter(|&&day| !day).count() as i64;
+        let mut count = weeks * working_days_per_week;
+        for serial_number in start + 7 * weeks..=end {
+            if is_working_day(serial_number) {
+                count += 1;
+            }
+        }
+        let holidays = holidays
+            .iter()
+            .filter(|&&holiday| start <= holiday && holiday <= end && is_working_day(holiday))
+            .count() as i64;
+        count - holidays
+    }
+
+    fn networkdays(
+        &mut self,
+        args: &[Node],
+        weekend: Option<&Node>,
+        holidays: Option<&Node>,
+        cell: CellReference,
+    ) -> CalcResult {
+        let start = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let end = match self.get_serial_number(&args[1], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let weekend = match weekend {
+            Some(node) => match self.get_weekend(node, cell) {
+                Ok(w) => w,
+                Err(s) => return s,
+            },
+            None => [false, false, false, false, false, true, true],
+        };
+        let holidays = match holidays {
+            Some(node) => match self.get_holidays(node, cell) {
+                Ok(h) => h,
+                Err(s) => return s,
+            },
+            None => HashSet::new(),
+        };
+        let days = if start <= end {
+            self.count_working_days(start, end, &weekend, &holidays)
+        } else {
+            -self.count_working_days(end, start, &weekend, &holidays)
+        };
+        CalcResult::Number(days as f64)
+    }
+
+    // start_date, end_date, [holidays]
+    pub(crate) fn fn_networkdays(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        self.networkdays(args, None, args.get(2), cell)
+    }
+
+    // start_date, end_date, [weekend], [holidays]
+    pub(crate) fn fn_networkdays_intl(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=4).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        self.networkdays(args, args.get(2), args.get(3), cell)
+    }
+
+    fn workday(
+        &mut self,
+        args: &[Node],
+        weekend: Option<&Node>,
+        holidays: Option<&Node>,
+        cell: CellReference,
+    ) -> CalcResult {
+        let start = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let days = match self.get_number(&args[1], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        let weekend = match weekend {
+            Some(node) => match self.get_weekend(node, cell) {
+                Ok(w) => w,
+                Err(s) => return s,
+            },
+            None => [false, false, false, false, false, true, true],
+        };
+        let holidays = match holidays {
+            Some(node) => match self.get_holidays(node, cell) {
+                Ok(h) => h,
+                Err(s) => return s,
+            },
+            None => HashSet::new(),
+        };
+        let date1904 = self.workbook.settings.date1904;
+        let max_serial_number = max_serial_number(date1904) as i64;
+        if days.abs() > max_serial_number as f64 {
+            return CalcResult::new_error(Error::NUM, cell, "Invalid date".to_string());
+        }
+        let step = if days < 0.0 { -1 } else { 1 };
+        let mut remaining = days.abs() as i64;
+        let mut serial_number = start;
+        while remaining > 0 {
+            serial_number += step;
+            if serial_number < 0 || serial_number > max_serial_number {
+                return CalcResult::new_error(Error::NUM, cell, "Invalid date".to_string());
+            }
+            if !weekend[self.day_of_the_week(serial_number)] && !holidays.contains(&serial_number) {
+                remaining -= 1;
+            }
+        }
+        CalcResult::Number(serial_number as f64)
+    }
+
+    // start_date, days, [holidays]
+    pub(crate) fn fn_workday(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        self.workday(args, None, args.get(2), cell)
+    }
+
+    // start_date, days, [weekend], [holidays]
+    pub(crate) fn fn_workday_intl(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=4).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        self.workday(args, args.get(2), args.get(3), cell)
+    }
+
+    // start_date, end_date, unit
+    pub(crate) fn fn_datedif(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let start_serial = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let end_serial = match self.get_serial_number(&args[1], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let unit = match self.get_string(&args[2], cell) {
+            Ok(s) => s.to_uppercase(),
+            Err(s) => return s,
+        };
+        if start_serial > end_serial {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Start date is after end date".to_string(),
+            );
+        }
+        let date1904 = self.workbook.settings.date1904;
+        let start = from_excel_date(start_serial, date1904);
+        let end = from_excel_date(end_serial, date1904);
+        let end_before_start_day = end.day() < start.day();
+        let months = 12 * (end.year() - start.year()) + end.month() as i32
+            - start.month() as i32
+            - end_before_start_day as i32;
+        let result = match unit.as_str() {
+            "Y" => (months / 12) as i64,
+            "M" => months as i64,
+            "D" => end_serial - start_serial,
+            "YM" => (months % 12) as i64,
+            "MD" => {
+                if end_before_start_day {
+                    // Days since the same day of the previous month. Like Excel, this is negative
+                    // if that month is shorter than the day of the start date.
+                    let (year, month) = if end.month() == 1 {
+                        (end.year() - 1, 12)
+                    } else {
+                        (end.year(), end.month() - 1)
+                    };
+                    (end - date_with_overflow(year, month, start.day())).num_days()
+                } else {
+                    (end.day() - start.day()) as i64
+                }
+            }
+            "YD" => {
+                // Days since the last anniversary of the start date
+                let mut anniversary = date_with_overflow(end.year(), start.month(), start.day());
+                if anniversary > end {
+                    anniversary = date_with_overflow(end.year() - 1, start.month(), start.day());
+                }
+                (end - anniversary).num_days()
+            }
+            _ => {
+                return CalcResult::new_error(Error::NUM, cell, "Invalid unit".to_string());
+            }
+        };
+        CalcResult::Number(result as f64)
+    }
+
+    // serial_number, [return_type]
+    pub(crate) fn fn_weekday(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(1..=2).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let serial_number = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let return_type = if args.len() == 2 {
+            match self.get_number(&args[1], cell) {
+                Ok(f) => f.trunc() as i64,
+                Err(s) => return s,
+            }
+        } else {
+            1
+        };
+        // The first day of the week (Monday = 0) and the number it is given
+        let (first_day, first_number) = match return_type {
+            1 => (6, 1),
+            2 => (0, 1),
+            3 => (0, 0),
+            11..=17 => (return_type - 11, 1),
+            _ => return CalcResult::new_error(Error::NUM, cell, "Invalid return type".to_string()),
+        };
+        let day = self.day_of_the_week(serial_number) as i64;
+        CalcResult::Number(((day - first_day).rem_euclid(7) + first_number) as f64)
+    }
+
+    // serial_number, [return_type]
+    pub(crate) fn fn_weeknum(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(1..=2).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let serial_number = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let return_type = if args.len() == 2 {
+            match self.get_number(&args[1], cell) {
+                Ok(f) => f.trunc() as i64,
+                Err(s) => return s,
+            }
+        } else {
+            1
+        };
+        let date = from_excel_date(serial_number, self.workbook.settings.date1904);
+        // The first day of the week, Monday = 0
+        let first_day = match return_type {
+            1 | 17 => 6,
+            2 | 11 => 0,
+            12..=16 => return_type - 11,
+            21 => return CalcResult::Number(date.iso_week().week() as f64),
+            _ => return CalcResult::new_error(Error::NUM, cell, "Invalid return type".to_string()),
+        };
+        // The week containing January 1 is the first week of the year
+        let january_1 = date.with_ordinal(1).unwrap_or(date);
+        let offset = (january_1.weekday().num_days_from_monday() as i64 - first_day).rem_euclid(7);
+        let week = (date.ordinal0() as i64 + offset) / 7 + 1;
+        CalcResult::Number(week as f64)
+    }
+
+    // date
+    pub(crate) fn fn_isoweeknum(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let serial_number = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let date = from_excel_date(serial_number, self.workbook.settings.date1904);
+        CalcResult::Number(date.iso_week().week() as f64)
+    }
+
+    // start_date, end_date, [basis]
+    pub(crate) fn fn_yearfrac(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let start = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let end = match self.get_serial_number(&args[1], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let basis = if args.len() == 3 {
+            match self.get_number(&args[2], cell) {
+                Ok(f) => f,
+                Err(s) => return s,
+            }
+        } else {
+            0.0
+        };
+        let basis = match DayCountBasis::from_number(basis) {
+            Some(basis) => basis,
+            None => return CalcResult::new_error(Error::NUM, cell, "Invalid basis".to_string()),
+        };
+        let date1904 = self.workbook.settings.date1904;
+        let (start, end) = (start.min(end), start.max(end));
+        let start = from_excel_date(start, date1904);
+        let end = from_excel_date(end, date1904);
+        CalcResult::Number(year_fraction(start, end, basis))
+    }
+
+    // start_date, end_date, [method]
+    pub(crate) fn fn_days360(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let start = match self.get_serial_number(&args[0], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let end = match self.get_serial_number(&args[1], cell) {
+            Ok(s) => s,
+            Err(s) => return s,
+        };
+        let european = if args.len() == 3 {
+            match self.get_boolean(&args[2], cell) {
+                Ok(b) => b,
+                Err(s) => return s,
+            }
+        } else {
+            false
+        };
+        let date1904 = self.workbook.settings.date1904;
+        let start = from_excel_date(start, date1904);
+        let end = from_excel_date(end, date1904);
+        let days = if european {
+            days_360_european(start, end)
+        } else {
+            days_360_us(start, end)
+        };
+        CalcResult::Number(days as f64)
+    }
+
+    /// Evaluates a date and time argument. Text is parsed like user input, i.e. "13:45".
+    fn get_date_time(&mut self, node: &Node, cell: CellReference) -> Result<f64, CalcResult> {
+        let value = match self.evaluate_node_in_context(node, cell) {
+            CalcResult::String(text) => match text.trim().parse::<f64>() {
+                Ok(value) => value,
+                Err(_) => self.parse_date_time_text(&text, cell)?,
+            },
+            result => self.cast_to_number(result, cell)?,
+        };
+        let date1904 = self.workbook.settings.date1904;
+        if value < 0.0 || value >= max_serial_number(date1904) as f64 + 1.0 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Invalid date".to_string(),
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Parses a time, a date or a date and time
+    fn parse_date_time_text(&self, text: &str, cell: CellReference) -> Result<f64, CalcResult> {
+        let date1904 = self.workbook.settings.date1904;
+        match parse_time(text, &self.locale) {
+            Ok((time, _)) => Ok(time),
+            Err(_) => match parse_date_time(text, &self.locale, date1904) {
+                Ok((value, _)) => Ok(value),
+                Err(message) => Err(CalcResult::new_error(Error::VALUE, cell, message)),
+            },
+        }
+    }
+
+    /// Seconds since midnight of a date and time, rounded to the nearest second
+    fn get_seconds_of_day(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<i64, CalcResult> {
+        if args.len() != 1 {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let value = self.get_date_time(&args[0], cell)?;
+        Ok((value * 86_400.0).round() as i64 % 86_400)
+    }
+
+    // hour, minute, second
+    pub(crate) fn fn_time(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let mut values = [0.0; 3];
+        for (index, arg) in args.iter().enumerate() {
+            values[index] = match self.get_number(arg, cell) {
+                Ok(f) => f.trunc(),
+                Err(s) => return s,
+            };
+            if values[index] > 32767.0 {
+                return CalcResult::new_error(Error::NUM, cell, "Invalid time".to_string());
+            }
+        }
+        let [hour, minute, second] = values;
+        let seconds = hour * 3600.0 + minute * 60.0 + second;
+        if seconds < 0.0 {
+            return CalcResult::new_error(Error::NUM, cell, "Invalid time".to_string());
+        }
+        CalcResult::Number((seconds % 86_400.0) / 86_400.0)
+    }
+
+    // serial_number
+    pub(crate) fn fn_hour(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_seconds_of_day(args, cell) {
+            Ok(seconds) => CalcResult::Number((seconds / 3600) as f64),
+            Err(s) => s,
+        }
+    }
+
+    // serial_number
+    pub(crate) fn fn_minute(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_seconds_of_day(args, cell) {
+            Ok(seconds) => CalcResult::Number(((seconds / 60) % 60) as f64),
+            Err(s) => s,
+        }
+    }
+
+    // serial_number
+    pub(crate) fn fn_second(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_seconds_of_day(args, cell) {
+            Ok(seconds) => CalcResult::Number((seconds % 60) as f64),
+            Err(s) => s,
+        }
+    }
+
+    // time_text
+    pub(crate) fn fn_timevalue(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let text = match self.evaluate_node_in_context(&args[0], cell) {
+            CalcResult::String(text) => text,
+            error @ CalcResult::Error { .. } => return error,
+            _ => return CalcResult::new_error(Error::VALUE, cell, "Expected text".to_string()),
+        };
+        match self.parse_date_time_text(&text, cell) {
+            // The date is ignored
+            Ok(value) => CalcResult::Number(value.fract()),
+            Err(s) => s,
+        }
+    }
+
+    // date_text
+    pub(crate) fn fn_datevalue(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let text = match self.evaluate_node_in_context(&args[0], cell) {
+            CalcResult::String(text) => text,
+            error @ CalcResult::Error { .. } => return error,
+            _ => return CalcResult::new_error(Error::VALUE, cell, "Expected text".to_string()),
+        };
+        let date1904 = self.workbook.settings.date1904;
+        match parse_date_time(&text, &self.locale, date1904) {
+            // The time is ignored
+            Ok((value, _)) => CalcResult::Number(value.floor()),
+            Err(message) => CalcResult::new_error(Error::VALUE, cell, message),
+        }
+    }
+}
+
+/// The date with the given year and month that is `day - 1` days after the first of the month.
+/// Like the DATE function in Excel, days past the end of the month roll over to the next one.
+fn date_with_overflow(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, 1).expect("problem with chrono::NaiveDate")
+        + Duration::days(day as i64 - 1)
 }
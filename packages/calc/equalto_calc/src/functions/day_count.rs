@@ -0,0 +1,142 @@
+use chrono::{Datelike, NaiveDate};
+
+// Day count conventions, used by YEARFRAC, DAYS360 and the functions taking a `basis` argument.
+// See:
+// https://en.wikipedia.org/wiki/Day_count_convention
+// https://support.microsoft.com/en-us/office/yearfrac-function-3844141e-c76d-4143-82b6-208454ddc6a8
+
+/// The `basis` argument of YEARFRAC and the security functions
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum DayCountBasis {
+    /// 0: US (NASD) 30/360
+    UsNasd30_360,
+    /// 1: Actual/actual
+    ActualActual,
+    /// 2: Actual/360
+    Actual360,
+    /// 3: Actual/365
+    Actual365,
+    /// 4: European 30/360
+    European30_360,
+}
+
+impl DayCountBasis {
+    /// The basis is truncated, values other than 0 to 4 are invalid
+    pub(crate) fn from_number(basis: f64) -> Option<DayCountBasis> {
+        match basis.trunc() as i64 {
+            0 => Some(DayCountBasis::UsNasd30_360),
+            1 => Some(DayCountBasis::ActualActual),
+            2 => Some(DayCountBasis::Actual360),
+            3 => Some(DayCountBasis::Actual365),
+            4 => Some(DayCountBasis::European30_360),
+            _ => None,
+        }
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub(crate) fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt().map(|next| next.month()) != Some(date.month())
+}
+
+fn is_last_day_of_february(date: NaiveDate) -> bool {
+    date.month() == 2 && is_last_day_of_month(date)
+}
+
+fn days_360(start: NaiveDate, end: NaiveDate, start_day: u32, end_day: u32) -> i64 {
+    let years = end.year() as i64 - start.year() as i64;
+    let months = end.month() as i64 - start.month() as i64;
+    360 * years + 30 * months + end_day as i64 - start_day as i64
+}
+
+/// Days between two dates in the US method of DAYS360: a start date on the last day of a month
+/// becomes the 30th, an end date on the 31st becomes the 30th if the start date is on the 30th or
+/// 31st and the 1st of the next month otherwise.
+pub(crate) fn days_360_us(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut start_day = start.day();
+    let mut end_day = end.day();
+    if is_last_day_of_month(start) {
+        start_day = 30;
+    }
+    if end_day == 31 && start_day >= 30 {
+        end_day = 30;
+    }
+    days_360(start, end, start_day, end_day)
+}
+
+/// Days between two dates in the European method: the 31st of a month becomes the 30th
+pub(crate) fn days_360_european(start: NaiveDate, end: NaiveDate) -> i64 {
+    days_360(start, end, start.day().min(30), end.day().min(30))
+}
+
+/// Days between two dates in the NASD method used with basis 0. It differs from the US method of
+/// DAYS360 in the treatment of the end of February.
+pub(crate) fn days_360_nasd(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut start_day = start.day();
+    let mut end_day = end.day();
+    if start_day == 31 {
+        start_day = 30;
+        if end_day == 31 {
+            end_day = 30;
+        }
+    } else if start_day == 30 && end_day == 31 {
+        end_day = 30;
+    } else if is_last_day_of_february(start) {
+        start_day = 30;
+        if is_last_day_of_february(end) {
+            end_day = 30;
+        }
+    }
+    days_360(start, end, start_day, end_day)
+}
+
+/// Whether the dates are at most a year apart
+fn within_one_year(start: NaiveDate, end: NaiveDate) -> bool {
+    start.year() == end.year()
+        || (start.year() + 1 == end.year()
+            && (end.month(), end.day()) <= (start.month(), start.day()))
+}
+
+/// Whether there is a 29 February between the two dates, both included
+fn contains_leap_day(start: NaiveDate, end: NaiveDate) -> bool {
+    (start.year()..=end.year()).any(|year| {
+        NaiveDate::from_ymd_opt(year, 2, 29)
+            .is_some_and(|leap_day| start <= leap_day && leap_day <= end)
+    })
+}
+
+/// The actual/actual fraction of year. For dates at most a year apart the length of the year is
+/// 366 days if the period contains a 29 February, otherwise it is the average length of the years
+/// in the period.
+fn actual_actual_year_fraction(start: NaiveDate, end: NaiveDate) -> f64 {
+    let days = (end - start).num_days() as f64;
+    if within_one_year(start, end) {
+        let year_length = if (start.year() == end.year() && is_leap_year(start.year()))
+            || contains_leap_day(start, end)
+        {
+            366.0
+        } else {
+            365.0
+        };
+        return days / year_length;
+    }
+    let years = (start.year()..=end.year())
+        .map(|year| if is_leap_year(year) { 366.0 } else { 365.0 })
+        .collect::<Vec<f64>>();
+    let average_year_length = years.iter().sum::<f64>() / years.len() as f64;
+    days / average_year_length
+}
+
+/// The fraction of a year between two dates, `start` must not be after `end`
+pub(crate) fn year_fraction(start: NaiveDate, end: NaiveDate, basis: DayCountBasis) -> f64 {
+    match basis {
+        DayCountBasis::UsNasd30_360 => days_360_nasd(start, end) as f64 / 360.0,
+        DayCountBasis::ActualActual => actual_actual_year_fraction(start, end),
+        DayCountBasis::Actual360 => (end - start).num_days() as f64 / 360.0,
+        DayCountBasis::Actual365 => (end - start).num_days() as f64 / 365.0,
+        DayCountBasis::European30_360 => days_360_european(start, end) as f64 / 360.0,
+    }
+}
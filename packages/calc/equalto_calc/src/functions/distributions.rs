@@ -0,0 +1,772 @@
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    expressions::{parser::Node, token::Error},
+    model::Model,
+};
+
+use super::distributions_util::{
+    beta_cdf, beta_pdf, binomial_cdf, binomial_inverse, binomial_pmf, chi_squared_cdf,
+    chi_squared_pdf, chi_squared_right_tail, f_cdf, f_pdf, f_right_tail, gamma_cdf, gamma_pdf,
+    inverse_increasing, poisson_cdf, poisson_pmf, standard_normal_cdf, standard_normal_inverse,
+    standard_normal_pdf, t_cdf, t_pdf, t_right_tail,
+};
+use super::engineering::transcendental::{gamma, ln_gamma};
+
+// Statistical distributions. The legacy names (NORMDIST, TDIST, ...) are kept for compatibility,
+// most of them are aliases of the 2010 functions.
+// See:
+// https://support.microsoft.com/en-us/office/statistical-functions-reference-624dac86-a375-4435-bc25-76d659719ffd
+
+// Excel rejects degrees of freedom above 10^10
+const MAX_DEGREES_OF_FREEDOM: f64 = 1e10;
+
+fn num_error(cell: CellReference, message: &str) -> CalcResult {
+    CalcResult::new_error(Error::NUM, cell, message.to_string())
+}
+
+// Infinities and NaNs are reported as #NUM!
+fn number_or_error(value: f64, cell: CellReference) -> CalcResult {
+    if value.is_finite() {
+        CalcResult::Number(value)
+    } else {
+        num_error(cell, "Invalid result")
+    }
+}
+
+fn is_valid_degrees_of_freedom(df: f64) -> bool {
+    (1.0..=MAX_DEGREES_OF_FREEDOM).contains(&df)
+}
+
+fn is_probability(p: f64) -> bool {
+    (0.0..=1.0).contains(&p)
+}
+
+/// Value x >= 0 such that the probability of a Student's t random variable being greater than x is p
+fn t_inverse_right_tail(p: f64, df: f64) -> f64 {
+    inverse_increasing(-p, f64::INFINITY, |x| -t_right_tail(x, df))
+}
+
+impl Model {
+    // Evaluates the first N arguments as numbers
+    fn get_numbers<const N: usize>(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<[f64; N], CalcResult> {
+        let mut numbers = [0.0; N];
+        for (number, arg) in numbers.iter_mut().zip(args) {
+            *number = self.get_number(arg, cell)?;
+        }
+        Ok(numbers)
+    }
+
+    // NORM.DIST(x, mean, standard_dev, cumulative)
+    pub(crate) fn fn_norm_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, mean, standard_dev] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        if standard_dev <= 0.0 {
+            return num_error(cell, "Standard deviation must be positive");
+        }
+        let z = (x - mean) / standard_dev;
+        if cumulative {
+            CalcResult::Number(standard_normal_cdf(z))
+        } else {
+            CalcResult::Number(standard_normal_pdf(z) / standard_dev)
+        }
+    }
+
+    // NORM.INV(probability, mean, standard_dev)
+    pub(crate) fn fn_norm_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, mean, standard_dev] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        if p <= 0.0 || p >= 1.0 {
+            return num_error(cell, "Probability must be between 0 and 1");
+        }
+        if standard_dev <= 0.0 {
+            return num_error(cell, "Standard deviation must be positive");
+        }
+        CalcResult::Number(mean + standard_dev * standard_normal_inverse(p))
+    }
+
+    // NORM.S.DIST(z, cumulative)
+    pub(crate) fn fn_norm_s_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let z = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[1], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        if cumulative {
+            CalcResult::Number(standard_normal_cdf(z))
+        } else {
+            CalcResult::Number(standard_normal_pdf(z))
+        }
+    }
+
+    // NORMSDIST(z), the cumulative standard normal distribution
+    pub(crate) fn fn_normsdist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_number(&args[0], cell) {
+            Ok(z) => CalcResult::Number(standard_normal_cdf(z)),
+            Err(s) => s,
+        }
+    }
+
+    // NORM.S.INV(probability)
+    pub(crate) fn fn_norm_s_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let p = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if p <= 0.0 || p >= 1.0 {
+            return num_error(cell, "Probability must be between 0 and 1");
+        }
+        CalcResult::Number(standard_normal_inverse(p))
+    }
+
+    // LOGNORM.DIST(x, mean, standard_dev, cumulative)
+    pub(crate) fn fn_lognorm_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, mean, standard_dev] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        if x <= 0.0 || standard_dev <= 0.0 {
+            return num_error(cell, "x and standard deviation must be positive");
+        }
+        let z = (x.ln() - mean) / standard_dev;
+        if cumulative {
+            CalcResult::Number(standard_normal_cdf(z))
+        } else {
+            CalcResult::Number(standard_normal_pdf(z) / (x * standard_dev))
+        }
+    }
+
+    // LOGNORMDIST(x, mean, standard_dev), the cumulative lognormal distribution
+    pub(crate) fn fn_lognormdist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, mean, standard_dev] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        if x <= 0.0 || standard_dev <= 0.0 {
+            return num_error(cell, "x and standard deviation must be positive");
+        }
+        CalcResult::Number(standard_normal_cdf((x.ln() - mean) / standard_dev))
+    }
+
+    // LOGNORM.INV(probability, mean, standard_dev)
+    pub(crate) fn fn_lognorm_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, mean, standard_dev] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        if p <= 0.0 || p >= 1.0 {
+            return num_error(cell, "Probability must be between 0 and 1");
+        }
+        if standard_dev <= 0.0 {
+            return num_error(cell, "Standard deviation must be positive");
+        }
+        number_or_error(
+            (mean + standard_dev * standard_normal_inverse(p)).exp(),
+            cell,
+        )
+    }
+
+    // T.DIST(x, deg_freedom, cumulative)
+    pub(crate) fn fn_t_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[2], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid degrees of freedom");
+        }
+        if cumulative {
+            CalcResult::Number(t_cdf(x, df))
+        } else {
+            CalcResult::Number(t_pdf(x, df))
+        }
+    }
+
+    // T.DIST.RT(x, deg_freedom)
+    pub(crate) fn fn_t_dist_rt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid degrees of freedom");
+        }
+        CalcResult::Number(t_right_tail(x, df))
+    }
+
+    // T.DIST.2T(x, deg_freedom)
+    pub(crate) fn fn_t_dist_2t(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        CalcResult::Number(2.0 * t_right_tail(x, df))
+    }
+
+    // TDIST(x, deg_freedom, tails)
+    pub(crate) fn fn_tdist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df, tails] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        match tails.trunc() as i32 {
+            1 => CalcResult::Number(t_right_tail(x, df)),
+            2 => CalcResult::Number(2.0 * t_right_tail(x, df)),
+            _ => num_error(cell, "Tails must be 1 or 2"),
+        }
+    }
+
+    // T.INV(probability, deg_freedom), the left-tailed inverse
+    pub(crate) fn fn_t_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if p <= 0.0 || p >= 1.0 {
+            return num_error(cell, "Probability must be between 0 and 1");
+        }
+        if !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid degrees of freedom");
+        }
+        if p < 0.5 {
+            CalcResult::Number(-t_inverse_right_tail(p, df))
+        } else {
+            CalcResult::Number(t_inverse_right_tail(1.0 - p, df))
+        }
+    }
+
+    // T.INV.2T(probability, deg_freedom), the two-tailed inverse
+    pub(crate) fn fn_t_inv_2t(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if p <= 0.0 || p > 1.0 {
+            return num_error(cell, "Probability must be between 0 and 1");
+        }
+        if !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid degrees of freedom");
+        }
+        CalcResult::Number(t_inverse_right_tail(p / 2.0, df))
+    }
+
+    // CHISQ.DIST(x, deg_freedom, cumulative)
+    pub(crate) fn fn_chisq_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[2], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        if cumulative {
+            CalcResult::Number(chi_squared_cdf(x, df))
+        } else {
+            number_or_error(chi_squared_pdf(x, df), cell)
+        }
+    }
+
+    // CHISQ.DIST.RT(x, deg_freedom) and CHIDIST(x, deg_freedom)
+    pub(crate) fn fn_chisq_dist_rt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        CalcResult::Number(chi_squared_right_tail(x, df))
+    }
+
+    // CHISQ.INV(probability, deg_freedom)
+    pub(crate) fn fn_chisq_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if !is_probability(p) || p == 1.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        let x = inverse_increasing(p, f64::INFINITY, |x| chi_squared_cdf(x, df));
+        number_or_error(x, cell)
+    }
+
+    // CHISQ.INV.RT(probability, deg_freedom) and CHIINV(probability, deg_freedom)
+    pub(crate) fn fn_chisq_inv_rt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df = df.trunc();
+        if !is_probability(p) || p == 0.0 || !is_valid_degrees_of_freedom(df) {
+            return num_error(cell, "Invalid arguments");
+        }
+        let x = inverse_increasing(-p, f64::INFINITY, |x| -chi_squared_right_tail(x, df));
+        number_or_error(x, cell)
+    }
+
+    // F.DIST(x, deg_freedom1, deg_freedom2, cumulative)
+    pub(crate) fn fn_f_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df1, df2] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let df1 = df1.trunc();
+        let df2 = df2.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df1) || !is_valid_degrees_of_freedom(df2) {
+            return num_error(cell, "Invalid arguments");
+        }
+        if cumulative {
+            CalcResult::Number(f_cdf(x, df1, df2))
+        } else {
+            number_or_error(f_pdf(x, df1, df2), cell)
+        }
+    }
+
+    // F.DIST.RT(x, deg_freedom1, deg_freedom2) and FDIST(x, deg_freedom1, deg_freedom2)
+    pub(crate) fn fn_f_dist_rt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, df1, df2] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df1 = df1.trunc();
+        let df2 = df2.trunc();
+        if x < 0.0 || !is_valid_degrees_of_freedom(df1) || !is_valid_degrees_of_freedom(df2) {
+            return num_error(cell, "Invalid arguments");
+        }
+        CalcResult::Number(f_right_tail(x, df1, df2))
+    }
+
+    // F.INV(probability, deg_freedom1, deg_freedom2)
+    pub(crate) fn fn_f_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df1, df2] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df1 = df1.trunc();
+        let df2 = df2.trunc();
+        if !is_probability(p)
+            || p == 1.0
+            || !is_valid_degrees_of_freedom(df1)
+            || !is_valid_degrees_of_freedom(df2)
+        {
+            return num_error(cell, "Invalid arguments");
+        }
+        let x = inverse_increasing(p, f64::INFINITY, |x| f_cdf(x, df1, df2));
+        number_or_error(x, cell)
+    }
+
+    // F.INV.RT(probability, deg_freedom1, deg_freedom2) and FINV(probability, deg_freedom1, deg_freedom2)
+    pub(crate) fn fn_f_inv_rt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, df1, df2] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let df1 = df1.trunc();
+        let df2 = df2.trunc();
+        if !is_probability(p)
+            || p == 0.0
+            || !is_valid_degrees_of_freedom(df1)
+            || !is_valid_degrees_of_freedom(df2)
+        {
+            return num_error(cell, "Invalid arguments");
+        }
+        let x = inverse_increasing(-p, f64::INFINITY, |x| -f_right_tail(x, df1, df2));
+        number_or_error(x, cell)
+    }
+
+    // BINOM.DIST(number_s, trials, probability_s, cumulative)
+    pub(crate) fn fn_binom_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [successes, trials, p] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let successes = successes.trunc();
+        let trials = trials.trunc();
+        if successes < 0.0 || successes > trials || !is_probability(p) {
+            return num_error(cell, "Invalid arguments");
+        }
+        if cumulative {
+            CalcResult::Number(binomial_cdf(successes, trials, p))
+        } else {
+            CalcResult::Number(binomial_pmf(successes, trials, p))
+        }
+    }
+
+    // BINOM.INV(trials, probability_s, alpha) and CRITBINOM(trials, probability_s, alpha)
+    pub(crate) fn fn_binom_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [trials, p, alpha] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let trials = trials.trunc();
+        if trials < 0.0 || !is_probability(p) || !is_probability(alpha) {
+            return num_error(cell, "Invalid arguments");
+        }
+        CalcResult::Number(binomial_inverse(trials, p, alpha))
+    }
+
+    // POISSON.DIST(x, mean, cumulative) and POISSON(x, mean, cumulative)
+    pub(crate) fn fn_poisson_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, mean] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[2], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let x = x.trunc();
+        if x < 0.0 || mean < 0.0 {
+            return num_error(cell, "x and mean must not be negative");
+        }
+        if cumulative {
+            CalcResult::Number(poisson_cdf(x, mean))
+        } else {
+            CalcResult::Number(poisson_pmf(x, mean))
+        }
+    }
+
+    // EXPON.DIST(x, lambda, cumulative) and EXPONDIST(x, lambda, cumulative)
+    pub(crate) fn fn_expon_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, lambda] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[2], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        if x < 0.0 || lambda <= 0.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        if cumulative {
+            CalcResult::Number(-(-lambda * x).exp_m1())
+        } else {
+            number_or_error(lambda * (-lambda * x).exp(), cell)
+        }
+    }
+
+    // GAMMA.DIST(x, alpha, beta, cumulative) and GAMMADIST(x, alpha, beta, cumulative)
+    pub(crate) fn fn_gamma_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, alpha, beta] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        if x < 0.0 || alpha <= 0.0 || beta <= 0.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        if cumulative {
+            CalcResult::Number(gamma_cdf(x, alpha, beta))
+        } else {
+            number_or_error(gamma_pdf(x, alpha, beta), cell)
+        }
+    }
+
+    // GAMMA.INV(probability, alpha, beta) and GAMMAINV(probability, alpha, beta)
+    pub(crate) fn fn_gamma_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, alpha, beta] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        if !is_probability(p) || p == 1.0 || alpha <= 0.0 || beta <= 0.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        let x = inverse_increasing(p, f64::INFINITY, |x| gamma_cdf(x, alpha, beta));
+        number_or_error(x, cell)
+    }
+
+    // GAMMA(number)
+    pub(crate) fn fn_gamma(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_number(&args[0], cell) {
+            Ok(x) => number_or_error(gamma(x), cell),
+            Err(s) => s,
+        }
+    }
+
+    // GAMMALN(x) and GAMMALN.PRECISE(x)
+    pub(crate) fn fn_gammaln(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let x = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if x <= 0.0 {
+            return num_error(cell, "x must be positive");
+        }
+        CalcResult::Number(ln_gamma(x))
+    }
+
+    // Evaluates the optional bounds [A, B] of the beta distribution, starting at `index`
+    fn get_beta_bounds(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        cell: CellReference,
+    ) -> Result<(f64, f64), CalcResult> {
+        let lower = if args.len() > index {
+            self.get_number(&args[index], cell)?
+        } else {
+            0.0
+        };
+        let upper = if args.len() > index + 1 {
+            self.get_number(&args[index + 1], cell)?
+        } else {
+            1.0
+        };
+        if lower >= upper {
+            return Err(num_error(cell, "A must be smaller than B"));
+        }
+        Ok((lower, upper))
+    }
+
+    // BETA.DIST(x, alpha, beta, cumulative, [A], [B])
+    pub(crate) fn fn_beta_dist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(4..=6).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, alpha, beta] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let cumulative = match self.get_boolean(&args[3], cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let (lower, upper) = match self.get_beta_bounds(args, 4, cell) {
+            Ok(bounds) => bounds,
+            Err(s) => return s,
+        };
+        if alpha <= 0.0 || beta <= 0.0 || x < lower || x > upper {
+            return num_error(cell, "Invalid arguments");
+        }
+        let t = (x - lower) / (upper - lower);
+        if cumulative {
+            CalcResult::Number(beta_cdf(t, alpha, beta))
+        } else {
+            number_or_error(beta_pdf(t, alpha, beta) / (upper - lower), cell)
+        }
+    }
+
+    // BETADIST(x, alpha, beta, [A], [B]), the cumulative beta distribution
+    pub(crate) fn fn_betadist(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(3..=5).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [x, alpha, beta] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let (lower, upper) = match self.get_beta_bounds(args, 3, cell) {
+            Ok(bounds) => bounds,
+            Err(s) => return s,
+        };
+        if alpha <= 0.0 || beta <= 0.0 || x < lower || x > upper {
+            return num_error(cell, "Invalid arguments");
+        }
+        CalcResult::Number(beta_cdf((x - lower) / (upper - lower), alpha, beta))
+    }
+
+    // BETA.INV(probability, alpha, beta, [A], [B]) and BETAINV(probability, alpha, beta, [A], [B])
+    pub(crate) fn fn_beta_inv(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(3..=5).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [p, alpha, beta] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let (lower, upper) = match self.get_beta_bounds(args, 3, cell) {
+            Ok(bounds) => bounds,
+            Err(s) => return s,
+        };
+        if p <= 0.0 || p > 1.0 || alpha <= 0.0 || beta <= 0.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        let t = inverse_increasing(p, 1.0, |t| beta_cdf(t, alpha, beta));
+        CalcResult::Number(lower + (upper - lower) * t)
+    }
+
+    // CONFIDENCE.NORM(alpha, standard_dev, size) and CONFIDENCE(alpha, standard_dev, size)
+    pub(crate) fn fn_confidence_norm(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [alpha, standard_dev, size] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let size = size.trunc();
+        if alpha <= 0.0 || alpha >= 1.0 || standard_dev <= 0.0 || size < 1.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        let z = -standard_normal_inverse(alpha / 2.0);
+        CalcResult::Number(z * standard_dev / size.sqrt())
+    }
+
+    // CONFIDENCE.T(alpha, standard_dev, size)
+    pub(crate) fn fn_confidence_t(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let [alpha, standard_dev, size] = match self.get_numbers(args, cell) {
+            Ok(numbers) => numbers,
+            Err(s) => return s,
+        };
+        let size = size.trunc();
+        if alpha <= 0.0 || alpha >= 1.0 || standard_dev <= 0.0 || size < 1.0 {
+            return num_error(cell, "Invalid arguments");
+        }
+        if size == 1.0 {
+            return CalcResult::new_error(
+                Error::DIV,
+                cell,
+                "Sample size must be greater than 1".to_string(),
+            );
+        }
+        let t = t_inverse_right_tail(alpha / 2.0, size - 1.0);
+        CalcResult::Number(t * standard_dev / size.sqrt())
+    }
+}
@@ -0,0 +1,313 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use super::engineering::transcendental::{
+    erfc, ln_beta, ln_gamma, regularized_beta, regularized_gamma_p, regularized_gamma_q,
+};
+
+// Probability density functions, cumulative distribution functions and their inverses.
+// The cumulative functions are written in terms of the regularized incomplete gamma and beta
+// functions, we compute right tails directly to avoid losing precision in 1 - cdf.
+// See:
+// https://en.wikipedia.org/wiki/Incomplete_gamma_function#Regularized_gamma_functions_and_Poisson_random_variables
+// https://en.wikipedia.org/wiki/Beta_function#Incomplete_beta_function
+
+// Bisection stops as soon as the interval can't be split, this is just a safe guard
+const MAX_BISECTION_ITERATIONS: usize = 2000;
+
+pub(crate) fn standard_normal_pdf(z: f64) -> f64 {
+    (-z * z / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+pub(crate) fn standard_normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / SQRT_2)
+}
+
+/// Inverse of the standard normal cumulative distribution, for 0 < p < 1.
+/// Wichura's algorithm AS241 (PPND16), accurate to about 1 part in 10^16
+pub(crate) fn standard_normal_inverse(p: f64) -> f64 {
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        return q
+            * (((((((r * 2_509.080_928_730_122_7 + 33_430.575_583_588_13) * r
+                + 67_265.770_927_008_7)
+                * r
+                + 45_921.953_931_549_87)
+                * r
+                + 13_731.693_765_509_46)
+                * r
+                + 1_971.590_950_306_551_3)
+                * r
+                + 133.141_667_891_784_38)
+                * r
+                + 3.387_132_872_796_366_5)
+            / (((((((r * 5_226.495_278_852_546 + 28_729.085_735_721_943) * r
+                + 39_307.895_800_092_71)
+                * r
+                + 21_213.794_301_586_597)
+                * r
+                + 5_394.196_021_424_751)
+                * r
+                + 687.187_007_492_057_9)
+                * r
+                + 42.313_330_701_600_91)
+                * r
+                + 1.0);
+    }
+    let r = if q < 0.0 { p } else { 1.0 - p };
+    let mut r = (-r.ln()).sqrt();
+    let value = if r <= 5.0 {
+        r -= 1.6;
+        (((((((r * 7.745_450_142_783_414e-4 + 0.022_723_844_989_269_184) * r
+            + 0.241_780_725_177_450_6)
+            * r
+            + 1.270_458_252_452_368_4)
+            * r
+            + 3.647_848_324_763_204_5)
+            * r
+            + 5.769_497_221_460_691)
+            * r
+            + 4.630_337_846_156_546)
+            * r
+            + 1.423_437_110_749_683_5)
+            / (((((((r * 1.050_750_071_644_416_9e-9 + 5.475_938_084_995_345e-4) * r
+                + 0.015_198_666_563_616_457)
+                * r
+                + 0.148_103_976_427_480_08)
+                * r
+                + 0.689_767_334_985_1)
+                * r
+                + 1.676_384_830_183_803_8)
+                * r
+                + 2.053_191_626_637_759)
+                * r
+                + 1.0)
+    } else {
+        r -= 5.0;
+        (((((((r * 2.010_334_399_292_288_1e-7 + 2.711_555_568_743_487_6e-5) * r
+            + 0.001_242_660_947_388_078_4)
+            * r
+            + 0.026_532_189_526_576_124)
+            * r
+            + 0.296_560_571_828_504_87)
+            * r
+            + 1.784_826_539_917_291_3)
+            * r
+            + 5.463_784_911_164_114)
+            * r
+            + 6.657_904_643_501_103)
+            / (((((((r * 2.044_263_103_389_939_7e-15 + 1.421_511_758_316_446e-7) * r
+                + 1.846_318_317_510_054_8e-5)
+                * r
+                + 7.868_691_311_456_133e-4)
+                * r
+                + 0.014_875_361_290_850_615)
+                * r
+                + 0.136_929_880_922_735_8)
+                * r
+                + 0.599_832_206_555_888)
+                * r
+                + 1.0)
+    };
+    if q < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Student's t distribution
+pub(crate) fn t_pdf(x: f64, df: f64) -> f64 {
+    (ln_gamma((df + 1.0) / 2.0)
+        - ln_gamma(df / 2.0)
+        - 0.5 * (df * PI).ln()
+        - (df + 1.0) / 2.0 * (x * x / df).ln_1p())
+    .exp()
+}
+
+/// Probability that a Student's t random variable is greater than x
+pub(crate) fn t_right_tail(x: f64, df: f64) -> f64 {
+    let tail = 0.5 * regularized_beta(df / (df + x * x), df / 2.0, 0.5);
+    if x >= 0.0 {
+        tail
+    } else {
+        1.0 - tail
+    }
+}
+
+pub(crate) fn t_cdf(x: f64, df: f64) -> f64 {
+    t_right_tail(-x, df)
+}
+
+/// Gamma distribution with shape alpha and scale beta. Infinite at 0 when alpha < 1.
+pub(crate) fn gamma_pdf(x: f64, alpha: f64, beta: f64) -> f64 {
+    if x == 0.0 {
+        return if alpha < 1.0 {
+            f64::INFINITY
+        } else if alpha == 1.0 {
+            1.0 / beta
+        } else {
+            0.0
+        };
+    }
+    ((alpha - 1.0) * x.ln() - x / beta - ln_gamma(alpha) - alpha * beta.ln()).exp()
+}
+
+pub(crate) fn gamma_cdf(x: f64, alpha: f64, beta: f64) -> f64 {
+    regularized_gamma_p(alpha, x / beta)
+}
+
+/// The chi-squared distribution is a gamma distribution with shape df/2 and scale 2
+pub(crate) fn chi_squared_pdf(x: f64, df: f64) -> f64 {
+    gamma_pdf(x, df / 2.0, 2.0)
+}
+
+pub(crate) fn chi_squared_cdf(x: f64, df: f64) -> f64 {
+    regularized_gamma_p(df / 2.0, x / 2.0)
+}
+
+pub(crate) fn chi_squared_right_tail(x: f64, df: f64) -> f64 {
+    regularized_gamma_q(df / 2.0, x / 2.0)
+}
+
+/// Fisher-Snedecor F distribution. Infinite at 0 when d1 < 2.
+pub(crate) fn f_pdf(x: f64, d1: f64, d2: f64) -> f64 {
+    if x == 0.0 {
+        return if d1 < 2.0 {
+            f64::INFINITY
+        } else if d1 == 2.0 {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    (0.5 * (d1 * (d1 * x).ln() + d2 * d2.ln() - (d1 + d2) * (d1 * x + d2).ln())
+        - x.ln()
+        - ln_beta(d1 / 2.0, d2 / 2.0))
+    .exp()
+}
+
+pub(crate) fn f_cdf(x: f64, d1: f64, d2: f64) -> f64 {
+    regularized_beta(d1 * x / (d1 * x + d2), d1 / 2.0, d2 / 2.0)
+}
+
+pub(crate) fn f_right_tail(x: f64, d1: f64, d2: f64) -> f64 {
+    regularized_beta(d2 / (d2 + d1 * x), d2 / 2.0, d1 / 2.0)
+}
+
+/// Beta distribution in [0, 1]. Infinite at the end points when alpha < 1 or beta < 1.
+pub(crate) fn beta_pdf(x: f64, alpha: f64, beta: f64) -> f64 {
+    if x == 0.0 {
+        return if alpha < 1.0 {
+            f64::INFINITY
+        } else if alpha == 1.0 {
+            beta
+        } else {
+            0.0
+        };
+    }
+    if x == 1.0 {
+        return if beta < 1.0 {
+            f64::INFINITY
+        } else if beta == 1.0 {
+            alpha
+        } else {
+            0.0
+        };
+    }
+    ((alpha - 1.0) * x.ln() + (beta - 1.0) * (-x).ln_1p() - ln_beta(alpha, beta)).exp()
+}
+
+pub(crate) fn beta_cdf(x: f64, alpha: f64, beta: f64) -> f64 {
+    regularized_beta(x, alpha, beta)
+}
+
+/// Probability of exactly k successes in n trials with probability p of success
+pub(crate) fn binomial_pmf(k: f64, n: f64, p: f64) -> f64 {
+    if p == 0.0 {
+        return if k == 0.0 { 1.0 } else { 0.0 };
+    }
+    if p == 1.0 {
+        return if k == n { 1.0 } else { 0.0 };
+    }
+    let ln_combinations = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+    (ln_combinations + k * p.ln() + (n - k) * (-p).ln_1p()).exp()
+}
+
+/// Probability of at most k successes in n trials with probability p of success
+pub(crate) fn binomial_cdf(k: f64, n: f64, p: f64) -> f64 {
+    if k >= n {
+        return 1.0;
+    }
+    regularized_beta(1.0 - p, n - k, k + 1.0)
+}
+
+/// Smallest number of successes k such that binomial_cdf(k, n, p) >= alpha
+pub(crate) fn binomial_inverse(n: f64, p: f64, alpha: f64) -> f64 {
+    let mut low = 0.0;
+    let mut high = n;
+    while low < high {
+        let middle = ((low + high) / 2.0).floor();
+        if binomial_cdf(middle, n, p) >= alpha {
+            high = middle;
+        } else {
+            low = middle + 1.0;
+        }
+    }
+    low
+}
+
+pub(crate) fn poisson_pmf(k: f64, mean: f64) -> f64 {
+    if mean == 0.0 {
+        return if k == 0.0 { 1.0 } else { 0.0 };
+    }
+    (k * mean.ln() - mean - ln_gamma(k + 1.0)).exp()
+}
+
+pub(crate) fn poisson_cdf(k: f64, mean: f64) -> f64 {
+    if mean == 0.0 {
+        return 1.0;
+    }
+    regularized_gamma_q(k + 1.0, mean)
+}
+
+/// Finds x in [0, upper] such that f(x) = target, where f is an increasing function.
+/// We first find an interval [low, high] with f(low) < target <= f(high) by halving or doubling,
+/// then we use bisection until the interval can't be split any further.
+/// Returns 0 if f(x) >= target for all positive x and infinity if f(x) < target for all x.
+pub(crate) fn inverse_increasing(target: f64, upper: f64, f: impl Fn(f64) -> f64) -> f64 {
+    let mut high = upper.min(1.0);
+    let mut low;
+    if f(high) >= target {
+        low = high / 2.0;
+        while f(low) >= target {
+            high = low;
+            low /= 2.0;
+            if low == 0.0 {
+                return 0.0;
+            }
+        }
+    } else {
+        low = high;
+        high *= 2.0;
+        while f(high) < target {
+            low = high;
+            high *= 2.0;
+            if high.is_infinite() {
+                return f64::INFINITY;
+            }
+        }
+    }
+    for _ in 0..MAX_BISECTION_ITERATIONS {
+        let middle = 0.5 * (low + high);
+        if middle <= low || middle >= high {
+            break;
+        }
+        if f(middle) < target {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    high
+}
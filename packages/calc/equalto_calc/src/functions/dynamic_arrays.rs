@@ -0,0 +1,720 @@
+//! Dynamic array functions: FILTER, SORT, UNIQUE, SEQUENCE, TAKE, VSTACK, ...
+
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use crate::{
+    arrays::array_top_left,
+    calc_result::{CalcResult, CellReference},
+    constants::{LAST_COLUMN, LAST_ROW, MAX_ARRAY_SIZE},
+    expressions::{parser::Node, token::Error},
+    model::Model,
+};
+
+use super::util::{compare_values, values_are_equal};
+
+fn new_empty_array_error(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::CALC, cell, "Empty array".to_string())
+}
+
+fn new_value_error(cell: CellReference, message: &str) -> CalcResult {
+    CalcResult::new_error(Error::VALUE, cell, message.to_string())
+}
+
+fn new_not_available(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::NA, cell, "Not available".to_string())
+}
+
+/// (rows, columns) of an array
+fn dimensions(array: &[Vec<CalcResult>]) -> (usize, usize) {
+    (array.len(), array.first().map_or(0, |row| row.len()))
+}
+
+fn transpose(array: Vec<Vec<CalcResult>>) -> Vec<Vec<CalcResult>> {
+    let (rows, columns) = dimensions(&array);
+    let mut result = vec![Vec::with_capacity(rows); columns];
+    for row in array {
+        for (column, value) in row.into_iter().enumerate() {
+            result[column].push(value);
+        }
+    }
+    result
+}
+
+/// The array as a result, with no elements it's an error
+fn array_result(array: Vec<Vec<CalcResult>>, cell: CellReference) -> CalcResult {
+    let (rows, columns) = dimensions(&array);
+    if rows == 0 || columns == 0 {
+        return new_empty_array_error(cell);
+    }
+    CalcResult::Array(array)
+}
+
+/// Values are sorted numbers first, then text, booleans, errors and empty cells
+fn sort_rank(value: &CalcResult) -> u8 {
+    match value {
+        CalcResult::Number(_) => 0,
+        CalcResult::String(_) => 1,
+        CalcResult::Boolean(_) => 2,
+        CalcResult::Error { .. } => 3,
+        _ => 4,
+    }
+}
+
+fn compare_for_sort(left: &CalcResult, right: &CalcResult) -> Ordering {
+    let (left_rank, right_rank) = (sort_rank(left), sort_rank(right));
+    if left_rank != right_rank {
+        return left_rank.cmp(&right_rank);
+    }
+    if left_rank < 3 {
+        compare_values(left, right).cmp(&0)
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Reorders `lines` by the keys. Each key has one value per line and a flag that is true when the
+/// order is descending. The sort is stable.
+fn sort_lines(
+    lines: Vec<Vec<CalcResult>>,
+    keys: &[(Vec<CalcResult>, bool)],
+) -> Vec<Vec<CalcResult>> {
+    let mut order: Vec<usize> = (0..lines.len()).collect();
+    order.sort_by(|&a, &b| {
+        keys.iter()
+            .map(|(values, descending)| {
+                let ordering = compare_for_sort(&values[a], &values[b]);
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    let mut lines: Vec<Option<Vec<CalcResult>>> = lines.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| lines[index].take())
+        .collect()
+}
+
+/// Same value for UNIQUE, unlike `values_are_equal` two equal errors are the same
+fn same_value(left: &CalcResult, right: &CalcResult) -> bool {
+    match (left, right) {
+        (CalcResult::Error { error: left, .. }, CalcResult::Error { error: right, .. }) => {
+            left == right
+        }
+        _ => values_are_equal(left, right),
+    }
+}
+
+/// Positions taken by TAKE: the first `count` if positive, the last ones if negative
+fn take_range(count: Option<i64>, size: usize) -> Range<usize> {
+    match count {
+        None => 0..size,
+        Some(count) if count >= 0 => 0..(count as usize).min(size),
+        Some(count) => size - (count.unsigned_abs() as usize).min(size)..size,
+    }
+}
+
+/// Positions left by DROP: all but the first `count` if positive, all but the last ones if negative
+fn drop_range(count: Option<i64>, size: usize) -> Range<usize> {
+    match count {
+        None => 0..size,
+        Some(count) if count >= 0 => (count as usize).min(size)..size,
+        Some(count) => 0..size - (count.unsigned_abs() as usize).min(size),
+    }
+}
+
+fn slice(
+    array: Vec<Vec<CalcResult>>,
+    rows: Range<usize>,
+    columns: Range<usize>,
+) -> Vec<Vec<CalcResult>> {
+    array
+        .into_iter()
+        .skip(rows.start)
+        .take(rows.len())
+        .map(|row| row[columns.clone()].to_vec())
+        .collect()
+}
+
+impl Model {
+    /// Numbers in an array argument, truncated to integers
+    fn get_integer_list(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<Vec<i64>, CalcResult> {
+        let mut integers = Vec::new();
+        for value in self.get_array_argument(node, cell)?.into_iter().flatten() {
+            integers.push(self.cast_to_number(value, cell)?.trunc() as i64);
+        }
+        Ok(integers)
+    }
+
+    /// Optional integer argument, None if omitted or empty
+    fn get_optional_integer(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        cell: CellReference,
+    ) -> Result<Option<i64>, CalcResult> {
+        match args.get(index) {
+            None | Some(Node::EmptyArgKind) => Ok(None),
+            Some(node) => Ok(Some(self.get_number(node, cell)?.trunc() as i64)),
+        }
+    }
+
+    /// Sort order argument: 1 ascending, -1 descending. Returns true if descending.
+    fn get_sort_order(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        cell: CellReference,
+    ) -> Result<bool, CalcResult> {
+        let order = self.get_optional_number(args, index, 1.0, cell)?.trunc();
+        if order == 1.0 {
+            Ok(false)
+        } else if order == -1.0 {
+            Ok(true)
+        } else {
+            Err(new_value_error(cell, "Sort order must be 1 or -1"))
+        }
+    }
+
+    /// FILTER(array, include, [if_empty])
+    pub(crate) fn fn_filter(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 2 || args.len() > 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let include = match self.get_array_argument(&args[1], cell) {
+            Ok(include) => include,
+            Err(error) => return error,
+        };
+        let (rows, columns) = dimensions(&array);
+        let by_rows = match dimensions(&include) {
+            (include_rows, 1) if include_rows == rows => true,
+            (1, include_columns) if include_columns == columns => false,
+            _ => return new_value_error(cell, "Include has the wrong size"),
+        };
+        let mut flags = Vec::new();
+        for value in include.into_iter().flatten() {
+            flags.push(match value {
+                CalcResult::Boolean(b) => b,
+                CalcResult::Number(f) => f != 0.0,
+                CalcResult::EmptyCell | CalcResult::EmptyArg => false,
+                error @ CalcResult::Error { .. } => return error,
+                _ => return new_value_error(cell, "Include must be booleans"),
+            });
+        }
+        let lines = if by_rows { array } else { transpose(array) };
+        let lines: Vec<Vec<CalcResult>> = lines
+            .into_iter()
+            .zip(flags)
+            .filter_map(|(line, flag)| if flag { Some(line) } else { None })
+            .collect();
+        if lines.is_empty() {
+            return match args.get(2) {
+                Some(node) => match self.get_array_argument(node, cell) {
+                    Ok(array) => CalcResult::Array(array),
+                    Err(error) => error,
+                },
+                None => new_empty_array_error(cell),
+            };
+        }
+        CalcResult::Array(if by_rows { lines } else { transpose(lines) })
+    }
+
+    /// SORT(array, [sort_index], [sort_order], [by_col])
+    pub(crate) fn fn_sort(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let indices = match args.get(1) {
+            None | Some(Node::EmptyArgKind) => vec![1],
+            Some(node) => match self.get_integer_list(node, cell) {
+                Ok(indices) => indices,
+                Err(error) => return error,
+            },
+        };
+        let orders = match args.get(2) {
+            None | Some(Node::EmptyArgKind) => vec![1],
+            Some(node) => match self.get_integer_list(node, cell) {
+                Ok(orders) => orders,
+                Err(error) => return error,
+            },
+        };
+        if orders.iter().any(|order| *order != 1 && *order != -1)
+            || (orders.len() != 1 && orders.len() != indices.len())
+        {
+            return new_value_error(cell, "Sort order must be 1 or -1");
+        }
+        let by_column = match self.get_optional_boolean(args, 3, false, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let lines = if by_column { transpose(array) } else { array };
+        let width = lines.first().map_or(0, |line| line.len());
+        let mut keys = Vec::new();
+        for (position, index) in indices.iter().enumerate() {
+            if *index < 1 || *index as usize > width {
+                return new_value_error(cell, "Invalid sort index");
+            }
+            let order = if orders.len() == 1 {
+                orders[0]
+            } else {
+                orders[position]
+            };
+            let values = lines
+                .iter()
+                .map(|line| line[*index as usize - 1].clone())
+                .collect();
+            keys.push((values, order == -1));
+        }
+        let lines = sort_lines(lines, &keys);
+        CalcResult::Array(if by_column { transpose(lines) } else { lines })
+    }
+
+    /// SORTBY(array, by_array1, [sort_order1], [by_array2, sort_order2], ...)
+    pub(crate) fn fn_sortby(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let (rows, columns) = dimensions(&array);
+        let mut by_rows = None;
+        let mut keys = Vec::new();
+        for index in (1..args.len()).step_by(2) {
+            let by_array = match self.get_array_argument(&args[index], cell) {
+                Ok(by_array) => by_array,
+                Err(error) => return error,
+            };
+            let rows_key = match dimensions(&by_array) {
+                (by_rows, 1) if by_rows == rows => true,
+                (1, by_columns) if by_columns == columns => false,
+                _ => return new_value_error(cell, "Sort arrays have the wrong size"),
+            };
+            if *by_rows.get_or_insert(rows_key) != rows_key {
+                return new_value_error(cell, "Sort arrays have different directions");
+            }
+            let descending = match self.get_sort_order(args, index + 1, cell) {
+                Ok(descending) => descending,
+                Err(error) => return error,
+            };
+            keys.push((by_array.into_iter().flatten().collect(), descending));
+        }
+        if by_rows == Some(true) {
+            CalcResult::Array(sort_lines(array, &keys))
+        } else {
+            CalcResult::Array(transpose(sort_lines(transpose(array), &keys)))
+        }
+    }
+
+    /// UNIQUE(array, [by_col], [exactly_once])
+    pub(crate) fn fn_unique(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let by_column = match self.get_optional_boolean(args, 1, false, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let exactly_once = match self.get_optional_boolean(args, 2, false, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let lines = if by_column { transpose(array) } else { array };
+        // distinct lines in order of first appearance and the number of times they appear
+        let mut distinct: Vec<(Vec<CalcResult>, usize)> = Vec::new();
+        for line in lines {
+            match distinct.iter_mut().find(|(other, _)| {
+                other
+                    .iter()
+                    .zip(&line)
+                    .all(|(left, right)| same_value(left, right))
+            }) {
+                Some((_, count)) => *count += 1,
+                None => distinct.push((line, 1)),
+            }
+        }
+        let lines: Vec<Vec<CalcResult>> = distinct
+            .into_iter()
+            .filter(|(_, count)| !exactly_once || *count == 1)
+            .map(|(line, _)| line)
+            .collect();
+        if lines.is_empty() {
+            return new_empty_array_error(cell);
+        }
+        CalcResult::Array(if by_column { transpose(lines) } else { lines })
+    }
+
+    /// Size of an array built by SEQUENCE or RANDARRAY
+    fn get_array_size(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<(usize, usize), CalcResult> {
+        let rows = self.get_optional_number(args, 0, 1.0, cell)?.trunc();
+        let columns = self.get_optional_number(args, 1, 1.0, cell)?.trunc();
+        if rows == 0.0 || columns == 0.0 {
+            return Err(new_empty_array_error(cell));
+        }
+        if rows < 0.0
+            || columns < 0.0
+            || rows > LAST_ROW as f64
+            || columns > LAST_COLUMN as f64
+            || rows * columns > MAX_ARRAY_SIZE as f64
+        {
+            return Err(new_value_error(cell, "Invalid array size"));
+        }
+        Ok((rows as usize, columns as usize))
+    }
+
+    /// SEQUENCE(rows, [columns], [start], [step])
+    pub(crate) fn fn_sequence(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (rows, columns) = match self.get_array_size(args, cell) {
+            Ok(size) => size,
+            Err(error) => return error,
+        };
+        let start = match self.get_optional_number(args, 2, 1.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let step = match self.get_optional_number(args, 3, 1.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let result = (0..rows)
+            .map(|row| {
+                (0..columns)
+                    .map(|column| {
+                        CalcResult::Number(start + step * (row * columns + column) as f64)
+                    })
+                    .collect()
+            })
+            .collect();
+        CalcResult::Array(result)
+    }
+
+    /// RANDARRAY([rows], [columns], [min], [max], [whole_number])
+    pub(crate) fn fn_randarray(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() > 5 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (rows, columns) = match self.get_array_size(args, cell) {
+            Ok(size) => size,
+            Err(error) => return error,
+        };
+        let min = match self.get_optional_number(args, 2, 0.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let max = match self.get_optional_number(args, 3, 1.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let whole_number = match self.get_optional_boolean(args, 4, false, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        if min > max || (whole_number && (min.fract() != 0.0 || max.fract() != 0.0)) {
+            return new_value_error(cell, "Invalid bounds");
+        }
+        let mut result = Vec::new();
+        for _ in 0..rows {
+            let mut values = Vec::new();
+            for _ in 0..columns {
+                let random = self.environment.random();
+                values.push(CalcResult::Number(if whole_number {
+                    min + (random * (max - min + 1.0)).floor()
+                } else {
+                    min + random * (max - min)
+                }));
+            }
+            result.push(values);
+        }
+        CalcResult::Array(result)
+    }
+
+    /// TAKE(array, rows, [columns]) and DROP(array, rows, [columns])
+    fn take_or_drop(
+        &mut self,
+        args: &[Node],
+        positions: fn(Option<i64>, usize) -> Range<usize>,
+        cell: CellReference,
+    ) -> CalcResult {
+        if args.len() < 2 || args.len() > 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let rows = match self.get_optional_integer(args, 1, cell) {
+            Ok(rows) => rows,
+            Err(error) => return error,
+        };
+        let columns = match self.get_optional_integer(args, 2, cell) {
+            Ok(columns) => columns,
+            Err(error) => return error,
+        };
+        let (row_count, column_count) = dimensions(&array);
+        let rows = positions(rows, row_count);
+        let columns = positions(columns, column_count);
+        array_result(slice(array, rows, columns), cell)
+    }
+
+    /// TAKE(array, rows, [columns])
+    pub(crate) fn fn_take(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.take_or_drop(args, take_range, cell)
+    }
+
+    /// DROP(array, rows, [columns])
+    pub(crate) fn fn_drop(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.take_or_drop(args, drop_range, cell)
+    }
+
+    /// Lines of the array at the positions given by the arguments after the first one, negative
+    /// positions count from the end
+    fn choose_lines(
+        &mut self,
+        args: &[Node],
+        lines: Vec<Vec<CalcResult>>,
+        cell: CellReference,
+    ) -> Result<Vec<Vec<CalcResult>>, CalcResult> {
+        let size = lines.len() as i64;
+        let mut result = Vec::new();
+        for node in &args[1..] {
+            for position in self.get_integer_list(node, cell)? {
+                let index = if position > 0 {
+                    position - 1
+                } else {
+                    size + position
+                };
+                if position == 0 || index < 0 || index >= size {
+                    return Err(new_value_error(cell, "Invalid index"));
+                }
+                result.push(lines[index as usize].clone());
+            }
+        }
+        Ok(result)
+    }
+
+    /// CHOOSEROWS(array, row_num1, [row_num2], ...)
+    pub(crate) fn fn_chooserows(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        match self.choose_lines(args, array, cell) {
+            Ok(rows) => CalcResult::Array(rows),
+            Err(error) => error,
+        }
+    }
+
+    /// CHOOSECOLS(array, col_num1, [col_num2], ...)
+    pub(crate) fn fn_choosecols(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        match self.choose_lines(args, transpose(array), cell) {
+            Ok(columns) => CalcResult::Array(transpose(columns)),
+            Err(error) => error,
+        }
+    }
+
+    fn get_array_arguments(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<Vec<Vec<CalcResult>>>, CalcResult> {
+        args.iter()
+            .map(|node| self.get_array_argument(node, cell))
+            .collect()
+    }
+
+    /// VSTACK(array1, [array2], ...)
+    /// Arrays with fewer columns are padded with #N/A
+    pub(crate) fn fn_vstack(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let arrays = match self.get_array_arguments(args, cell) {
+            Ok(arrays) => arrays,
+            Err(error) => return error,
+        };
+        let width = arrays
+            .iter()
+            .map(|array| dimensions(array).1)
+            .max()
+            .unwrap_or(0);
+        let mut result = Vec::new();
+        for array in arrays {
+            for mut row in array {
+                row.resize_with(width, || new_not_available(cell));
+                result.push(row);
+            }
+        }
+        CalcResult::Array(result)
+    }
+
+    /// HSTACK(array1, [array2], ...)
+    /// Arrays with fewer rows are padded with #N/A
+    pub(crate) fn fn_hstack(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let arrays = match self.get_array_arguments(args, cell) {
+            Ok(arrays) => arrays,
+            Err(error) => return error,
+        };
+        let height = arrays.iter().map(|array| array.len()).max().unwrap_or(0);
+        let mut result = vec![Vec::new(); height];
+        for array in arrays {
+            let width = dimensions(&array).1;
+            let mut rows = array.into_iter();
+            for result_row in result.iter_mut() {
+                match rows.next() {
+                    Some(row) => result_row.extend(row),
+                    None => result_row.extend((0..width).map(|_| new_not_available(cell))),
+                }
+            }
+        }
+        CalcResult::Array(result)
+    }
+
+    /// Values of TOCOL and TOROW in order
+    fn get_vector_values(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<CalcResult>, CalcResult> {
+        if args.is_empty() || args.len() > 3 {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let array = self.get_array_argument(&args[0], cell)?;
+        // 0: keep all values, 1: ignore blanks, 2: ignore errors, 3: ignore blanks and errors
+        let ignore = self.get_optional_number(args, 1, 0.0, cell)?.trunc();
+        if !(0.0..=3.0).contains(&ignore) {
+            return Err(new_value_error(cell, "Invalid ignore value"));
+        }
+        let ignore = ignore as u8;
+        let scan_by_column = self.get_optional_boolean(args, 2, false, cell)?;
+        let array = if scan_by_column {
+            transpose(array)
+        } else {
+            array
+        };
+        let values: Vec<CalcResult> = array
+            .into_iter()
+            .flatten()
+            .filter(|value| match value {
+                CalcResult::EmptyCell | CalcResult::EmptyArg => ignore & 1 == 0,
+                CalcResult::Error { .. } => ignore & 2 == 0,
+                _ => true,
+            })
+            .collect();
+        if values.is_empty() {
+            return Err(new_empty_array_error(cell));
+        }
+        Ok(values)
+    }
+
+    /// TOCOL(array, [ignore], [scan_by_column])
+    pub(crate) fn fn_tocol(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_vector_values(args, cell) {
+            Ok(values) => CalcResult::Array(values.into_iter().map(|value| vec![value]).collect()),
+            Err(error) => error,
+        }
+    }
+
+    /// TOROW(array, [ignore], [scan_by_column])
+    pub(crate) fn fn_torow(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_vector_values(args, cell) {
+            Ok(values) => CalcResult::Array(vec![values]),
+            Err(error) => error,
+        }
+    }
+
+    /// The values of a vector in lines of `wrap_count` values, the last one padded
+    fn wrap_vector(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<Vec<CalcResult>>, CalcResult> {
+        if args.len() < 2 || args.len() > 3 {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let vector = self.get_array_argument(&args[0], cell)?;
+        let (rows, columns) = dimensions(&vector);
+        if rows != 1 && columns != 1 {
+            return Err(new_value_error(cell, "Expecting a row or a column"));
+        }
+        let wrap_count = self.get_number(&args[1], cell)?.trunc();
+        if wrap_count < 1.0 || wrap_count > LAST_ROW as f64 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Invalid wrap count".to_string(),
+            ));
+        }
+        let pad_with = match args.get(2) {
+            None | Some(Node::EmptyArgKind) => new_not_available(cell),
+            Some(node) => array_top_left(self.get_array_argument(node, cell)?, cell),
+        };
+        let values: Vec<CalcResult> = vector.into_iter().flatten().collect();
+        let wrap_count = wrap_count as usize;
+        Ok(values
+            .chunks(wrap_count)
+            .map(|chunk| {
+                let mut line = chunk.to_vec();
+                line.resize(wrap_count, pad_with.clone());
+                line
+            })
+            .collect())
+    }
+
+    /// WRAPROWS(vector, wrap_count, [pad_with])
+    pub(crate) fn fn_wraprows(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.wrap_vector(args, cell) {
+            Ok(rows) => CalcResult::Array(rows),
+            Err(error) => error,
+        }
+    }
+
+    /// WRAPCOLS(vector, wrap_count, [pad_with])
+    pub(crate) fn fn_wrapcols(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.wrap_vector(args, cell) {
+            Ok(columns) => CalcResult::Array(transpose(columns)),
+            Err(error) => error,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::gamma::ln_gamma;
+
+// See Numerical Recipes, 3rd edition, section 6.4
+
+const MAX_ITERATIONS: usize = 100_000;
+const EPSILON: f64 = f64::EPSILON;
+const FLOAT_MIN: f64 = f64::MIN_POSITIVE / EPSILON;
+
+/// Logarithm of the beta function B(a, b) = Γ(a)Γ(b) / Γ(a + b), for a, b > 0
+pub(crate) fn ln_beta(a: f64, b: f64) -> f64 {
+    ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+}
+
+// Continued fraction for the incomplete beta function (modified Lentz's method)
+fn incomplete_beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    let qab = a + b;
+    let qap = a + 1.0;
+    let qam = a - 1.0;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < FLOAT_MIN {
+        d = FLOAT_MIN;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        // even step
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FLOAT_MIN {
+            d = FLOAT_MIN;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < FLOAT_MIN {
+            c = FLOAT_MIN;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+        // odd step
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < FLOAT_MIN {
+            d = FLOAT_MIN;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < FLOAT_MIN {
+            c = FLOAT_MIN;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b), for 0 <= x <= 1 and a, b > 0
+pub(crate) fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    // x^a (1-x)^b / B(a, b)
+    let factor = (a * x.ln() + b * (-x).ln_1p() - ln_beta(a, b)).exp();
+    // The continued fraction converges quickly for x < (a + 1) / (a + b + 2),
+    // otherwise we use the symmetry I_x(a, b) = 1 - I_(1-x)(b, a)
+    if x < (a + 1.0) / (a + b + 2.0) {
+        factor * incomplete_beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - factor * incomplete_beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
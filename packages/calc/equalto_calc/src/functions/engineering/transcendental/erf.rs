@@ -1,4 +1,5 @@
-pub(crate) fn erf(x: f64) -> f64 {
+// Complementary error function for x >= 0
+fn erfc_positive(x_abs: f64) -> f64 {
     let cof = vec![
         -1.3026537197817094,
         6.419_697_923_564_902e-1,
@@ -33,8 +34,6 @@ pub(crate) fn erf(x: f64) -> f64 {
     let mut d = 0.0;
     let mut dd = 0.0;
 
-    let x_abs = x.abs();
-
     let t = 2.0 / (2.0 + x_abs);
     let ty = 4.0 * t - 2.0;
 
@@ -44,10 +43,24 @@ pub(crate) fn erf(x: f64) -> f64 {
         dd = tmp;
     }
 
-    let res = t * f64::exp(-x_abs * x_abs + 0.5 * (cof[0] + ty * d) - dd);
+    t * f64::exp(-x_abs * x_abs + 0.5 * (cof[0] + ty * d) - dd)
+}
+
+pub(crate) fn erf(x: f64) -> f64 {
+    let res = erfc_positive(x.abs());
     if x < 0.0 {
         res - 1.0
     } else {
         1.0 - res
     }
 }
+
+/// Complementary error function. Unlike 1 - erf(x) it keeps full precision for large x.
+pub(crate) fn erfc(x: f64) -> f64 {
+    let res = erfc_positive(x.abs());
+    if x < 0.0 {
+        2.0 - res
+    } else {
+        res
+    }
+}
@@ -0,0 +1,131 @@
+use std::f64::consts::PI;
+
+// Lanczos approximation with g = 7 and 9 coefficients
+// See:
+// https://en.wikipedia.org/wiki/Lanczos_approximation
+// Numerical Recipes, 3rd edition, section 6.1 and 6.2
+
+const LANCZOS_G: f64 = 7.0;
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+const MAX_ITERATIONS: usize = 100_000;
+const EPSILON: f64 = f64::EPSILON;
+const FLOAT_MIN: f64 = f64::MIN_POSITIVE / EPSILON;
+
+// Returns (t, sum) where Γ(x+1) = sqrt(2π) t^(x+1/2) e^(-t) sum
+fn lanczos(x: f64) -> (f64, f64) {
+    let mut sum = LANCZOS_COEFFICIENTS[0];
+    for (i, coefficient) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+    (x + LANCZOS_G + 0.5, sum)
+}
+
+/// Logarithm of the absolute value of the gamma function.
+/// Returns infinity at zero and the negative integers.
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::INFINITY;
+    }
+    if x < 0.5 {
+        // Reflection formula: Γ(x)Γ(1-x) = π / sin(πx)
+        return (PI / (PI * x).sin().abs()).ln() - ln_gamma(1.0 - x);
+    }
+    let (t, sum) = lanczos(x - 1.0);
+    0.5 * (2.0 * PI).ln() + (x - 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The gamma function. Returns NaN at zero and the negative integers.
+pub(crate) fn gamma(x: f64) -> f64 {
+    if x <= 0.0 && x.fract() == 0.0 {
+        return f64::NAN;
+    }
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    let (t, sum) = lanczos(x - 1.0);
+    // t^(x-1/2) is computed in two halves to avoid a premature overflow
+    let power = t.powf((x - 0.5) / 2.0);
+    (2.0 * PI).sqrt() * power * (power * (-t).exp()) * sum
+}
+
+// e^(-x) x^a / Γ(a), the common factor of the series and the continued fraction
+fn incomplete_gamma_factor(a: f64, x: f64) -> f64 {
+    (a * x.ln() - x - ln_gamma(a)).exp()
+}
+
+// Series expansion of P(a, x), converges quickly for x < a + 1
+fn incomplete_gamma_series(a: f64, x: f64) -> f64 {
+    let mut denominator = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    for _ in 0..MAX_ITERATIONS {
+        denominator += 1.0;
+        term *= x / denominator;
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+    sum * incomplete_gamma_factor(a, x)
+}
+
+// Continued fraction of Q(a, x) (modified Lentz's method), converges quickly for x > a + 1
+fn incomplete_gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / FLOAT_MIN;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let i = i as f64;
+        let an = -i * (i - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < FLOAT_MIN {
+            d = FLOAT_MIN;
+        }
+        c = b + an / c;
+        if c.abs() < FLOAT_MIN {
+            c = FLOAT_MIN;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    incomplete_gamma_factor(a, x) * h
+}
+
+/// Regularized lower incomplete gamma function P(a, x) = γ(a, x) / Γ(a), for a > 0
+pub(crate) fn regularized_gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        incomplete_gamma_series(a, x)
+    } else {
+        1.0 - incomplete_gamma_continued_fraction(a, x)
+    }
+}
+
+/// Regularized upper incomplete gamma function Q(a, x) = 1 - P(a, x), for a > 0
+pub(crate) fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - incomplete_gamma_series(a, x)
+    } else {
+        incomplete_gamma_continued_fraction(a, x)
+    }
+}
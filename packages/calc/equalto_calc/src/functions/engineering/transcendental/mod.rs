@@ -4,13 +4,20 @@ mod bessel_j1_y1;
 mod bessel_jn_yn;
 mod bessel_k;
 mod bessel_util;
+mod beta;
 mod erf;
+mod gamma;
 
 #[cfg(test)]
 mod test_bessel;
 
+#[cfg(test)]
+mod test_gamma;
+
 pub(crate) use bessel_i::bessel_i;
 pub(crate) use bessel_jn_yn::jn as bessel_j;
 pub(crate) use bessel_jn_yn::yn as bessel_y;
 pub(crate) use bessel_k::bessel_k;
-pub(crate) use erf::erf;
+pub(crate) use beta::{ln_beta, regularized_beta};
+pub(crate) use erf::{erf, erfc};
+pub(crate) use gamma::{gamma, ln_gamma, regularized_gamma_p, regularized_gamma_q};
@@ -0,0 +1,148 @@
+use super::{
+    beta::regularized_beta,
+    erf::erfc,
+    gamma::{gamma, ln_gamma, regularized_gamma_p, regularized_gamma_q},
+};
+
+const EPS: f64 = 1e-13;
+const EPS_LOW: f64 = 1e-10;
+
+// Known values computed with mpmath in Python (50 digits of precision)
+// Please do not use Excel or any other software without arbitrary precision
+
+fn numbers_are_close(a: f64, b: f64) -> bool {
+    if a == b {
+        // avoid underflow if a = b = 0.0
+        return true;
+    }
+    (a - b).abs() / ((a * a + b * b).sqrt()) < EPS
+}
+
+fn numbers_are_somewhat_close(a: f64, b: f64) -> bool {
+    if a == b {
+        // avoid underflow if a = b = 0.0
+        return true;
+    }
+    (a - b).abs() / ((a * a + b * b).sqrt()) < EPS_LOW
+}
+
+#[test]
+fn ln_gamma_known_values() {
+    let cases = [
+        (0.1, 2.252712651734206),
+        (0.5, 0.5723649429247001),
+        (3.5, 1.2009736023470743),
+        (4.0, 1.791759469228055),
+        (10.3, 13.482036786138357),
+        (100.0, 359.1342053695754),
+        (1e5, 1051287.7089736569),
+        (1e-8, 18.42068073818021),
+        (-2.5, -0.056243716497674054),
+        (-0.3, 1.4648400508576025),
+    ];
+    for (value, known) in cases {
+        let f = ln_gamma(value);
+        assert!(
+            numbers_are_close(f, known),
+            "Got: {f}, expected: {known} for ln_gamma({value})"
+        );
+    }
+    assert_eq!(ln_gamma(0.0), f64::INFINITY);
+    assert_eq!(ln_gamma(-3.0), f64::INFINITY);
+}
+
+#[test]
+fn gamma_known_values() {
+    let cases = [
+        (0.1, 9.513507698668732),
+        (0.5, 1.772453850905516),
+        (2.5, 1.329340388179137),
+        (5.0, 24.0),
+        (10.3, 716430.6890623752),
+        (-3.75, 0.2678661288614166),
+        (-0.5, -3.544907701811032),
+        (100.5, 9.320963104082716e+156),
+        (170.2, 1.1918411166367392e+305),
+        (1e-8, 99999999.42278434),
+    ];
+    for (value, known) in cases {
+        let f = gamma(value);
+        assert!(
+            numbers_are_somewhat_close(f, known),
+            "Got: {f}, expected: {known} for gamma({value})"
+        );
+    }
+    assert!(gamma(0.0).is_nan());
+    assert!(gamma(-2.0).is_nan());
+    assert_eq!(gamma(172.0), f64::INFINITY);
+}
+
+#[test]
+fn regularized_gamma_known_values() {
+    // (a, x, P(a, x), Q(a, x))
+    let cases = [
+        (0.5, 0.1, 0.34527915398142295, 0.654720846018577),
+        (1.0, 1.0, 0.6321205588285577, 0.36787944117144233),
+        (3.0, 2.5, 0.45618688411667047, 0.5438131158833295),
+        (9.0, 5.0000057, 0.06809400680730911, 0.9319059931926909),
+        (10.0, 30.0, 0.9999928782491372, 7.121750862815577e-06),
+        (100.0, 90.0, 0.15822098918643016, 0.8417790108135699),
+        (2.5, 0.001, 9.50853459860795e-09, 0.9999999904914654),
+        (1000.0, 1100.0, 0.99894067674607, 0.0010593232539299773),
+        (0.1, 10.0, 0.9999994452014282, 5.547985717901905e-07),
+    ];
+    for (a, x, known_p, known_q) in cases {
+        let p = regularized_gamma_p(a, x);
+        assert!(
+            numbers_are_somewhat_close(p, known_p),
+            "Got: {p}, expected: {known_p} for P({a}, {x})"
+        );
+        let q = regularized_gamma_q(a, x);
+        assert!(
+            numbers_are_somewhat_close(q, known_q),
+            "Got: {q}, expected: {known_q} for Q({a}, {x})"
+        );
+    }
+}
+
+#[test]
+fn regularized_beta_known_values() {
+    // (x, a, b, I_x(a, b))
+    let cases = [
+        (0.5, 8.0, 10.0, 0.6854705810546875),
+        (0.1, 0.5, 0.5, 0.20483276469913345),
+        (0.9, 2.0, 3.0, 0.9963),
+        (0.3, 30.0, 40.0, 0.012126160623329718),
+        (0.001, 2.0, 100.0, 0.0047286381749200536),
+        (0.999, 5.0, 0.5, 0.9222819921009668),
+        (0.5, 500.0, 500.0, 0.5),
+        (0.0, 2.0, 3.0, 0.0),
+        (1.0, 2.0, 3.0, 1.0),
+    ];
+    for (x, a, b, known) in cases {
+        let f = regularized_beta(x, a, b);
+        assert!(
+            numbers_are_somewhat_close(f, known),
+            "Got: {f}, expected: {known} for I_{x}({a}, {b})"
+        );
+    }
+}
+
+#[test]
+fn erfc_known_values() {
+    let cases = [
+        (0.0, 1.0),
+        (0.5, 0.4795001221869535),
+        (-1.0, 1.8427007929497148),
+        (3.0, 2.209049699858544e-05),
+        (10.0, 2.088487583762545e-45),
+        (15.0, 7.212994172451207e-100),
+    ];
+    for (value, known) in cases {
+        let f = erfc(value);
+        assert!(
+            numbers_are_somewhat_close(f, known),
+            "Got: {f}, expected: {known} for erfc({value})"
+        );
+    }
+}
@@ -18,12 +18,12 @@ fn is_valid_date(date: f64) -> bool {
     date > 0.0
 }
 
-fn is_less_than_one_year(start_date: i64, end_date: i64) -> bool {
+fn is_less_than_one_year(start_date: i64, end_date: i64, date1904: bool) -> bool {
     if end_date - start_date < 365 {
         return true;
     }
-    let end = from_excel_date(end_date);
-    let start = from_excel_date(start_date);
+    let end = from_excel_date(end_date, date1904);
+    let start = from_excel_date(start_date, date1904);
     let end_year = end.year();
     let start_year = start.year();
     if end_year == start_year {
@@ -1436,7 +1436,8 @@ impl Model {
                 "settlement should be <= maturity".to_string(),
             );
         }
-        if !is_less_than_one_year(settlement as i64, maturity as i64) {
+        let date1904 = self.workbook.settings.date1904;
+        if !is_less_than_one_year(settlement as i64, maturity as i64, date1904) {
             return CalcResult::new_error(
                 Error::NUM,
                 cell,
@@ -1500,7 +1501,8 @@ impl Model {
                 "settlement should be <= maturity".to_string(),
             );
         }
-        if !is_less_than_one_year(settlement as i64, maturity as i64) {
+        let date1904 = self.workbook.settings.date1904;
+        if !is_less_than_one_year(settlement as i64, maturity as i64, date1904) {
             return CalcResult::new_error(
                 Error::NUM,
                 cell,
@@ -1550,7 +1552,8 @@ impl Model {
                 "settlement should be <= maturity".to_string(),
             );
         }
-        if !is_less_than_one_year(settlement as i64, maturity as i64) {
+        let date1904 = self.workbook.settings.date1904;
+        if !is_less_than_one_year(settlement as i64, maturity as i64, date1904) {
             return CalcResult::new_error(
                 Error::NUM,
                 cell,
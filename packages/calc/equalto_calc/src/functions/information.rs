@@ -114,9 +114,10 @@ impl Model {
             return CalcResult::new_args_number_error(cell);
         }
         match &args[0] {
-            Node::ReferenceKind { .. } | Node::RangeKind { .. } | Node::OpRangeKind { .. } => {
-                CalcResult::Boolean(true)
-            }
+            Node::ReferenceKind { .. }
+            | Node::RangeKind { .. }
+            | Node::OpRangeKind { .. }
+            | Node::SpillRefKind(_) => CalcResult::Boolean(true),
             Node::FunctionKind { kind, args: _ } => CalcResult::Boolean(kind.returns_reference()),
             _ => CalcResult::Boolean(false),
         }
@@ -229,7 +230,7 @@ impl Model {
             CalcResult::Number(_) => CalcResult::Number(1.0),
             CalcResult::Boolean(_) => CalcResult::Number(4.0),
             CalcResult::Error { .. } => CalcResult::Number(16.0),
-            CalcResult::Range { .. } => CalcResult::Number(64.0),
+            CalcResult::Range { .. } | CalcResult::Array(_) => CalcResult::Number(64.0),
             CalcResult::EmptyCell => CalcResult::Number(1.0),
             CalcResult::EmptyArg => {
                 // This cannot happen
@@ -259,7 +260,20 @@ impl Model {
                     ParsedDefinedName::RangeReference(range) => {
                         return CalcResult::Number(range.left.sheet as f64 + 1.0)
                     }
-                    ParsedDefinedName::InvalidDefinedNameFormula => {
+                    ParsedDefinedName::Formula(node) => {
+                        let node = node.clone();
+                        if let CalcResult::Range { left, .. } =
+                            self.evaluate_defined_name_formula(name, &node, cell)
+                        {
+                            return CalcResult::Number(left.sheet as f64 + 1.0);
+                        }
+                        return CalcResult::Error {
+                            error: Error::NA,
+                            origin: cell,
+                            message: "Invalid name".to_string(),
+                        };
+                    }
+                    ParsedDefinedName::Lambda(_) | ParsedDefinedName::InvalidDefinedNameFormula => {
                         return CalcResult::Error {
                             error: Error::NA,
                             origin: cell,
@@ -0,0 +1,422 @@
+//! LET, LAMBDA and the LAMBDA helper functions (MAP, REDUCE, MAKEARRAY, ...).
+
+use crate::{
+    arrays::broadcast_element,
+    calc_result::{CalcResult, CellReference},
+    constants::{LAST_COLUMN, LAST_ROW, MAX_ARRAY_SIZE},
+    expressions::{parser::Node, token::Error},
+    model::{Model, ParsedDefinedName},
+};
+
+use super::Function;
+
+/// Maximum depth of nested LAMBDA calls, recursive named lambdas stop here
+pub(crate) const LAMBDA_MAX_DEPTH: usize = 64;
+
+#[derive(Clone)]
+pub(crate) struct Lambda {
+    /// Names of the parameters in lower case
+    parameters: Vec<String>,
+    body: Node,
+    /// Bindings in scope where the LAMBDA was defined
+    closure: Vec<(String, LocalValue)>,
+}
+
+/// The value bound to a LET name or to a LAMBDA parameter
+#[derive(Clone)]
+pub(crate) enum LocalValue {
+    Value(CalcResult),
+    Lambda(Lambda),
+}
+
+fn new_lambda_must_be_called_error(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::CALC, cell, "LAMBDA must be called".to_string())
+}
+
+fn new_expected_lambda_error(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::VALUE, cell, "Expected a LAMBDA".to_string())
+}
+
+impl Model {
+    /// Value bound to the LET name or LAMBDA parameter `name`
+    fn get_local_value(&self, name: &str) -> Option<&LocalValue> {
+        let name = name.to_lowercase();
+        self.locals
+            .iter()
+            .rev()
+            .find(|(local, _)| local == &name)
+            .map(|(_, value)| value)
+    }
+
+    /// Evaluates a LET name or LAMBDA parameter used as a value
+    pub(crate) fn evaluate_local_variable(&self, name: &str, cell: CellReference) -> CalcResult {
+        match self.get_local_value(name) {
+            Some(LocalValue::Value(value)) => value.clone(),
+            Some(LocalValue::Lambda(_)) => new_lambda_must_be_called_error(cell),
+            None => {
+                CalcResult::new_error(Error::NAME, cell, format!("Name \"{}\" not found", name))
+            }
+        }
+    }
+
+    /// Returns the LAMBDA defined as the name `name` in the workbook, if any
+    pub(crate) fn get_defined_lambda(&self, name: &str, cell: CellReference) -> Option<Lambda> {
+        let name = name.to_lowercase();
+        let parsed_defined_name = self
+            .parsed_defined_names
+            .get(&(Some(cell.sheet), name.clone()))
+            .or_else(|| self.parsed_defined_names.get(&(None, name)))?;
+        match parsed_defined_name {
+            ParsedDefinedName::Lambda(Node::FunctionKind {
+                kind: Function::Lambda,
+                args,
+            }) => new_lambda(args, Vec::new()),
+            _ => None,
+        }
+    }
+
+    /// Returns the LAMBDA that `node` evaluates to: a LAMBDA function, a LET name bound to a
+    /// LAMBDA or a LAMBDA defined in the workbook.
+    fn get_lambda(&self, node: &Node, cell: CellReference) -> Result<Lambda, CalcResult> {
+        let lambda = match node {
+            Node::FunctionKind {
+                kind: Function::Lambda,
+                args,
+            } => new_lambda(args, self.locals.clone()),
+            Node::LocalVariableKind(name) => match self.get_local_value(name) {
+                Some(LocalValue::Lambda(lambda)) => Some(lambda.clone()),
+                _ => None,
+            },
+            Node::VariableKind(name) => self.get_defined_lambda(name, cell),
+            _ => None,
+        };
+        lambda.ok_or_else(|| new_expected_lambda_error(cell))
+    }
+
+    /// Evaluates `node` as the value of a LET name or the argument of a LAMBDA call
+    fn evaluate_local(&mut self, node: &Node, cell: CellReference) -> LocalValue {
+        match self.get_lambda(node, cell) {
+            Ok(lambda) => LocalValue::Lambda(lambda),
+            Err(_) => LocalValue::Value(self.evaluate_node_in_context(node, cell)),
+        }
+    }
+
+    /// Calls `lambda` with `args`. The body is evaluated with the bindings of the closure
+    /// and the parameters only.
+    fn call_lambda(
+        &mut self,
+        lambda: &Lambda,
+        args: Vec<LocalValue>,
+        cell: CellReference,
+    ) -> CalcResult {
+        if args.len() != lambda.parameters.len() {
+            return CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "Wrong number of arguments for LAMBDA".to_string(),
+            );
+        }
+        if self.lambda_depth >= LAMBDA_MAX_DEPTH {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Maximum LAMBDA call depth exceeded".to_string(),
+            );
+        }
+        let mut locals = lambda.closure.clone();
+        locals.extend(lambda.parameters.iter().cloned().zip(args));
+        let locals = std::mem::replace(&mut self.locals, locals);
+        self.lambda_depth += 1;
+        let result = self.evaluate_node_in_context(&lambda.body, cell);
+        self.lambda_depth -= 1;
+        self.locals = locals;
+        result
+    }
+
+    /// Calls `lambda` with `args` and returns a single value. Used by the helper functions,
+    /// whose LAMBDA must return a value per element.
+    fn call_lambda_with_values(
+        &mut self,
+        lambda: &Lambda,
+        args: Vec<CalcResult>,
+        cell: CellReference,
+    ) -> CalcResult {
+        let args = args.into_iter().map(LocalValue::Value).collect();
+        match self.call_lambda(lambda, args, cell) {
+            CalcResult::Range { left, right } if left == right => self.evaluate_cell(left),
+            CalcResult::Range { .. } | CalcResult::Array(_) => CalcResult::new_error(
+                Error::CALC,
+                cell,
+                "Nested arrays are not supported".to_string(),
+            ),
+            result => result,
+        }
+    }
+
+    /// Evaluates the call `lambda(args)`
+    pub(crate) fn evaluate_lambda_call(
+        &mut self,
+        lambda: &Node,
+        args: &[Node],
+        cell: CellReference,
+    ) -> CalcResult {
+        let lambda = match self.get_lambda(lambda, cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let args = args
+            .iter()
+            .map(|arg| self.evaluate_local(arg, cell))
+            .collect();
+        self.call_lambda(&lambda, args, cell)
+    }
+
+    /// Evaluates a call to a LAMBDA defined as a name in the workbook, `=MYTAX(A1)`
+    pub(crate) fn evaluate_defined_lambda_call(
+        &mut self,
+        lambda: &Lambda,
+        args: &[Node],
+        cell: CellReference,
+    ) -> CalcResult {
+        let args = args
+            .iter()
+            .map(|arg| self.evaluate_local(arg, cell))
+            .collect();
+        self.call_lambda(lambda, args, cell)
+    }
+
+    /// LET(name1, value1, [name2, value2, ...], calculation)
+    pub(crate) fn fn_let(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 3 || args.len().is_multiple_of(2) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let depth = self.locals.len();
+        for binding in args[..args.len() - 1].chunks(2) {
+            let name = match &binding[0] {
+                Node::LocalVariableKind(name) => name.to_lowercase(),
+                _ => {
+                    self.locals.truncate(depth);
+                    return CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Expected a name".to_string(),
+                    );
+                }
+            };
+            let value = self.evaluate_local(&binding[1], cell);
+            self.locals.push((name, value));
+        }
+        let result = self.evaluate_node_in_context(&args[args.len() - 1], cell);
+        self.locals.truncate(depth);
+        result
+    }
+
+    /// LAMBDA([parameter1, ...], calculation)
+    /// A LAMBDA that is not called, `=LAMBDA(x, x+1)`, is an error
+    pub(crate) fn fn_lambda(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        new_lambda_must_be_called_error(cell)
+    }
+
+    /// MAP(array1, [array2, ...], lambda)
+    /// Applies the LAMBDA to the elements of the arrays, that are broadcast to the same size
+    pub(crate) fn fn_map(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let lambda = match self.get_lambda(&args[args.len() - 1], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let mut arrays = Vec::new();
+        for arg in &args[..args.len() - 1] {
+            match self.get_array_argument(arg, cell) {
+                Ok(array) => arrays.push(CalcResult::Array(array)),
+                Err(error) => return error,
+            }
+        }
+        let mut rows = 0;
+        let mut columns = 0;
+        for array in &arrays {
+            if let CalcResult::Array(array) = array {
+                rows = rows.max(array.len());
+                columns = columns.max(array.first().map_or(0, |row| row.len()));
+            }
+        }
+        let mut result = Vec::new();
+        for row in 0..rows {
+            let mut values = Vec::new();
+            for column in 0..columns {
+                let mut lambda_args = Vec::new();
+                for array in &arrays {
+                    lambda_args.push(broadcast_element(array, row, column).unwrap_or_else(|| {
+                        CalcResult::new_error(
+                            Error::NA,
+                            cell,
+                            "Arrays of different sizes".to_string(),
+                        )
+                    }));
+                }
+                values.push(self.call_lambda_with_values(&lambda, lambda_args, cell));
+            }
+            result.push(values);
+        }
+        CalcResult::Array(result)
+    }
+
+    /// REDUCE([initial_value], array, lambda(accumulator, value))
+    pub(crate) fn fn_reduce(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let mut accumulator = self.evaluate_node_in_context(&args[0], cell);
+        let array = match self.get_array_argument(&args[1], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let lambda = match self.get_lambda(&args[2], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        for value in array.into_iter().flatten() {
+            accumulator = self.call_lambda_with_values(&lambda, vec![accumulator, value], cell);
+        }
+        accumulator
+    }
+
+    /// SCAN([initial_value], array, lambda(accumulator, value))
+    /// Like REDUCE but returns an array with all the intermediate values
+    pub(crate) fn fn_scan(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let mut accumulator = self.evaluate_node_in_context(&args[0], cell);
+        let array = match self.get_array_argument(&args[1], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let lambda = match self.get_lambda(&args[2], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let mut result = Vec::new();
+        for row in array {
+            let mut values = Vec::new();
+            for value in row {
+                accumulator = self.call_lambda_with_values(&lambda, vec![accumulator, value], cell);
+                values.push(accumulator.clone());
+            }
+            result.push(values);
+        }
+        CalcResult::Array(result)
+    }
+
+    /// BYROW(array, lambda(row))
+    /// Returns a column with the result of the LAMBDA on each row
+    pub(crate) fn fn_byrow(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let lambda = match self.get_lambda(&args[1], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let mut result = Vec::new();
+        for row in array {
+            let value = CalcResult::Array(vec![row]);
+            result.push(vec![self.call_lambda_with_values(
+                &lambda,
+                vec![value],
+                cell,
+            )]);
+        }
+        CalcResult::Array(result)
+    }
+
+    /// BYCOL(array, lambda(column))
+    /// Returns a row with the result of the LAMBDA on each column
+    pub(crate) fn fn_bycol(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let lambda = match self.get_lambda(&args[1], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let columns = array.first().map_or(0, |row| row.len());
+        let mut result = Vec::new();
+        for column in 0..columns {
+            let value =
+                CalcResult::Array(array.iter().map(|row| vec![row[column].clone()]).collect());
+            result.push(self.call_lambda_with_values(&lambda, vec![value], cell));
+        }
+        CalcResult::Array(vec![result])
+    }
+
+    /// MAKEARRAY(rows, columns, lambda(row, column))
+    pub(crate) fn fn_makearray(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let rows = match self.get_number(&args[0], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        let columns = match self.get_number(&args[1], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        if rows < 1.0
+            || columns < 1.0
+            || rows > LAST_ROW as f64
+            || columns > LAST_COLUMN as f64
+            || rows * columns > MAX_ARRAY_SIZE as f64
+        {
+            return CalcResult::new_error(Error::VALUE, cell, "Invalid array size".to_string());
+        }
+        let lambda = match self.get_lambda(&args[2], cell) {
+            Ok(lambda) => lambda,
+            Err(error) => return error,
+        };
+        let mut result = Vec::new();
+        for row in 1..=(rows as i32) {
+            let mut values = Vec::new();
+            for column in 1..=(columns as i32) {
+                let args = vec![
+                    CalcResult::Number(row as f64),
+                    CalcResult::Number(column as f64),
+                ];
+                values.push(self.call_lambda_with_values(&lambda, args, cell));
+            }
+            result.push(values);
+        }
+        CalcResult::Array(result)
+    }
+}
+
+/// The LAMBDA with the arguments `args` of a LAMBDA function
+fn new_lambda(args: &[Node], closure: Vec<(String, LocalValue)>) -> Option<Lambda> {
+    let (body, parameters) = args.split_last()?;
+    let parameters = parameters
+        .iter()
+        .map(|parameter| match parameter {
+            Node::LocalVariableKind(name) => Some(name.to_lowercase()),
+            _ => None,
+        })
+        .collect::<Option<Vec<String>>>()?;
+    Some(Lambda {
+        parameters,
+        body: body.clone(),
+        closure,
+    })
+}
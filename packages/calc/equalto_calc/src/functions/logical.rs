@@ -18,8 +18,10 @@ impl Model {
                 }
             };
             if cond {
+                self.trace_branch(cell, 1);
                 return self.evaluate_node_in_context(&args[1], cell);
             } else if args.len() == 3 {
+                self.trace_branch(cell, 2);
                 return self.evaluate_node_in_context(&args[2], cell);
             } else {
                 return CalcResult::Boolean(false);
@@ -33,9 +35,13 @@ impl Model {
             let value = self.evaluate_node_in_context(&args[0], cell);
             match value {
                 CalcResult::Error { .. } => {
+                    self.trace_branch(cell, 1);
                     return self.evaluate_node_in_context(&args[1], cell);
                 }
-                _ => return value,
+                _ => {
+                    self.trace_branch(cell, 0);
+                    return value;
+                }
             }
         }
         CalcResult::new_args_number_error(cell)
@@ -46,9 +52,11 @@ impl Model {
             let value = self.evaluate_node_in_context(&args[0], cell);
             if let CalcResult::Error { error, .. } = &value {
                 if error == &Error::NA {
+                    self.trace_branch(cell, 1);
                     return self.evaluate_node_in_context(&args[1], cell);
                 }
             }
+            self.trace_branch(cell, 0);
             return value;
         }
         CalcResult::new_args_number_error(cell)
@@ -70,6 +78,29 @@ impl Model {
         let mut true_count = 0;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Boolean(b) => {
+                                if !b {
+                                    return CalcResult::Boolean(false);
+                                }
+                                true_count += 1;
+                            }
+                            CalcResult::Number(value) => {
+                                if *value == 0.0 {
+                                    return CalcResult::Boolean(false);
+                                }
+                                true_count += 1;
+                            }
+                            CalcResult::String(_value) => {
+                                true_count += 1;
+                            }
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {}
+                        }
+                    }
+                }
                 CalcResult::Boolean(b) => {
                     if !b {
                         return CalcResult::Boolean(false);
@@ -116,7 +147,7 @@ impl Model {
                                     true_count += 1;
                                 }
                                 error @ CalcResult::Error { .. } => return error,
-                                CalcResult::Range { .. } => {}
+                                CalcResult::Range { .. } | CalcResult::Array(_) => {}
                                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
                             }
                         }
@@ -140,6 +171,23 @@ impl Model {
         let mut result = false;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Boolean(value) => {
+                                result = *value || result;
+                            }
+                            CalcResult::Number(value) if *value != 0.0 => {
+                                return CalcResult::Boolean(true);
+                            }
+                            CalcResult::String(_value) => {
+                                return CalcResult::Boolean(true);
+                            }
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {}
+                        }
+                    }
+                }
                 CalcResult::Boolean(value) => result = value || result,
                 CalcResult::Number(value) => {
                     if value != 0.0 {
@@ -176,7 +224,7 @@ impl Model {
                                     return CalcResult::Boolean(true);
                                 }
                                 error @ CalcResult::Error { .. } => return error,
-                                CalcResult::Range { .. } => {}
+                                CalcResult::Range { .. } | CalcResult::Array(_) => {}
                                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
                             }
                         }
@@ -275,12 +323,14 @@ impl Model {
                 return case;
             }
             if compare_values(&expr, &case) == 0 {
+                self.trace_branch(cell, 2 * case_index + 2);
                 return self.evaluate_node_in_context(&args[2 * case_index + 2], cell);
             }
         }
         // None of the cases matched so we return the default
         // If there is an even number of args is the last one otherwise is #N/A
         if args_count % 2 == 0 {
+            self.trace_branch(cell, args_count - 1);
             return self.evaluate_node_in_context(&args[args_count - 1], cell);
         }
         CalcResult::Error {
@@ -306,6 +356,7 @@ impl Model {
             match value {
                 Ok(b) => {
                     if b {
+                        self.trace_branch(cell, 2 * case_index + 1);
                         return self.evaluate_node_in_context(&args[2 * case_index + 1], cell);
                     }
                 }
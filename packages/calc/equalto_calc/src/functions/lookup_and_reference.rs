@@ -7,9 +7,129 @@ use crate::{
     utils::ParsedReference,
 };
 
+use super::binary_search::binary_search;
 use super::util::{compare_values, from_wildcard_to_regex, result_matches_regex, values_are_equal};
 
+/// The table or vector argument of a lookup function: a range or an array value
+pub(crate) enum LookupArray {
+    Range {
+        left: CellReference,
+        right: CellReference,
+    },
+    Array(Vec<Vec<CalcResult>>),
+}
+
+impl LookupArray {
+    /// (rows, columns) of the range or array
+    pub(crate) fn dimensions(&self) -> (i32, i32) {
+        match self {
+            LookupArray::Range { left, right } => {
+                (right.row - left.row + 1, right.column - left.column + 1)
+            }
+            LookupArray::Array(array) => (
+                array.len() as i32,
+                array.first().map_or(0, |row| row.len()) as i32,
+            ),
+        }
+    }
+
+    /// (row, column) of the item `index` of a vector. A vector with a single row is horizontal.
+    pub(crate) fn vector_position(&self, index: i32) -> (i32, i32) {
+        if self.dimensions().0 == 1 {
+            (0, index)
+        } else {
+            (index, 0)
+        }
+    }
+
+    /// The cell at (row, column) relative to the top left corner, `None` for arrays
+    fn reference(&self, row: i32, column: i32) -> Option<CellReference> {
+        match self {
+            LookupArray::Range { left, .. } => Some(CellReference {
+                sheet: left.sheet,
+                row: left.row + row,
+                column: left.column + column,
+            }),
+            LookupArray::Array(_) => None,
+        }
+    }
+}
+
 impl Model {
+    /// Value at (row, column) relative to the top left corner of the range or array
+    pub(crate) fn lookup_array_value(
+        &mut self,
+        array: &LookupArray,
+        row: i32,
+        column: i32,
+    ) -> CalcResult {
+        match array {
+            LookupArray::Range { left, .. } => self.evaluate_cell(CellReference {
+                sheet: left.sheet,
+                row: left.row + row,
+                column: left.column + column,
+            }),
+            LookupArray::Array(values) => usize::try_from(row)
+                .ok()
+                .zip(usize::try_from(column).ok())
+                .and_then(|(row, column)| values.get(row)?.get(column).cloned())
+                .unwrap_or(CalcResult::EmptyCell),
+        }
+    }
+
+    /// Values of the items of a vector, `None` if there is more than one row and column
+    pub(crate) fn lookup_vector(&mut self, array: &LookupArray) -> Option<Vec<CalcResult>> {
+        let (rows, columns) = array.dimensions();
+        if rows != 1 && columns != 1 {
+            return None;
+        }
+        let length = if rows == 1 { columns } else { rows };
+        Some(
+            (0..length)
+                .map(|index| {
+                    let (row, column) = array.vector_position(index);
+                    self.lookup_array_value(array, row, column)
+                })
+                .collect(),
+        )
+    }
+
+    /// Records the match at (row, column) of a lookup function over a range
+    pub(crate) fn trace_lookup_array_match(
+        &mut self,
+        cell: CellReference,
+        array: &LookupArray,
+        row: i32,
+        column: i32,
+    ) {
+        if let Some(reference) = array.reference(row, column) {
+            self.trace_lookup_match(cell, reference);
+        }
+    }
+
+    /// (rows, columns) of a reference or an array
+    fn get_dimensions(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<(i32, i32), CalcResult> {
+        if let Node::ReferenceKind { .. } = node {
+            return Ok((1, 1));
+        }
+        match self.evaluate_node_in_context(node, cell) {
+            CalcResult::Range { left, right } => {
+                Ok(LookupArray::Range { left, right }.dimensions())
+            }
+            CalcResult::Array(array) => Ok(LookupArray::Array(array).dimensions()),
+            error @ CalcResult::Error { .. } => Err(error),
+            _ => Err(CalcResult::Error {
+                error: Error::VALUE,
+                origin: cell,
+                message: "Expected reference".to_string(),
+            }),
+        }
+    }
+
     pub(crate) fn fn_index(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
         let row_num;
         let col_num;
@@ -58,49 +178,41 @@ impl Model {
         } else {
             return CalcResult::new_args_number_error(cell);
         }
-        match self.evaluate_node_in_context(&args[0], cell) {
-            CalcResult::Range { left, right } => {
-                let row;
-                let column;
-                if (col_num + 1.0).abs() < f64::EPSILON {
-                    if left.row == right.row {
-                        column = left.column + (row_num as i32) - 1;
-                        row = left.row;
-                    } else {
-                        column = left.column;
-                        row = left.row + (row_num as i32) - 1;
-                    }
-                } else {
-                    row = left.row + (row_num as i32) - 1;
-                    column = left.column + (col_num as i32) - 1;
-                }
-                if row > right.row {
-                    return CalcResult::Error {
-                        error: Error::REF,
-                        origin: cell,
-                        message: "Wrong reference".to_string(),
-                    };
-                }
-                if column > right.column {
-                    return CalcResult::Error {
-                        error: Error::REF,
-                        origin: cell,
-                        message: "Wrong reference".to_string(),
-                    };
+        let array = match self.evaluate_node_in_context(&args[0], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            _ => {
+                return CalcResult::Error {
+                    error: Error::VALUE,
+                    origin: cell,
+                    message: "Expecting a Range".to_string(),
                 }
-                self.evaluate_cell(CellReference {
-                    sheet: left.sheet,
-                    row,
-                    column,
-                })
             }
-            error @ CalcResult::Error { .. } => error,
-            _ => CalcResult::Error {
-                error: Error::VALUE,
+        };
+        let (rows, columns) = array.dimensions();
+        let row;
+        let column;
+        if (col_num + 1.0).abs() < f64::EPSILON {
+            if rows == 1 {
+                column = (row_num as i32) - 1;
+                row = 0;
+            } else {
+                column = 0;
+                row = (row_num as i32) - 1;
+            }
+        } else {
+            row = (row_num as i32) - 1;
+            column = (col_num as i32) - 1;
+        }
+        if row >= rows || column >= columns {
+            return CalcResult::Error {
+                error: Error::REF,
                 origin: cell,
-                message: "Expecting a Range".to_string(),
-            },
+                message: "Wrong reference".to_string(),
+            };
         }
+        self.lookup_array_value(&array, row, column)
     }
 
     //     MATCH(lookup_value, lookup_array, [match_type])
@@ -136,158 +248,89 @@ impl Model {
         } else {
             1
         };
-        let match_range = self.evaluate_node_in_context(&args[1], cell);
-
-        match match_range {
-            CalcResult::Range { left, right } => {
-                match match_type {
-                    -1 => {
-                        // We apply binary search leftmost for value in the range
-                        let is_row_vector;
-                        if left.row == right.row {
-                            is_row_vector = false;
-                        } else if left.column == right.column {
-                            is_row_vector = true;
-                        } else {
-                            // second argument must be a vector
-                            return CalcResult::Error {
-                                error: Error::ERROR,
-                                origin: cell,
-                                message: "Argument must be a vector".to_string(),
-                            };
-                        }
-                        let n = if is_row_vector {
-                            right.row - left.row
-                        } else {
-                            right.column - left.column
-                        } + 1;
-                        let mut l = 0;
-                        let mut r = n;
-                        while l < r {
-                            let m = (l + r) / 2;
-                            let row;
-                            let column;
-                            if is_row_vector {
-                                row = left.row + m;
-                                column = left.column;
-                            } else {
-                                column = left.column + m;
-                                row = left.row;
-                            }
-                            let value = self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column,
-                            });
+        let match_range = match self.evaluate_node_in_context(&args[1], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            _ => {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Invalid".to_string(),
+                }
+            }
+        };
+        let (rows, columns) = match_range.dimensions();
+        if rows != 1 && columns != 1 {
+            // second argument must be a vector
+            return CalcResult::Error {
+                error: Error::ERROR,
+                origin: cell,
+                message: "Argument must be a vector".to_string(),
+            };
+        }
+        let n = rows.max(columns);
+        let index = match match_type {
+            -1 => {
+                // We apply binary search leftmost for value in the range
+                let mut l = 0;
+                let mut r = n;
+                while l < r {
+                    let m = (l + r) / 2;
+                    let (row, column) = match_range.vector_position(m);
+                    let value = self.lookup_array_value(&match_range, row, column);
 
-                            if compare_values(&value, &target) >= 0 {
-                                l = m + 1;
-                            } else {
-                                r = m;
-                            }
-                        }
-                        // r is the number of elements less than target in the vector
-                        // If target is less than the minimum return #N/A
-                        if l == 0 {
-                            return CalcResult::Error {
-                                error: Error::NA,
-                                origin: cell,
-                                message: "Not found".to_string(),
-                            };
-                        }
-                        // Now l points to the leftmost element
-                        CalcResult::Number(l as f64)
-                    }
-                    0 => {
-                        // We apply linear search
-                        let is_row_vector;
-                        if left.row == right.row {
-                            is_row_vector = false;
-                        } else if left.column == right.column {
-                            is_row_vector = true;
-                        } else {
-                            // second argument must be a vector
-                            return CalcResult::Error {
-                                error: Error::ERROR,
-                                origin: cell,
-                                message: "Argument must be a vector".to_string(),
-                            };
-                        }
-                        let n = if is_row_vector {
-                            right.row - left.row
-                        } else {
-                            right.column - left.column
-                        } + 1;
-                        let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
-                            if let CalcResult::String(s) = &target {
-                                if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
-                                    Box::new(move |x| result_matches_regex(x, &reg))
-                                } else {
-                                    Box::new(move |_| false)
-                                }
-                            } else {
-                                Box::new(move |x| values_are_equal(x, &target))
-                            };
-                        for l in 0..n {
-                            let row;
-                            let column;
-                            if is_row_vector {
-                                row = left.row + l;
-                                column = left.column;
-                            } else {
-                                column = left.column + l;
-                                row = left.row;
-                            }
-                            let value = self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column,
-                            });
-                            if result_matches(&value) {
-                                return CalcResult::Number(l as f64 + 1.0);
-                            }
-                        }
-                        CalcResult::Error {
-                            error: Error::NA,
-                            origin: cell,
-                            message: "Not found".to_string(),
-                        }
+                    if compare_values(&value, &target) >= 0 {
+                        l = m + 1;
+                    } else {
+                        r = m;
                     }
-                    _ => {
-                        // l is the number of elements less than target in the vector
-                        let is_row_vector;
-                        if left.row == right.row {
-                            is_row_vector = false;
-                        } else if left.column == right.column {
-                            is_row_vector = true;
+                }
+                // r is the number of elements less than target in the vector
+                // If target is less than the minimum return #N/A
+                // Otherwise l - 1 points to the leftmost element
+                l - 1
+            }
+            0 => {
+                // We apply linear search
+                let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
+                    if let CalcResult::String(s) = &target {
+                        if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
+                            Box::new(move |x| result_matches_regex(x, &reg))
                         } else {
-                            // second argument must be a vector
-                            return CalcResult::Error {
-                                error: Error::ERROR,
-                                origin: cell,
-                                message: "Argument must be a vector".to_string(),
-                            };
-                        }
-                        let l = self.binary_search(&target, &left, &right, is_row_vector);
-                        if l == -2 {
-                            return CalcResult::Error {
-                                error: Error::NA,
-                                origin: cell,
-                                message: "Not found".to_string(),
-                            };
+                            Box::new(move |_| false)
                         }
-
-                        CalcResult::Number(l as f64 + 1.0)
+                    } else {
+                        Box::new(move |x| values_are_equal(x, &target))
+                    };
+                let mut index = -1;
+                for l in 0..n {
+                    let (row, column) = match_range.vector_position(l);
+                    let value = self.lookup_array_value(&match_range, row, column);
+                    if result_matches(&value) {
+                        index = l;
+                        break;
                     }
                 }
+                index
             }
-            error @ CalcResult::Error { .. } => error,
-            _ => CalcResult::Error {
+            _ => {
+                // l is the number of elements less than target in the vector
+                // match_range is a vector
+                let values = self.lookup_vector(&match_range).unwrap_or_default();
+                binary_search(&target, &values)
+            }
+        };
+        if index < 0 {
+            return CalcResult::Error {
                 error: Error::NA,
                 origin: cell,
-                message: "Invalid".to_string(),
-            },
+                message: "Not found".to_string(),
+            };
         }
+        let (row, column) = match_range.vector_position(index);
+        self.trace_lookup_array_match(cell, &match_range, row, column);
+        CalcResult::Number(index as f64 + 1.0)
     }
 
     /// HLOOKUP(lookup_value, table_array, row_index, [is_sorted])
@@ -314,86 +357,80 @@ impl Model {
         } else {
             true
         };
-        let range = self.evaluate_node_in_context(&args[1], cell);
-        match range {
-            CalcResult::Range { left, right } => {
-                if is_sorted {
-                    // This assumes the values in row are in order
-                    let l = self.binary_search(&lookup_value, &left, &right, false);
-                    if l == -2 {
-                        return CalcResult::Error {
-                            error: Error::NA,
-                            origin: cell,
-                            message: "Not found".to_string(),
-                        };
-                    }
-                    let row = left.row + row_index - 1;
-                    let column = left.column + l;
-                    if row > right.row {
-                        return CalcResult::Error {
-                            error: Error::REF,
-                            origin: cell,
-                            message: "Invalid reference".to_string(),
-                        };
+        let table = match self.evaluate_node_in_context(&args[1], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            CalcResult::String(_) => {
+                return CalcResult::Error {
+                    error: Error::VALUE,
+                    origin: cell,
+                    message: "Range expected".to_string(),
+                }
+            }
+            _ => {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Range expected".to_string(),
+                }
+            }
+        };
+        let (rows, columns) = table.dimensions();
+        let row = row_index - 1;
+        if is_sorted {
+            // This assumes the values in row are in order
+            let first_row: Vec<CalcResult> = (0..columns)
+                .map(|column| self.lookup_array_value(&table, 0, column))
+                .collect();
+            let l = binary_search(&lookup_value, &first_row);
+            if l == -2 {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Not found".to_string(),
+                };
+            }
+            if row >= rows {
+                return CalcResult::Error {
+                    error: Error::REF,
+                    origin: cell,
+                    message: "Invalid reference".to_string(),
+                };
+            }
+            self.trace_lookup_array_match(cell, &table, 0, l);
+            self.lookup_array_value(&table, row, l)
+        } else {
+            // Linear search for exact match
+            if row >= rows {
+                return CalcResult::Error {
+                    error: Error::REF,
+                    origin: cell,
+                    message: "Invalid reference".to_string(),
+                };
+            }
+            let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
+                if let CalcResult::String(s) = &lookup_value {
+                    if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
+                        Box::new(move |x| result_matches_regex(x, &reg))
+                    } else {
+                        Box::new(move |_| false)
                     }
-                    self.evaluate_cell(CellReference {
-                        sheet: left.sheet,
-                        row,
-                        column,
-                    })
                 } else {
-                    // Linear search for exact match
-                    let n = right.column - left.column + 1;
-                    let row = left.row + row_index - 1;
-                    if row > right.row {
-                        return CalcResult::Error {
-                            error: Error::REF,
-                            origin: cell,
-                            message: "Invalid reference".to_string(),
-                        };
-                    }
-                    let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
-                        if let CalcResult::String(s) = &lookup_value {
-                            if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
-                                Box::new(move |x| result_matches_regex(x, &reg))
-                            } else {
-                                Box::new(move |_| false)
-                            }
-                        } else {
-                            Box::new(move |x| compare_values(x, &lookup_value) == 0)
-                        };
-                    for l in 0..n {
-                        let value = self.evaluate_cell(CellReference {
-                            sheet: left.sheet,
-                            row: left.row,
-                            column: left.column + l,
-                        });
-                        if result_matches(&value) {
-                            return self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column: left.column + l,
-                            });
-                        }
-                    }
-                    CalcResult::Error {
-                        error: Error::NA,
-                        origin: cell,
-                        message: "Not found".to_string(),
-                    }
+                    Box::new(move |x| compare_values(x, &lookup_value) == 0)
+                };
+            for l in 0..columns {
+                let value = self.lookup_array_value(&table, 0, l);
+                if result_matches(&value) {
+                    self.trace_lookup_array_match(cell, &table, 0, l);
+                    return self.lookup_array_value(&table, row, l);
                 }
             }
-            error @ CalcResult::Error { .. } => error,
-            CalcResult::String(_) => CalcResult::Error {
-                error: Error::VALUE,
-                origin: cell,
-                message: "Range expected".to_string(),
-            },
-            _ => CalcResult::Error {
+            CalcResult::Error {
                 error: Error::NA,
                 origin: cell,
-                message: "Range expected".to_string(),
-            },
+                message: "Not found".to_string(),
+            }
         }
     }
 
@@ -421,86 +458,80 @@ impl Model {
         } else {
             true
         };
-        let range = self.evaluate_node_in_context(&args[1], cell);
-        match range {
-            CalcResult::Range { left, right } => {
-                if is_sorted {
-                    // This assumes the values in column are in order
-                    let l = self.binary_search(&lookup_value, &left, &right, true);
-                    if l == -2 {
-                        return CalcResult::Error {
-                            error: Error::NA,
-                            origin: cell,
-                            message: "Not found".to_string(),
-                        };
-                    }
-                    let row = left.row + l;
-                    let column = left.column + column_index - 1;
-                    if column > right.column {
-                        return CalcResult::Error {
-                            error: Error::REF,
-                            origin: cell,
-                            message: "Invalid reference".to_string(),
-                        };
+        let table = match self.evaluate_node_in_context(&args[1], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            CalcResult::String(_) => {
+                return CalcResult::Error {
+                    error: Error::VALUE,
+                    origin: cell,
+                    message: "Range expected".to_string(),
+                }
+            }
+            _ => {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Range expected".to_string(),
+                }
+            }
+        };
+        let (rows, columns) = table.dimensions();
+        let column = column_index - 1;
+        if is_sorted {
+            // This assumes the values in column are in order
+            let first_column: Vec<CalcResult> = (0..rows)
+                .map(|row| self.lookup_array_value(&table, row, 0))
+                .collect();
+            let l = binary_search(&lookup_value, &first_column);
+            if l == -2 {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Not found".to_string(),
+                };
+            }
+            if column >= columns {
+                return CalcResult::Error {
+                    error: Error::REF,
+                    origin: cell,
+                    message: "Invalid reference".to_string(),
+                };
+            }
+            self.trace_lookup_array_match(cell, &table, l, 0);
+            self.lookup_array_value(&table, l, column)
+        } else {
+            // Linear search for exact match
+            if column >= columns {
+                return CalcResult::Error {
+                    error: Error::REF,
+                    origin: cell,
+                    message: "Invalid reference".to_string(),
+                };
+            }
+            let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
+                if let CalcResult::String(s) = &lookup_value {
+                    if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
+                        Box::new(move |x| result_matches_regex(x, &reg))
+                    } else {
+                        Box::new(move |_| false)
                     }
-                    self.evaluate_cell(CellReference {
-                        sheet: left.sheet,
-                        row,
-                        column,
-                    })
                 } else {
-                    // Linear search for exact match
-                    let n = right.row - left.row + 1;
-                    let column = left.column + column_index - 1;
-                    if column > right.column {
-                        return CalcResult::Error {
-                            error: Error::REF,
-                            origin: cell,
-                            message: "Invalid reference".to_string(),
-                        };
-                    }
-                    let result_matches: Box<dyn Fn(&CalcResult) -> bool> =
-                        if let CalcResult::String(s) = &lookup_value {
-                            if let Ok(reg) = from_wildcard_to_regex(&s.to_lowercase(), true) {
-                                Box::new(move |x| result_matches_regex(x, &reg))
-                            } else {
-                                Box::new(move |_| false)
-                            }
-                        } else {
-                            Box::new(move |x| compare_values(x, &lookup_value) == 0)
-                        };
-                    for l in 0..n {
-                        let value = self.evaluate_cell(CellReference {
-                            sheet: left.sheet,
-                            row: left.row + l,
-                            column: left.column,
-                        });
-                        if result_matches(&value) {
-                            return self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row: left.row + l,
-                                column,
-                            });
-                        }
-                    }
-                    CalcResult::Error {
-                        error: Error::NA,
-                        origin: cell,
-                        message: "Not found".to_string(),
-                    }
+                    Box::new(move |x| compare_values(x, &lookup_value) == 0)
+                };
+            for l in 0..rows {
+                let value = self.lookup_array_value(&table, l, 0);
+                if result_matches(&value) {
+                    self.trace_lookup_array_match(cell, &table, l, 0);
+                    return self.lookup_array_value(&table, l, column);
                 }
             }
-            error @ CalcResult::Error { .. } => error,
-            CalcResult::String(_) => CalcResult::Error {
-                error: Error::VALUE,
-                origin: cell,
-                message: "Range expected".to_string(),
-            },
-            _ => CalcResult::Error {
+            CalcResult::Error {
                 error: Error::NA,
                 origin: cell,
-                message: "Range expected".to_string(),
-            },
+                message: "Not found".to_string(),
+            }
         }
     }
 
@@ -520,83 +551,56 @@ impl Model {
         if target.is_error() {
             return target;
         }
-        let value = self.evaluate_node_in_context(&args[1], cell);
-        match value {
-            CalcResult::Range { left, right } => {
-                let is_row_vector;
-                if left.row == right.row {
-                    is_row_vector = false;
-                } else if left.column == right.column {
-                    is_row_vector = true;
-                } else {
-                    // second argument must be a vector
-                    return CalcResult::Error {
-                        error: Error::ERROR,
-                        origin: cell,
-                        message: "Second argument must be a vector".to_string(),
-                    };
+        let lookup_vector = match self.evaluate_node_in_context(&args[1], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            _ => {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Range expected".to_string(),
                 }
-                let l = self.binary_search(&target, &left, &right, is_row_vector);
-                if l == -2 {
+            }
+        };
+        let values = match self.lookup_vector(&lookup_vector) {
+            Some(values) => values,
+            None => {
+                // second argument must be a vector
+                return CalcResult::Error {
+                    error: Error::ERROR,
+                    origin: cell,
+                    message: "Second argument must be a vector".to_string(),
+                };
+            }
+        };
+        let l = binary_search(&target, &values);
+        if l == -2 {
+            return CalcResult::Error {
+                error: Error::NA,
+                origin: cell,
+                message: "Not found".to_string(),
+            };
+        }
+        let (row, column) = lookup_vector.vector_position(l);
+        self.trace_lookup_array_match(cell, &lookup_vector, row, column);
+
+        if args.len() == 3 {
+            let result_vector = match self.evaluate_node_in_context(&args[2], cell) {
+                CalcResult::Range { left, right } => LookupArray::Range { left, right },
+                CalcResult::Array(array) => LookupArray::Array(array),
+                error @ CalcResult::Error { .. } => return error,
+                _ => {
                     return CalcResult::Error {
                         error: Error::NA,
                         origin: cell,
-                        message: "Not found".to_string(),
-                    };
-                }
-
-                if args.len() == 3 {
-                    let target_range = self.evaluate_node_in_context(&args[2], cell);
-                    match target_range {
-                        CalcResult::Range {
-                            left: l1,
-                            right: _r1,
-                        } => {
-                            let row;
-                            let column;
-                            if is_row_vector {
-                                row = l1.row + l;
-                                column = l1.column;
-                            } else {
-                                column = l1.column + l;
-                                row = l1.row;
-                            }
-                            self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column,
-                            })
-                        }
-                        error @ CalcResult::Error { .. } => error,
-                        _ => CalcResult::Error {
-                            error: Error::NA,
-                            origin: cell,
-                            message: "Range expected".to_string(),
-                        },
+                        message: "Range expected".to_string(),
                     }
-                } else {
-                    let row;
-                    let column;
-                    if is_row_vector {
-                        row = left.row + l;
-                        column = left.column;
-                    } else {
-                        column = left.column + l;
-                        row = left.row;
-                    }
-                    self.evaluate_cell(CellReference {
-                        sheet: left.sheet,
-                        row,
-                        column,
-                    })
                 }
-            }
-            error @ CalcResult::Error { .. } => error,
-            _ => CalcResult::Error {
-                error: Error::NA,
-                origin: cell,
-                message: "Range expected".to_string(),
-            },
+            };
+            self.lookup_array_value(&result_vector, row, column)
+        } else {
+            values[l as usize].clone()
         }
     }
 
@@ -616,14 +620,14 @@ impl Model {
         }
     }
 
-    // ROWS(range)
-    // Returns the number of rows in range
+    // ROWS(array)
+    // Returns the number of rows in a range or an array
     pub(crate) fn fn_rows(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
         if args.len() != 1 {
             return CalcResult::new_args_number_error(cell);
         }
-        match self.get_reference(&args[0], cell) {
-            Ok(c) => CalcResult::Number((c.right.row - c.left.row + 1) as f64),
+        match self.get_dimensions(&args[0], cell) {
+            Ok((rows, _)) => CalcResult::Number(rows as f64),
             Err(s) => s,
         }
     }
@@ -665,17 +669,18 @@ impl Model {
             };
         }
 
+        self.trace_branch(cell, index_num);
         self.evaluate_node_with_reference(&args[index_num], cell)
     }
 
-    // COLUMNS(range)
-    // Returns the number of columns in range
+    // COLUMNS(array)
+    // Returns the number of columns in a range or an array
     pub(crate) fn fn_columns(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
         if args.len() != 1 {
             return CalcResult::new_args_number_error(cell);
         }
-        match self.get_reference(&args[0], cell) {
-            Ok(c) => CalcResult::Number((c.right.column - c.left.column + 1) as f64),
+        match self.get_dimensions(&args[0], cell) {
+            Ok((_, columns)) => CalcResult::Number(columns as f64),
             Err(s) => s,
         }
     }
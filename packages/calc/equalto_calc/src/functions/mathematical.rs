@@ -7,15 +7,60 @@ use crate::{
 };
 use std::f64::consts::PI;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub fn random() -> f64 {
-    rand::random()
+/// Largest integer accepted by GCD and LCM (2^53)
+const MAX_INTEGER: f64 = 9007199254740992.0;
+
+/// Rounds a quotient to 15 significant digits, so that `1.1/0.1` (11.000000000000002) is not
+/// rounded up to 12
+fn to_significant_digits(value: f64) -> f64 {
+    format!("{value:.14e}").parse::<f64>().unwrap_or(value)
+}
+
+/// Rounds `number` to a multiple of `significance` applying `round` to the quotient
+fn round_to_multiple(number: f64, significance: f64, round: fn(f64) -> f64) -> f64 {
+    if significance == 0.0 {
+        return 0.0;
+    }
+    round(to_significant_digits(number / significance)) * significance
 }
 
-#[cfg(target_arch = "wasm32")]
-pub fn random() -> f64 {
-    use js_sys::Math;
-    Math::random()
+/// n! of a non negative integer, infinite if it is too big
+fn factorial(n: f64) -> f64 {
+    let mut result = 1.0_f64;
+    let mut factor = 2.0;
+    while factor <= n && result.is_finite() {
+        result *= factor;
+        factor += 1.0;
+    }
+    result
+}
+
+/// Number of ways of choosing k elements out of n, 0 <= k <= n
+fn combinations(n: f64, k: f64) -> f64 {
+    let k = k.min(n - k);
+    let mut result = 1.0_f64;
+    let mut i = 1.0;
+    while i <= k && result.is_finite() {
+        result = result * (n - k + i) / i;
+        i += 1.0;
+    }
+    result.round()
+}
+
+/// The result of a combinatorial function, #NUM! if it is too big
+fn checked_integer(value: f64, cell: CellReference) -> CalcResult {
+    if !value.is_finite() {
+        return CalcResult::new_error(Error::NUM, cell, "Result is too big".to_string());
+    }
+    CalcResult::Number(value)
+}
+
+fn gcd(a: f64, b: f64) -> f64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0.0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 impl Model {
@@ -23,6 +68,17 @@ impl Model {
         let mut result = f64::NAN;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => result = value.min(result),
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {
+                                // We ignore booleans and strings
+                            }
+                        }
+                    }
+                }
                 CalcResult::Number(value) => result = value.min(result),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
@@ -66,6 +122,17 @@ impl Model {
         let mut result = f64::NAN;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => result = value.max(result),
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {
+                                // We ignore booleans and strings
+                            }
+                        }
+                    }
+                }
                 CalcResult::Number(value) => result = value.max(result),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
@@ -113,6 +180,17 @@ impl Model {
         let mut result = 0.0;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => result += value,
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {
+                                // We ignore booleans and strings
+                            }
+                        }
+                    }
+                }
                 CalcResult::Number(value) => result += value,
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
@@ -180,6 +258,20 @@ impl Model {
         let mut seen_value = false;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => {
+                                seen_value = true;
+                                result *= value;
+                            }
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {
+                                // We ignore booleans and strings
+                            }
+                        }
+                    }
+                }
                 CalcResult::Number(value) => {
                     seen_value = true;
                     result *= value;
@@ -643,7 +735,7 @@ impl Model {
         if !args.is_empty() {
             return CalcResult::new_args_number_error(cell);
         }
-        CalcResult::Number(random())
+        CalcResult::Number(self.environment.random())
     }
 
     // TODO: Add tests for RANDBETWEEN
@@ -666,6 +758,630 @@ impl Model {
                 message: format!("{x}>{y}"),
             };
         }
-        CalcResult::Number((x + random() * (y - x)).floor())
+        CalcResult::Number((x + self.environment.random() * (y - x)).floor())
+    }
+
+    /// Shared implementation of CEILING and FLOOR. The significance must have the same sign as
+    /// the number unless the number is negative. A zero significance is an error in FLOOR.
+    fn round_to_significance(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        round: fn(f64) -> f64,
+        zero_significance_error: bool,
+    ) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let significance = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if zero_significance_error && significance == 0.0 && value != 0.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        if value > 0.0 && significance < 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Significance must be positive for a positive number".to_string(),
+            );
+        }
+        CalcResult::Number(round_to_multiple(value, significance, round))
+    }
+
+    /// CEILING(number, significance)
+    pub(crate) fn fn_ceiling(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance(args, cell, f64::ceil, false)
+    }
+
+    /// FLOOR(number, significance)
+    pub(crate) fn fn_floor(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance(args, cell, f64::floor, true)
+    }
+
+    /// Shared implementation of the .MATH and .PRECISE variants of CEILING and FLOOR. The sign of
+    /// the significance is ignored and negative numbers are rounded away from zero if `mode`
+    /// is not zero.
+    fn round_to_significance_math(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        max_args: usize,
+        round_toward_zero: fn(f64) -> f64,
+        round_away_from_zero: fn(f64) -> f64,
+    ) -> CalcResult {
+        if args.is_empty() || args.len() > max_args {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let significance = match self.get_optional_number(args, 1, 1.0, cell) {
+            Ok(f) => f.abs(),
+            Err(s) => return s,
+        };
+        let mode = match self.get_optional_number(args, 2, 0.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let round = if value < 0.0 && mode != 0.0 {
+            round_away_from_zero
+        } else {
+            round_toward_zero
+        };
+        CalcResult::Number(round_to_multiple(value, significance, round))
+    }
+
+    /// CEILING.MATH(number, [significance], [mode])
+    pub(crate) fn fn_ceiling_math(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance_math(args, cell, 3, f64::ceil, f64::floor)
+    }
+
+    /// CEILING.PRECISE(number, [significance])
+    pub(crate) fn fn_ceiling_precise(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance_math(args, cell, 2, f64::ceil, f64::floor)
+    }
+
+    /// FLOOR.MATH(number, [significance], [mode])
+    pub(crate) fn fn_floor_math(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance_math(args, cell, 3, f64::floor, f64::ceil)
+    }
+
+    /// FLOOR.PRECISE(number, [significance])
+    pub(crate) fn fn_floor_precise(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.round_to_significance_math(args, cell, 2, f64::floor, f64::ceil)
+    }
+
+    /// MROUND(number, multiple)
+    pub(crate) fn fn_mround(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let multiple = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value * multiple < 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Number and multiple must have the same sign".to_string(),
+            );
+        }
+        CalcResult::Number(round_to_multiple(value, multiple, f64::round))
+    }
+
+    pub(crate) fn fn_int(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        CalcResult::Number(value.floor())
+    }
+
+    /// TRUNC(number, [num_digits])
+    pub(crate) fn fn_trunc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let number_of_digits = match self.get_optional_number(args, 1, 0.0, cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        let scale = 10.0_f64.powf(number_of_digits);
+        CalcResult::Number((value * scale).trunc() / scale)
+    }
+
+    /// MOD(number, divisor), the result has the sign of the divisor
+    pub(crate) fn fn_mod(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let divisor = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if divisor == 0.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        CalcResult::Number(value - divisor * (value / divisor).floor())
+    }
+
+    /// QUOTIENT(numerator, denominator)
+    pub(crate) fn fn_quotient(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let numerator = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let denominator = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if denominator == 0.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        CalcResult::Number((numerator / denominator).trunc())
+    }
+
+    /// Arguments of GCD and LCM, the numbers are truncated and must be non negative
+    fn get_non_negative_integers(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<f64>, CalcResult> {
+        if args.is_empty() {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let values = self.get_statistical_values(args, cell)?;
+        if values
+            .iter()
+            .any(|&value| !(0.0..MAX_INTEGER).contains(&value))
+        {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Arguments must be non negative integers smaller than 2^53".to_string(),
+            ));
+        }
+        Ok(values.into_iter().map(f64::trunc).collect())
+    }
+
+    /// GCD(number1, [number2], ...)
+    pub(crate) fn fn_gcd(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_non_negative_integers(args, cell) {
+            Ok(values) => CalcResult::Number(values.into_iter().fold(0.0, gcd)),
+            Err(error) => error,
+        }
+    }
+
+    /// LCM(number1, [number2], ...)
+    pub(crate) fn fn_lcm(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let values = match self.get_non_negative_integers(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        let mut result = 1.0;
+        for value in values {
+            if value == 0.0 {
+                return CalcResult::Number(0.0);
+            }
+            result *= value / gcd(result, value);
+        }
+        if result >= MAX_INTEGER {
+            return CalcResult::new_error(Error::NUM, cell, "Result is too big".to_string());
+        }
+        CalcResult::Number(result)
+    }
+
+    /// EVEN(number), rounds away from zero to the nearest even integer
+    pub(crate) fn fn_even(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let result = 2.0 * (value.abs() / 2.0).ceil();
+        CalcResult::Number(if value < 0.0 { -result } else { result })
+    }
+
+    /// ODD(number), rounds away from zero to the nearest odd integer
+    pub(crate) fn fn_odd(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let result = 2.0 * ((value.abs() + 1.0) / 2.0).ceil() - 1.0;
+        CalcResult::Number(if value < 0.0 { -result } else { result })
+    }
+
+    pub(crate) fn fn_sign(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let result = if value > 0.0 {
+            1.0
+        } else if value < 0.0 {
+            -1.0
+        } else {
+            0.0
+        };
+        CalcResult::Number(result)
+    }
+
+    pub(crate) fn fn_exp(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let result = value.exp();
+        if result.is_infinite() {
+            return CalcResult::new_error(Error::NUM, cell, "EXP returned infinity".to_string());
+        }
+        CalcResult::Number(result)
+    }
+
+    pub(crate) fn fn_ln(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value <= 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument of LN should be > 0".to_string(),
+            );
+        }
+        CalcResult::Number(value.ln())
+    }
+
+    /// LOG(number, [base])
+    pub(crate) fn fn_log(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let base = match self.get_optional_number(args, 1, 10.0, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value <= 0.0 || base <= 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Number and base of LOG should be > 0".to_string(),
+            );
+        }
+        if base == 1.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        CalcResult::Number(value.log(base))
+    }
+
+    pub(crate) fn fn_log10(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value <= 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument of LOG10 should be > 0".to_string(),
+            );
+        }
+        CalcResult::Number(value.log10())
+    }
+
+    pub(crate) fn fn_degrees(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        CalcResult::Number(value.to_degrees())
+    }
+
+    pub(crate) fn fn_radians(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        CalcResult::Number(value.to_radians())
+    }
+
+    /// Argument of COT, SEC and CSC, Excel only accepts angles smaller than 2^27
+    fn get_angle(&mut self, args: &[Node], cell: CellReference) -> Result<f64, CalcResult> {
+        if args.len() != 1 {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let value = self.get_number(&args[0], cell)?;
+        if value.abs() >= 134217728.0 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Angle must be smaller than 2^27".to_string(),
+            ));
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn fn_cot(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let value = match self.get_angle(args, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value == 0.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        CalcResult::Number(1.0 / value.tan())
+    }
+
+    pub(crate) fn fn_sec(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_angle(args, cell) {
+            Ok(value) => CalcResult::Number(1.0 / value.cos()),
+            Err(s) => s,
+        }
+    }
+
+    pub(crate) fn fn_csc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let value = match self.get_angle(args, cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if value == 0.0 {
+            return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+        }
+        CalcResult::Number(1.0 / value.sin())
+    }
+
+    /// FACT(number)
+    pub(crate) fn fn_fact(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        if value < 0.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument of FACT should be >= 0".to_string(),
+            );
+        }
+        checked_integer(factorial(value), cell)
+    }
+
+    /// FACTDOUBLE(number), n!! = n(n-2)(n-4)...
+    pub(crate) fn fn_factdouble(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let value = match self.get_number(&args[0], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        if value < -1.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument of FACTDOUBLE should be >= -1".to_string(),
+            );
+        }
+        let mut result = 1.0_f64;
+        let mut factor = value;
+        while factor > 1.0 && result.is_finite() {
+            result *= factor;
+            factor -= 2.0;
+        }
+        checked_integer(result, cell)
+    }
+
+    /// Number of elements and number chosen of COMBIN, COMBINA, PERMUT and PERMUTATIONA. Both
+    /// are truncated and must be non negative.
+    fn get_choose_arguments(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<(f64, f64), CalcResult> {
+        if args.len() != 2 {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let number = self.get_number(&args[0], cell)?.trunc();
+        let number_chosen = self.get_number(&args[1], cell)?.trunc();
+        if number < 0.0 || number_chosen < 0.0 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Arguments should be >= 0".to_string(),
+            ));
+        }
+        Ok((number, number_chosen))
+    }
+
+    /// COMBIN(number, number_chosen)
+    pub(crate) fn fn_combin(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let (number, number_chosen) = match self.get_choose_arguments(args, cell) {
+            Ok(arguments) => arguments,
+            Err(error) => return error,
+        };
+        if number < number_chosen {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Number should be >= number_chosen".to_string(),
+            );
+        }
+        checked_integer(combinations(number, number_chosen), cell)
+    }
+
+    /// COMBINA(number, number_chosen), combinations with repetitions
+    pub(crate) fn fn_combina(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let (number, number_chosen) = match self.get_choose_arguments(args, cell) {
+            Ok(arguments) => arguments,
+            Err(error) => return error,
+        };
+        if number_chosen == 0.0 {
+            return CalcResult::Number(1.0);
+        }
+        if number == 0.0 {
+            return CalcResult::new_error(Error::NUM, cell, "Number should be > 0".to_string());
+        }
+        checked_integer(
+            combinations(number + number_chosen - 1.0, number_chosen),
+            cell,
+        )
+    }
+
+    /// PERMUT(number, number_chosen)
+    pub(crate) fn fn_permut(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let (number, number_chosen) = match self.get_choose_arguments(args, cell) {
+            Ok(arguments) => arguments,
+            Err(error) => return error,
+        };
+        if number < number_chosen {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Number should be >= number_chosen".to_string(),
+            );
+        }
+        let mut result = 1.0_f64;
+        let mut factor = number;
+        while factor > number - number_chosen && result.is_finite() {
+            result *= factor;
+            factor -= 1.0;
+        }
+        checked_integer(result, cell)
+    }
+
+    /// PERMUTATIONA(number, number_chosen), permutations with repetitions
+    pub(crate) fn fn_permutationa(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_choose_arguments(args, cell) {
+            Ok((number, number_chosen)) => checked_integer(number.powf(number_chosen), cell),
+            Err(error) => error,
+        }
+    }
+
+    /// MULTINOMIAL(number1, [number2], ...), (n1 + n2 + ...)! / (n1! n2! ...)
+    pub(crate) fn fn_multinomial(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_statistical_values(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        if values.iter().any(|&value| value < 0.0) {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Arguments of MULTINOMIAL should be >= 0".to_string(),
+            );
+        }
+        let mut total = 0.0;
+        let mut result = 1.0_f64;
+        for value in values {
+            let value = value.trunc();
+            total += value;
+            result *= combinations(total, value);
+        }
+        checked_integer(result, cell)
+    }
+
+    /// SERIESSUM(x, n, m, coefficients), a1 x^n + a2 x^(n+m) + a3 x^(n+2m) + ...
+    pub(crate) fn fn_seriessum(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let x = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let n = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let m = match self.get_number(&args[2], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let coefficients = match self.get_number_matrix(&args[3], cell) {
+            Ok(matrix) => matrix,
+            Err(error) => return error,
+        };
+        let result: f64 = coefficients
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, coefficient)| coefficient * x.powf(n + i as f64 * m))
+            .sum();
+        if !result.is_finite() {
+            return CalcResult::new_error(Error::NUM, cell, "Invalid result".to_string());
+        }
+        CalcResult::Number(result)
+    }
+
+    /// SUMSQ(number1, [number2], ...)
+    pub(crate) fn fn_sumsq(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_statistical_values(args, cell) {
+            Ok(values) => CalcResult::Number(values.iter().map(|value| value * value).sum()),
+            Err(error) => error,
+        }
     }
 }
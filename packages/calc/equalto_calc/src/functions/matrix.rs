@@ -0,0 +1,315 @@
+//! Matrix and array math: SUMPRODUCT, MMULT, MINVERSE, MDETERM, MUNIT, TRANSPOSE, SUMX2MY2, ...
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    constants::MAX_ARRAY_SIZE,
+    expressions::{parser::Node, token::Error},
+    model::Model,
+};
+
+/// A pivot smaller than this fraction of the largest value of the matrix is considered zero
+const SINGULAR_TOLERANCE: f64 = 1e-15;
+
+/// LU decomposition PA = LU of a square matrix with partial pivoting, used by MINVERSE and
+/// MDETERM. L and U are stored in the same matrix (the unit diagonal of L is not stored)
+/// See: https://en.wikipedia.org/wiki/LU_decomposition
+struct LuDecomposition {
+    lu: Vec<Vec<f64>>,
+    /// Row of the original matrix at each position
+    permutation: Vec<usize>,
+    /// +1 or -1, the sign of the permutation
+    sign: f64,
+    /// True if a pivot is (numerically) zero
+    singular: bool,
+}
+
+fn lu_decomposition(mut lu: Vec<Vec<f64>>) -> LuDecomposition {
+    let n = lu.len();
+    let scale = lu
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |max, value| max.max(value.abs()));
+    let mut permutation: Vec<usize> = (0..n).collect();
+    let mut sign = 1.0;
+    let mut singular = scale == 0.0;
+    for k in 0..n {
+        // partial pivoting: the row with the largest value in column k
+        let mut pivot_row = k;
+        for row in k + 1..n {
+            if lu[row][k].abs() > lu[pivot_row][k].abs() {
+                pivot_row = row;
+            }
+        }
+        if lu[pivot_row][k].abs() <= SINGULAR_TOLERANCE * scale {
+            singular = true;
+            continue;
+        }
+        if pivot_row != k {
+            lu.swap(pivot_row, k);
+            permutation.swap(pivot_row, k);
+            sign = -sign;
+        }
+        let (upper, lower) = lu.split_at_mut(k + 1);
+        let pivot = &upper[k];
+        for row in lower.iter_mut() {
+            let factor = row[k] / pivot[k];
+            row[k] = factor;
+            for (value, pivot_value) in row[k + 1..].iter_mut().zip(&pivot[k + 1..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    LuDecomposition {
+        lu,
+        permutation,
+        sign,
+        singular,
+    }
+}
+
+impl LuDecomposition {
+    fn determinant(&self) -> f64 {
+        if self.singular {
+            return 0.0;
+        }
+        self.sign * (0..self.lu.len()).map(|k| self.lu[k][k]).product::<f64>()
+    }
+
+    /// Solution of A x = e_column, the column of the identity matrix
+    fn solve_unit(&self, column: usize) -> Vec<f64> {
+        let n = self.lu.len();
+        // forward substitution L y = P e_column
+        let mut x: Vec<f64> = self
+            .permutation
+            .iter()
+            .map(|&row| if row == column { 1.0 } else { 0.0 })
+            .collect();
+        for row in 0..n {
+            for k in 0..row {
+                x[row] -= self.lu[row][k] * x[k];
+            }
+        }
+        // back substitution U x = y
+        for row in (0..n).rev() {
+            for k in row + 1..n {
+                x[row] -= self.lu[row][k] * x[k];
+            }
+            x[row] /= self.lu[row][row];
+        }
+        x
+    }
+
+    /// Inverse of the matrix, column by column
+    fn inverse(&self) -> Vec<Vec<f64>> {
+        let n = self.lu.len();
+        let columns: Vec<Vec<f64>> = (0..n).map(|column| self.solve_unit(column)).collect();
+        (0..n)
+            .map(|row| columns.iter().map(|column| column[row]).collect())
+            .collect()
+    }
+}
+
+fn number_array(matrix: Vec<Vec<f64>>) -> CalcResult {
+    CalcResult::Array(
+        matrix
+            .into_iter()
+            .map(|row| row.into_iter().map(CalcResult::Number).collect())
+            .collect(),
+    )
+}
+
+impl Model {
+    /// Square matrix argument of MINVERSE and MDETERM
+    fn get_square_matrix(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<Vec<Vec<f64>>, CalcResult> {
+        let matrix = self.get_number_matrix(node, cell)?;
+        if matrix.iter().any(|row| row.len() != matrix.len()) {
+            return Err(CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "Expecting a square matrix".to_string(),
+            ));
+        }
+        Ok(matrix)
+    }
+
+    /// SUMPRODUCT(array1, [array2], ...)
+    /// All arrays must have the same size, values that are not numbers are treated as zeros
+    pub(crate) fn fn_sumproduct(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let mut products: Option<Vec<Vec<f64>>> = None;
+        for arg in args {
+            let array = match self.get_array_argument(arg, cell) {
+                Ok(array) => array,
+                Err(error) => return error,
+            };
+            if let Some(products) = &products {
+                if products.len() != array.len()
+                    || products.first().map(|row| row.len()) != array.first().map(|row| row.len())
+                {
+                    return CalcResult::new_error(
+                        Error::VALUE,
+                        cell,
+                        "Arrays of different sizes".to_string(),
+                    );
+                }
+            }
+            let products =
+                products.get_or_insert_with(|| vec![vec![1.0; array[0].len()]; array.len()]);
+            for (product_row, row) in products.iter_mut().zip(array) {
+                for (product, value) in product_row.iter_mut().zip(row) {
+                    match value {
+                        CalcResult::Number(f) => *product *= f,
+                        error @ CalcResult::Error { .. } => return error,
+                        _ => *product = 0.0,
+                    }
+                }
+            }
+        }
+        CalcResult::Number(products.into_iter().flatten().flatten().sum())
+    }
+
+    /// MMULT(array1, array2)
+    pub(crate) fn fn_mmult(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let left = match self.get_number_matrix(&args[0], cell) {
+            Ok(matrix) => matrix,
+            Err(error) => return error,
+        };
+        let right = match self.get_number_matrix(&args[1], cell) {
+            Ok(matrix) => matrix,
+            Err(error) => return error,
+        };
+        if left[0].len() != right.len() {
+            return CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "The number of columns of array1 must be the rows of array2".to_string(),
+            );
+        }
+        let columns = right[0].len();
+        let product = left
+            .iter()
+            .map(|row| {
+                (0..columns)
+                    .map(|column| {
+                        row.iter()
+                            .zip(&right)
+                            .map(|(value, right_row)| value * right_row[column])
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+        number_array(product)
+    }
+
+    /// MINVERSE(array)
+    pub(crate) fn fn_minverse(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let matrix = match self.get_square_matrix(&args[0], cell) {
+            Ok(matrix) => matrix,
+            Err(error) => return error,
+        };
+        let lu = lu_decomposition(matrix);
+        if lu.singular {
+            return CalcResult::new_error(Error::NUM, cell, "Singular matrix".to_string());
+        }
+        number_array(lu.inverse())
+    }
+
+    /// MDETERM(array)
+    pub(crate) fn fn_mdeterm(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_square_matrix(&args[0], cell) {
+            Ok(matrix) => CalcResult::Number(lu_decomposition(matrix).determinant()),
+            Err(error) => error,
+        }
+    }
+
+    /// MUNIT(dimension)
+    pub(crate) fn fn_munit(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let dimension = match self.get_number(&args[0], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        if dimension < 1.0 || dimension * dimension > MAX_ARRAY_SIZE as f64 {
+            return CalcResult::new_error(Error::VALUE, cell, "Invalid dimension".to_string());
+        }
+        let dimension = dimension as usize;
+        number_array(
+            (0..dimension)
+                .map(|row| {
+                    (0..dimension)
+                        .map(|column| if row == column { 1.0 } else { 0.0 })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// TRANSPOSE(array)
+    pub(crate) fn fn_transpose(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 1 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let array = match self.get_array_argument(&args[0], cell) {
+            Ok(array) => array,
+            Err(error) => return error,
+        };
+        let columns = array[0].len();
+        CalcResult::Array(
+            (0..columns)
+                .map(|column| array.iter().map(|row| row[column].clone()).collect())
+                .collect(),
+        )
+    }
+
+    /// Sum of f(x, y) over the pairs of numbers of two arrays
+    fn sum_of_pairs(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        f: fn(f64, f64) -> f64,
+    ) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_number_pairs(&args[0], &args[1], cell) {
+            Ok(pairs) if pairs.is_empty() => {
+                CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string())
+            }
+            Ok(pairs) => CalcResult::Number(pairs.into_iter().map(|(x, y)| f(x, y)).sum()),
+            Err(error) => error,
+        }
+    }
+
+    /// SUMX2MY2(array_x, array_y)
+    pub(crate) fn fn_sumx2my2(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.sum_of_pairs(args, cell, |x, y| x * x - y * y)
+    }
+
+    /// SUMX2PY2(array_x, array_y)
+    pub(crate) fn fn_sumx2py2(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.sum_of_pairs(args, cell, |x, y| x * x + y * y)
+    }
+
+    /// SUMXMY2(array_x, array_y)
+    pub(crate) fn fn_sumxmy2(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.sum_of_pairs(args, cell, |x, y| (x - y) * (x - y))
+    }
+}
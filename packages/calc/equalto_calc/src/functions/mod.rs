@@ -8,13 +8,23 @@ use crate::{
 
 pub(crate) mod binary_search;
 mod date_and_time;
+mod day_count;
+mod distributions;
+mod distributions_util;
+mod dynamic_arrays;
 mod engineering;
 mod financial;
 mod financial_util;
 mod information;
+pub(crate) mod lambda;
 mod logical;
 mod lookup_and_reference;
 mod mathematical;
+mod matrix;
+mod regression;
+mod regression_util;
+mod securities;
+mod securities_util;
 mod statistical;
 mod subtotal;
 mod text;
@@ -38,6 +48,34 @@ pub enum Function {
     True,
     Xor,
 
+    // LET, LAMBDA and LAMBDA helpers
+    Let,
+    Lambda,
+    Map,
+    Reduce,
+    Scan,
+    Byrow,
+    Bycol,
+    Makearray,
+
+    // Dynamic arrays
+    Filter,
+    Sort,
+    Sortby,
+    Unique,
+    Sequence,
+    Randarray,
+    Take,
+    Drop,
+    Chooserows,
+    Choosecols,
+    Vstack,
+    Hstack,
+    Tocol,
+    Torow,
+    Wraprows,
+    Wrapcols,
+
     // Mathematical and trigonometry
     Abs,
     Acos,
@@ -71,6 +109,51 @@ pub enum Function {
     Sumifs,
     Tan,
     Tanh,
+    Ceiling,
+    CeilingMath,
+    CeilingPrecise,
+    Floor,
+    FloorMath,
+    FloorPrecise,
+    Mround,
+    Int,
+    Trunc,
+    Mod,
+    Quotient,
+    Gcd,
+    Lcm,
+    Even,
+    Odd,
+    Sign,
+    Exp,
+    Ln,
+    Log,
+    Log10,
+    Degrees,
+    Radians,
+    Cot,
+    Sec,
+    Csc,
+    Fact,
+    Factdouble,
+    Combin,
+    Combina,
+    Permut,
+    Permutationa,
+    Multinomial,
+    Seriessum,
+    Sumsq,
+
+    // Matrix and array math
+    Sumproduct,
+    Mmult,
+    Minverse,
+    Mdeterm,
+    Munit,
+    Transpose,
+    Sumx2my2,
+    Sumx2py2,
+    Sumxmy2,
 
     // Information
     ErrorType,
@@ -137,16 +220,141 @@ pub enum Function {
     Countifs,
     Maxifs,
     Minifs,
+    Avedev,
+    Devsq,
+    Geomean,
+    Harmean,
+    Kurt,
+    Large,
+    Median,
+    Mode,
+    ModeMult,
+    ModeSngl,
+    Percentile,
+    PercentileExc,
+    PercentileInc,
+    Percentrank,
+    PercentrankExc,
+    PercentrankInc,
+    Quartile,
+    QuartileExc,
+    QuartileInc,
+    Rank,
+    RankAvg,
+    RankEq,
+    Skew,
+    Small,
+    Stdev,
+    StdevP,
+    StdevS,
+    Stdeva,
+    Stdevp,
+    Stdevpa,
+    Trimmean,
+    Var,
+    VarP,
+    VarS,
+    Vara,
+    Varp,
+    Varpa,
+
+    // Regression and correlation
+    Correl,
+    Covar,
+    CovarianceP,
+    CovarianceS,
+    Forecast,
+    ForecastLinear,
+    Growth,
+    Intercept,
+    Linest,
+    Logest,
+    Pearson,
+    Rsq,
+    Slope,
+    Steyx,
+    Trend,
+
+    // Statistical distributions
+    NormDist,
+    Normdist,
+    NormInv,
+    Norminv,
+    NormSDist,
+    Normsdist,
+    NormSInv,
+    Normsinv,
+    LognormDist,
+    Lognormdist,
+    LognormInv,
+    Loginv,
+    TDist,
+    TDistRt,
+    TDist2T,
+    Tdist,
+    TInv,
+    TInv2T,
+    Tinv,
+    ChisqDist,
+    ChisqDistRt,
+    Chidist,
+    ChisqInv,
+    ChisqInvRt,
+    Chiinv,
+    FDist,
+    FDistRt,
+    Fdist,
+    FInv,
+    FInvRt,
+    Finv,
+    BinomDist,
+    Binomdist,
+    BinomInv,
+    Critbinom,
+    PoissonDist,
+    Poisson,
+    ExponDist,
+    Expondist,
+    GammaDist,
+    Gammadist,
+    GammaInv,
+    Gammainv,
+    Gamma,
+    Gammaln,
+    GammalnPrecise,
+    BetaDist,
+    Betadist,
+    BetaInv,
+    Betainv,
+    ConfidenceNorm,
+    Confidence,
+    ConfidenceT,
 
     // Date and time
     Date,
+    Datedif,
+    Datevalue,
     Day,
+    Days360,
     Edate,
     Eomonth,
+    Hour,
+    Isoweeknum,
+    Minute,
     Month,
+    Networkdays,
+    NetworkdaysIntl,
     Now,
+    Second,
+    Time,
+    Timevalue,
     Today,
+    Weekday,
+    Weeknum,
+    Workday,
+    WorkdayIntl,
     Year,
+    Yearfrac,
 
     // Financial
     Cumipmt,
@@ -178,6 +386,27 @@ pub enum Function {
     Xirr,
     Xnpv,
 
+    // Financial: securities
+    Accrint,
+    Accrintm,
+    Coupdaybs,
+    Coupdays,
+    Coupdaysnc,
+    Coupncd,
+    Coupnum,
+    Couppcd,
+    Disc,
+    Duration,
+    Intrate,
+    Mduration,
+    Price,
+    Pricedisc,
+    Pricemat,
+    Received,
+    Yield,
+    Yielddisc,
+    Yieldmat,
+
     // Engineering: Bessel and transcendental functions
     Besseli,
     Besselj,
@@ -242,6 +471,7 @@ pub enum Function {
     Delta,
     Gestep,
     Subtotal,
+    Aggregate,
 }
 
 impl Function {
@@ -253,9 +483,61 @@ impl Function {
             Function::Ifs => "_xlfn.IFS".to_string(),
             Function::Maxifs => "_xlfn.MAXIFS".to_string(),
             Function::Minifs => "_xlfn.MINIFS".to_string(),
+            Function::ModeMult => "_xlfn.MODE.MULT".to_string(),
+            Function::ModeSngl => "_xlfn.MODE.SNGL".to_string(),
+            Function::PercentileExc => "_xlfn.PERCENTILE.EXC".to_string(),
+            Function::PercentileInc => "_xlfn.PERCENTILE.INC".to_string(),
+            Function::PercentrankExc => "_xlfn.PERCENTRANK.EXC".to_string(),
+            Function::PercentrankInc => "_xlfn.PERCENTRANK.INC".to_string(),
+            Function::QuartileExc => "_xlfn.QUARTILE.EXC".to_string(),
+            Function::QuartileInc => "_xlfn.QUARTILE.INC".to_string(),
+            Function::RankAvg => "_xlfn.RANK.AVG".to_string(),
+            Function::RankEq => "_xlfn.RANK.EQ".to_string(),
+            Function::StdevP => "_xlfn.STDEV.P".to_string(),
+            Function::StdevS => "_xlfn.STDEV.S".to_string(),
+            Function::VarP => "_xlfn.VAR.P".to_string(),
+            Function::VarS => "_xlfn.VAR.S".to_string(),
+            Function::CovarianceP => "_xlfn.COVARIANCE.P".to_string(),
+            Function::CovarianceS => "_xlfn.COVARIANCE.S".to_string(),
+            Function::ForecastLinear => "_xlfn.FORECAST.LINEAR".to_string(),
             Function::Switch => "_xlfn.SWITCH".to_string(),
             Function::Xlookup => "_xlfn.XLOOKUP".to_string(),
             Function::Xor => "_xlfn.XOR".to_string(),
+            Function::Let => "_xlfn.LET".to_string(),
+            Function::Lambda => "_xlfn.LAMBDA".to_string(),
+            Function::Map => "_xlfn.MAP".to_string(),
+            Function::Reduce => "_xlfn.REDUCE".to_string(),
+            Function::Scan => "_xlfn.SCAN".to_string(),
+            Function::Byrow => "_xlfn.BYROW".to_string(),
+            Function::Bycol => "_xlfn.BYCOL".to_string(),
+            Function::Makearray => "_xlfn.MAKEARRAY".to_string(),
+            Function::Munit => "_xlfn.MUNIT".to_string(),
+            Function::CeilingMath => "_xlfn.CEILING.MATH".to_string(),
+            Function::CeilingPrecise => "_xlfn.CEILING.PRECISE".to_string(),
+            Function::FloorMath => "_xlfn.FLOOR.MATH".to_string(),
+            Function::FloorPrecise => "_xlfn.FLOOR.PRECISE".to_string(),
+            Function::Cot => "_xlfn.COT".to_string(),
+            Function::Sec => "_xlfn.SEC".to_string(),
+            Function::Csc => "_xlfn.CSC".to_string(),
+            Function::Combina => "_xlfn.COMBINA".to_string(),
+            Function::Permutationa => "_xlfn.PERMUTATIONA".to_string(),
+            Function::Aggregate => "_xlfn.AGGREGATE".to_string(),
+            Function::Filter => "_xlfn._xlws.FILTER".to_string(),
+            Function::Sort => "_xlfn._xlws.SORT".to_string(),
+            Function::Sortby => "_xlfn.SORTBY".to_string(),
+            Function::Unique => "_xlfn.UNIQUE".to_string(),
+            Function::Sequence => "_xlfn.SEQUENCE".to_string(),
+            Function::Randarray => "_xlfn.RANDARRAY".to_string(),
+            Function::Take => "_xlfn.TAKE".to_string(),
+            Function::Drop => "_xlfn.DROP".to_string(),
+            Function::Chooserows => "_xlfn.CHOOSEROWS".to_string(),
+            Function::Choosecols => "_xlfn.CHOOSECOLS".to_string(),
+            Function::Vstack => "_xlfn.VSTACK".to_string(),
+            Function::Hstack => "_xlfn.HSTACK".to_string(),
+            Function::Tocol => "_xlfn.TOCOL".to_string(),
+            Function::Torow => "_xlfn.TOROW".to_string(),
+            Function::Wraprows => "_xlfn.WRAPROWS".to_string(),
+            Function::Wrapcols => "_xlfn.WRAPCOLS".to_string(),
             Function::Textbefore => "_xlfn.TEXTBEFORE".to_string(),
             Function::Textafter => "_xlfn.TEXTAFTER".to_string(),
             Function::Textjoin => "_xlfn.TEXTJOIN".to_string(),
@@ -278,6 +560,40 @@ impl Function {
             Function::Valuetotext => "_xlfn.VALUETOTEXT".to_string(),
             Function::Isformula => "_xlfn.ISFORMULA".to_string(),
             Function::Sheet => "_xlfn.SHEET".to_string(),
+            Function::Isoweeknum => "_xlfn.ISOWEEKNUM".to_string(),
+            Function::NetworkdaysIntl => "_xlfn.NETWORKDAYS.INTL".to_string(),
+            Function::WorkdayIntl => "_xlfn.WORKDAY.INTL".to_string(),
+            Function::NormDist => "_xlfn.NORM.DIST".to_string(),
+            Function::NormInv => "_xlfn.NORM.INV".to_string(),
+            Function::NormSDist => "_xlfn.NORM.S.DIST".to_string(),
+            Function::NormSInv => "_xlfn.NORM.S.INV".to_string(),
+            Function::LognormDist => "_xlfn.LOGNORM.DIST".to_string(),
+            Function::LognormInv => "_xlfn.LOGNORM.INV".to_string(),
+            Function::TDist => "_xlfn.T.DIST".to_string(),
+            Function::TDistRt => "_xlfn.T.DIST.RT".to_string(),
+            Function::TDist2T => "_xlfn.T.DIST.2T".to_string(),
+            Function::TInv => "_xlfn.T.INV".to_string(),
+            Function::TInv2T => "_xlfn.T.INV.2T".to_string(),
+            Function::ChisqDist => "_xlfn.CHISQ.DIST".to_string(),
+            Function::ChisqDistRt => "_xlfn.CHISQ.DIST.RT".to_string(),
+            Function::ChisqInv => "_xlfn.CHISQ.INV".to_string(),
+            Function::ChisqInvRt => "_xlfn.CHISQ.INV.RT".to_string(),
+            Function::FDist => "_xlfn.F.DIST".to_string(),
+            Function::FDistRt => "_xlfn.F.DIST.RT".to_string(),
+            Function::FInv => "_xlfn.F.INV".to_string(),
+            Function::FInvRt => "_xlfn.F.INV.RT".to_string(),
+            Function::BinomDist => "_xlfn.BINOM.DIST".to_string(),
+            Function::BinomInv => "_xlfn.BINOM.INV".to_string(),
+            Function::PoissonDist => "_xlfn.POISSON.DIST".to_string(),
+            Function::ExponDist => "_xlfn.EXPON.DIST".to_string(),
+            Function::GammaDist => "_xlfn.GAMMA.DIST".to_string(),
+            Function::GammaInv => "_xlfn.GAMMA.INV".to_string(),
+            Function::Gamma => "_xlfn.GAMMA".to_string(),
+            Function::GammalnPrecise => "_xlfn.GAMMALN.PRECISE".to_string(),
+            Function::BetaDist => "_xlfn.BETA.DIST".to_string(),
+            Function::BetaInv => "_xlfn.BETA.INV".to_string(),
+            Function::ConfidenceNorm => "_xlfn.CONFIDENCE.NORM".to_string(),
+            Function::ConfidenceT => "_xlfn.CONFIDENCE.T".to_string(),
             _ => self.to_string(),
         }
     }
@@ -285,6 +601,20 @@ impl Function {
     pub(crate) fn returns_reference(&self) -> bool {
         matches!(self, Function::Indirect | Function::Offset)
     }
+
+    /// Functions whose result can change even if none of their arguments do
+    pub(crate) fn is_volatile(&self) -> bool {
+        matches!(
+            self,
+            Function::Now
+                | Function::Today
+                | Function::Rand
+                | Function::Randbetween
+                | Function::Randarray
+                | Function::Indirect
+                | Function::Offset
+        )
+    }
     /// Gets the function from the name.
     /// Note that in Excel some (modern) functions are prefixed by `_xlfn.`
     pub fn get_function(name: &str) -> Option<Function> {
@@ -301,6 +631,32 @@ impl Function {
             "TRUE" => Some(Function::True),
             "XOR" | "_XLFN.XOR" => Some(Function::Xor),
 
+            "LET" | "_XLFN.LET" => Some(Function::Let),
+            "LAMBDA" | "_XLFN.LAMBDA" => Some(Function::Lambda),
+            "MAP" | "_XLFN.MAP" => Some(Function::Map),
+            "REDUCE" | "_XLFN.REDUCE" => Some(Function::Reduce),
+            "SCAN" | "_XLFN.SCAN" => Some(Function::Scan),
+            "BYROW" | "_XLFN.BYROW" => Some(Function::Byrow),
+            "BYCOL" | "_XLFN.BYCOL" => Some(Function::Bycol),
+            "MAKEARRAY" | "_XLFN.MAKEARRAY" => Some(Function::Makearray),
+
+            "FILTER" | "_XLFN._XLWS.FILTER" => Some(Function::Filter),
+            "SORT" | "_XLFN._XLWS.SORT" => Some(Function::Sort),
+            "SORTBY" | "_XLFN.SORTBY" => Some(Function::Sortby),
+            "UNIQUE" | "_XLFN.UNIQUE" => Some(Function::Unique),
+            "SEQUENCE" | "_XLFN.SEQUENCE" => Some(Function::Sequence),
+            "RANDARRAY" | "_XLFN.RANDARRAY" => Some(Function::Randarray),
+            "TAKE" | "_XLFN.TAKE" => Some(Function::Take),
+            "DROP" | "_XLFN.DROP" => Some(Function::Drop),
+            "CHOOSEROWS" | "_XLFN.CHOOSEROWS" => Some(Function::Chooserows),
+            "CHOOSECOLS" | "_XLFN.CHOOSECOLS" => Some(Function::Choosecols),
+            "VSTACK" | "_XLFN.VSTACK" => Some(Function::Vstack),
+            "HSTACK" | "_XLFN.HSTACK" => Some(Function::Hstack),
+            "TOCOL" | "_XLFN.TOCOL" => Some(Function::Tocol),
+            "TOROW" | "_XLFN.TOROW" => Some(Function::Torow),
+            "WRAPROWS" | "_XLFN.WRAPROWS" => Some(Function::Wraprows),
+            "WRAPCOLS" | "_XLFN.WRAPCOLS" => Some(Function::Wrapcols),
+
             "SIN" => Some(Function::Sin),
             "COS" => Some(Function::Cos),
             "TAN" => Some(Function::Tan),
@@ -312,6 +668,40 @@ impl Function {
             "SINH" => Some(Function::Sinh),
             "COSH" => Some(Function::Cosh),
             "TANH" => Some(Function::Tanh),
+            "CEILING" => Some(Function::Ceiling),
+            "CEILING.MATH" | "_XLFN.CEILING.MATH" => Some(Function::CeilingMath),
+            "CEILING.PRECISE" | "_XLFN.CEILING.PRECISE" => Some(Function::CeilingPrecise),
+            "FLOOR" => Some(Function::Floor),
+            "FLOOR.MATH" | "_XLFN.FLOOR.MATH" => Some(Function::FloorMath),
+            "FLOOR.PRECISE" | "_XLFN.FLOOR.PRECISE" => Some(Function::FloorPrecise),
+            "MROUND" => Some(Function::Mround),
+            "INT" => Some(Function::Int),
+            "TRUNC" => Some(Function::Trunc),
+            "MOD" => Some(Function::Mod),
+            "QUOTIENT" => Some(Function::Quotient),
+            "GCD" => Some(Function::Gcd),
+            "LCM" => Some(Function::Lcm),
+            "EVEN" => Some(Function::Even),
+            "ODD" => Some(Function::Odd),
+            "SIGN" => Some(Function::Sign),
+            "EXP" => Some(Function::Exp),
+            "LN" => Some(Function::Ln),
+            "LOG" => Some(Function::Log),
+            "LOG10" => Some(Function::Log10),
+            "DEGREES" => Some(Function::Degrees),
+            "RADIANS" => Some(Function::Radians),
+            "COT" | "_XLFN.COT" => Some(Function::Cot),
+            "SEC" | "_XLFN.SEC" => Some(Function::Sec),
+            "CSC" | "_XLFN.CSC" => Some(Function::Csc),
+            "FACT" => Some(Function::Fact),
+            "FACTDOUBLE" => Some(Function::Factdouble),
+            "COMBIN" => Some(Function::Combin),
+            "COMBINA" | "_XLFN.COMBINA" => Some(Function::Combina),
+            "PERMUT" => Some(Function::Permut),
+            "PERMUTATIONA" | "_XLFN.PERMUTATIONA" => Some(Function::Permutationa),
+            "MULTINOMIAL" => Some(Function::Multinomial),
+            "SERIESSUM" => Some(Function::Seriessum),
+            "SUMSQ" => Some(Function::Sumsq),
 
             "ASINH" => Some(Function::Asinh),
             "ACOSH" => Some(Function::Acosh),
@@ -336,6 +726,16 @@ impl Function {
             "SUMIF" => Some(Function::Sumif),
             "SUMIFS" => Some(Function::Sumifs),
 
+            "SUMPRODUCT" => Some(Function::Sumproduct),
+            "MMULT" => Some(Function::Mmult),
+            "MINVERSE" => Some(Function::Minverse),
+            "MDETERM" => Some(Function::Mdeterm),
+            "MUNIT" | "_XLFN.MUNIT" => Some(Function::Munit),
+            "TRANSPOSE" => Some(Function::Transpose),
+            "SUMX2MY2" => Some(Function::Sumx2my2),
+            "SUMX2PY2" => Some(Function::Sumx2py2),
+            "SUMXMY2" => Some(Function::Sumxmy2),
+
             // Lookup and Reference
             "CHOOSE" => Some(Function::Choose),
             "COLUMN" => Some(Function::Column),
@@ -402,6 +802,112 @@ impl Function {
             "COUNTIFS" => Some(Function::Countifs),
             "MAXIFS" | "_XLFN.MAXIFS" => Some(Function::Maxifs),
             "MINIFS" | "_XLFN.MINIFS" => Some(Function::Minifs),
+            "AVEDEV" => Some(Function::Avedev),
+            "DEVSQ" => Some(Function::Devsq),
+            "GEOMEAN" => Some(Function::Geomean),
+            "HARMEAN" => Some(Function::Harmean),
+            "KURT" => Some(Function::Kurt),
+            "LARGE" => Some(Function::Large),
+            "MEDIAN" => Some(Function::Median),
+            "MODE" => Some(Function::Mode),
+            "MODE.MULT" | "_XLFN.MODE.MULT" => Some(Function::ModeMult),
+            "MODE.SNGL" | "_XLFN.MODE.SNGL" => Some(Function::ModeSngl),
+            "PERCENTILE" => Some(Function::Percentile),
+            "PERCENTILE.EXC" | "_XLFN.PERCENTILE.EXC" => Some(Function::PercentileExc),
+            "PERCENTILE.INC" | "_XLFN.PERCENTILE.INC" => Some(Function::PercentileInc),
+            "PERCENTRANK" => Some(Function::Percentrank),
+            "PERCENTRANK.EXC" | "_XLFN.PERCENTRANK.EXC" => Some(Function::PercentrankExc),
+            "PERCENTRANK.INC" | "_XLFN.PERCENTRANK.INC" => Some(Function::PercentrankInc),
+            "QUARTILE" => Some(Function::Quartile),
+            "QUARTILE.EXC" | "_XLFN.QUARTILE.EXC" => Some(Function::QuartileExc),
+            "QUARTILE.INC" | "_XLFN.QUARTILE.INC" => Some(Function::QuartileInc),
+            "RANK" => Some(Function::Rank),
+            "RANK.AVG" | "_XLFN.RANK.AVG" => Some(Function::RankAvg),
+            "RANK.EQ" | "_XLFN.RANK.EQ" => Some(Function::RankEq),
+            "SKEW" => Some(Function::Skew),
+            "SMALL" => Some(Function::Small),
+            "STDEV" => Some(Function::Stdev),
+            "STDEV.P" | "_XLFN.STDEV.P" => Some(Function::StdevP),
+            "STDEV.S" | "_XLFN.STDEV.S" => Some(Function::StdevS),
+            "STDEVA" => Some(Function::Stdeva),
+            "STDEVP" => Some(Function::Stdevp),
+            "STDEVPA" => Some(Function::Stdevpa),
+            "TRIMMEAN" => Some(Function::Trimmean),
+            "VAR" => Some(Function::Var),
+            "VAR.P" | "_XLFN.VAR.P" => Some(Function::VarP),
+            "VAR.S" | "_XLFN.VAR.S" => Some(Function::VarS),
+            "VARA" => Some(Function::Vara),
+            "VARP" => Some(Function::Varp),
+            "VARPA" => Some(Function::Varpa),
+            "CORREL" => Some(Function::Correl),
+            "COVAR" => Some(Function::Covar),
+            "COVARIANCE.P" | "_XLFN.COVARIANCE.P" => Some(Function::CovarianceP),
+            "COVARIANCE.S" | "_XLFN.COVARIANCE.S" => Some(Function::CovarianceS),
+            "FORECAST" => Some(Function::Forecast),
+            "FORECAST.LINEAR" | "_XLFN.FORECAST.LINEAR" => Some(Function::ForecastLinear),
+            "GROWTH" => Some(Function::Growth),
+            "INTERCEPT" => Some(Function::Intercept),
+            "LINEST" => Some(Function::Linest),
+            "LOGEST" => Some(Function::Logest),
+            "PEARSON" => Some(Function::Pearson),
+            "RSQ" => Some(Function::Rsq),
+            "SLOPE" => Some(Function::Slope),
+            "STEYX" => Some(Function::Steyx),
+            "TREND" => Some(Function::Trend),
+            // Statistical distributions
+            "NORM.DIST" | "_XLFN.NORM.DIST" => Some(Function::NormDist),
+            "NORMDIST" => Some(Function::Normdist),
+            "NORM.INV" | "_XLFN.NORM.INV" => Some(Function::NormInv),
+            "NORMINV" => Some(Function::Norminv),
+            "NORM.S.DIST" | "_XLFN.NORM.S.DIST" => Some(Function::NormSDist),
+            "NORMSDIST" => Some(Function::Normsdist),
+            "NORM.S.INV" | "_XLFN.NORM.S.INV" => Some(Function::NormSInv),
+            "NORMSINV" => Some(Function::Normsinv),
+            "LOGNORM.DIST" | "_XLFN.LOGNORM.DIST" => Some(Function::LognormDist),
+            "LOGNORMDIST" => Some(Function::Lognormdist),
+            "LOGNORM.INV" | "_XLFN.LOGNORM.INV" => Some(Function::LognormInv),
+            "LOGINV" => Some(Function::Loginv),
+            "T.DIST" | "_XLFN.T.DIST" => Some(Function::TDist),
+            "T.DIST.RT" | "_XLFN.T.DIST.RT" => Some(Function::TDistRt),
+            "T.DIST.2T" | "_XLFN.T.DIST.2T" => Some(Function::TDist2T),
+            "TDIST" => Some(Function::Tdist),
+            "T.INV" | "_XLFN.T.INV" => Some(Function::TInv),
+            "T.INV.2T" | "_XLFN.T.INV.2T" => Some(Function::TInv2T),
+            "TINV" => Some(Function::Tinv),
+            "CHISQ.DIST" | "_XLFN.CHISQ.DIST" => Some(Function::ChisqDist),
+            "CHISQ.DIST.RT" | "_XLFN.CHISQ.DIST.RT" => Some(Function::ChisqDistRt),
+            "CHIDIST" => Some(Function::Chidist),
+            "CHISQ.INV" | "_XLFN.CHISQ.INV" => Some(Function::ChisqInv),
+            "CHISQ.INV.RT" | "_XLFN.CHISQ.INV.RT" => Some(Function::ChisqInvRt),
+            "CHIINV" => Some(Function::Chiinv),
+            "F.DIST" | "_XLFN.F.DIST" => Some(Function::FDist),
+            "F.DIST.RT" | "_XLFN.F.DIST.RT" => Some(Function::FDistRt),
+            "FDIST" => Some(Function::Fdist),
+            "F.INV" | "_XLFN.F.INV" => Some(Function::FInv),
+            "F.INV.RT" | "_XLFN.F.INV.RT" => Some(Function::FInvRt),
+            "FINV" => Some(Function::Finv),
+            "BINOM.DIST" | "_XLFN.BINOM.DIST" => Some(Function::BinomDist),
+            "BINOMDIST" => Some(Function::Binomdist),
+            "BINOM.INV" | "_XLFN.BINOM.INV" => Some(Function::BinomInv),
+            "CRITBINOM" => Some(Function::Critbinom),
+            "POISSON.DIST" | "_XLFN.POISSON.DIST" => Some(Function::PoissonDist),
+            "POISSON" => Some(Function::Poisson),
+            "EXPON.DIST" | "_XLFN.EXPON.DIST" => Some(Function::ExponDist),
+            "EXPONDIST" => Some(Function::Expondist),
+            "GAMMA.DIST" | "_XLFN.GAMMA.DIST" => Some(Function::GammaDist),
+            "GAMMADIST" => Some(Function::Gammadist),
+            "GAMMA.INV" | "_XLFN.GAMMA.INV" => Some(Function::GammaInv),
+            "GAMMAINV" => Some(Function::Gammainv),
+            "GAMMA" | "_XLFN.GAMMA" => Some(Function::Gamma),
+            "GAMMALN" => Some(Function::Gammaln),
+            "GAMMALN.PRECISE" | "_XLFN.GAMMALN.PRECISE" => Some(Function::GammalnPrecise),
+            "BETA.DIST" | "_XLFN.BETA.DIST" => Some(Function::BetaDist),
+            "BETADIST" => Some(Function::Betadist),
+            "BETA.INV" | "_XLFN.BETA.INV" => Some(Function::BetaInv),
+            "BETAINV" => Some(Function::Betainv),
+            "CONFIDENCE.NORM" | "_XLFN.CONFIDENCE.NORM" => Some(Function::ConfidenceNorm),
+            "CONFIDENCE" => Some(Function::Confidence),
+            "CONFIDENCE.T" | "_XLFN.CONFIDENCE.T" => Some(Function::ConfidenceT),
             // Date and Time
             "YEAR" => Some(Function::Year),
             "DAY" => Some(Function::Day),
@@ -411,6 +917,22 @@ impl Function {
             "EDATE" => Some(Function::Edate),
             "TODAY" => Some(Function::Today),
             "NOW" => Some(Function::Now),
+            "DATEDIF" => Some(Function::Datedif),
+            "DAYS360" => Some(Function::Days360),
+            "ISOWEEKNUM" | "_XLFN.ISOWEEKNUM" => Some(Function::Isoweeknum),
+            "NETWORKDAYS" => Some(Function::Networkdays),
+            "NETWORKDAYS.INTL" | "_XLFN.NETWORKDAYS.INTL" => Some(Function::NetworkdaysIntl),
+            "WEEKDAY" => Some(Function::Weekday),
+            "WEEKNUM" => Some(Function::Weeknum),
+            "WORKDAY" => Some(Function::Workday),
+            "WORKDAY.INTL" | "_XLFN.WORKDAY.INTL" => Some(Function::WorkdayIntl),
+            "YEARFRAC" => Some(Function::Yearfrac),
+            "TIME" => Some(Function::Time),
+            "HOUR" => Some(Function::Hour),
+            "MINUTE" => Some(Function::Minute),
+            "SECOND" => Some(Function::Second),
+            "TIMEVALUE" => Some(Function::Timevalue),
+            "DATEVALUE" => Some(Function::Datevalue),
             // Financial
             "PMT" => Some(Function::Pmt),
             "PV" => Some(Function::Pv),
@@ -434,6 +956,25 @@ impl Function {
             "PDURATION" | "_XLFN.PDURATION" => Some(Function::Pduration),
 
             "TBILLYIELD" => Some(Function::Tbillyield),
+            "ACCRINT" => Some(Function::Accrint),
+            "ACCRINTM" => Some(Function::Accrintm),
+            "COUPDAYBS" => Some(Function::Coupdaybs),
+            "COUPDAYS" => Some(Function::Coupdays),
+            "COUPDAYSNC" => Some(Function::Coupdaysnc),
+            "COUPNCD" => Some(Function::Coupncd),
+            "COUPNUM" => Some(Function::Coupnum),
+            "COUPPCD" => Some(Function::Couppcd),
+            "DISC" => Some(Function::Disc),
+            "DURATION" => Some(Function::Duration),
+            "INTRATE" => Some(Function::Intrate),
+            "MDURATION" => Some(Function::Mduration),
+            "PRICE" => Some(Function::Price),
+            "PRICEDISC" => Some(Function::Pricedisc),
+            "PRICEMAT" => Some(Function::Pricemat),
+            "RECEIVED" => Some(Function::Received),
+            "YIELD" => Some(Function::Yield),
+            "YIELDDISC" => Some(Function::Yielddisc),
+            "YIELDMAT" => Some(Function::Yieldmat),
             "TBILLPRICE" => Some(Function::Tbillprice),
             "TBILLEQ" => Some(Function::Tbilleq),
 
@@ -502,6 +1043,7 @@ impl Function {
             "GESTEP" => Some(Function::Gestep),
 
             "SUBTOTAL" => Some(Function::Subtotal),
+            "AGGREGATE" | "_XLFN.AGGREGATE" => Some(Function::Aggregate),
             _ => None,
         }
     }
@@ -521,6 +1063,30 @@ impl fmt::Display for Function {
             Function::Switch => write!(f, "SWITCH"),
             Function::True => write!(f, "TRUE"),
             Function::Xor => write!(f, "XOR"),
+            Function::Let => write!(f, "LET"),
+            Function::Lambda => write!(f, "LAMBDA"),
+            Function::Map => write!(f, "MAP"),
+            Function::Reduce => write!(f, "REDUCE"),
+            Function::Scan => write!(f, "SCAN"),
+            Function::Byrow => write!(f, "BYROW"),
+            Function::Bycol => write!(f, "BYCOL"),
+            Function::Makearray => write!(f, "MAKEARRAY"),
+            Function::Filter => write!(f, "FILTER"),
+            Function::Sort => write!(f, "SORT"),
+            Function::Sortby => write!(f, "SORTBY"),
+            Function::Unique => write!(f, "UNIQUE"),
+            Function::Sequence => write!(f, "SEQUENCE"),
+            Function::Randarray => write!(f, "RANDARRAY"),
+            Function::Take => write!(f, "TAKE"),
+            Function::Drop => write!(f, "DROP"),
+            Function::Chooserows => write!(f, "CHOOSEROWS"),
+            Function::Choosecols => write!(f, "CHOOSECOLS"),
+            Function::Vstack => write!(f, "VSTACK"),
+            Function::Hstack => write!(f, "HSTACK"),
+            Function::Tocol => write!(f, "TOCOL"),
+            Function::Torow => write!(f, "TOROW"),
+            Function::Wraprows => write!(f, "WRAPROWS"),
+            Function::Wrapcols => write!(f, "WRAPCOLS"),
             Function::Sin => write!(f, "SIN"),
             Function::Cos => write!(f, "COS"),
             Function::Tan => write!(f, "TAN"),
@@ -530,6 +1096,40 @@ impl fmt::Display for Function {
             Function::Sinh => write!(f, "SINH"),
             Function::Cosh => write!(f, "COSH"),
             Function::Tanh => write!(f, "TANH"),
+            Function::Ceiling => write!(f, "CEILING"),
+            Function::CeilingMath => write!(f, "CEILING.MATH"),
+            Function::CeilingPrecise => write!(f, "CEILING.PRECISE"),
+            Function::Floor => write!(f, "FLOOR"),
+            Function::FloorMath => write!(f, "FLOOR.MATH"),
+            Function::FloorPrecise => write!(f, "FLOOR.PRECISE"),
+            Function::Mround => write!(f, "MROUND"),
+            Function::Int => write!(f, "INT"),
+            Function::Trunc => write!(f, "TRUNC"),
+            Function::Mod => write!(f, "MOD"),
+            Function::Quotient => write!(f, "QUOTIENT"),
+            Function::Gcd => write!(f, "GCD"),
+            Function::Lcm => write!(f, "LCM"),
+            Function::Even => write!(f, "EVEN"),
+            Function::Odd => write!(f, "ODD"),
+            Function::Sign => write!(f, "SIGN"),
+            Function::Exp => write!(f, "EXP"),
+            Function::Ln => write!(f, "LN"),
+            Function::Log => write!(f, "LOG"),
+            Function::Log10 => write!(f, "LOG10"),
+            Function::Degrees => write!(f, "DEGREES"),
+            Function::Radians => write!(f, "RADIANS"),
+            Function::Cot => write!(f, "COT"),
+            Function::Sec => write!(f, "SEC"),
+            Function::Csc => write!(f, "CSC"),
+            Function::Fact => write!(f, "FACT"),
+            Function::Factdouble => write!(f, "FACTDOUBLE"),
+            Function::Combin => write!(f, "COMBIN"),
+            Function::Combina => write!(f, "COMBINA"),
+            Function::Permut => write!(f, "PERMUT"),
+            Function::Permutationa => write!(f, "PERMUTATIONA"),
+            Function::Multinomial => write!(f, "MULTINOMIAL"),
+            Function::Seriessum => write!(f, "SERIESSUM"),
+            Function::Sumsq => write!(f, "SUMSQ"),
             Function::Asinh => write!(f, "ASINH"),
             Function::Acosh => write!(f, "ACOSH"),
             Function::Atanh => write!(f, "ATANH"),
@@ -550,6 +1150,15 @@ impl fmt::Display for Function {
             Function::Sum => write!(f, "SUM"),
             Function::Sumif => write!(f, "SUMIF"),
             Function::Sumifs => write!(f, "SUMIFS"),
+            Function::Sumproduct => write!(f, "SUMPRODUCT"),
+            Function::Mmult => write!(f, "MMULT"),
+            Function::Minverse => write!(f, "MINVERSE"),
+            Function::Mdeterm => write!(f, "MDETERM"),
+            Function::Munit => write!(f, "MUNIT"),
+            Function::Transpose => write!(f, "TRANSPOSE"),
+            Function::Sumx2my2 => write!(f, "SUMX2MY2"),
+            Function::Sumx2py2 => write!(f, "SUMX2PY2"),
+            Function::Sumxmy2 => write!(f, "SUMXMY2"),
             Function::Choose => write!(f, "CHOOSE"),
             Function::Column => write!(f, "COLUMN"),
             Function::Columns => write!(f, "COLUMNS"),
@@ -607,6 +1216,111 @@ impl fmt::Display for Function {
             Function::Countifs => write!(f, "COUNTIFS"),
             Function::Maxifs => write!(f, "MAXIFS"),
             Function::Minifs => write!(f, "MINIFS"),
+            Function::Avedev => write!(f, "AVEDEV"),
+            Function::Devsq => write!(f, "DEVSQ"),
+            Function::Geomean => write!(f, "GEOMEAN"),
+            Function::Harmean => write!(f, "HARMEAN"),
+            Function::Kurt => write!(f, "KURT"),
+            Function::Large => write!(f, "LARGE"),
+            Function::Median => write!(f, "MEDIAN"),
+            Function::Mode => write!(f, "MODE"),
+            Function::ModeMult => write!(f, "MODE.MULT"),
+            Function::ModeSngl => write!(f, "MODE.SNGL"),
+            Function::Percentile => write!(f, "PERCENTILE"),
+            Function::PercentileExc => write!(f, "PERCENTILE.EXC"),
+            Function::PercentileInc => write!(f, "PERCENTILE.INC"),
+            Function::Percentrank => write!(f, "PERCENTRANK"),
+            Function::PercentrankExc => write!(f, "PERCENTRANK.EXC"),
+            Function::PercentrankInc => write!(f, "PERCENTRANK.INC"),
+            Function::Quartile => write!(f, "QUARTILE"),
+            Function::QuartileExc => write!(f, "QUARTILE.EXC"),
+            Function::QuartileInc => write!(f, "QUARTILE.INC"),
+            Function::Rank => write!(f, "RANK"),
+            Function::RankAvg => write!(f, "RANK.AVG"),
+            Function::RankEq => write!(f, "RANK.EQ"),
+            Function::Skew => write!(f, "SKEW"),
+            Function::Small => write!(f, "SMALL"),
+            Function::Stdev => write!(f, "STDEV"),
+            Function::StdevP => write!(f, "STDEV.P"),
+            Function::StdevS => write!(f, "STDEV.S"),
+            Function::Stdeva => write!(f, "STDEVA"),
+            Function::Stdevp => write!(f, "STDEVP"),
+            Function::Stdevpa => write!(f, "STDEVPA"),
+            Function::Trimmean => write!(f, "TRIMMEAN"),
+            Function::Var => write!(f, "VAR"),
+            Function::VarP => write!(f, "VAR.P"),
+            Function::VarS => write!(f, "VAR.S"),
+            Function::Vara => write!(f, "VARA"),
+            Function::Varp => write!(f, "VARP"),
+            Function::Varpa => write!(f, "VARPA"),
+            Function::Correl => write!(f, "CORREL"),
+            Function::Covar => write!(f, "COVAR"),
+            Function::CovarianceP => write!(f, "COVARIANCE.P"),
+            Function::CovarianceS => write!(f, "COVARIANCE.S"),
+            Function::Forecast => write!(f, "FORECAST"),
+            Function::ForecastLinear => write!(f, "FORECAST.LINEAR"),
+            Function::Growth => write!(f, "GROWTH"),
+            Function::Intercept => write!(f, "INTERCEPT"),
+            Function::Linest => write!(f, "LINEST"),
+            Function::Logest => write!(f, "LOGEST"),
+            Function::Pearson => write!(f, "PEARSON"),
+            Function::Rsq => write!(f, "RSQ"),
+            Function::Slope => write!(f, "SLOPE"),
+            Function::Steyx => write!(f, "STEYX"),
+            Function::Trend => write!(f, "TREND"),
+            Function::NormDist => write!(f, "NORM.DIST"),
+            Function::Normdist => write!(f, "NORMDIST"),
+            Function::NormInv => write!(f, "NORM.INV"),
+            Function::Norminv => write!(f, "NORMINV"),
+            Function::NormSDist => write!(f, "NORM.S.DIST"),
+            Function::Normsdist => write!(f, "NORMSDIST"),
+            Function::NormSInv => write!(f, "NORM.S.INV"),
+            Function::Normsinv => write!(f, "NORMSINV"),
+            Function::LognormDist => write!(f, "LOGNORM.DIST"),
+            Function::Lognormdist => write!(f, "LOGNORMDIST"),
+            Function::LognormInv => write!(f, "LOGNORM.INV"),
+            Function::Loginv => write!(f, "LOGINV"),
+            Function::TDist => write!(f, "T.DIST"),
+            Function::TDistRt => write!(f, "T.DIST.RT"),
+            Function::TDist2T => write!(f, "T.DIST.2T"),
+            Function::Tdist => write!(f, "TDIST"),
+            Function::TInv => write!(f, "T.INV"),
+            Function::TInv2T => write!(f, "T.INV.2T"),
+            Function::Tinv => write!(f, "TINV"),
+            Function::ChisqDist => write!(f, "CHISQ.DIST"),
+            Function::ChisqDistRt => write!(f, "CHISQ.DIST.RT"),
+            Function::Chidist => write!(f, "CHIDIST"),
+            Function::ChisqInv => write!(f, "CHISQ.INV"),
+            Function::ChisqInvRt => write!(f, "CHISQ.INV.RT"),
+            Function::Chiinv => write!(f, "CHIINV"),
+            Function::FDist => write!(f, "F.DIST"),
+            Function::FDistRt => write!(f, "F.DIST.RT"),
+            Function::Fdist => write!(f, "FDIST"),
+            Function::FInv => write!(f, "F.INV"),
+            Function::FInvRt => write!(f, "F.INV.RT"),
+            Function::Finv => write!(f, "FINV"),
+            Function::BinomDist => write!(f, "BINOM.DIST"),
+            Function::Binomdist => write!(f, "BINOMDIST"),
+            Function::BinomInv => write!(f, "BINOM.INV"),
+            Function::Critbinom => write!(f, "CRITBINOM"),
+            Function::PoissonDist => write!(f, "POISSON.DIST"),
+            Function::Poisson => write!(f, "POISSON"),
+            Function::ExponDist => write!(f, "EXPON.DIST"),
+            Function::Expondist => write!(f, "EXPONDIST"),
+            Function::GammaDist => write!(f, "GAMMA.DIST"),
+            Function::Gammadist => write!(f, "GAMMADIST"),
+            Function::GammaInv => write!(f, "GAMMA.INV"),
+            Function::Gammainv => write!(f, "GAMMAINV"),
+            Function::Gamma => write!(f, "GAMMA"),
+            Function::Gammaln => write!(f, "GAMMALN"),
+            Function::GammalnPrecise => write!(f, "GAMMALN.PRECISE"),
+            Function::BetaDist => write!(f, "BETA.DIST"),
+            Function::Betadist => write!(f, "BETADIST"),
+            Function::BetaInv => write!(f, "BETA.INV"),
+            Function::Betainv => write!(f, "BETAINV"),
+            Function::ConfidenceNorm => write!(f, "CONFIDENCE.NORM"),
+            Function::Confidence => write!(f, "CONFIDENCE"),
+            Function::ConfidenceT => write!(f, "CONFIDENCE.T"),
             Function::Year => write!(f, "YEAR"),
             Function::Day => write!(f, "DAY"),
             Function::Month => write!(f, "MONTH"),
@@ -615,6 +1329,22 @@ impl fmt::Display for Function {
             Function::Edate => write!(f, "EDATE"),
             Function::Today => write!(f, "TODAY"),
             Function::Now => write!(f, "NOW"),
+            Function::Datedif => write!(f, "DATEDIF"),
+            Function::Days360 => write!(f, "DAYS360"),
+            Function::Isoweeknum => write!(f, "ISOWEEKNUM"),
+            Function::Networkdays => write!(f, "NETWORKDAYS"),
+            Function::NetworkdaysIntl => write!(f, "NETWORKDAYS.INTL"),
+            Function::Weekday => write!(f, "WEEKDAY"),
+            Function::Weeknum => write!(f, "WEEKNUM"),
+            Function::Workday => write!(f, "WORKDAY"),
+            Function::WorkdayIntl => write!(f, "WORKDAY.INTL"),
+            Function::Yearfrac => write!(f, "YEARFRAC"),
+            Function::Time => write!(f, "TIME"),
+            Function::Hour => write!(f, "HOUR"),
+            Function::Minute => write!(f, "MINUTE"),
+            Function::Second => write!(f, "SECOND"),
+            Function::Timevalue => write!(f, "TIMEVALUE"),
+            Function::Datevalue => write!(f, "DATEVALUE"),
             Function::Pmt => write!(f, "PMT"),
             Function::Pv => write!(f, "PV"),
             Function::Rate => write!(f, "RATE"),
@@ -640,6 +1370,25 @@ impl fmt::Display for Function {
             Function::Effect => write!(f, "EFFECT"),
             Function::Pduration => write!(f, "PDURATION"),
             Function::Tbillyield => write!(f, "TBILLYIELD"),
+            Function::Accrint => write!(f, "ACCRINT"),
+            Function::Accrintm => write!(f, "ACCRINTM"),
+            Function::Coupdaybs => write!(f, "COUPDAYBS"),
+            Function::Coupdays => write!(f, "COUPDAYS"),
+            Function::Coupdaysnc => write!(f, "COUPDAYSNC"),
+            Function::Coupncd => write!(f, "COUPNCD"),
+            Function::Coupnum => write!(f, "COUPNUM"),
+            Function::Couppcd => write!(f, "COUPPCD"),
+            Function::Disc => write!(f, "DISC"),
+            Function::Duration => write!(f, "DURATION"),
+            Function::Intrate => write!(f, "INTRATE"),
+            Function::Mduration => write!(f, "MDURATION"),
+            Function::Price => write!(f, "PRICE"),
+            Function::Pricedisc => write!(f, "PRICEDISC"),
+            Function::Pricemat => write!(f, "PRICEMAT"),
+            Function::Received => write!(f, "RECEIVED"),
+            Function::Yield => write!(f, "YIELD"),
+            Function::Yielddisc => write!(f, "YIELDDISC"),
+            Function::Yieldmat => write!(f, "YIELDMAT"),
             Function::Tbillprice => write!(f, "TBILLPRICE"),
             Function::Tbilleq => write!(f, "TBILLEQ"),
             Function::Dollarde => write!(f, "DOLLARDE"),
@@ -704,6 +1453,7 @@ impl fmt::Display for Function {
             Function::Gestep => write!(f, "GESTEP"),
 
             Function::Subtotal => write!(f, "SUBTOTAL"),
+            Function::Aggregate => write!(f, "AGGREGATE"),
         }
     }
 }
@@ -728,6 +1478,31 @@ impl Model {
             Function::Switch => self.fn_switch(args, cell),
             Function::True => CalcResult::Boolean(true),
             Function::Xor => self.fn_xor(args, cell),
+            // LET, LAMBDA and LAMBDA helpers
+            Function::Let => self.fn_let(args, cell),
+            Function::Lambda => self.fn_lambda(args, cell),
+            Function::Map => self.fn_map(args, cell),
+            Function::Reduce => self.fn_reduce(args, cell),
+            Function::Scan => self.fn_scan(args, cell),
+            Function::Byrow => self.fn_byrow(args, cell),
+            Function::Bycol => self.fn_bycol(args, cell),
+            Function::Makearray => self.fn_makearray(args, cell),
+            Function::Filter => self.fn_filter(args, cell),
+            Function::Sort => self.fn_sort(args, cell),
+            Function::Sortby => self.fn_sortby(args, cell),
+            Function::Unique => self.fn_unique(args, cell),
+            Function::Sequence => self.fn_sequence(args, cell),
+            Function::Randarray => self.fn_randarray(args, cell),
+            Function::Take => self.fn_take(args, cell),
+            Function::Drop => self.fn_drop(args, cell),
+            Function::Chooserows => self.fn_chooserows(args, cell),
+            Function::Choosecols => self.fn_choosecols(args, cell),
+            Function::Vstack => self.fn_vstack(args, cell),
+            Function::Hstack => self.fn_hstack(args, cell),
+            Function::Tocol => self.fn_tocol(args, cell),
+            Function::Torow => self.fn_torow(args, cell),
+            Function::Wraprows => self.fn_wraprows(args, cell),
+            Function::Wrapcols => self.fn_wrapcols(args, cell),
             // Math and trigonometry
             Function::Sin => self.fn_sin(args, cell),
             Function::Cos => self.fn_cos(args, cell),
@@ -740,6 +1515,40 @@ impl Model {
             Function::Sinh => self.fn_sinh(args, cell),
             Function::Cosh => self.fn_cosh(args, cell),
             Function::Tanh => self.fn_tanh(args, cell),
+            Function::Ceiling => self.fn_ceiling(args, cell),
+            Function::CeilingMath => self.fn_ceiling_math(args, cell),
+            Function::CeilingPrecise => self.fn_ceiling_precise(args, cell),
+            Function::Floor => self.fn_floor(args, cell),
+            Function::FloorMath => self.fn_floor_math(args, cell),
+            Function::FloorPrecise => self.fn_floor_precise(args, cell),
+            Function::Mround => self.fn_mround(args, cell),
+            Function::Int => self.fn_int(args, cell),
+            Function::Trunc => self.fn_trunc(args, cell),
+            Function::Mod => self.fn_mod(args, cell),
+            Function::Quotient => self.fn_quotient(args, cell),
+            Function::Gcd => self.fn_gcd(args, cell),
+            Function::Lcm => self.fn_lcm(args, cell),
+            Function::Even => self.fn_even(args, cell),
+            Function::Odd => self.fn_odd(args, cell),
+            Function::Sign => self.fn_sign(args, cell),
+            Function::Exp => self.fn_exp(args, cell),
+            Function::Ln => self.fn_ln(args, cell),
+            Function::Log => self.fn_log(args, cell),
+            Function::Log10 => self.fn_log10(args, cell),
+            Function::Degrees => self.fn_degrees(args, cell),
+            Function::Radians => self.fn_radians(args, cell),
+            Function::Cot => self.fn_cot(args, cell),
+            Function::Sec => self.fn_sec(args, cell),
+            Function::Csc => self.fn_csc(args, cell),
+            Function::Fact => self.fn_fact(args, cell),
+            Function::Factdouble => self.fn_factdouble(args, cell),
+            Function::Combin => self.fn_combin(args, cell),
+            Function::Combina => self.fn_combina(args, cell),
+            Function::Permut => self.fn_permut(args, cell),
+            Function::Permutationa => self.fn_permutationa(args, cell),
+            Function::Multinomial => self.fn_multinomial(args, cell),
+            Function::Seriessum => self.fn_seriessum(args, cell),
+            Function::Sumsq => self.fn_sumsq(args, cell),
 
             Function::Asinh => self.fn_asinh(args, cell),
             Function::Acosh => self.fn_acosh(args, cell),
@@ -764,6 +1573,15 @@ impl Model {
             Function::Sum => self.fn_sum(args, cell),
             Function::Sumif => self.fn_sumif(args, cell),
             Function::Sumifs => self.fn_sumifs(args, cell),
+            Function::Sumproduct => self.fn_sumproduct(args, cell),
+            Function::Mmult => self.fn_mmult(args, cell),
+            Function::Minverse => self.fn_minverse(args, cell),
+            Function::Mdeterm => self.fn_mdeterm(args, cell),
+            Function::Munit => self.fn_munit(args, cell),
+            Function::Transpose => self.fn_transpose(args, cell),
+            Function::Sumx2my2 => self.fn_sumx2my2(args, cell),
+            Function::Sumx2py2 => self.fn_sumx2py2(args, cell),
+            Function::Sumxmy2 => self.fn_sumxmy2(args, cell),
 
             // Lookup and Reference
             Function::Choose => self.fn_choose(args, cell),
@@ -825,6 +1643,112 @@ impl Model {
             Function::Countifs => self.fn_countifs(args, cell),
             Function::Maxifs => self.fn_maxifs(args, cell),
             Function::Minifs => self.fn_minifs(args, cell),
+            Function::Avedev => self.fn_avedev(args, cell),
+            Function::Devsq => self.fn_devsq(args, cell),
+            Function::Geomean => self.fn_geomean(args, cell),
+            Function::Harmean => self.fn_harmean(args, cell),
+            Function::Kurt => self.fn_kurt(args, cell),
+            Function::Large => self.fn_large(args, cell),
+            Function::Median => self.fn_median(args, cell),
+            Function::Mode => self.fn_mode_sngl(args, cell),
+            Function::ModeMult => self.fn_mode_mult(args, cell),
+            Function::ModeSngl => self.fn_mode_sngl(args, cell),
+            Function::Percentile => self.fn_percentile_inc(args, cell),
+            Function::PercentileExc => self.fn_percentile_exc(args, cell),
+            Function::PercentileInc => self.fn_percentile_inc(args, cell),
+            Function::Percentrank => self.fn_percentrank_inc(args, cell),
+            Function::PercentrankExc => self.fn_percentrank_exc(args, cell),
+            Function::PercentrankInc => self.fn_percentrank_inc(args, cell),
+            Function::Quartile => self.fn_quartile_inc(args, cell),
+            Function::QuartileExc => self.fn_quartile_exc(args, cell),
+            Function::QuartileInc => self.fn_quartile_inc(args, cell),
+            Function::Rank => self.fn_rank_eq(args, cell),
+            Function::RankAvg => self.fn_rank_avg(args, cell),
+            Function::RankEq => self.fn_rank_eq(args, cell),
+            Function::Skew => self.fn_skew(args, cell),
+            Function::Small => self.fn_small(args, cell),
+            Function::Stdev => self.fn_stdev_s(args, cell),
+            Function::StdevP => self.fn_stdev_p(args, cell),
+            Function::StdevS => self.fn_stdev_s(args, cell),
+            Function::Stdeva => self.fn_stdeva(args, cell),
+            Function::Stdevp => self.fn_stdev_p(args, cell),
+            Function::Stdevpa => self.fn_stdevpa(args, cell),
+            Function::Trimmean => self.fn_trimmean(args, cell),
+            Function::Var => self.fn_var_s(args, cell),
+            Function::VarP => self.fn_var_p(args, cell),
+            Function::VarS => self.fn_var_s(args, cell),
+            Function::Vara => self.fn_vara(args, cell),
+            Function::Varp => self.fn_var_p(args, cell),
+            Function::Varpa => self.fn_varpa(args, cell),
+            Function::Correl => self.fn_correl(args, cell),
+            Function::Covar => self.fn_covariance_p(args, cell),
+            Function::CovarianceP => self.fn_covariance_p(args, cell),
+            Function::CovarianceS => self.fn_covariance_s(args, cell),
+            Function::Forecast => self.fn_forecast_linear(args, cell),
+            Function::ForecastLinear => self.fn_forecast_linear(args, cell),
+            Function::Growth => self.fn_growth(args, cell),
+            Function::Intercept => self.fn_intercept(args, cell),
+            Function::Linest => self.fn_linest(args, cell),
+            Function::Logest => self.fn_logest(args, cell),
+            Function::Pearson => self.fn_correl(args, cell),
+            Function::Rsq => self.fn_rsq(args, cell),
+            Function::Slope => self.fn_slope(args, cell),
+            Function::Steyx => self.fn_steyx(args, cell),
+            Function::Trend => self.fn_trend(args, cell),
+            // Statistical distributions
+            Function::NormDist => self.fn_norm_dist(args, cell),
+            Function::Normdist => self.fn_norm_dist(args, cell),
+            Function::NormInv => self.fn_norm_inv(args, cell),
+            Function::Norminv => self.fn_norm_inv(args, cell),
+            Function::NormSDist => self.fn_norm_s_dist(args, cell),
+            Function::Normsdist => self.fn_normsdist(args, cell),
+            Function::NormSInv => self.fn_norm_s_inv(args, cell),
+            Function::Normsinv => self.fn_norm_s_inv(args, cell),
+            Function::LognormDist => self.fn_lognorm_dist(args, cell),
+            Function::Lognormdist => self.fn_lognormdist(args, cell),
+            Function::LognormInv => self.fn_lognorm_inv(args, cell),
+            Function::Loginv => self.fn_lognorm_inv(args, cell),
+            Function::TDist => self.fn_t_dist(args, cell),
+            Function::TDistRt => self.fn_t_dist_rt(args, cell),
+            Function::TDist2T => self.fn_t_dist_2t(args, cell),
+            Function::Tdist => self.fn_tdist(args, cell),
+            Function::TInv => self.fn_t_inv(args, cell),
+            Function::TInv2T => self.fn_t_inv_2t(args, cell),
+            Function::Tinv => self.fn_t_inv_2t(args, cell),
+            Function::ChisqDist => self.fn_chisq_dist(args, cell),
+            Function::ChisqDistRt => self.fn_chisq_dist_rt(args, cell),
+            Function::Chidist => self.fn_chisq_dist_rt(args, cell),
+            Function::ChisqInv => self.fn_chisq_inv(args, cell),
+            Function::ChisqInvRt => self.fn_chisq_inv_rt(args, cell),
+            Function::Chiinv => self.fn_chisq_inv_rt(args, cell),
+            Function::FDist => self.fn_f_dist(args, cell),
+            Function::FDistRt => self.fn_f_dist_rt(args, cell),
+            Function::Fdist => self.fn_f_dist_rt(args, cell),
+            Function::FInv => self.fn_f_inv(args, cell),
+            Function::FInvRt => self.fn_f_inv_rt(args, cell),
+            Function::Finv => self.fn_f_inv_rt(args, cell),
+            Function::BinomDist => self.fn_binom_dist(args, cell),
+            Function::Binomdist => self.fn_binom_dist(args, cell),
+            Function::BinomInv => self.fn_binom_inv(args, cell),
+            Function::Critbinom => self.fn_binom_inv(args, cell),
+            Function::PoissonDist => self.fn_poisson_dist(args, cell),
+            Function::Poisson => self.fn_poisson_dist(args, cell),
+            Function::ExponDist => self.fn_expon_dist(args, cell),
+            Function::Expondist => self.fn_expon_dist(args, cell),
+            Function::GammaDist => self.fn_gamma_dist(args, cell),
+            Function::Gammadist => self.fn_gamma_dist(args, cell),
+            Function::GammaInv => self.fn_gamma_inv(args, cell),
+            Function::Gammainv => self.fn_gamma_inv(args, cell),
+            Function::Gamma => self.fn_gamma(args, cell),
+            Function::Gammaln => self.fn_gammaln(args, cell),
+            Function::GammalnPrecise => self.fn_gammaln(args, cell),
+            Function::BetaDist => self.fn_beta_dist(args, cell),
+            Function::Betadist => self.fn_betadist(args, cell),
+            Function::BetaInv => self.fn_beta_inv(args, cell),
+            Function::Betainv => self.fn_beta_inv(args, cell),
+            Function::ConfidenceNorm => self.fn_confidence_norm(args, cell),
+            Function::Confidence => self.fn_confidence_norm(args, cell),
+            Function::ConfidenceT => self.fn_confidence_t(args, cell),
             // Date and Time
             Function::Year => self.fn_year(args, cell),
             Function::Day => self.fn_day(args, cell),
@@ -834,6 +1758,22 @@ impl Model {
             Function::Edate => self.fn_edate(args, cell),
             Function::Today => self.fn_today(args, cell),
             Function::Now => self.fn_now(args, cell),
+            Function::Datedif => self.fn_datedif(args, cell),
+            Function::Days360 => self.fn_days360(args, cell),
+            Function::Isoweeknum => self.fn_isoweeknum(args, cell),
+            Function::Networkdays => self.fn_networkdays(args, cell),
+            Function::NetworkdaysIntl => self.fn_networkdays_intl(args, cell),
+            Function::Weekday => self.fn_weekday(args, cell),
+            Function::Weeknum => self.fn_weeknum(args, cell),
+            Function::Workday => self.fn_workday(args, cell),
+            Function::WorkdayIntl => self.fn_workday_intl(args, cell),
+            Function::Yearfrac => self.fn_yearfrac(args, cell),
+            Function::Time => self.fn_time(args, cell),
+            Function::Hour => self.fn_hour(args, cell),
+            Function::Minute => self.fn_minute(args, cell),
+            Function::Second => self.fn_second(args, cell),
+            Function::Timevalue => self.fn_timevalue(args, cell),
+            Function::Datevalue => self.fn_datevalue(args, cell),
             // Financial
             Function::Pmt => self.fn_pmt(args, cell),
             Function::Pv => self.fn_pv(args, cell),
@@ -860,6 +1800,25 @@ impl Model {
             Function::Effect => self.fn_effect(args, cell),
             Function::Pduration => self.fn_pduration(args, cell),
             Function::Tbillyield => self.fn_tbillyield(args, cell),
+            Function::Accrint => self.fn_accrint(args, cell),
+            Function::Accrintm => self.fn_accrintm(args, cell),
+            Function::Coupdaybs => self.fn_coupdaybs(args, cell),
+            Function::Coupdays => self.fn_coupdays(args, cell),
+            Function::Coupdaysnc => self.fn_coupdaysnc(args, cell),
+            Function::Coupncd => self.fn_coupncd(args, cell),
+            Function::Coupnum => self.fn_coupnum(args, cell),
+            Function::Couppcd => self.fn_couppcd(args, cell),
+            Function::Disc => self.fn_disc(args, cell),
+            Function::Duration => self.fn_duration(args, cell),
+            Function::Intrate => self.fn_intrate(args, cell),
+            Function::Mduration => self.fn_mduration(args, cell),
+            Function::Price => self.fn_price(args, cell),
+            Function::Pricedisc => self.fn_pricedisc(args, cell),
+            Function::Pricemat => self.fn_pricemat(args, cell),
+            Function::Received => self.fn_received(args, cell),
+            Function::Yield => self.fn_yield(args, cell),
+            Function::Yielddisc => self.fn_yielddisc(args, cell),
+            Function::Yieldmat => self.fn_yieldmat(args, cell),
             Function::Tbillprice => self.fn_tbillprice(args, cell),
             Function::Tbilleq => self.fn_tbilleq(args, cell),
             Function::Dollarde => self.fn_dollarde(args, cell),
@@ -925,6 +1884,7 @@ impl Model {
             Function::Gestep => self.fn_gestep(args, cell),
 
             Function::Subtotal => self.fn_subtotal(args, cell),
+            Function::Aggregate => self.fn_aggregate(args, cell),
         }
     }
 }
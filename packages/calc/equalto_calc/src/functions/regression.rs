@@ -0,0 +1,452 @@
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    expressions::{parser::Node, token::Error},
+    model::Model,
+};
+
+use super::regression_util::{linear_least_squares, LinearFit};
+
+// Regression and correlation functions.
+// SLOPE, INTERCEPT, RSQ, ... work with pairs of numbers ignoring any other value, while LINEST,
+// LOGEST, TREND and GROWTH need all the known values to be numbers.
+
+/// Sums of a list of pairs (x, y) around their means
+struct PairStatistics {
+    count: f64,
+    x_mean: f64,
+    y_mean: f64,
+    /// sum of (x - x_mean)^2
+    sxx: f64,
+    /// sum of (y - y_mean)^2
+    syy: f64,
+    /// sum of (x - x_mean)(y - y_mean)
+    sxy: f64,
+}
+
+fn pair_statistics(pairs: &[(f64, f64)]) -> PairStatistics {
+    let count = pairs.len() as f64;
+    let x_mean = pairs.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let y_mean = pairs.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let mut sxx = 0.0;
+    let mut syy = 0.0;
+    let mut sxy = 0.0;
+    for (x, y) in pairs {
+        sxx += (x - x_mean) * (x - x_mean);
+        syy += (y - y_mean) * (y - y_mean);
+        sxy += (x - x_mean) * (y - y_mean);
+    }
+    PairStatistics {
+        count,
+        x_mean,
+        y_mean,
+        sxx,
+        syy,
+        sxy,
+    }
+}
+
+fn division_by_zero(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string())
+}
+
+fn size_mismatch(cell: CellReference) -> CalcResult {
+    CalcResult::new_error(Error::REF, cell, "Arrays of incompatible sizes".to_string())
+}
+
+fn number_or_error(value: f64, cell: CellReference) -> CalcResult {
+    if value.is_finite() {
+        CalcResult::Number(value)
+    } else {
+        CalcResult::new_error(Error::NUM, cell, "Invalid result".to_string())
+    }
+}
+
+/// Known values of LINEST, LOGEST, TREND and GROWTH
+struct RegressionData {
+    /// Observed values
+    y: Vec<f64>,
+    /// One row per observation with the value of each variable
+    x: Vec<Vec<f64>>,
+    /// The known x's, as given or the default {1, 2, 3, ...} with the shape of the known y's
+    known_x: Vec<Vec<f64>>,
+    /// If true each row of the known x's is a variable, otherwise each column
+    variables_in_rows: bool,
+}
+
+impl RegressionData {
+    fn variable_count(&self) -> usize {
+        self.x.first().map_or(0, |row| row.len())
+    }
+}
+
+impl Model {
+    // Pairs of numbers at the same position of two arrays. Pairs where any of the values is not a
+    // number are ignored.
+    pub(crate) fn get_number_pairs(
+        &mut self,
+        first: &Node,
+        second: &Node,
+        cell: CellReference,
+    ) -> Result<Vec<(f64, f64)>, CalcResult> {
+        let first: Vec<CalcResult> = self
+            .get_array_argument(first, cell)?
+            .into_iter()
+            .flatten()
+            .collect();
+        let second: Vec<CalcResult> = self
+            .get_array_argument(second, cell)?
+            .into_iter()
+            .flatten()
+            .collect();
+        if first.len() != second.len() {
+            return Err(CalcResult::new_error(
+                Error::NA,
+                cell,
+                "Arrays of different sizes".to_string(),
+            ));
+        }
+        let mut pairs = Vec::new();
+        for (a, b) in first.into_iter().zip(second) {
+            match (a, b) {
+                (error @ CalcResult::Error { .. }, _) | (_, error @ CalcResult::Error { .. }) => {
+                    return Err(error)
+                }
+                (CalcResult::Number(a), CalcResult::Number(b)) => pairs.push((a, b)),
+                _ => {}
+            }
+        }
+        Ok(pairs)
+    }
+
+    // Statistics of the pairs (x, y) of the arguments `known_y's` and `known_x's`
+    fn get_known_pair_statistics(
+        &mut self,
+        known_y: &Node,
+        known_x: &Node,
+        cell: CellReference,
+    ) -> Result<PairStatistics, CalcResult> {
+        let pairs = self.get_number_pairs(known_x, known_y, cell)?;
+        if pairs.is_empty() {
+            return Err(division_by_zero(cell));
+        }
+        Ok(pair_statistics(&pairs))
+    }
+
+    // SLOPE(known_y's, known_x's)
+    pub(crate) fn fn_slope(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.sxx != 0.0 => CalcResult::Number(stats.sxy / stats.sxx),
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // INTERCEPT(known_y's, known_x's)
+    pub(crate) fn fn_intercept(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.sxx != 0.0 => {
+                CalcResult::Number(stats.y_mean - stats.sxy / stats.sxx * stats.x_mean)
+            }
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // RSQ(known_y's, known_x's)
+    pub(crate) fn fn_rsq(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.sxx != 0.0 && stats.syy != 0.0 => {
+                CalcResult::Number(stats.sxy * stats.sxy / (stats.sxx * stats.syy))
+            }
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // STEYX(known_y's, known_x's)
+    pub(crate) fn fn_steyx(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.count >= 3.0 && stats.sxx != 0.0 => {
+                let residual = stats.syy - stats.sxy * stats.sxy / stats.sxx;
+                CalcResult::Number((residual.max(0.0) / (stats.count - 2.0)).sqrt())
+            }
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // CORREL(array1, array2) and PEARSON(array1, array2)
+    pub(crate) fn fn_correl(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.sxx != 0.0 && stats.syy != 0.0 => {
+                CalcResult::Number(stats.sxy / (stats.sxx * stats.syy).sqrt())
+            }
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // COVARIANCE.P(array1, array2) and COVAR(array1, array2)
+    pub(crate) fn fn_covariance_p(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) => CalcResult::Number(stats.sxy / stats.count),
+            Err(error) => error,
+        }
+    }
+
+    // COVARIANCE.S(array1, array2)
+    pub(crate) fn fn_covariance_s(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_known_pair_statistics(&args[0], &args[1], cell) {
+            Ok(stats) if stats.count >= 2.0 => CalcResult::Number(stats.sxy / (stats.count - 1.0)),
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // FORECAST.LINEAR(x, known_y's, known_x's) and FORECAST(x, known_y's, known_x's)
+    pub(crate) fn fn_forecast_linear(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let x = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        match self.get_known_pair_statistics(&args[1], &args[2], cell) {
+            Ok(stats) if stats.sxx != 0.0 => {
+                let slope = stats.sxy / stats.sxx;
+                CalcResult::Number(stats.y_mean + slope * (x - stats.x_mean))
+            }
+            Ok(_) => division_by_zero(cell),
+            Err(error) => error,
+        }
+    }
+
+    // The known y's and x's of a regression. If `logarithm` we take the logarithm of the y's for
+    // the exponential models of LOGEST and GROWTH.
+    fn get_regression_data(
+        &mut self,
+        args: &[Node],
+        logarithm: bool,
+        cell: CellReference,
+    ) -> Result<RegressionData, CalcResult> {
+        let known_y = self.get_number_matrix(&args[0], cell)?;
+        let rows = known_y.len();
+        let columns = known_y.first().map_or(0, |row| row.len());
+        let mut y: Vec<f64> = known_y.into_iter().flatten().collect();
+        if logarithm {
+            if y.iter().any(|&value| value <= 0.0) {
+                return Err(CalcResult::new_error(
+                    Error::NUM,
+                    cell,
+                    "Known y's must be positive".to_string(),
+                ));
+            }
+            y = y.iter().map(|value| value.ln()).collect();
+        }
+        let known_x = match args.get(1) {
+            None | Some(Node::EmptyArgKind) => (0..rows)
+                .map(|row| {
+                    (0..columns)
+                        .map(|column| (row * columns + column + 1) as f64)
+                        .collect()
+                })
+                .collect(),
+            Some(node) => self.get_number_matrix(node, cell)?,
+        };
+        let x_rows = known_x.len();
+        let x_columns = known_x.first().map_or(0, |row| row.len());
+        let variables_in_rows = rows == 1 && columns > 1;
+        let x = if columns == 1 {
+            // each column of the known x's is a variable
+            if x_rows != rows {
+                return Err(size_mismatch(cell));
+            }
+            known_x.clone()
+        } else if rows == 1 {
+            // each row of the known x's is a variable
+            if x_columns != columns {
+                return Err(size_mismatch(cell));
+            }
+            (0..columns)
+                .map(|column| known_x.iter().map(|row| row[column]).collect())
+                .collect()
+        } else {
+            // a single variable with the same shape as the known y's
+            if x_rows != rows || x_columns != columns {
+                return Err(size_mismatch(cell));
+            }
+            known_x.iter().flatten().map(|&value| vec![value]).collect()
+        };
+        Ok(RegressionData {
+            y,
+            x,
+            known_x,
+            variables_in_rows,
+        })
+    }
+
+    // LINEST and LOGEST: the coefficients m_k, ..., m_1, b and optionally the regression statistics
+    fn fit_output(&mut self, args: &[Node], exponential: bool, cell: CellReference) -> CalcResult {
+        if args.is_empty() || args.len() > 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let data = match self.get_regression_data(args, exponential, cell) {
+            Ok(data) => data,
+            Err(error) => return error,
+        };
+        let constant = match self.get_optional_boolean(args, 2, true, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let stats = match self.get_optional_boolean(args, 3, false, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let fit = linear_least_squares(&data.y, &data.x, constant);
+        let transform = |value: f64| if exponential { value.exp() } else { value };
+        let width = data.variable_count() + 1;
+        let not_available = || CalcResult::new_error(Error::NA, cell, "Not available".to_string());
+
+        let mut coefficients: Vec<CalcResult> = fit
+            .coefficients
+            .iter()
+            .rev()
+            .map(|&m| number_or_error(transform(m), cell))
+            .collect();
+        coefficients.push(number_or_error(transform(fit.intercept), cell));
+        let mut result = vec![coefficients];
+        if stats {
+            let mut errors: Vec<CalcResult> = fit
+                .coefficient_errors
+                .iter()
+                .rev()
+                .map(|&error| number_or_error(error, cell))
+                .collect();
+            errors.push(match fit.intercept_error {
+                Some(error) => number_or_error(error, cell),
+                None => not_available(),
+            });
+            result.push(errors);
+            let LinearFit {
+                r_squared,
+                standard_error,
+                f_statistic,
+                degrees_of_freedom,
+                regression_sum_of_squares,
+                residual_sum_of_squares,
+                ..
+            } = fit;
+            for (first, second) in [
+                (r_squared, standard_error),
+                (f_statistic, degrees_of_freedom),
+                (regression_sum_of_squares, residual_sum_of_squares),
+            ] {
+                let mut row = vec![number_or_error(first, cell), number_or_error(second, cell)];
+                row.resize_with(width, not_available);
+                result.push(row);
+            }
+        }
+        CalcResult::Array(result)
+    }
+
+    // LINEST(known_y's, [known_x's], [const], [stats])
+    pub(crate) fn fn_linest(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.fit_output(args, false, cell)
+    }
+
+    // LOGEST(known_y's, [known_x's], [const], [stats])
+    pub(crate) fn fn_logest(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.fit_output(args, true, cell)
+    }
+
+    // TREND and GROWTH: the fitted values at new x's, by default the known x's
+    fn predict_output(
+        &mut self,
+        args: &[Node],
+        exponential: bool,
+        cell: CellReference,
+    ) -> CalcResult {
+        if args.is_empty() || args.len() > 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let data = match self.get_regression_data(args, exponential, cell) {
+            Ok(data) => data,
+            Err(error) => return error,
+        };
+        let new_x = match args.get(2) {
+            None | Some(Node::EmptyArgKind) => data.known_x.clone(),
+            Some(node) => match self.get_number_matrix(node, cell) {
+                Ok(matrix) => matrix,
+                Err(error) => return error,
+            },
+        };
+        let constant = match self.get_optional_boolean(args, 3, true, cell) {
+            Ok(b) => b,
+            Err(error) => return error,
+        };
+        let fit = linear_least_squares(&data.y, &data.x, constant);
+        let predict = |x: &[f64]| {
+            let value = fit.predict(x);
+            number_or_error(if exponential { value.exp() } else { value }, cell)
+        };
+        let variable_count = data.variable_count();
+        let result = if variable_count == 1 {
+            // one prediction per new x, with the same shape
+            new_x
+                .iter()
+                .map(|row| row.iter().map(|&x| predict(&[x])).collect())
+                .collect()
+        } else if data.variables_in_rows {
+            // each column of the new x's is a point, the result is a row
+            if new_x.len() != variable_count {
+                return size_mismatch(cell);
+            }
+            let columns = new_x.first().map_or(0, |row| row.len());
+            vec![(0..columns)
+                .map(|column| {
+                    let point: Vec<f64> = new_x.iter().map(|row| row[column]).collect();
+                    predict(&point)
+                })
+                .collect()]
+        } else {
+            // each row of the new x's is a point, the result is a column
+            if new_x.first().map_or(0, |row| row.len()) != variable_count {
+                return size_mismatch(cell);
+            }
+            new_x.iter().map(|row| vec![predict(row)]).collect()
+        };
+        CalcResult::Array(result)
+    }
+
+    // TREND(known_y's, [known_x's], [new_x's], [const])
+    pub(crate) fn fn_trend(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.predict_output(args, false, cell)
+    }
+
+    // GROWTH(known_y's, [known_x's], [new_x's], [const])
+    pub(crate) fn fn_growth(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.predict_output(args, true, cell)
+    }
+}
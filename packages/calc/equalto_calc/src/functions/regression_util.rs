@@ -0,0 +1,193 @@
+// Linear least squares for LINEST, LOGEST, TREND and GROWTH.
+//
+// We solve the problem with a Householder QR decomposition of the design matrix rather than with
+// the normal equations, that would square the condition number of the problem. When the model has
+// a constant term the variables are centered first.
+// Like Excel, collinear variables are removed from the model: their coefficient and standard
+// error are zero.
+// See:
+// https://support.microsoft.com/en-us/office/linest-function-84d7d0d9-6e50-4101-977a-fa7abf772b6d
+// https://en.wikipedia.org/wiki/QR_decomposition#Using_Householder_reflections
+
+// A column whose norm drops below this fraction of its original norm is considered collinear
+const COLLINEARITY_TOLERANCE: f64 = 1e-12;
+
+/// Result of a linear fit y = m_1 x_1 + ... + m_k x_k + b
+pub(crate) struct LinearFit {
+    /// m_1, ..., m_k
+    pub(crate) coefficients: Vec<f64>,
+    /// b, zero if the model has no constant term
+    pub(crate) intercept: f64,
+    /// Standard errors of m_1, ..., m_k
+    pub(crate) coefficient_errors: Vec<f64>,
+    /// Standard error of b, None if the model has no constant term
+    pub(crate) intercept_error: Option<f64>,
+    pub(crate) r_squared: f64,
+    /// Standard error of the y estimate
+    pub(crate) standard_error: f64,
+    pub(crate) f_statistic: f64,
+    pub(crate) degrees_of_freedom: f64,
+    pub(crate) regression_sum_of_squares: f64,
+    pub(crate) residual_sum_of_squares: f64,
+}
+
+impl LinearFit {
+    /// Estimated y for the values of the variables x_1, ..., x_k
+    pub(crate) fn predict(&self, x: &[f64]) -> f64 {
+        self.intercept
+            + self
+                .coefficients
+                .iter()
+                .zip(x)
+                .map(|(m, x)| m * x)
+                .sum::<f64>()
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Fits `y` as a linear function of the variables. `x` has one row per observation with the
+/// value of each variable, all rows must have the same length and there must be as many rows as
+/// values in `y`. There must be at least one observation.
+pub(crate) fn linear_least_squares(y: &[f64], x: &[Vec<f64>], constant: bool) -> LinearFit {
+    let n = y.len();
+    let k = x.first().map_or(0, |row| row.len());
+
+    let (y_mean, x_means) = if constant {
+        let x_means = (0..k)
+            .map(|j| mean(&x.iter().map(|row| row[j]).collect::<Vec<f64>>()))
+            .collect();
+        (mean(y), x_means)
+    } else {
+        (0.0, vec![0.0; k])
+    };
+
+    // columns of the (centered) design matrix and the (centered) observations
+    let mut columns: Vec<Vec<f64>> = (0..k)
+        .map(|j| x.iter().map(|row| row[j] - x_means[j]).collect())
+        .collect();
+    let centered_y: Vec<f64> = y.iter().map(|value| value - y_mean).collect();
+    let mut qty = centered_y.clone();
+
+    // Householder triangularization, `kept` are the indices of the independent columns
+    let mut kept: Vec<usize> = Vec::new();
+    for j in 0..k {
+        let rank = kept.len();
+        if rank == n {
+            break;
+        }
+        let original_norm = columns[j].iter().map(|v| v * v).sum::<f64>().sqrt();
+        let norm = columns[j][rank..].iter().map(|v| v * v).sum::<f64>().sqrt();
+        if original_norm == 0.0 || norm <= COLLINEARITY_TOLERANCE * original_norm {
+            continue;
+        }
+        // v = x + sign(x_0) |x| e_0, the reflection H = I - 2 v v^T / (v^T v) maps x to -sign(x_0) |x| e_0
+        let alpha = if columns[j][rank] > 0.0 { -norm } else { norm };
+        let mut v: Vec<f64> = columns[j][rank..].to_vec();
+        v[0] -= alpha;
+        let v_norm_squared: f64 = v.iter().map(|x| x * x).sum();
+        let reflect = |values: &mut [f64]| {
+            let dot: f64 = v.iter().zip(values.iter()).map(|(a, b)| a * b).sum();
+            let factor = 2.0 * dot / v_norm_squared;
+            for (value, v) in values.iter_mut().zip(v.iter()) {
+                *value -= factor * v;
+            }
+        };
+        for column in columns.iter_mut().skip(j) {
+            reflect(&mut column[rank..]);
+        }
+        reflect(&mut qty[rank..]);
+        kept.push(j);
+    }
+    let rank = kept.len();
+
+    // Back substitution in the triangular system R m = Q^T y
+    let mut solution = vec![0.0; rank];
+    for row in (0..rank).rev() {
+        let mut value = qty[row];
+        for column in row + 1..rank {
+            value -= columns[kept[column]][row] * solution[column];
+        }
+        solution[row] = value / columns[kept[row]][row];
+    }
+
+    // Inverse of R, used for the standard errors: (X^T X)^-1 = R^-1 R^-T
+    let mut r_inverse = vec![vec![0.0; rank]; rank];
+    for column in 0..rank {
+        r_inverse[column][column] = 1.0 / columns[kept[column]][column];
+        for row in (0..column).rev() {
+            let mut value = 0.0;
+            for l in row + 1..=column {
+                value += columns[kept[l]][row] * r_inverse[l][column];
+            }
+            r_inverse[row][column] = -value / columns[kept[row]][row];
+        }
+    }
+
+    let mut coefficients = vec![0.0; k];
+    for (index, &j) in kept.iter().enumerate() {
+        coefficients[j] = solution[index];
+    }
+    let intercept = if constant {
+        y_mean
+            - coefficients
+                .iter()
+                .zip(&x_means)
+                .map(|(m, x)| m * x)
+                .sum::<f64>()
+    } else {
+        0.0
+    };
+
+    let residual_sum_of_squares: f64 = x
+        .iter()
+        .zip(&centered_y)
+        .map(|(row, y)| {
+            let estimate: f64 = coefficients
+                .iter()
+                .zip(row.iter().zip(&x_means))
+                .map(|(m, (x, x_mean))| m * (x - x_mean))
+                .sum();
+            (y - estimate) * (y - estimate)
+        })
+        .sum();
+    let total_sum_of_squares: f64 = centered_y.iter().map(|y| y * y).sum();
+    let regression_sum_of_squares = total_sum_of_squares - residual_sum_of_squares;
+
+    let degrees_of_freedom = n as f64 - rank as f64 - if constant { 1.0 } else { 0.0 };
+    let variance = residual_sum_of_squares / degrees_of_freedom;
+    let standard_error = variance.sqrt();
+
+    let mut coefficient_errors = vec![0.0; k];
+    for (index, &j) in kept.iter().enumerate() {
+        let diagonal: f64 = r_inverse[index].iter().map(|v| v * v).sum();
+        coefficient_errors[j] = (variance * diagonal).sqrt();
+    }
+    let intercept_error = if constant {
+        // Var(b) = s^2 (1/n + x_mean^T (X^T X)^-1 x_mean) = s^2 (1/n + |R^-T x_mean|^2)
+        let quadratic_form: f64 = (0..rank)
+            .map(|l| {
+                let value: f64 = (0..=l).map(|j| r_inverse[j][l] * x_means[kept[j]]).sum();
+                value * value
+            })
+            .sum();
+        Some((variance * (1.0 / n as f64 + quadratic_form)).sqrt())
+    } else {
+        None
+    };
+
+    LinearFit {
+        coefficients,
+        intercept,
+        coefficient_errors,
+        intercept_error,
+        r_squared: regression_sum_of_squares / total_sum_of_squares,
+        standard_error,
+        f_statistic: (regression_sum_of_squares / rank as f64) / variance,
+        degrees_of_freedom,
+        regression_sum_of_squares,
+        residual_sum_of_squares,
+    }
+}
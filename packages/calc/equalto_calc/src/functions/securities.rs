@@ -0,0 +1,490 @@
+//! Functions of bonds paying periodic coupons, securities paying interest at maturity and
+//! discounted securities.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    expressions::{parser::Node, token::Error},
+    formatter::dates::{date_to_serial_number, from_excel_date, max_serial_number},
+    model::Model,
+};
+
+use super::{
+    day_count::{year_fraction, DayCountBasis},
+    securities_util::CouponPeriod,
+};
+
+impl Model {
+    /// A date argument, truncated to a whole day
+    fn get_security_date(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<NaiveDate, CalcResult> {
+        let serial_number = self.get_number_no_bools(node, cell)?.trunc();
+        let date1904 = self.workbook.settings.date1904;
+        if serial_number < 0.0 || serial_number > max_serial_number(date1904) as f64 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Invalid date".to_string(),
+            ));
+        }
+        Ok(from_excel_date(serial_number as i64, date1904))
+    }
+
+    /// Two date arguments, the first one must be before the second one
+    fn get_ordered_dates(
+        &mut self,
+        start: &Node,
+        end: &Node,
+        cell: CellReference,
+    ) -> Result<(NaiveDate, NaiveDate), CalcResult> {
+        let start = self.get_security_date(start, cell)?;
+        let end = self.get_security_date(end, cell)?;
+        if start >= end {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Settlement should be before maturity".to_string(),
+            ));
+        }
+        Ok((start, end))
+    }
+
+    /// Optional `basis` argument, 0 (US 30/360) by default
+    fn get_basis(
+        &mut self,
+        args: &[Node],
+        index: usize,
+        cell: CellReference,
+    ) -> Result<DayCountBasis, CalcResult> {
+        let basis = self.get_optional_number(args, index, 0.0, cell)?;
+        DayCountBasis::from_number(basis)
+            .ok_or_else(|| CalcResult::new_error(Error::NUM, cell, "Invalid basis".to_string()))
+    }
+
+    /// Positive number argument
+    fn get_positive_number(&mut self, node: &Node, cell: CellReference) -> Result<f64, CalcResult> {
+        let value = self.get_number_no_bools(node, cell)?;
+        if value <= 0.0 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument should be > 0".to_string(),
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Non negative number argument
+    fn get_non_negative_number(
+        &mut self,
+        node: &Node,
+        cell: CellReference,
+    ) -> Result<f64, CalcResult> {
+        let value = self.get_number_no_bools(node, cell)?;
+        if value < 0.0 {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Argument should be >= 0".to_string(),
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Coupon period of a bond: settlement and maturity are the first two arguments, the
+    /// frequency (1, 2 or 4) is at `frequency_index` followed by the optional basis
+    fn get_coupon_period(
+        &mut self,
+        args: &[Node],
+        frequency_index: usize,
+        cell: CellReference,
+    ) -> Result<CouponPeriod, CalcResult> {
+        let (settlement, maturity) = self.get_ordered_dates(&args[0], &args[1], cell)?;
+        let frequency = self
+            .get_number_no_bools(&args[frequency_index], cell)?
+            .trunc();
+        if ![1.0, 2.0, 4.0].contains(&frequency) {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Frequency should be 1, 2 or 4".to_string(),
+            ));
+        }
+        let basis = self.get_basis(args, frequency_index + 1, cell)?;
+        Ok(CouponPeriod::new(
+            settlement,
+            maturity,
+            frequency as i32,
+            basis,
+        ))
+    }
+
+    /// Arguments of the COUP* functions: settlement, maturity, frequency, [basis]
+    fn get_coupon_function_period(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<CouponPeriod, CalcResult> {
+        if !(3..=4).contains(&args.len()) {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        self.get_coupon_period(args, 2, cell)
+    }
+
+    fn date_result(&self, date: NaiveDate, cell: CellReference) -> CalcResult {
+        match date_to_serial_number(
+            date.day(),
+            date.month(),
+            date.year(),
+            self.workbook.settings.date1904,
+        ) {
+            Ok(serial_number) => CalcResult::Number(serial_number as f64),
+            Err(message) => CalcResult::new_error(Error::NUM, cell, message),
+        }
+    }
+
+    /// COUPDAYBS(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_coupdaybs(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => CalcResult::Number(period.days_before_settlement()),
+            Err(error) => error,
+        }
+    }
+
+    /// COUPDAYS(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_coupdays(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => CalcResult::Number(period.days()),
+            Err(error) => error,
+        }
+    }
+
+    /// COUPDAYSNC(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_coupdaysnc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => CalcResult::Number(period.days_to_next_coupon()),
+            Err(error) => error,
+        }
+    }
+
+    /// COUPNCD(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_coupncd(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => self.date_result(period.next, cell),
+            Err(error) => error,
+        }
+    }
+
+    /// COUPNUM(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_coupnum(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => CalcResult::Number(period.remaining as f64),
+            Err(error) => error,
+        }
+    }
+
+    /// COUPPCD(settlement, maturity, frequency, [basis])
+    pub(crate) fn fn_couppcd(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_coupon_function_period(args, cell) {
+            Ok(period) => self.date_result(period.previous, cell),
+            Err(error) => error,
+        }
+    }
+
+    /// PRICE(settlement, maturity, rate, yld, redemption, frequency, [basis])
+    pub(crate) fn fn_price(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(6..=7).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let rate = match self.get_non_negative_number(&args[2], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let yld = match self.get_non_negative_number(&args[3], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let redemption = match self.get_positive_number(&args[4], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        match self.get_coupon_period(args, 5, cell) {
+            Ok(period) => CalcResult::Number(period.price(rate, yld, redemption)),
+            Err(error) => error,
+        }
+    }
+
+    /// YIELD(settlement, maturity, rate, pr, redemption, frequency, [basis])
+    pub(crate) fn fn_yield(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(6..=7).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let rate = match self.get_non_negative_number(&args[2], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let price = match self.get_positive_number(&args[3], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let redemption = match self.get_positive_number(&args[4], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let period = match self.get_coupon_period(args, 5, cell) {
+            Ok(period) => period,
+            Err(error) => return error,
+        };
+        match period.yield_of_price(rate, price, redemption) {
+            Some(yld) => CalcResult::Number(yld),
+            None => CalcResult::new_error(Error::NUM, cell, "YIELD did not converge".to_string()),
+        }
+    }
+
+    /// Arguments of DURATION and MDURATION: the coupon period, the coupon rate and the yield
+    fn get_duration_arguments(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<(CouponPeriod, f64, f64), CalcResult> {
+        if !(5..=6).contains(&args.len()) {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let coupon = self.get_non_negative_number(&args[2], cell)?;
+        let yld = self.get_non_negative_number(&args[3], cell)?;
+        let period = self.get_coupon_period(args, 4, cell)?;
+        Ok((period, coupon, yld))
+    }
+
+    /// DURATION(settlement, maturity, coupon, yld, frequency, [basis])
+    pub(crate) fn fn_duration(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_duration_arguments(args, cell) {
+            Ok((period, coupon, yld)) => CalcResult::Number(period.duration(coupon, yld)),
+            Err(error) => error,
+        }
+    }
+
+    /// MDURATION(settlement, maturity, coupon, yld, frequency, [basis])
+    pub(crate) fn fn_mduration(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_duration_arguments(args, cell) {
+            Ok((period, coupon, yld)) => CalcResult::Number(period.modified_duration(coupon, yld)),
+            Err(error) => error,
+        }
+    }
+
+    /// ACCRINT(issue, first_interest, settlement, rate, par, frequency, [basis], [calc_method])
+    /// The interest accrues from the issue date, or from the first interest date if
+    /// `calc_method` is FALSE and the settlement is after it.
+    pub(crate) fn fn_accrint(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(6..=8).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (issue, settlement) = match self.get_ordered_dates(&args[0], &args[2], cell) {
+            Ok(dates) => dates,
+            Err(error) => return error,
+        };
+        let first_interest = match self.get_security_date(&args[1], cell) {
+            Ok(date) => date,
+            Err(error) => return error,
+        };
+        let rate = match self.get_positive_number(&args[3], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let par = match self.get_positive_number(&args[4], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let frequency = match self.get_number_no_bools(&args[5], cell) {
+            Ok(f) => f.trunc(),
+            Err(s) => return s,
+        };
+        if ![1.0, 2.0, 4.0].contains(&frequency) {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Frequency should be 1, 2 or 4".to_string(),
+            );
+        }
+        let basis = match self.get_basis(args, 6, cell) {
+            Ok(basis) => basis,
+            Err(error) => return error,
+        };
+        let from_issue = match self.get_optional_boolean(args, 7, true, cell) {
+            Ok(b) => b,
+            Err(s) => return s,
+        };
+        let start = if !from_issue && first_interest < settlement {
+            first_interest
+        } else {
+            issue
+        };
+        CalcResult::Number(par * rate * year_fraction(start, settlement, basis))
+    }
+
+    /// ACCRINTM(issue, settlement, rate, par, [basis])
+    pub(crate) fn fn_accrintm(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if !(4..=5).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (issue, settlement) = match self.get_ordered_dates(&args[0], &args[1], cell) {
+            Ok(dates) => dates,
+            Err(error) => return error,
+        };
+        let rate = match self.get_positive_number(&args[2], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let par = match self.get_positive_number(&args[3], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let basis = match self.get_basis(args, 4, cell) {
+            Ok(basis) => basis,
+            Err(error) => return error,
+        };
+        CalcResult::Number(par * rate * year_fraction(issue, settlement, basis))
+    }
+
+    /// Arguments of the discounted securities functions:
+    /// settlement, maturity, value1, value2, [basis]
+    /// Returns the fraction of year between settlement and maturity and the two positive values.
+    fn get_discounted_security(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<(f64, f64, f64), CalcResult> {
+        if !(4..=5).contains(&args.len()) {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let (settlement, maturity) = self.get_ordered_dates(&args[0], &args[1], cell)?;
+        let value1 = self.get_positive_number(&args[2], cell)?;
+        let value2 = self.get_positive_number(&args[3], cell)?;
+        let basis = self.get_basis(args, 4, cell)?;
+        Ok((year_fraction(settlement, maturity, basis), value1, value2))
+    }
+
+    /// PRICEDISC(settlement, maturity, discount, redemption, [basis])
+    pub(crate) fn fn_pricedisc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_discounted_security(args, cell) {
+            Ok((years, discount, redemption)) => {
+                CalcResult::Number(redemption * (1.0 - discount * years))
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// YIELDDISC(settlement, maturity, pr, redemption, [basis])
+    pub(crate) fn fn_yielddisc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_discounted_security(args, cell) {
+            Ok((years, price, redemption)) => {
+                CalcResult::Number((redemption / price - 1.0) / years)
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// DISC(settlement, maturity, pr, redemption, [basis])
+    pub(crate) fn fn_disc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_discounted_security(args, cell) {
+            Ok((years, price, redemption)) => {
+                CalcResult::Number((1.0 - price / redemption) / years)
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// INTRATE(settlement, maturity, investment, redemption, [basis])
+    pub(crate) fn fn_intrate(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_discounted_security(args, cell) {
+            Ok((years, investment, redemption)) => {
+                CalcResult::Number((redemption / investment - 1.0) / years)
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// RECEIVED(settlement, maturity, investment, discount, [basis])
+    pub(crate) fn fn_received(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_discounted_security(args, cell) {
+            Ok((years, investment, discount)) => {
+                let factor = 1.0 - discount * years;
+                if factor <= 0.0 {
+                    return CalcResult::new_error(
+                        Error::NUM,
+                        cell,
+                        "Discount is too big".to_string(),
+                    );
+                }
+                CalcResult::Number(investment / factor)
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// Arguments of the securities paying interest at maturity:
+    /// settlement, maturity, issue, rate, value, [basis]
+    /// Returns the fractions of year from issue to maturity, from issue to settlement and from
+    /// settlement to maturity, the rate and the value.
+    fn get_security_at_maturity(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<([f64; 3], f64, f64), CalcResult> {
+        if !(5..=6).contains(&args.len()) {
+            return Err(CalcResult::new_args_number_error(cell));
+        }
+        let (settlement, maturity) = self.get_ordered_dates(&args[0], &args[1], cell)?;
+        let issue = self.get_security_date(&args[2], cell)?;
+        if issue > settlement {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Issue should not be after settlement".to_string(),
+            ));
+        }
+        let rate = self.get_non_negative_number(&args[3], cell)?;
+        let value = self.get_non_negative_number(&args[4], cell)?;
+        let basis = self.get_basis(args, 5, cell)?;
+        let years = [
+            year_fraction(issue, maturity, basis),
+            year_fraction(issue, settlement, basis),
+            year_fraction(settlement, maturity, basis),
+        ];
+        Ok((years, rate, value))
+    }
+
+    /// PRICEMAT(settlement, maturity, issue, rate, yld, [basis])
+    pub(crate) fn fn_pricemat(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        match self.get_security_at_maturity(args, cell) {
+            Ok(([issue_maturity, issue_settlement, settlement_maturity], rate, yld)) => {
+                CalcResult::Number(
+                    100.0
+                        * ((1.0 + issue_maturity * rate) / (1.0 + settlement_maturity * yld)
+                            - issue_settlement * rate),
+                )
+            }
+            Err(error) => error,
+        }
+    }
+
+    /// YIELDMAT(settlement, maturity, issue, rate, pr, [basis])
+    pub(crate) fn fn_yieldmat(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        let ([issue_maturity, issue_settlement, settlement_maturity], rate, price) =
+            match self.get_security_at_maturity(args, cell) {
+                Ok(arguments) => arguments,
+                Err(error) => return error,
+            };
+        if price == 0.0 {
+            return CalcResult::new_error(Error::NUM, cell, "Price should be > 0".to_string());
+        }
+        CalcResult::Number(
+            ((1.0 + issue_maturity * rate) / (price / 100.0 + issue_settlement * rate) - 1.0)
+                / settlement_maturity,
+        )
+    }
+}
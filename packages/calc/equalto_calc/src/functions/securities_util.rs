@@ -0,0 +1,179 @@
+//! Coupon schedules, prices and yields of securities, shared by the security functions.
+//! See: https://support.microsoft.com/en-us/office/price-function-3ea9deac-8dfa-436f-a7c8-17ea02c21b0a
+
+use chrono::{Datelike, NaiveDate};
+
+use super::day_count::{days_360_european, days_360_nasd, is_last_day_of_month, DayCountBasis};
+
+/// Days between two dates with the day count of the basis
+pub(crate) fn days_between(start: NaiveDate, end: NaiveDate, basis: DayCountBasis) -> f64 {
+    match basis {
+        DayCountBasis::UsNasd30_360 => days_360_nasd(start, end) as f64,
+        DayCountBasis::European30_360 => days_360_european(start, end) as f64,
+        DayCountBasis::ActualActual | DayCountBasis::Actual360 | DayCountBasis::Actual365 => {
+            (end - start).num_days() as f64
+        }
+    }
+}
+
+/// The coupon date `months` months before `maturity`. If the maturity is on the last day of a
+/// month, so are all coupon dates.
+fn coupon_date(maturity: NaiveDate, months: i32) -> NaiveDate {
+    let total_months = maturity.year() * 12 + maturity.month0() as i32 - months;
+    let year = total_months.div_euclid(12);
+    let month = total_months.rem_euclid(12) as u32 + 1;
+    let last_day = (28..=31)
+        .rev()
+        .find(|&day| NaiveDate::from_ymd_opt(year, month, day).is_some())
+        .unwrap_or(28);
+    let day = if is_last_day_of_month(maturity) {
+        last_day
+    } else {
+        maturity.day().min(last_day)
+    };
+    NaiveDate::from_ymd_opt(year, month, day).unwrap_or(maturity)
+}
+
+/// The coupon period containing the settlement date of a security
+pub(crate) struct CouponPeriod {
+    settlement: NaiveDate,
+    /// Previous coupon date, on or before the settlement date
+    pub(crate) previous: NaiveDate,
+    /// Next coupon date, after the settlement date
+    pub(crate) next: NaiveDate,
+    /// Number of coupons payable between the settlement and the maturity dates
+    pub(crate) remaining: i32,
+    frequency: i32,
+    basis: DayCountBasis,
+}
+
+impl CouponPeriod {
+    /// `settlement` must be before `maturity` and `frequency` one of 1, 2 or 4
+    pub(crate) fn new(
+        settlement: NaiveDate,
+        maturity: NaiveDate,
+        frequency: i32,
+        basis: DayCountBasis,
+    ) -> CouponPeriod {
+        let months = 12 / frequency;
+        let mut remaining = 1;
+        while coupon_date(maturity, remaining * months) > settlement {
+            remaining += 1;
+        }
+        CouponPeriod {
+            settlement,
+            previous: coupon_date(maturity, remaining * months),
+            next: coupon_date(maturity, (remaining - 1) * months),
+            remaining,
+            frequency,
+            basis,
+        }
+    }
+
+    /// Days in the coupon period (COUPDAYS)
+    pub(crate) fn days(&self) -> f64 {
+        let frequency = self.frequency as f64;
+        match self.basis {
+            DayCountBasis::ActualActual => (self.next - self.previous).num_days() as f64,
+            DayCountBasis::Actual365 => 365.0 / frequency,
+            DayCountBasis::UsNasd30_360
+            | DayCountBasis::Actual360
+            | DayCountBasis::European30_360 => 360.0 / frequency,
+        }
+    }
+
+    /// Days from the beginning of the coupon period to the settlement date (COUPDAYBS)
+    pub(crate) fn days_before_settlement(&self) -> f64 {
+        days_between(self.previous, self.settlement, self.basis)
+    }
+
+    /// Days from the settlement date to the next coupon date (COUPDAYSNC)
+    pub(crate) fn days_to_next_coupon(&self) -> f64 {
+        match self.basis {
+            DayCountBasis::UsNasd30_360 | DayCountBasis::European30_360 => {
+                self.days() - self.days_before_settlement()
+            }
+            DayCountBasis::ActualActual | DayCountBasis::Actual360 | DayCountBasis::Actual365 => {
+                (self.next - self.settlement).num_days() as f64
+            }
+        }
+    }
+
+    /// Price per 100 of face value of a security paying `rate` with yield `yld` (PRICE).
+    /// In the last coupon period the yield is simple interest.
+    pub(crate) fn price(&self, rate: f64, yld: f64, redemption: f64) -> f64 {
+        let frequency = self.frequency as f64;
+        let coupon = 100.0 * rate / frequency;
+        let days = self.days();
+        let accrued = self.days_before_settlement() / days * coupon;
+        let days_to_next = self.days_to_next_coupon();
+        if self.remaining == 1 {
+            return (redemption + coupon) / (1.0 + days_to_next / days * yld / frequency) - accrued;
+        }
+        let base = 1.0 + yld / frequency;
+        let first = days_to_next / days;
+        let coupons: f64 = (0..self.remaining)
+            .map(|k| coupon / base.powf(k as f64 + first))
+            .sum();
+        redemption / base.powf((self.remaining - 1) as f64 + first) + coupons - accrued
+    }
+
+    /// Yield of a security paying `rate` with the given price (YIELD), None if the numerical
+    /// method does not converge
+    pub(crate) fn yield_of_price(&self, rate: f64, price: f64, redemption: f64) -> Option<f64> {
+        let frequency = self.frequency as f64;
+        if self.remaining == 1 {
+            let coupon = 100.0 * rate / frequency;
+            let days = self.days();
+            let dirty_price = price + self.days_before_settlement() / days * coupon;
+            return Some(
+                ((redemption + coupon) / dirty_price - 1.0) * frequency * days
+                    / self.days_to_next_coupon(),
+            );
+        }
+        // Newton-Raphson, the price is a decreasing function of the yield
+        let mut yld = if rate > 0.0 { rate } else { 0.05 };
+        let step = 1e-7;
+        for _ in 0..100 {
+            let difference = self.price(rate, yld, redemption) - price;
+            if difference.abs() < 1e-10 {
+                return Some(yld);
+            }
+            let derivative = (self.price(rate, yld + step, redemption)
+                - self.price(rate, yld - step, redemption))
+                / (2.0 * step);
+            yld -= difference / derivative;
+            if !yld.is_finite() || yld <= -frequency {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Macaulay duration in years of a security paying `rate` with yield `yld` (DURATION)
+    pub(crate) fn duration(&self, rate: f64, yld: f64) -> f64 {
+        let frequency = self.frequency as f64;
+        let coupon = 100.0 * rate / frequency;
+        let base = 1.0 + yld / frequency;
+        let first = self.days_to_next_coupon() / self.days();
+        let mut weighted_value = 0.0;
+        let mut value = 0.0;
+        for k in 0..self.remaining {
+            let periods = k as f64 + first;
+            let cash_flow = if k == self.remaining - 1 {
+                coupon + 100.0
+            } else {
+                coupon
+            };
+            let present_value = cash_flow / base.powf(periods);
+            weighted_value += periods * present_value;
+            value += present_value;
+        }
+        weighted_value / value / frequency
+    }
+
+    /// Modified duration of a security paying `rate` with yield `yld` (MDURATION)
+    pub(crate) fn modified_duration(&self, rate: f64, yld: f64) -> f64 {
+        self.duration(rate, yld) / (1.0 + yld / self.frequency as f64)
+    }
+}
@@ -8,71 +8,225 @@ use crate::{
 
 use super::util::build_criteria;
 
+pub(crate) fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sum_of_squared_deviations(values: &[f64]) -> f64 {
+    let average = mean(values);
+    values.iter().map(|x| (x - average) * (x - average)).sum()
+}
+
+pub(crate) fn sorted(mut values: Vec<f64>) -> Vec<f64> {
+    values.sort_by(|a, b| a.total_cmp(b));
+    values
+}
+
+// Variance of the values. If `sample` the sum of squares is divided by n - 1 instead of n.
+pub(crate) fn variance(values: &[f64], sample: bool, cell: CellReference) -> CalcResult {
+    let count = values.len() as f64;
+    let denominator = if sample { count - 1.0 } else { count };
+    if denominator <= 0.0 {
+        return CalcResult::new_error(Error::DIV, cell, "Division by Zero".to_string());
+    }
+    CalcResult::Number(sum_of_squared_deviations(values) / denominator)
+}
+
+// Linear interpolation at a (zero based) fractional position of a sorted list
+fn interpolate(sorted_values: &[f64], position: f64) -> f64 {
+    let index = position.floor() as usize;
+    let fraction = position - position.floor();
+    if index + 1 >= sorted_values.len() {
+        return sorted_values[sorted_values.len() - 1];
+    }
+    sorted_values[index] + fraction * (sorted_values[index + 1] - sorted_values[index])
+}
+
+// Inclusive percentile of a sorted non empty list, 0 <= k <= 1
+pub(crate) fn percentile_inclusive(sorted_values: &[f64], k: f64) -> f64 {
+    interpolate(sorted_values, k * (sorted_values.len() - 1) as f64)
+}
+
+// Exclusive percentile of a sorted non empty list, None if k is out of range
+fn percentile_exclusive(sorted_values: &[f64], k: f64) -> Option<f64> {
+    let position = k * (sorted_values.len() + 1) as f64 - 1.0;
+    if position < 0.0 || position > (sorted_values.len() - 1) as f64 {
+        return None;
+    }
+    Some(interpolate(sorted_values, position))
+}
+
+// PERCENTILE.INC or PERCENTILE.EXC of a sorted non empty list, None if k is out of range
+pub(crate) fn percentile(sorted_values: &[f64], k: f64, inclusive: bool) -> Option<f64> {
+    if inclusive {
+        if (0.0..=1.0).contains(&k) {
+            Some(percentile_inclusive(sorted_values, k))
+        } else {
+            None
+        }
+    } else if k > 0.0 && k < 1.0 {
+        percentile_exclusive(sorted_values, k)
+    } else {
+        None
+    }
+}
+
+// QUARTILE.INC or QUARTILE.EXC of a sorted non empty list, None if quart is out of range
+pub(crate) fn quartile(sorted_values: &[f64], quart: f64, inclusive: bool) -> Option<f64> {
+    let quart = quart.trunc();
+    if inclusive {
+        if (0.0..=4.0).contains(&quart) {
+            Some(percentile_inclusive(sorted_values, quart / 4.0))
+        } else {
+            None
+        }
+    } else if (1.0..=3.0).contains(&quart) {
+        percentile_exclusive(sorted_values, quart / 4.0)
+    } else {
+        None
+    }
+}
+
+// Values that appear the most times, in the order they first appear.
+// Empty if no value appears more than once.
+pub(crate) fn modes(values: Vec<f64>) -> Vec<f64> {
+    let mut counts: Vec<(f64, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    if max_count < 2 {
+        return vec![];
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count == max_count)
+        .map(|(value, _)| value)
+        .collect()
+}
+
+// Position of x in a sorted list interpolating between consecutive values, None if x is out of range
+fn fractional_position(sorted_values: &[f64], x: f64) -> Option<f64> {
+    let count = sorted_values.len();
+    if count == 0 || x < sorted_values[0] || x > sorted_values[count - 1] {
+        return None;
+    }
+    let smaller = sorted_values.iter().filter(|&&value| value < x).count();
+    if sorted_values[smaller] == x {
+        return Some(smaller as f64);
+    }
+    let lower = sorted_values[smaller - 1];
+    let upper = sorted_values[smaller];
+    Some((smaller - 1) as f64 + (x - lower) / (upper - lower))
+}
+
+// Rounds down to the number of significant digits of PERCENTRANK
+fn truncate_to_significance(value: f64, significance: i32) -> f64 {
+    let factor = 10.0_f64.powi(significance);
+    // We round first to avoid 0.29 becoming 0.28
+    let scaled = (value * factor * 1e6).round() / 1e6;
+    scaled.floor() / factor
+}
+
 impl Model {
-    pub(crate) fn fn_average(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
-        if args.is_empty() {
-            return CalcResult::new_args_number_error(cell);
+    // Evaluates all the cells in a range. Whole rows and columns are clipped to the used part of
+    // the sheet.
+    fn evaluate_range_cells(
+        &mut self,
+        left: CellReference,
+        right: CellReference,
+        cell: CellReference,
+    ) -> Result<Vec<CalcResult>, CalcResult> {
+        if left.sheet != right.sheet {
+            return Err(CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "Ranges are in different sheets".to_string(),
+            ));
         }
-        let mut count = 0.0;
-        let mut sum = 0.0;
+        let mut right = right;
+        if let Ok(worksheet) = self.workbook.worksheet(left.sheet) {
+            let dimension = worksheet.dimension();
+            if left.row == 1 && right.row == LAST_ROW {
+                right.row = dimension.max_row;
+            }
+            if left.column == 1 && right.column == LAST_COLUMN {
+                right.column = dimension.max_column;
+            }
+        }
+        let mut values = Vec::new();
+        for row in left.row..(right.row + 1) {
+            for column in left.column..(right.column + 1) {
+                values.push(self.evaluate_cell(CellReference {
+                    sheet: left.sheet,
+                    row,
+                    column,
+                }));
+            }
+        }
+        Ok(values)
+    }
+
+    /// Returns the numbers in the arguments following the rules of AVERAGE: in ranges and arrays
+    /// only numbers are taken into account, booleans and text representing numbers are taken into
+    /// account only if typed directly as arguments.
+    pub(crate) fn get_statistical_values(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<f64>, CalcResult> {
+        let mut values = Vec::new();
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
-                CalcResult::Number(value) => {
-                    count += 1.0;
-                    sum += value;
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => values.push(*value),
+                            error @ CalcResult::Error { .. } => return Err(error.clone()),
+                            _ => {
+                                // We ignore booleans and strings
+                            }
+                        }
+                    }
                 }
+                CalcResult::Number(value) => values.push(value),
                 CalcResult::Boolean(b) => {
                     if let Node::ReferenceKind { .. } = arg {
                     } else {
-                        sum += if b { 1.0 } else { 0.0 };
-                        count += 1.0;
+                        values.push(if b { 1.0 } else { 0.0 });
                     }
                 }
                 CalcResult::Range { left, right } => {
-                    if left.sheet != right.sheet {
-                        return CalcResult::new_error(
-                            Error::VALUE,
-                            cell,
-                            "Ranges are in different sheets".to_string(),
-                        );
-                    }
-                    for row in left.row..(right.row + 1) {
-                        for column in left.column..(right.column + 1) {
-                            match self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column,
-                            }) {
-                                CalcResult::Number(value) => {
-                                    count += 1.0;
-                                    sum += value;
-                                }
-                                error @ CalcResult::Error { .. } => return error,
-                                CalcResult::Range { .. } => {
-                                    return CalcResult::new_error(
-                                        Error::ERROR,
-                                        cell,
-                                        "Unexpected Range".to_string(),
-                                    );
-                                }
-                                _ => {}
+                    for value in self.evaluate_range_cells(left, right, cell)? {
+                        match value {
+                            CalcResult::Number(value) => values.push(value),
+                            error @ CalcResult::Error { .. } => return Err(error),
+                            CalcResult::Range { .. } => {
+                                return Err(CalcResult::new_error(
+                                    Error::ERROR,
+                                    cell,
+                                    "Unexpected Range".to_string(),
+                                ));
                             }
+                            _ => {}
                         }
                     }
                 }
-                error @ CalcResult::Error { .. } => return error,
+                error @ CalcResult::Error { .. } => return Err(error),
                 CalcResult::String(s) => {
                     if let Node::ReferenceKind { .. } = arg {
                         // Do nothing
                     } else if let Ok(t) = s.parse::<f64>() {
-                        sum += t;
-                        count += 1.0;
+                        values.push(t);
                     } else {
-                        return CalcResult::Error {
+                        return Err(CalcResult::Error {
                             error: Error::VALUE,
                             origin: cell,
                             message: "Argument cannot be cast into number".to_string(),
-                        };
+                        });
                     }
                 }
                 _ => {
@@ -80,100 +234,104 @@ impl Model {
                 }
             };
         }
-        if count == 0.0 {
-            return CalcResult::Error {
-                error: Error::DIV,
-                origin: cell,
-                message: "Division by Zero".to_string(),
-            };
-        }
-        CalcResult::Number(sum / count)
+        Ok(values)
     }
 
-    pub(crate) fn fn_averagea(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
-        if args.is_empty() {
-            return CalcResult::new_args_number_error(cell);
-        }
-        let mut count = 0.0;
-        let mut sum = 0.0;
+    /// Returns the numbers in the arguments following the rules of AVERAGEA: text counts as 0 and
+    /// booleans as 0 or 1, also in ranges and arrays.
+    pub(crate) fn get_statistical_values_a(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<f64>, CalcResult> {
+        let mut values = Vec::new();
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
-                CalcResult::Range { left, right } => {
-                    if left.sheet != right.sheet {
-                        return CalcResult::new_error(
-                            Error::VALUE,
-                            cell,
-                            "Ranges are in different sheets".to_string(),
-                        );
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::String(_) => values.push(0.0),
+                            CalcResult::Number(value) => values.push(*value),
+                            CalcResult::Boolean(b) => values.push(if *b { 1.0 } else { 0.0 }),
+                            error @ CalcResult::Error { .. } => return Err(error.clone()),
+                            _ => {}
+                        }
                     }
-                    for row in left.row..(right.row + 1) {
-                        for column in left.column..(right.column + 1) {
-                            match self.evaluate_cell(CellReference {
-                                sheet: left.sheet,
-                                row,
-                                column,
-                            }) {
-                                CalcResult::String(_) => count += 1.0,
-                                CalcResult::Number(value) => {
-                                    count += 1.0;
-                                    sum += value;
-                                }
-                                CalcResult::Boolean(b) => {
-                                    if b {
-                                        sum += 1.0;
-                                    }
-                                    count += 1.0;
-                                }
-                                error @ CalcResult::Error { .. } => return error,
-                                CalcResult::Range { .. } => {
-                                    return CalcResult::new_error(
-                                        Error::ERROR,
-                                        cell,
-                                        "Unexpected Range".to_string(),
-                                    );
-                                }
-                                CalcResult::EmptyCell | CalcResult::EmptyArg => {}
+                }
+                CalcResult::Range { left, right } => {
+                    for value in self.evaluate_range_cells(left, right, cell)? {
+                        match value {
+                            CalcResult::String(_) => values.push(0.0),
+                            CalcResult::Number(value) => values.push(value),
+                            CalcResult::Boolean(b) => values.push(if b { 1.0 } else { 0.0 }),
+                            error @ CalcResult::Error { .. } => return Err(error),
+                            CalcResult::Range { .. } | CalcResult::Array(_) => {
+                                return Err(CalcResult::new_error(
+                                    Error::ERROR,
+                                    cell,
+                                    "Unexpected Range".to_string(),
+                                ));
                             }
+                            CalcResult::EmptyCell | CalcResult::EmptyArg => {}
                         }
                     }
                 }
-                CalcResult::Number(value) => {
-                    count += 1.0;
-                    sum += value;
-                }
+                CalcResult::Number(value) => values.push(value),
                 CalcResult::String(s) => {
                     if let Node::ReferenceKind { .. } = arg {
-                        // Do nothing
-                        count += 1.0;
+                        values.push(0.0);
                     } else if let Ok(t) = s.parse::<f64>() {
-                        sum += t;
-                        count += 1.0;
+                        values.push(t);
                     } else {
-                        return CalcResult::Error {
+                        return Err(CalcResult::Error {
                             error: Error::VALUE,
                             origin: cell,
                             message: "Argument cannot be cast into number".to_string(),
-                        };
+                        });
                     }
                 }
-                CalcResult::Boolean(b) => {
-                    count += 1.0;
-                    if b {
-                        sum += 1.0;
-                    }
-                }
-                error @ CalcResult::Error { .. } => return error,
+                CalcResult::Boolean(b) => values.push(if b { 1.0 } else { 0.0 }),
+                error @ CalcResult::Error { .. } => return Err(error),
                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
             };
         }
-        if count == 0.0 {
+        Ok(values)
+    }
+
+    pub(crate) fn fn_average(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_statistical_values(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        if values.is_empty() {
             return CalcResult::Error {
                 error: Error::DIV,
                 origin: cell,
                 message: "Division by Zero".to_string(),
             };
         }
-        CalcResult::Number(sum / count)
+        CalcResult::Number(mean(&values))
+    }
+
+    pub(crate) fn fn_averagea(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_statistical_values_a(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        if values.is_empty() {
+            return CalcResult::Error {
+                error: Error::DIV,
+                origin: cell,
+                message: "Division by Zero".to_string(),
+            };
+        }
+        CalcResult::Number(mean(&values))
     }
 
     pub(crate) fn fn_count(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
@@ -183,6 +341,13 @@ impl Model {
         let mut result = 0.0;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        if let CalcResult::Number(_) = value {
+                            result += 1.0;
+                        }
+                    }
+                }
                 CalcResult::Number(_) => {
                     result += 1.0;
                 }
@@ -231,6 +396,13 @@ impl Model {
         let mut result = 0.0;
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        if !matches!(value, CalcResult::EmptyCell | CalcResult::EmptyArg) {
+                            result += 1.0;
+                        }
+                    }
+                }
                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
@@ -621,4 +793,474 @@ impl Model {
         }
         CalcResult::Number(max)
     }
+
+    // Variance and standard deviation of the arguments. If `count_text_and_booleans` the values
+    // follow the rules of AVERAGEA, otherwise the rules of AVERAGE.
+    fn variance_of_args(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        count_text_and_booleans: bool,
+        sample: bool,
+    ) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = if count_text_and_booleans {
+            self.get_statistical_values_a(args, cell)
+        } else {
+            self.get_statistical_values(args, cell)
+        };
+        match values {
+            Ok(values) => variance(&values, sample, cell),
+            Err(error) => error,
+        }
+    }
+
+    fn standard_deviation_of_args(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        count_text_and_booleans: bool,
+        sample: bool,
+    ) -> CalcResult {
+        match self.variance_of_args(args, cell, count_text_and_booleans, sample) {
+            CalcResult::Number(variance) => CalcResult::Number(variance.sqrt()),
+            error => error,
+        }
+    }
+
+    pub(crate) fn fn_var_s(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.variance_of_args(args, cell, false, true)
+    }
+
+    pub(crate) fn fn_var_p(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.variance_of_args(args, cell, false, false)
+    }
+
+    pub(crate) fn fn_vara(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.variance_of_args(args, cell, true, true)
+    }
+
+    pub(crate) fn fn_varpa(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.variance_of_args(args, cell, true, false)
+    }
+
+    pub(crate) fn fn_stdev_s(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.standard_deviation_of_args(args, cell, false, true)
+    }
+
+    pub(crate) fn fn_stdev_p(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.standard_deviation_of_args(args, cell, false, false)
+    }
+
+    pub(crate) fn fn_stdeva(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.standard_deviation_of_args(args, cell, true, true)
+    }
+
+    pub(crate) fn fn_stdevpa(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.standard_deviation_of_args(args, cell, true, false)
+    }
+
+    // Values of the arguments, #NUM! if there are none
+    fn get_non_empty_statistical_values(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+    ) -> Result<Vec<f64>, CalcResult> {
+        let values = self.get_statistical_values(args, cell)?;
+        if values.is_empty() {
+            return Err(CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "No numbers in the arguments".to_string(),
+            ));
+        }
+        Ok(values)
+    }
+
+    pub(crate) fn fn_median(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_non_empty_statistical_values(args, cell) {
+            Ok(values) => CalcResult::Number(percentile_inclusive(&sorted(values), 0.5)),
+            Err(error) => error,
+        }
+    }
+
+    fn get_modes(&mut self, args: &[Node], cell: CellReference) -> Result<Vec<f64>, CalcResult> {
+        Ok(modes(self.get_statistical_values(args, cell)?))
+    }
+
+    pub(crate) fn fn_mode_sngl(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_modes(args, cell) {
+            Ok(modes) => match modes.first() {
+                Some(mode) => CalcResult::Number(*mode),
+                None => CalcResult::new_error(Error::NA, cell, "No repeated values".to_string()),
+            },
+            Err(error) => error,
+        }
+    }
+
+    pub(crate) fn fn_mode_mult(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_modes(args, cell) {
+            Ok(modes) => {
+                if modes.is_empty() {
+                    return CalcResult::new_error(
+                        Error::NA,
+                        cell,
+                        "No repeated values".to_string(),
+                    );
+                }
+                // The result is a vertical array
+                CalcResult::Array(
+                    modes
+                        .into_iter()
+                        .map(|mode| vec![CalcResult::Number(mode)])
+                        .collect(),
+                )
+            }
+            Err(error) => error,
+        }
+    }
+
+    // PERCENTILE(array, k) with `inclusive` or exclusive percentiles
+    fn percentile_of_array(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        inclusive: bool,
+    ) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(&args[0..1], cell) {
+            Ok(values) => sorted(values),
+            Err(error) => return error,
+        };
+        let k = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        match percentile(&values, k, inclusive) {
+            Some(f) => CalcResult::Number(f),
+            None => CalcResult::new_error(Error::NUM, cell, "Invalid percentile".to_string()),
+        }
+    }
+
+    pub(crate) fn fn_percentile_inc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.percentile_of_array(args, cell, true)
+    }
+
+    pub(crate) fn fn_percentile_exc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.percentile_of_array(args, cell, false)
+    }
+
+    // QUARTILE(array, quart) with `inclusive` or exclusive percentiles
+    fn quartile_of_array(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        inclusive: bool,
+    ) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(&args[0..1], cell) {
+            Ok(values) => sorted(values),
+            Err(error) => return error,
+        };
+        let quart = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        match quartile(&values, quart, inclusive) {
+            Some(f) => CalcResult::Number(f),
+            None => CalcResult::new_error(Error::NUM, cell, "Invalid quartile".to_string()),
+        }
+    }
+
+    pub(crate) fn fn_quartile_inc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.quartile_of_array(args, cell, true)
+    }
+
+    pub(crate) fn fn_quartile_exc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.quartile_of_array(args, cell, false)
+    }
+
+    // PERCENTRANK(array, x, [significance]) with `inclusive` or exclusive ranks
+    fn percent_rank(&mut self, args: &[Node], cell: CellReference, inclusive: bool) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(&args[0..1], cell) {
+            Ok(values) => sorted(values),
+            Err(error) => return error,
+        };
+        let x = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let significance = if args.len() == 3 {
+            match self.get_number(&args[2], cell) {
+                Ok(f) => f.trunc(),
+                Err(s) => return s,
+            }
+        } else {
+            3.0
+        };
+        if significance < 1.0 {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Significance must be at least 1".to_string(),
+            );
+        }
+        let position = match fractional_position(&values, x) {
+            Some(position) => position,
+            None => {
+                return CalcResult::new_error(Error::NA, cell, "Value out of range".to_string())
+            }
+        };
+        let count = values.len() as f64;
+        let rank = if inclusive {
+            if count == 1.0 {
+                1.0
+            } else {
+                position / (count - 1.0)
+            }
+        } else {
+            (position + 1.0) / (count + 1.0)
+        };
+        CalcResult::Number(truncate_to_significance(rank, significance as i32))
+    }
+
+    pub(crate) fn fn_percentrank_inc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.percent_rank(args, cell, true)
+    }
+
+    pub(crate) fn fn_percentrank_exc(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.percent_rank(args, cell, false)
+    }
+
+    // RANK(number, ref, [order]). If `average` ties get the average of their ranks.
+    fn rank(&mut self, args: &[Node], cell: CellReference, average: bool) -> CalcResult {
+        if !(2..=3).contains(&args.len()) {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let number = match self.get_number(&args[0], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        let values = match self.get_statistical_values(&args[1..2], cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        let ascending = if args.len() == 3 {
+            match self.get_number(&args[2], cell) {
+                Ok(f) => f != 0.0,
+                Err(s) => return s,
+            }
+        } else {
+            false
+        };
+        let equal = values.iter().filter(|&&value| value == number).count();
+        if equal == 0 {
+            return CalcResult::new_error(Error::NA, cell, "Number not found".to_string());
+        }
+        let before = values
+            .iter()
+            .filter(|&&value| {
+                if ascending {
+                    value < number
+                } else {
+                    value > number
+                }
+            })
+            .count();
+        let rank = before as f64 + 1.0;
+        if average {
+            CalcResult::Number(rank + (equal - 1) as f64 / 2.0)
+        } else {
+            CalcResult::Number(rank)
+        }
+    }
+
+    pub(crate) fn fn_rank_eq(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.rank(args, cell, false)
+    }
+
+    pub(crate) fn fn_rank_avg(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.rank(args, cell, true)
+    }
+
+    // k-th largest or smallest value. k is rounded up.
+    fn nth_value(&mut self, args: &[Node], cell: CellReference, largest: bool) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let mut values = match self.get_statistical_values(&args[0..1], cell) {
+            Ok(values) => sorted(values),
+            Err(error) => return error,
+        };
+        let k = match self.get_number(&args[1], cell) {
+            Ok(f) => f.ceil(),
+            Err(s) => return s,
+        };
+        if k < 1.0 || k > values.len() as f64 {
+            return CalcResult::new_error(Error::NUM, cell, "Invalid k".to_string());
+        }
+        if largest {
+            values.reverse();
+        }
+        CalcResult::Number(values[k as usize - 1])
+    }
+
+    pub(crate) fn fn_large(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.nth_value(args, cell, true)
+    }
+
+    pub(crate) fn fn_small(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        self.nth_value(args, cell, false)
+    }
+
+    pub(crate) fn fn_geomean(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        if values.iter().any(|&value| value <= 0.0) {
+            return CalcResult::new_error(Error::NUM, cell, "Values must be positive".to_string());
+        }
+        let logarithms: Vec<f64> = values.iter().map(|value| value.ln()).collect();
+        CalcResult::Number(mean(&logarithms).exp())
+    }
+
+    pub(crate) fn fn_harmean(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        if values.iter().any(|&value| value <= 0.0) {
+            return CalcResult::new_error(Error::NUM, cell, "Values must be positive".to_string());
+        }
+        let inverses: Vec<f64> = values.iter().map(|value| 1.0 / value).collect();
+        CalcResult::Number(1.0 / mean(&inverses))
+    }
+
+    pub(crate) fn fn_avedev(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(args, cell) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        let average = mean(&values);
+        let deviations: Vec<f64> = values.iter().map(|x| (x - average).abs()).collect();
+        CalcResult::Number(mean(&deviations))
+    }
+
+    pub(crate) fn fn_devsq(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        match self.get_non_empty_statistical_values(args, cell) {
+            Ok(values) => CalcResult::Number(sum_of_squared_deviations(&values)),
+            Err(error) => error,
+        }
+    }
+
+    // Sum of the standardized values raised to `power`, using the sample standard deviation.
+    // `min_count` is the minimum number of values needed.
+    fn sum_of_standardized_powers(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        power: i32,
+        min_count: usize,
+    ) -> Result<(f64, f64), CalcResult> {
+        let values = self.get_statistical_values(args, cell)?;
+        let count = values.len();
+        let standard_deviation = if count > 1 {
+            (sum_of_squared_deviations(&values) / (count - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        if count < min_count || standard_deviation == 0.0 {
+            return Err(CalcResult::new_error(
+                Error::DIV,
+                cell,
+                "Division by Zero".to_string(),
+            ));
+        }
+        let average = mean(&values);
+        let sum = values
+            .iter()
+            .map(|x| ((x - average) / standard_deviation).powi(power))
+            .sum();
+        Ok((sum, count as f64))
+    }
+
+    pub(crate) fn fn_kurt(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (sum, n) = match self.sum_of_standardized_powers(args, cell, 4, 4) {
+            Ok(result) => result,
+            Err(error) => return error,
+        };
+        CalcResult::Number(
+            n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0)) * sum
+                - 3.0 * (n - 1.0) * (n - 1.0) / ((n - 2.0) * (n - 3.0)),
+        )
+    }
+
+    pub(crate) fn fn_skew(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.is_empty() {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let (sum, n) = match self.sum_of_standardized_powers(args, cell, 3, 3) {
+            Ok(result) => result,
+            Err(error) => return error,
+        };
+        CalcResult::Number(n / ((n - 1.0) * (n - 2.0)) * sum)
+    }
+
+    pub(crate) fn fn_trimmean(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() != 2 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values = match self.get_non_empty_statistical_values(&args[0..1], cell) {
+            Ok(values) => sorted(values),
+            Err(error) => return error,
+        };
+        let percent = match self.get_number(&args[1], cell) {
+            Ok(f) => f,
+            Err(s) => return s,
+        };
+        if !(0.0..1.0).contains(&percent) {
+            return CalcResult::new_error(
+                Error::NUM,
+                cell,
+                "Percent must be between 0 and 1".to_string(),
+            );
+        }
+        // The number of excluded values is rounded down to the nearest multiple of 2
+        let trimmed = (values.len() as f64 * percent / 2.0).floor() as usize;
+        CalcResult::Number(mean(&values[trimmed..values.len() - trimmed]))
+    }
 }
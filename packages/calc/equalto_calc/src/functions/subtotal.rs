@@ -8,6 +8,8 @@ use crate::{
     model::Model,
 };
 
+use super::statistical::{mean, modes, percentile, quartile, sorted, variance};
+
 /// Excel has a complicated way of filtering + hidden rows
 /// As a first a approximation a table can either have filtered rows or hidden rows, but not both.
 /// Internally hey both will be marked as hidden rows. Hidden rows
@@ -31,6 +33,52 @@ pub enum CellTableStatus {
     Filtered,
 }
 
+/// The values AGGREGATE ignores, given by its `options` argument:
+///
+/// | options | nested SUBTOTAL and AGGREGATE | hidden rows | errors  |
+/// |---------|-------------------------------|-------------|---------|
+/// | 0       | ignored                       |             |         |
+/// | 1       | ignored                       | ignored     |         |
+/// | 2       | ignored                       |             | ignored |
+/// | 3       | ignored                       | ignored     | ignored |
+/// | 4       |                               |             |         |
+/// | 5       |                               | ignored     |         |
+/// | 6       |                               |             | ignored |
+/// | 7       |                               | ignored     | ignored |
+///
+/// Unlike SUBTOTAL, filtered rows are treated as hidden rows.
+struct AggregateOptions {
+    skip_nested: bool,
+    skip_hidden: bool,
+    skip_errors: bool,
+}
+
+impl AggregateOptions {
+    fn new(options: i32) -> Option<AggregateOptions> {
+        if !(0..=7).contains(&options) {
+            return None;
+        }
+        Some(AggregateOptions {
+            skip_nested: options < 4,
+            skip_hidden: options & 1 == 1,
+            skip_errors: options & 2 == 2,
+        })
+    }
+}
+
+/// The numbers in the values, the first error if there is one
+fn aggregate_numbers(values: Vec<CalcResult>) -> Result<Vec<f64>, CalcResult> {
+    let mut numbers = Vec::new();
+    for value in values {
+        match value {
+            CalcResult::Number(f) => numbers.push(f),
+            error @ CalcResult::Error { .. } => return Err(error),
+            _ => {}
+        }
+    }
+    Ok(numbers)
+}
+
 impl Model {
     fn get_table_for_cell(&self, sheet_index: u32, row: i32, column: i32) -> bool {
         let worksheet = match self.workbook.worksheet(sheet_index) {
@@ -73,6 +121,7 @@ impl Model {
     }
 
     // FIXME(TD): This is too much
+    // Nested AGGREGATE functions are treated as subtotals
     fn cell_is_subtotal(&self, sheet_index: u32, row: i32, column: i32) -> bool {
         let row_data = match self.workbook.worksheets[sheet_index as usize]
             .sheet_data
@@ -94,7 +143,7 @@ impl Model {
                 matches!(
                     node,
                     Node::FunctionKind {
-                        kind: Function::Subtotal,
+                        kind: Function::Subtotal | Function::Aggregate,
                         args: _
                     }
                 )
@@ -172,6 +221,13 @@ impl Model {
                             }
                         }
                         CalcResult::EmptyCell | CalcResult::EmptyArg => result.push(0.0),
+                        CalcResult::Array(_) => {
+                            return Err(CalcResult::new_error(
+                                Error::VALUE,
+                                cell,
+                                "Expected a reference".to_string(),
+                            ));
+                        }
                     }
                 }
             }
@@ -409,6 +465,13 @@ impl Model {
                         | CalcResult::Number(_)
                         | CalcResult::Boolean(_)
                         | CalcResult::Error { .. } => counta += 1,
+                        CalcResult::Array(_) => {
+                            return CalcResult::new_error(
+                                Error::VALUE,
+                                cell,
+                                "Expected a reference".to_string(),
+                            );
+                        }
                     }
                 }
             }
@@ -581,4 +644,194 @@ impl Model {
         }
         CalcResult::Number(result)
     }
+
+    /// Values of the arguments of AGGREGATE, without empty cells and the values ignored by
+    /// `options`. Like in array formulas, operators work element-wise on ranges:
+    /// `AGGREGATE(14, 6, A1:A9/(B1:B9>0), 1)`.
+    fn aggregate_get_values(
+        &mut self,
+        args: &[Node],
+        cell: CellReference,
+        options: &AggregateOptions,
+    ) -> Result<Vec<CalcResult>, CalcResult> {
+        let mut values = Vec::new();
+        for arg in args {
+            if options.skip_nested
+                && matches!(
+                    arg,
+                    Node::FunctionKind {
+                        kind: Function::Subtotal | Function::Aggregate,
+                        args: _
+                    }
+                )
+            {
+                continue;
+            }
+            let array_context = std::mem::replace(&mut self.array_context, true);
+            let result = self.evaluate_node_with_reference(arg, cell);
+            self.array_context = array_context;
+            match result {
+                CalcResult::Range { left, right } => {
+                    if left.sheet != right.sheet {
+                        return Err(CalcResult::new_error(
+                            Error::VALUE,
+                            cell,
+                            "Ranges are in different sheets".to_string(),
+                        ));
+                    }
+                    for row in left.row..=right.row {
+                        if options.skip_hidden
+                            && self.cell_hidden_status(left.sheet, row, left.column)
+                                != CellTableStatus::Normal
+                        {
+                            continue;
+                        }
+                        for column in left.column..=right.column {
+                            if options.skip_nested && self.cell_is_subtotal(left.sheet, row, column)
+                            {
+                                continue;
+                            }
+                            values.push(self.evaluate_cell(CellReference {
+                                sheet: left.sheet,
+                                row,
+                                column,
+                            }));
+                        }
+                    }
+                }
+                CalcResult::Array(array) => values.extend(array.into_iter().flatten()),
+                value => values.push(value),
+            }
+        }
+        values.retain(|value| match value {
+            CalcResult::EmptyCell | CalcResult::EmptyArg => false,
+            CalcResult::Error { .. } => !options.skip_errors,
+            _ => true,
+        });
+        Ok(values)
+    }
+
+    /// AGGREGATE(function_num, options, ref1, [ref2], ...)
+    /// AGGREGATE(function_num, options, array, k) for the functions 14 to 19
+    pub(crate) fn fn_aggregate(&mut self, args: &[Node], cell: CellReference) -> CalcResult {
+        if args.len() < 3 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let function = match self.get_number(&args[0], cell) {
+            Ok(f) => f.trunc() as i32,
+            Err(s) => return s,
+        };
+        let options = match self.get_optional_number(args, 1, 0.0, cell) {
+            Ok(f) => AggregateOptions::new(f.trunc() as i32),
+            Err(s) => return s,
+        };
+        let options = match options {
+            Some(options) => options,
+            None => {
+                return CalcResult::new_error(
+                    Error::VALUE,
+                    cell,
+                    "Invalid options for AGGREGATE".to_string(),
+                )
+            }
+        };
+        if !(1..=19).contains(&function) {
+            return CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                format!("Invalid value for AGGREGATE: {function}"),
+            );
+        }
+        if function >= 14 && args.len() != 4 {
+            return CalcResult::new_args_number_error(cell);
+        }
+        let values_args = if function >= 14 {
+            &args[2..3]
+        } else {
+            &args[2..]
+        };
+        let values = match self.aggregate_get_values(values_args, cell, &options) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        match function {
+            2 => {
+                let count = values
+                    .iter()
+                    .filter(|value| matches!(value, CalcResult::Number(_)))
+                    .count();
+                return CalcResult::Number(count as f64);
+            }
+            3 => return CalcResult::Number(values.len() as f64),
+            _ => {}
+        }
+        let values = match aggregate_numbers(values) {
+            Ok(values) => values,
+            Err(error) => return error,
+        };
+        let k = if function >= 14 {
+            match self.get_number(&args[3], cell) {
+                Ok(f) => f,
+                Err(s) => return s,
+            }
+        } else {
+            0.0
+        };
+        if values.is_empty() && ![4, 5, 6, 9].contains(&function) {
+            let error = if [1, 7, 8, 10, 11].contains(&function) {
+                Error::DIV
+            } else {
+                Error::NUM
+            };
+            return CalcResult::new_error(error, cell, "No numbers in the arguments".to_string());
+        }
+        let result = match function {
+            1 => Some(mean(&values)),
+            4 => Some(values.iter().copied().fold(f64::NAN, f64::max)),
+            5 => Some(values.iter().copied().fold(f64::NAN, f64::min)),
+            6 => Some(if values.is_empty() {
+                0.0
+            } else {
+                values.iter().product()
+            }),
+            7 | 8 | 10 | 11 => {
+                return match variance(&values, function == 7 || function == 10, cell) {
+                    CalcResult::Number(f) if function == 7 || function == 8 => {
+                        CalcResult::Number(f.sqrt())
+                    }
+                    result => result,
+                };
+            }
+            9 => Some(values.iter().sum()),
+            12 => percentile(&sorted(values), 0.5, true),
+            13 => match modes(values).first() {
+                Some(mode) => Some(*mode),
+                None => {
+                    return CalcResult::new_error(Error::NA, cell, "No repeated values".to_string())
+                }
+            },
+            14 | 15 => {
+                let mut values = sorted(values);
+                if function == 14 {
+                    values.reverse();
+                }
+                let k = k.ceil();
+                if k < 1.0 || k > values.len() as f64 {
+                    None
+                } else {
+                    Some(values[k as usize - 1])
+                }
+            }
+            16 => percentile(&sorted(values), k, true),
+            17 => quartile(&sorted(values), k, true),
+            18 => percentile(&sorted(values), k, false),
+            _ => quartile(&sorted(values), k, false),
+        };
+        match result {
+            // MAX and MIN of no numbers
+            Some(f) if f.is_nan() => CalcResult::Number(0.0),
+            Some(f) => CalcResult::Number(f),
+            None => CalcResult::new_error(Error::NUM, cell, "Invalid argument k".to_string()),
+        }
+    }
 }
@@ -3,7 +3,7 @@ use crate::{
     constants::{LAST_COLUMN, LAST_ROW},
     expressions::parser::Node,
     expressions::token::Error,
-    formatter::format::{format_number, parse_formatted_number},
+    formatter::format::{format_number_with_date_system, parse_formatted_number},
     model::Model,
     number_format::to_precision,
 };
@@ -56,6 +56,23 @@ impl Model {
         let mut result = "".to_string();
         for arg in args {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::String(value) => result = format!("{}{}", result, value),
+                            CalcResult::Number(value) => result = format!("{}{}", result, value),
+                            CalcResult::Boolean(value) => {
+                                if *value {
+                                    result = format!("{}TRUE", result);
+                                } else {
+                                    result = format!("{}FALSE", result);
+                                }
+                            }
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {}
+                        }
+                    }
+                }
                 CalcResult::String(value) => result = format!("{}{}", result, value),
                 CalcResult::Number(value) => result = format!("{}{}", result, value),
                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
@@ -97,7 +114,7 @@ impl Model {
                                 }
                                 error @ CalcResult::Error { .. } => return error,
                                 CalcResult::EmptyCell | CalcResult::EmptyArg => {}
-                                CalcResult::Range { .. } => {}
+                                CalcResult::Range { .. } | CalcResult::Array(_) => {}
                             }
                         }
                     }
@@ -117,7 +134,7 @@ impl Model {
                     return CalcResult::Boolean(b);
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -131,7 +148,8 @@ impl Model {
                 Ok(s) => s,
                 Err(s) => return s,
             };
-            let d = format_number(value, &format_code, &self.locale);
+            let date1904 = self.workbook.settings.date1904;
+            let d = format_number_with_date_system(value, &format_code, &self.locale, date1904);
             if let Some(_e) = d.error {
                 return CalcResult::Error {
                     error: Error::VALUE,
@@ -272,7 +290,7 @@ impl Model {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -300,7 +318,7 @@ impl Model {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -328,7 +346,7 @@ impl Model {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -356,7 +374,7 @@ impl Model {
                     }
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -386,7 +404,7 @@ impl Model {
                 }
             }
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // Implicit Intersection not implemented
                 return CalcResult::Error {
                     error: Error::NIMPL,
@@ -416,7 +434,7 @@ impl Model {
                     };
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -454,7 +472,7 @@ impl Model {
                 }
             }
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // Implicit Intersection not implemented
                 return CalcResult::Error {
                     error: Error::NIMPL,
@@ -484,7 +502,7 @@ impl Model {
                     };
                 }
                 error @ CalcResult::Error { .. } => return error,
-                CalcResult::Range { .. } => {
+                CalcResult::Range { .. } | CalcResult::Array(_) => {
                     // Implicit Intersection not implemented
                     return CalcResult::Error {
                         error: Error::NIMPL,
@@ -522,7 +540,7 @@ impl Model {
                 }
             }
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // Implicit Intersection not implemented
                 return CalcResult::Error {
                     error: Error::NIMPL,
@@ -544,7 +562,7 @@ impl Model {
                 v.floor() as usize
             }
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // Implicit Intersection not implemented
                 return CalcResult::Error {
                     error: Error::NIMPL,
@@ -586,7 +604,7 @@ impl Model {
                 }
             }
             error @ CalcResult::Error { .. } => return error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // Implicit Intersection not implemented
                 return CalcResult::Error {
                     error: Error::NIMPL,
@@ -875,6 +893,24 @@ impl Model {
         let mut values = Vec::new();
         for arg in &args[2..] {
             match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Array(array) => {
+                    for value in array.iter().flatten() {
+                        match value {
+                            CalcResult::Number(value) => values.push(format!("{value}")),
+                            CalcResult::String(value) => values.push(value.clone()),
+                            CalcResult::Boolean(value) => {
+                                if *value {
+                                    values.push("TRUE".to_string())
+                                } else {
+                                    values.push("FALSE".to_string())
+                                }
+                            }
+                            CalcResult::EmptyCell if !ignore_empty => values.push("".to_string()),
+                            error @ CalcResult::Error { .. } => return error.clone(),
+                            _ => {}
+                        }
+                    }
+                }
                 CalcResult::Number(value) => values.push(format!("{value}")),
                 CalcResult::Range { left, right } => {
                     if left.sheet != right.sheet {
@@ -928,7 +964,9 @@ impl Model {
                                     }
                                 }
                                 error @ CalcResult::Error { .. } => return error,
-                                CalcResult::EmptyArg | CalcResult::Range { .. } => {}
+                                CalcResult::EmptyArg
+                                | CalcResult::Range { .. }
+                                | CalcResult::Array(_) => {}
                             }
                         }
                     }
@@ -1046,7 +1084,10 @@ impl Model {
         match self.evaluate_node_in_context(&args[0], cell) {
             CalcResult::String(text) => {
                 let currencies = vec!["$", "€"];
-                if let Ok((value, _)) = parse_formatted_number(&text, &currencies) {
+                let date1904 = self.workbook.settings.date1904;
+                if let Ok((value, _)) =
+                    parse_formatted_number(&text, &currencies, &self.locale, date1904)
+                {
                     return CalcResult::Number(value);
                 };
                 CalcResult::Error {
@@ -1062,7 +1103,7 @@ impl Model {
                 message: "Invalid number".to_string(),
             },
             error @ CalcResult::Error { .. } => error,
-            CalcResult::Range { .. } => {
+            CalcResult::Range { .. } | CalcResult::Array(_) => {
                 // TODO Implicit Intersection
                 CalcResult::Error {
                     error: Error::VALUE,
@@ -392,7 +392,7 @@ pub(crate) fn build_criteria<'a>(value: &'a CalcResult) -> Box<dyn Fn(&CalcResul
             // An error will match an error (never a string that is an error)
             Box::new(move |x| result_is_equal_to_error(x, &error.to_string()))
         }
-        CalcResult::Range { left: _, right: _ } => {
+        CalcResult::Range { left: _, right: _ } | CalcResult::Array(_) => {
             // TODO: Implicit Intersection
             Box::new(move |_x| false)
         }
@@ -11,6 +11,7 @@ use super::{
         binary_search_descending_or_greater, binary_search_descending_or_smaller,
         binary_search_or_greater, binary_search_or_smaller,
     },
+    lookup_and_reference::LookupArray,
     util::{compare_values, from_wildcard_to_regex, result_matches_regex},
 };
 
@@ -216,169 +217,128 @@ impl Model {
             SearchMode::StartAtFirstItem
         };
         // lookup_array
-        match self.evaluate_node_in_context(&args[1], cell) {
-            CalcResult::Range { left, right } => {
-                let is_row_vector;
-                if left.row == right.row {
-                    is_row_vector = false;
-                } else if left.column == right.column {
-                    is_row_vector = true;
-                } else {
-                    // second argument must be a vector
-                    return CalcResult::Error {
-                        error: Error::ERROR,
-                        origin: cell,
-                        message: "Second argument must be a vector".to_string(),
-                    };
+        let lookup_array = match self.evaluate_node_in_context(&args[1], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            _ => {
+                return CalcResult::Error {
+                    error: Error::NA,
+                    origin: cell,
+                    message: "Range expected".to_string(),
                 }
-                // return array
-                match self.evaluate_node_in_context(&args[2], cell) {
-                    CalcResult::Range {
-                        left: result_left,
-                        right: result_right,
-                    } => {
-                        if result_right.row - result_left.row != right.row - left.row
-                            || result_right.column - result_left.column
-                                != right.column - left.column
-                        {
-                            return CalcResult::Error {
-                                error: Error::VALUE,
-                                origin: cell,
-                                message: "Arrays must be of the same size".to_string(),
-                            };
-                        }
-                        let mut row2 = right.row;
-                        let row1 = left.row;
-                        let mut column2 = right.column;
-                        let column1 = left.column;
+            }
+        };
+        let (rows, columns) = lookup_array.dimensions();
+        if rows != 1 && columns != 1 {
+            // second argument must be a vector
+            return CalcResult::Error {
+                error: Error::ERROR,
+                origin: cell,
+                message: "Second argument must be a vector".to_string(),
+            };
+        }
+        // return array
+        let return_array = match self.evaluate_node_in_context(&args[2], cell) {
+            CalcResult::Range { left, right } => LookupArray::Range { left, right },
+            CalcResult::Array(array) => LookupArray::Array(array),
+            error @ CalcResult::Error { .. } => return error,
+            _ => {
+                return CalcResult::Error {
+                    error: Error::VALUE,
+                    origin: cell,
+                    message: "Range expected".to_string(),
+                }
+            }
+        };
+        if return_array.dimensions() != (rows, columns) {
+            return CalcResult::Error {
+                error: Error::VALUE,
+                origin: cell,
+                message: "Arrays must be of the same size".to_string(),
+            };
+        }
+        let lookup_array = match lookup_array {
+            LookupArray::Range { left, right } => {
+                let mut row2 = right.row;
+                let row1 = left.row;
+                let mut column2 = right.column;
+                let column1 = left.column;
 
-                        if row1 == 1 && row2 == LAST_ROW {
-                            row2 = self
-                                .workbook
-                                .worksheet(left.sheet)
-                                .expect("Sheet expected during evaluation.")
-                                .dimension()
-                                .max_row;
-                        }
-                        if column1 == 1 && column2 == LAST_COLUMN {
-                            column2 = self
-                                .workbook
-                                .worksheet(left.sheet)
-                                .expect("Sheet expected during evaluation.")
-                                .dimension()
-                                .max_column;
+                if row1 == 1 && row2 == LAST_ROW {
+                    row2 = self
+                        .workbook
+                        .worksheet(left.sheet)
+                        .expect("Sheet expected during evaluation.")
+                        .dimension()
+                        .max_row;
+                }
+                if column1 == 1 && column2 == LAST_COLUMN {
+                    column2 = self
+                        .workbook
+                        .worksheet(left.sheet)
+                        .expect("Sheet expected during evaluation.")
+                        .dimension()
+                        .max_column;
+                }
+                let left = CellReference {
+                    sheet: left.sheet,
+                    column: column1,
+                    row: row1,
+                };
+                let right = CellReference {
+                    sheet: left.sheet,
+                    column: column2,
+                    row: row2,
+                };
+                LookupArray::Range { left, right }
+            }
+            array => array,
+        };
+        // lookup_array is a vector
+        let array = self.lookup_vector(&lookup_array).unwrap_or_default();
+        let index = match search_mode {
+            SearchMode::StartAtFirstItem | SearchMode::StartAtLastItem => {
+                linear_search(&lookup_value, &array, search_mode, match_mode)
+            }
+            SearchMode::BinarySearchAscending | SearchMode::BinarySearchDescending => {
+                let index = if match_mode == MatchMode::ExactMatchLarger {
+                    if search_mode == SearchMode::BinarySearchAscending {
+                        binary_search_or_greater(&lookup_value, &array)
+                    } else {
+                        binary_search_descending_or_greater(&lookup_value, &array)
+                    }
+                } else if search_mode == SearchMode::BinarySearchAscending {
+                    binary_search_or_smaller(&lookup_value, &array)
+                } else {
+                    binary_search_descending_or_smaller(&lookup_value, &array)
+                };
+                match match_mode {
+                    MatchMode::ExactMatch => {
+                        index.filter(|&l| compare_values(&array[l as usize], &lookup_value) == 0)
+                    }
+                    MatchMode::ExactMatchSmaller | MatchMode::ExactMatchLarger => index,
+                    MatchMode::WildcardMatch => {
+                        if index.is_none() {
+                            return if_not_found;
                         }
-                        let left = CellReference {
-                            sheet: left.sheet,
-                            column: column1,
-                            row: row1,
-                        };
-                        let right = CellReference {
-                            sheet: left.sheet,
-                            column: column2,
-                            row: row2,
+                        return CalcResult::Error {
+                            error: Error::VALUE,
+                            origin: cell,
+                            message: "Cannot use wildcard in binary search".to_string(),
                         };
-                        match search_mode {
-                            SearchMode::StartAtFirstItem | SearchMode::StartAtLastItem => {
-                                let array = &self.prepare_array(&left, &right, is_row_vector);
-                                match linear_search(&lookup_value, array, search_mode, match_mode) {
-                                    Some(index) => {
-                                        let row_index =
-                                            if is_row_vector { index as i32 } else { 0 };
-                                        let column_index =
-                                            if is_row_vector { 0 } else { index as i32 };
-                                        self.evaluate_cell(CellReference {
-                                            sheet: result_left.sheet,
-                                            row: result_left.row + row_index,
-                                            column: result_left.column + column_index,
-                                        })
-                                    }
-                                    None => if_not_found,
-                                }
-                            }
-                            SearchMode::BinarySearchAscending
-                            | SearchMode::BinarySearchDescending => {
-                                let index = if match_mode == MatchMode::ExactMatchLarger {
-                                    if search_mode == SearchMode::BinarySearchAscending {
-                                        binary_search_or_greater(
-                                            &lookup_value,
-                                            &self.prepare_array(&left, &right, is_row_vector),
-                                        )
-                                    } else {
-                                        binary_search_descending_or_greater(
-                                            &lookup_value,
-                                            &self.prepare_array(&left, &right, is_row_vector),
-                                        )
-                                    }
-                                } else if search_mode == SearchMode::BinarySearchAscending {
-                                    binary_search_or_smaller(
-                                        &lookup_value,
-                                        &self.prepare_array(&left, &right, is_row_vector),
-                                    )
-                                } else {
-                                    binary_search_descending_or_smaller(
-                                        &lookup_value,
-                                        &self.prepare_array(&left, &right, is_row_vector),
-                                    )
-                                };
-                                match index {
-                                    None => if_not_found,
-                                    Some(l) => {
-                                        let row =
-                                            result_left.row + if is_row_vector { l } else { 0 };
-                                        let column =
-                                            result_left.column + if is_row_vector { 0 } else { l };
-                                        if match_mode == MatchMode::ExactMatch {
-                                            let value = self.evaluate_cell(CellReference {
-                                                sheet: left.sheet,
-                                                row: left.row + if is_row_vector { l } else { 0 },
-                                                column: left.column
-                                                    + if is_row_vector { 0 } else { l },
-                                            });
-                                            if compare_values(&value, &lookup_value) == 0 {
-                                                self.evaluate_cell(CellReference {
-                                                    sheet: result_left.sheet,
-                                                    row,
-                                                    column,
-                                                })
-                                            } else {
-                                                if_not_found
-                                            }
-                                        } else if match_mode == MatchMode::ExactMatchSmaller
-                                            || match_mode == MatchMode::ExactMatchLarger
-                                        {
-                                            self.evaluate_cell(CellReference {
-                                                sheet: result_left.sheet,
-                                                row,
-                                                column,
-                                            })
-                                        } else {
-                                            CalcResult::Error {
-                                                error: Error::VALUE,
-                                                origin: cell,
-                                                message: "Cannot use wildcard in binary search"
-                                                    .to_string(),
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
                     }
-                    error @ CalcResult::Error { .. } => error,
-                    _ => CalcResult::Error {
-                        error: Error::VALUE,
-                        origin: cell,
-                        message: "Range expected".to_string(),
-                    },
                 }
+                .map(|l| l as usize)
             }
-            error @ CalcResult::Error { .. } => error,
-            _ => CalcResult::Error {
-                error: Error::NA,
-                origin: cell,
-                message: "Range expected".to_string(),
-            },
+        };
+        match index {
+            Some(index) => {
+                let (row, column) = lookup_array.vector_position(index as i32);
+                self.trace_lookup_array_match(cell, &lookup_array, row, column);
+                self.lookup_array_value(&return_array, row, column)
+            }
+            None => if_not_found,
         }
     }
 }
@@ -0,0 +1,228 @@
+//! Iterative calculation of circular references: the cycles of the dependency graph are
+//! recomputed until their values converge.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    dependencies::{CellKey, DependencyGraph},
+    expressions::token::Error,
+    model::Model,
+    types::{Cell, FormulaValue, IterativeCalculation},
+};
+
+/// Strongly connected components of the graph, each component after all the components it has
+/// edges to (Tarjan's algorithm).
+fn strongly_connected_components(
+    nodes: &[CellKey],
+    edges: &HashMap<CellKey, Vec<CellKey>>,
+) -> Vec<Vec<CellKey>> {
+    let no_edges = Vec::new();
+    let mut index: HashMap<CellKey, usize> = HashMap::new();
+    let mut low: HashMap<CellKey, usize> = HashMap::new();
+    let mut on_stack: HashSet<CellKey> = HashSet::new();
+    let mut stack: Vec<CellKey> = Vec::new();
+    let mut components = Vec::new();
+    for &root in nodes {
+        if index.contains_key(&root) {
+            continue;
+        }
+        // The nodes being visited and the next of their edges to follow
+        let mut work: Vec<(CellKey, usize)> = vec![(root, 0)];
+        while let Some((node, edge)) = work.pop() {
+            if edge == 0 {
+                index.insert(node, index.len());
+                low.insert(node, index[&node]);
+                stack.push(node);
+                on_stack.insert(node);
+            }
+            if let Some(&next) = edges.get(&node).unwrap_or(&no_edges).get(edge) {
+                work.push((node, edge + 1));
+                if !index.contains_key(&next) {
+                    work.push((next, 0));
+                } else if on_stack.contains(&next) {
+                    let next_index = index[&next];
+                    low.entry(node).and_modify(|l| *l = (*l).min(next_index));
+                }
+                continue;
+            }
+            if low[&node] == index[&node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+            if let Some(&(parent, _)) = work.last() {
+                let node_low = low[&node];
+                low.entry(parent).and_modify(|l| *l = (*l).min(node_low));
+            }
+        }
+    }
+    components
+}
+
+/// How much a value changed between two iterations. Values that are not numbers either did not
+/// change at all or changed infinitely.
+fn change(previous: &CalcResult, current: &CalcResult) -> f64 {
+    let unchanged = match (previous, current) {
+        (CalcResult::Number(a), CalcResult::Number(b)) => return (a - b).abs(),
+        (CalcResult::String(a), CalcResult::String(b)) => a == b,
+        (CalcResult::Boolean(a), CalcResult::Boolean(b)) => a == b,
+        (CalcResult::Error { error: a, .. }, CalcResult::Error { error: b, .. }) => a == b,
+        (CalcResult::EmptyCell, CalcResult::EmptyCell) => true,
+        _ => false,
+    };
+    if unchanged {
+        0.0
+    } else {
+        f64::INFINITY
+    }
+}
+
+impl Model {
+    /// Enables or disables iterative calculation. Like in Excel the maximum number of iterations
+    /// must be between 1 and 32767 and the maximum change cannot be negative.
+    pub fn set_iterative_calculation(
+        &mut self,
+        enabled: bool,
+        max_iterations: i32,
+        max_change: f64,
+    ) -> Result<(), String> {
+        if !(1..=32767).contains(&max_iterations) {
+            return Err(format!(
+                "Invalid maximum number of iterations: {max_iterations}"
+            ));
+        }
+        if max_change.is_nan() || max_change < 0.0 {
+            return Err(format!("Invalid maximum change: {max_change}"));
+        }
+        self.workbook.settings.iterative_calculation = IterativeCalculation {
+            enabled,
+            max_iterations,
+            max_change,
+        };
+        Ok(())
+    }
+
+    /// The value of a cell in a circular reference, as computed in the previous iteration.
+    /// Formulas that were never evaluated start at 0.
+    pub(crate) fn previous_iteration_value(
+        &self,
+        cell: &Cell,
+        cell_reference: CellReference,
+    ) -> CalcResult {
+        match cell {
+            Cell::CellFormula { .. }
+            | Cell::CellFormulaError {
+                ei: Error::CIRC, ..
+            }
+            | Cell::ArrayFormula {
+                v:
+                    FormulaValue::Unevaluated
+                    | FormulaValue::Error {
+                        ei: Error::CIRC, ..
+                    },
+                ..
+            } => CalcResult::Number(0.0),
+            _ => self.get_cell_value(cell, cell_reference),
+        }
+    }
+
+    /// The formula cells (or spill anchors) each formula reads
+    fn formula_precedents(&self, graph: &DependencyGraph) -> HashMap<CellKey, Vec<CellKey>> {
+        let formulas = graph.precedents();
+        let mut edges = HashMap::new();
+        for (key, precedents) in formulas {
+            let mut cells = Vec::new();
+            for range in precedents {
+                if range.left == range.right {
+                    cells.push((range.left.sheet, range.left.row, range.left.column));
+                } else {
+                    cells.extend(self.cells_in_range(range));
+                }
+            }
+            let mut successors: Vec<CellKey> = cells
+                .into_iter()
+                .filter_map(|(sheet, row, column)| {
+                    if formulas.contains_key(&(sheet, row, column)) {
+                        return Some((sheet, row, column));
+                    }
+                    // A spilled value is computed by its anchor
+                    self.workbook
+                        .worksheet(sheet)
+                        .ok()?
+                        .cell(row, column)?
+                        .get_spill_anchor(row, column)
+                        .map(|(r, c)| (sheet, r, c))
+                })
+                .collect();
+            successors.sort_unstable();
+            successors.dedup();
+            edges.insert(*key, successors);
+        }
+        edges
+    }
+
+    /// Recomputes the circular references until they converge. Called after a full evaluation.
+    pub(crate) fn iterate_circular_references(&mut self) {
+        let settings = self.workbook.settings.iterative_calculation.clone();
+        if !settings.enabled {
+            return;
+        }
+        let graph = self.build_dependency_graph();
+        let edges = self.formula_precedents(&graph);
+        let mut nodes: Vec<CellKey> = edges.keys().copied().collect();
+        nodes.sort_unstable();
+
+        // Cells evaluated again after the full evaluation
+        let mut recomputed: HashSet<CellKey> = HashSet::new();
+        for component in strongly_connected_components(&nodes, &edges) {
+            let is_cycle = component.len() > 1
+                || edges
+                    .get(&component[0])
+                    .is_some_and(|successors| successors.contains(&component[0]));
+            if is_cycle {
+                // The full evaluation was the first iteration
+                for _ in 1..settings.max_iterations {
+                    let previous = self.evaluate_cells(&component);
+                    for key in &component {
+                        self.cells.remove(key);
+                    }
+                    let current = self.evaluate_cells(&component);
+                    let max_change = previous
+                        .iter()
+                        .zip(&current)
+                        .map(|(a, b)| change(a, b))
+                        .fold(0.0, f64::max);
+                    if max_change < settings.max_change {
+                        break;
+                    }
+                }
+                recomputed.extend(component);
+            } else {
+                let key = component[0];
+                let is_stale = edges
+                    .get(&key)
+                    .is_some_and(|successors| successors.iter().any(|s| recomputed.contains(s)));
+                if is_stale {
+                    self.cells.remove(&key);
+                    self.evaluate_cells(&component);
+                    recomputed.insert(key);
+                }
+            }
+        }
+    }
+
+    fn evaluate_cells(&mut self, cells: &[CellKey]) -> Vec<CalcResult> {
+        cells
+            .iter()
+            .map(|&(sheet, row, column)| self.evaluate_cell(CellReference { sheet, row, column }))
+            .collect()
+    }
+}
@@ -1,6 +1,9 @@
 #![deny(clippy::unwrap_used)]
+pub mod auditing;
 pub mod calc_result;
 pub mod cell;
+pub mod environment;
+pub mod evaluation_trace;
 pub mod expressions;
 pub mod formatter;
 pub mod language;
@@ -8,18 +11,27 @@ pub mod locale;
 pub mod model;
 pub mod new_empty;
 pub mod number_format;
+pub mod operations;
 pub mod types;
+pub mod user_functions;
 pub mod worksheet;
 
 mod functions;
 
 mod actions;
+mod array_formula;
+mod arrays;
 mod cast;
 mod constants;
 mod styles;
 
+mod defined_names;
+mod dependencies;
 mod diffs;
 mod implicit_intersection;
+mod iterative_calculation;
+mod parallel;
+mod spill;
 
 mod units;
 mod utils;
@@ -2,12 +2,18 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::vec::Vec;
 
 use crate::{
+    arrays::array_top_left,
     calc_result::{CalcResult, CellReference, Range},
     cell::CellValue,
     constants,
+    dependencies::{CellKey, DependencyGraph},
+    diffs::{Diff, History},
+    environment::{default_environment, Environment},
+    evaluation_trace::EvaluationTracer,
     expressions::token::{Error, OpCompare, OpProduct, OpSum, OpUnary},
     expressions::{
         parser::move_formula::{move_formula, MoveContext},
@@ -23,47 +29,21 @@ use crate::{
         utils::is_valid_column_number,
     },
     formatter::{
-        format::{format_number, parse_formatted_number},
+        format::{format_number_with_date_system, parse_formatted_number},
         lexer::is_likely_date_number_format,
     },
-    functions::util::compare_values,
+    functions::{lambda::LocalValue, util::compare_values},
     implicit_intersection::implicit_intersection,
     language::{get_language, Language},
     locale::{get_locale, Currency, Locale},
+    spill::{SpillArea, MAX_SPILL_PASSES},
     types::*,
+    user_functions::UserFunction,
     utils as common,
 };
 
 pub use chrono_tz::Tz;
 
-#[cfg(test)]
-pub fn get_milliseconds_since_epoch() -> i64 {
-    use std::env;
-    // 8 November 2022 12:13 Berlin time
-    let timestamp_default = 1667906008578;
-    match env::var("TIMESTAMP") {
-        Ok(s) => s.parse::<i64>().unwrap_or(timestamp_default),
-        Err(_) => timestamp_default,
-    }
-}
-
-#[cfg(not(test))]
-#[cfg(not(target_arch = "wasm32"))]
-pub fn get_milliseconds_since_epoch() -> i64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("problem with system time")
-        .as_millis() as i64
-}
-
-#[cfg(not(test))]
-#[cfg(target_arch = "wasm32")]
-pub fn get_milliseconds_since_epoch() -> i64 {
-    use js_sys::Date;
-    Date::now() as i64
-}
-
 #[derive(Clone)]
 pub enum CellState {
     Evaluated,
@@ -74,9 +54,11 @@ pub enum CellState {
 pub enum ParsedDefinedName {
     CellReference(CellReference),
     RangeReference(Range),
+    /// A LAMBDA function, called like a built-in function `=MYTAX(A1)`
+    Lambda(Node),
+    /// Any other formula, including constants. Evaluated in the context of the calling cell
+    Formula(Node),
     InvalidDefinedNameFormula,
-    // TODO: Support constants in defined names
-    // TODO: Support formulas in defined names
     // TODO: Support tables in defined names
 }
 
@@ -84,6 +66,12 @@ pub enum ParsedDefinedName {
 ///     * A Workbook: An internal representation of and Excel workbook
 ///     * Parsed Formulas: All the formulas in the workbook are parsed here (runtime only)
 ///     * A list of cells with its status (evaluating, evaluated, not evaluated)
+///     * The dependency graph used by the incremental evaluation (runtime only, built on demand)
+///     * The ranges spilled by dynamic array formulas (runtime only)
+///     * Whether a legacy array formula is being evaluated (runtime only)
+///     * The LET and LAMBDA bindings in scope and the depth of LAMBDA calls (runtime only)
+///     * The defined names whose formulas are being evaluated (runtime only)
+///     * The undo and redo history (runtime only)
 #[derive(Clone)]
 pub struct Model {
     pub workbook: Workbook,
@@ -91,6 +79,16 @@ pub struct Model {
     pub parsed_defined_names: HashMap<(Option<u32>, String), ParsedDefinedName>,
     pub parser: Parser,
     pub cells: HashMap<(u32, i32, i32), CellState>,
+    pub(crate) dependency_graph: Option<DependencyGraph>,
+    pub(crate) spill_areas: HashMap<CellKey, SpillArea>,
+    pub(crate) array_context: bool,
+    pub(crate) locals: Vec<(String, LocalValue)>,
+    pub(crate) lambda_depth: usize,
+    pub(crate) evaluating_names: Vec<String>,
+    pub(crate) history: History,
+    pub(crate) evaluation_trace: Option<EvaluationTracer>,
+    pub(crate) user_functions: HashMap<String, Arc<dyn UserFunction>>,
+    pub(crate) environment: Arc<dyn Environment>,
     pub locale: Locale,
     pub language: Language,
     pub tz: Tz,
@@ -113,11 +111,19 @@ pub struct Style {
 }
 
 impl Model {
+    /// Evaluates the node, references and ranges are not dereferenced
     pub(crate) fn evaluate_node_with_reference(
         &mut self,
         node: &Node,
         cell: CellReference,
     ) -> CalcResult {
+        if self.evaluation_trace.is_some() {
+            return self.trace_step(node, cell, |model| model.evaluate_reference(node, cell));
+        }
+        self.evaluate_reference(node, cell)
+    }
+
+    fn evaluate_reference(&mut self, node: &Node, cell: CellReference) -> CalcResult {
         match node {
             Node::ReferenceKind {
                 sheet_name: _,
@@ -190,7 +196,7 @@ impl Model {
                     },
                 }
             }
-            _ => self.evaluate_node_in_context(node, cell),
+            _ => self.evaluate_node(node, cell),
         }
     }
 
@@ -237,28 +243,42 @@ impl Model {
         node: &Node,
         cell: CellReference,
     ) -> CalcResult {
+        if self.evaluation_trace.is_some() {
+            return self.trace_step(node, cell, |model| model.evaluate_node(node, cell));
+        }
+        self.evaluate_node(node, cell)
+    }
+
+    fn evaluate_node(&mut self, node: &Node, cell: CellReference) -> CalcResult {
         use Node::*;
         match node {
             OpSumKind { kind, left, right } => {
-                // In the future once the feature try trait stabilizes we could use the '?' operator for this :)
-                // See: https://play.rust-lang.org/?version=nightly&mode=debug&edition=2018&gist=236044e8321a1450988e6ffe5a27dab5
-                let l = match self.get_number(left, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let r = match self.get_number(right, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let result = match kind {
-                    OpSum::Add => l + r,
-                    OpSum::Minus => l - r,
-                };
-                CalcResult::Number(result)
+                let l = self.evaluate_operand(left, cell);
+                if l.is_error() {
+                    return l;
+                }
+                let r = self.evaluate_operand(right, cell);
+                self.apply_binary(l, r, cell, |model, l, r| {
+                    // In the future once the feature try trait stabilizes we could use the '?' operator for this :)
+                    // See: https://play.rust-lang.org/?version=nightly&mode=debug&edition=2018&gist=236044e8321a1450988e6ffe5a27dab5
+                    let l = match model.cast_to_number(l, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let r = match model.cast_to_number(r, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let result = match kind {
+                        OpSum::Add => l + r,
+                        OpSum::Minus => l - r,
+                    };
+                    CalcResult::Number(result)
+                })
             }
             NumberKind(value) => CalcResult::Number(*value),
             StringKind(value) => CalcResult::String(value.replace(r#""""#, r#"""#)),
@@ -332,73 +352,98 @@ impl Model {
                 },
             },
             OpConcatenateKind { left, right } => {
-                let l = match self.get_string(left, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let r = match self.get_string(right, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let result = format!("{}{}", l, r);
-                CalcResult::String(result)
+                let l = self.evaluate_operand(left, cell);
+                if l.is_error() {
+                    return l;
+                }
+                let r = self.evaluate_operand(right, cell);
+                self.apply_binary(l, r, cell, |model, l, r| {
+                    let l = match model.cast_to_string(l, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let r = match model.cast_to_string(r, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let result = format!("{}{}", l, r);
+                    CalcResult::String(result)
+                })
             }
             OpProductKind { kind, left, right } => {
-                let l = match self.get_number(left, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let r = match self.get_number(right, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let result = match kind {
-                    OpProduct::Times => l * r,
-                    OpProduct::Divide => {
-                        if r == 0.0 {
-                            return CalcResult::new_error(
-                                Error::DIV,
-                                cell,
-                                "Divide by Zero".to_string(),
-                            );
+                let l = self.evaluate_operand(left, cell);
+                if l.is_error() {
+                    return l;
+                }
+                let r = self.evaluate_operand(right, cell);
+                self.apply_binary(l, r, cell, |model, l, r| {
+                    let l = match model.cast_to_number(l, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
                         }
-                        l / r
-                    }
-                };
-                CalcResult::Number(result)
+                    };
+                    let r = match model.cast_to_number(r, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let result = match kind {
+                        OpProduct::Times => l * r,
+                        OpProduct::Divide => {
+                            if r == 0.0 {
+                                return CalcResult::new_error(
+                                    Error::DIV,
+                                    cell,
+                                    "Divide by Zero".to_string(),
+                                );
+                            }
+                            l / r
+                        }
+                    };
+                    CalcResult::Number(result)
+                })
             }
             OpPowerKind { left, right } => {
-                let l = match self.get_number(left, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                let r = match self.get_number(right, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
-                    }
-                };
-                // Deal with errors properly
-                CalcResult::Number(l.powf(r))
+                let l = self.evaluate_operand(left, cell);
+                if l.is_error() {
+                    return l;
+                }
+                let r = self.evaluate_operand(right, cell);
+                self.apply_binary(l, r, cell, |model, l, r| {
+                    let l = match model.cast_to_number(l, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    let r = match model.cast_to_number(r, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    // Deal with errors properly
+                    CalcResult::Number(l.powf(r))
+                })
             }
             FunctionKind { kind, args } => self.evaluate_function(kind, args, cell),
-            InvalidFunctionKind { name, args: _ } => {
-                CalcResult::new_error(Error::ERROR, cell, format!("Invalid function: {}", name))
-            }
-            ArrayKind(_) => {
-                // TODO: NOT IMPLEMENTED
-                CalcResult::new_error(Error::NIMPL, cell, "Arrays not implemented".to_string())
-            }
+            InvalidFunctionKind { name, args } => match self.get_defined_lambda(name, cell) {
+                Some(lambda) => self.evaluate_defined_lambda_call(&lambda, args, cell),
+                None => {
+                    CalcResult::new_error(Error::ERROR, cell, format!("Invalid function: {}", name))
+                }
+            },
+            UserFunctionKind { name, args } => self.evaluate_user_function(name, args, cell),
+            LocalVariableKind(name) => self.evaluate_local_variable(name, cell),
+            LambdaCallKind { lambda, args } => self.evaluate_lambda_call(lambda, args, cell),
+            ArrayKind(rows) => self.evaluate_array_literal(rows, cell),
+            SpillRefKind(reference) => self.get_spill_range(reference, cell),
             VariableKind(defined_name) => {
                 let parsed_defined_name = self
                     .parsed_defined_names
@@ -417,10 +462,19 @@ impl Model {
                             left: range.left,
                             right: range.right,
                         },
+                        ParsedDefinedName::Formula(node) => {
+                            let node = node.clone();
+                            self.evaluate_defined_name_formula(defined_name, &node, cell)
+                        }
+                        ParsedDefinedName::Lambda(_) => CalcResult::new_error(
+                            Error::CALC,
+                            cell,
+                            "LAMBDA must be called".to_string(),
+                        ),
                         ParsedDefinedName::InvalidDefinedNameFormula => CalcResult::new_error(
-                            Error::NIMPL,
+                            Error::ERROR,
                             cell,
-                            format!("Defined name \"{}\" is not a reference.", defined_name),
+                            format!("Defined name \"{}\" has an invalid formula.", defined_name),
                         ),
                     }
                 } else {
@@ -432,71 +486,82 @@ impl Model {
                 }
             }
             CompareKind { kind, left, right } => {
-                let l = self.evaluate_node_in_context(left, cell);
+                let l = self.evaluate_operand(left, cell);
                 if l.is_error() {
                     return l;
                 }
-                let r = self.evaluate_node_in_context(right, cell);
+                let r = self.evaluate_operand(right, cell);
                 if r.is_error() {
                     return r;
                 }
-                let compare = compare_values(&l, &r);
-                match kind {
-                    OpCompare::Equal => {
-                        if compare == 0 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
-                        }
+                self.apply_binary(l, r, cell, |_, l, r| {
+                    if l.is_error() {
+                        return l;
                     }
-                    OpCompare::LessThan => {
-                        if compare == -1 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
-                        }
+                    if r.is_error() {
+                        return r;
                     }
-                    OpCompare::GreaterThan => {
-                        if compare == 1 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
+                    let compare = compare_values(&l, &r);
+                    match kind {
+                        OpCompare::Equal => {
+                            if compare == 0 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
                         }
-                    }
-                    OpCompare::LessOrEqualThan => {
-                        if compare < 1 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
+                        OpCompare::LessThan => {
+                            if compare == -1 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
                         }
-                    }
-                    OpCompare::GreaterOrEqualThan => {
-                        if compare > -1 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
+                        OpCompare::GreaterThan => {
+                            if compare == 1 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
                         }
-                    }
-                    OpCompare::NonEqual => {
-                        if compare != 0 {
-                            CalcResult::Boolean(true)
-                        } else {
-                            CalcResult::Boolean(false)
+                        OpCompare::LessOrEqualThan => {
+                            if compare < 1 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
+                        }
+                        OpCompare::GreaterOrEqualThan => {
+                            if compare > -1 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
+                        }
+                        OpCompare::NonEqual => {
+                            if compare != 0 {
+                                CalcResult::Boolean(true)
+                            } else {
+                                CalcResult::Boolean(false)
+                            }
                         }
                     }
-                }
+                })
             }
             UnaryKind { kind, right } => {
-                let r = match self.get_number(right, cell) {
-                    Ok(f) => f,
-                    Err(s) => {
-                        return s;
+                let r = self.evaluate_operand(right, cell);
+                self.apply_unary(r, |model, r| {
+                    let r = match model.cast_to_number(r, cell) {
+                        Ok(f) => f,
+                        Err(s) => {
+                            return s;
+                        }
+                    };
+                    match kind {
+                        OpUnary::Minus => CalcResult::Number(-r),
+                        OpUnary::Percentage => CalcResult::Number(r / 100.0),
                     }
-                };
-                match kind {
-                    OpUnary::Minus => CalcResult::Number(-r),
-                    OpUnary::Percentage => CalcResult::Number(r / 100.0),
-                }
+                })
             }
             ErrorKind(kind) => CalcResult::new_error(kind.clone(), cell, "".to_string()),
             ParseErrorKind {
@@ -512,7 +577,10 @@ impl Model {
         }
     }
 
-    fn cell_reference_to_string(&self, cell_reference: &CellReference) -> Result<String, String> {
+    pub(crate) fn cell_reference_to_string(
+        &self,
+        cell_reference: &CellReference,
+    ) -> Result<String, String> {
         let sheet = self.workbook.worksheet(cell_reference.sheet)?;
         let column = utils::number_to_column(cell_reference.column)
             .ok_or_else(|| "Invalid column".to_string())?;
@@ -528,6 +596,17 @@ impl Model {
         let CellReference { sheet, column, row } = cell_reference;
         let cell = &self.workbook.worksheets[sheet as usize].sheet_data[&row][&column];
         let s = cell.get_style();
+        if let Cell::ArrayFormula { .. } = cell {
+            let value = self.get_formula_value(result, cell_reference);
+            if let Some(Cell::ArrayFormula { v, .. }) = self.workbook.worksheets[sheet as usize]
+                .sheet_data
+                .get_mut(&row)
+                .and_then(|row_data| row_data.get_mut(&column))
+            {
+                *v = value;
+            }
+            return;
+        }
         if let Some(f) = cell.get_formula() {
             match result {
                 CalcResult::Number(value) => {
@@ -628,23 +707,34 @@ impl Model {
                         .get_mut(&column)
                         .expect("expected a column") = Cell::CellFormulaNumber { f, s, v: 0.0 };
                 }
+                CalcResult::Array(array) => {
+                    // Arrays are spilled before getting here, only the top left value is shown
+                    let v = array_top_left(array.clone(), cell_reference);
+                    self.set_cell_value(cell_reference, &v);
+                }
             }
         }
     }
 
     pub fn set_sheet_color(&mut self, sheet: u32, color: &str) -> Result<(), String> {
-        let mut worksheet = self.workbook.worksheet_mut(sheet)?;
-        if color.is_empty() {
-            worksheet.color = None;
-            return Ok(());
+        let old_value = self.workbook.worksheet(sheet)?.color.clone();
+        let new_value = if color.is_empty() {
+            None
         } else if common::is_valid_hex_color(color) {
-            worksheet.color = Some(color.to_string());
-            return Ok(());
-        }
-        Err(format!("Invalid color: {}", color))
+            Some(color.to_string())
+        } else {
+            return Err(format!("Invalid color: {}", color));
+        };
+        self.push_diff(Diff::SetSheetColor {
+            sheet,
+            old_value,
+            new_value: new_value.clone(),
+        });
+        self.workbook.worksheet_mut(sheet)?.color = new_value;
+        Ok(())
     }
 
-    fn get_cell_value(&self, cell: &Cell, cell_reference: CellReference) -> CalcResult {
+    pub(crate) fn get_cell_value(&self, cell: &Cell, cell_reference: CellReference) -> CalcResult {
         use Cell::*;
         match cell {
             EmptyCell { .. } => CalcResult::EmptyCell,
@@ -681,6 +771,34 @@ impl Model {
                     }
                 }
             }
+            ArrayFormula { v, .. } => match v {
+                FormulaValue::Unevaluated => CalcResult::Error {
+                    error: Error::ERROR,
+                    origin: cell_reference,
+                    message: "Unevaluated formula".to_string(),
+                },
+                FormulaValue::Boolean { v } => CalcResult::Boolean(*v),
+                FormulaValue::Number { v } => CalcResult::Number(*v),
+                FormulaValue::String { v } => CalcResult::String(v.clone()),
+                FormulaValue::Error { ei, o, m } => {
+                    if let Some(cell_reference) = self.parse_reference(o) {
+                        CalcResult::new_error(ei.clone(), cell_reference, m.clone())
+                    } else {
+                        CalcResult::Error {
+                            error: ei.clone(),
+                            origin: cell_reference,
+                            message: ei.to_localized_error_string(&self.language),
+                        }
+                    }
+                }
+            },
+            SpillBoolean { v, .. } => CalcResult::Boolean(*v),
+            SpillNumber { v, .. } => CalcResult::Number(*v),
+            SpillString { v, .. } => CalcResult::String(v.clone()),
+            SpillError { ei, .. } => {
+                let message = ei.to_localized_error_string(&self.language);
+                CalcResult::new_error(ei.clone(), cell_reference, message)
+            }
         }
     }
 
@@ -715,6 +833,9 @@ impl Model {
                 );
                 match self.cells.get(&key) {
                     Some(CellState::Evaluating) => {
+                        if self.workbook.settings.iterative_calculation.enabled {
+                            return self.previous_iteration_value(cell, cell_reference);
+                        }
                         return CalcResult::new_error(
                             Error::CIRC,
                             cell_reference,
@@ -729,14 +850,46 @@ impl Model {
                         self.cells.insert(key, CellState::Evaluating);
                     }
                 }
+                let array_formula_size = cell.get_array_formula_size();
                 let node = &self.parsed_formulas[cell_reference.sheet as usize][f as usize].clone();
-                let result = self.evaluate_node_in_context(node, cell_reference);
+                let array_context =
+                    std::mem::replace(&mut self.array_context, array_formula_size.is_some());
+                // LET and LAMBDA bindings are not visible in referenced cells
+                let locals = std::mem::take(&mut self.locals);
+                let evaluating_names = std::mem::take(&mut self.evaluating_names);
+                let result = match array_formula_size {
+                    Some((rows, columns)) => {
+                        self.evaluate_array_formula(cell_reference, node, rows, columns)
+                    }
+                    None => match self.evaluate_node_in_context(node, cell_reference) {
+                        CalcResult::Array(array) => self.spill_array(cell_reference, array),
+                        result => {
+                            self.clear_spill(cell_reference);
+                            result
+                        }
+                    },
+                };
+                self.array_context = array_context;
+                self.locals = locals;
+                self.evaluating_names = evaluating_names;
                 self.set_cell_value(cell_reference, &result);
                 // mark cell as evaluated
                 self.cells.insert(key, CellState::Evaluated);
                 result
             }
-            None => self.get_cell_value(cell, cell_reference),
+            None => {
+                if let Some((row, column)) =
+                    cell.get_spill_anchor(cell_reference.row, cell_reference.column)
+                {
+                    let anchor = CellReference {
+                        sheet: cell_reference.sheet,
+                        row,
+                        column,
+                    };
+                    return self.evaluate_spilled_cell(cell_reference, anchor);
+                }
+                self.get_cell_value(cell, cell_reference)
+            }
         }
     }
 
@@ -793,6 +946,16 @@ impl Model {
             parsed_defined_names: HashMap::new(),
             parser,
             cells,
+            dependency_graph: None,
+            spill_areas: HashMap::new(),
+            array_context: false,
+            locals: Vec::new(),
+            lambda_depth: 0,
+            evaluating_names: Vec::new(),
+            history: History::default(),
+            evaluation_trace: None,
+            user_functions: HashMap::new(),
+            environment: Arc::new(default_environment()),
             language,
             locale,
             tz,
@@ -992,52 +1155,75 @@ impl Model {
     /// Updates the value of a cell with some text
     /// It does not change the style unless needs to add "quoting"
     pub fn update_cell_with_text(&mut self, sheet: u32, row: i32, column: i32, value: &str) {
-        let style_index = self.get_cell_style_index(sheet, row, column);
-        let new_style_index;
-        if common::value_needs_quoting(value, &self.language) {
-            new_style_index = self
-                .workbook
-                .styles
-                .get_style_with_quote_prefix(style_index);
-        } else if self.workbook.styles.style_is_quote_prefix(style_index) {
-            new_style_index = self
-                .workbook
-                .styles
-                .get_style_without_quote_prefix(style_index);
-        } else {
-            new_style_index = style_index;
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return;
         }
-        self.set_cell_with_string(sheet, row, column, value, new_style_index);
+        self.transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            let style_index = model.get_cell_style_index(sheet, row, column);
+            let new_style_index;
+            if common::value_needs_quoting(value, &model.language) {
+                new_style_index = model
+                    .workbook
+                    .styles
+                    .get_style_with_quote_prefix(style_index);
+            } else if model.workbook.styles.style_is_quote_prefix(style_index) {
+                new_style_index = model
+                    .workbook
+                    .styles
+                    .get_style_without_quote_prefix(style_index);
+            } else {
+                new_style_index = style_index;
+            }
+            model.set_cell_with_string(sheet, row, column, value, new_style_index);
+        })
     }
 
     /// Updates the value of a cell with a boolean value
     /// It does not change the style
     pub fn update_cell_with_bool(&mut self, sheet: u32, row: i32, column: i32, value: bool) {
-        let style_index = self.get_cell_style_index(sheet, row, column);
-        let new_style_index = if self.workbook.styles.style_is_quote_prefix(style_index) {
-            self.workbook
-                .styles
-                .get_style_without_quote_prefix(style_index)
-        } else {
-            style_index
-        };
-        let worksheet = &mut self.workbook.worksheets[sheet as usize];
-        worksheet.set_cell_with_boolean(row, column, value, new_style_index);
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return;
+        }
+        self.transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            let style_index = model.get_cell_style_index(sheet, row, column);
+            let new_style_index = if model.workbook.styles.style_is_quote_prefix(style_index) {
+                model
+                    .workbook
+                    .styles
+                    .get_style_without_quote_prefix(style_index)
+            } else {
+                style_index
+            };
+            let worksheet = &mut model.workbook.worksheets[sheet as usize];
+            worksheet.set_cell_with_boolean(row, column, value, new_style_index);
+        })
     }
 
     /// Updates the value of a cell with a number
     /// It does not change the style
     pub fn update_cell_with_number(&mut self, sheet: u32, row: i32, column: i32, value: f64) {
-        let style_index = self.get_cell_style_index(sheet, row, column);
-        let new_style_index = if self.workbook.styles.style_is_quote_prefix(style_index) {
-            self.workbook
-                .styles
-                .get_style_without_quote_prefix(style_index)
-        } else {
-            style_index
-        };
-        let worksheet = &mut self.workbook.worksheets[sheet as usize];
-        worksheet.set_cell_with_number(row, column, value, new_style_index);
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return;
+        }
+        self.transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            let style_index = model.get_cell_style_index(sheet, row, column);
+            let new_style_index = if model.workbook.styles.style_is_quote_prefix(style_index) {
+                model
+                    .workbook
+                    .styles
+                    .get_style_without_quote_prefix(style_index)
+            } else {
+                style_index
+            };
+            let worksheet = &mut model.workbook.worksheets[sheet as usize];
+            worksheet.set_cell_with_number(row, column, value, new_style_index);
+        })
     }
 
     /// Updates the formula of given cell
@@ -1050,18 +1236,25 @@ impl Model {
         column: i32,
         formula: String,
     ) -> Result<(), String> {
-        let mut style_index = self.get_cell_style_index(sheet, row, column);
-        if self.workbook.styles.style_is_quote_prefix(style_index) {
-            style_index = self
-                .workbook
-                .styles
-                .get_style_without_quote_prefix(style_index);
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return Err("Cannot change part of an array formula".to_string());
         }
-        let formula = formula
-            .strip_prefix('=')
-            .ok_or_else(|| format!("\"{formula}\" is not a valid formula"))?;
-        self.set_cell_with_formula(sheet, row, column, formula, style_index)?;
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            let mut style_index = model.get_cell_style_index(sheet, row, column);
+            if model.workbook.styles.style_is_quote_prefix(style_index) {
+                style_index = model
+                    .workbook
+                    .styles
+                    .get_style_without_quote_prefix(style_index);
+            }
+            let formula = formula
+                .strip_prefix('=')
+                .ok_or_else(|| format!("\"{formula}\" is not a valid formula"))?;
+            model.set_cell_with_formula(sheet, row, column, formula, style_index)?;
+            Ok(())
+        })
     }
 
     /// Sets a cell parametrized by (`sheet`, `row`, `column`) with `value`
@@ -1069,88 +1262,154 @@ impl Model {
     /// If you enter a currency `$100` it will set as a number and update the style
     /// Note that for currencies/percentage there is only one possible style
     /// The value is always a string, so we need to try to cast it into numbers/booleans/errors
+    /// Cells in the block of an array formula, other than its anchor, cannot be changed and the
+    /// input is ignored.
     pub fn set_user_input(&mut self, sheet: u32, row: i32, column: i32, value: String) {
-        // If value starts with "'" then we force the style to be quote_prefix
-        let style_index = self.get_cell_style_index(sheet, row, column);
-        if let Some(new_value) = value.strip_prefix('\'') {
-            // First check if it needs quoting
-            let new_style = if common::value_needs_quoting(new_value, &self.language) {
-                self.workbook
-                    .styles
-                    .get_style_with_quote_prefix(style_index)
-            } else {
-                style_index
-            };
-            self.set_cell_with_string(sheet, row, column, new_value, new_style);
-        } else {
-            let mut new_style_index = style_index;
-            if self.workbook.styles.style_is_quote_prefix(style_index) {
-                new_style_index = self
-                    .workbook
-                    .styles
-                    .get_style_without_quote_prefix(style_index);
-            }
-            if let Some(formula) = value.strip_prefix('=') {
-                let formula_index = self
-                    .set_cell_with_formula(sheet, row, column, formula, new_style_index)
-                    .expect("could not set the cell formula");
-                // Update the style if needed
-                let cell = CellReference { sheet, row, column };
-                let parsed_formula = &self.parsed_formulas[sheet as usize][formula_index as usize];
-                if let Some(units) = self.compute_node_units(parsed_formula, &cell) {
-                    let new_style_index = self
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return;
+        }
+        self.set_cell_user_input(sheet, row, column, value);
+    }
+
+    /// Like [`Model::set_user_input`] without the array formula check, for cells being moved
+    pub(crate) fn set_cell_user_input(&mut self, sheet: u32, row: i32, column: i32, value: String) {
+        self.transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            // If value starts with "'" then we force the style to be quote_prefix
+            let style_index = model.get_cell_style_index(sheet, row, column);
+            if let Some(new_value) = value.strip_prefix('\'') {
+                // First check if it needs quoting
+                let new_style = if common::value_needs_quoting(new_value, &model.language) {
+                    model
                         .workbook
                         .styles
-                        .get_style_with_format(new_style_index, &units.get_num_fmt());
-                    let style = self.workbook.styles.get_style(new_style_index);
-                    self.set_cell_style(sheet, row, column, &style)
-                        .expect("Failed setting the style");
-                }
+                        .get_style_with_quote_prefix(style_index)
+                } else {
+                    style_index
+                };
+                model.set_cell_with_string(sheet, row, column, new_value, new_style);
             } else {
-                let worksheets = &mut self.workbook.worksheets;
-                let worksheet = &mut worksheets[sheet as usize];
-
-                // The list of currencies is '$', '€' and the local currency
-                let mut currencies = vec!["$", "€"];
-                let currency = &self.locale.currency.symbol;
-                if !currencies.iter().any(|e| e == currency) {
-                    currencies.push(currency);
+                let mut new_style_index = style_index;
+                if model.workbook.styles.style_is_quote_prefix(style_index) {
+                    new_style_index = model
+                        .workbook
+                        .styles
+                        .get_style_without_quote_prefix(style_index);
                 }
-                //  We try to parse as number
-                if let Ok((v, number_format)) = parse_formatted_number(&value, &currencies) {
-                    if let Some(num_fmt) = number_format {
-                        // Should not apply the format in the following cases:
-                        // - we assign a date to already date-formatted cell
-                        let should_apply_format = !(is_likely_date_number_format(
-                            &self.workbook.styles.get_style(new_style_index).num_fmt,
-                        ) && is_likely_date_number_format(&num_fmt));
-                        if should_apply_format {
-                            new_style_index = self
-                                .workbook
-                                .styles
-                                .get_style_with_format(new_style_index, &num_fmt);
+                if let Some(formula) = value.strip_prefix('=') {
+                    let formula_index = model
+                        .set_cell_with_formula(sheet, row, column, formula, new_style_index)
+                        .expect("could not set the cell formula");
+                    // Update the style if needed
+                    let cell = CellReference { sheet, row, column };
+                    let parsed_formula =
+                        &model.parsed_formulas[sheet as usize][formula_index as usize];
+                    if let Some(units) = model.compute_node_units(parsed_formula, &cell) {
+                        let new_style_index = model
+                            .workbook
+                            .styles
+                            .get_style_with_format(new_style_index, &units.get_num_fmt());
+                        let style = model.workbook.styles.get_style(new_style_index);
+                        model
+                            .set_cell_style(sheet, row, column, &style)
+                            .expect("Failed setting the style");
+                    }
+                } else {
+                    let worksheets = &mut model.workbook.worksheets;
+                    let worksheet = &mut worksheets[sheet as usize];
+
+                    // The list of currencies is '$', '€' and the local currency
+                    let mut currencies = vec!["$", "€"];
+                    let currency = &model.locale.currency.symbol;
+                    if !currencies.iter().any(|e| e == currency) {
+                        currencies.push(currency);
+                    }
+                    //  We try to parse as number
+                    let date1904 = model.workbook.settings.date1904;
+                    if let Ok((v, number_format)) =
+                        parse_formatted_number(&value, &currencies, &model.locale, date1904)
+                    {
+                        if let Some(num_fmt) = number_format {
+                            // Should not apply the format in the following cases:
+                            // - we assign a date to already date-formatted cell
+                            let should_apply_format =
+                                !(is_likely_date_number_format(
+                                    &model.workbook.styles.get_style(new_style_index).num_fmt,
+                                ) && is_likely_date_number_format(&num_fmt));
+                            if should_apply_format {
+                                new_style_index = model
+                                    .workbook
+                                    .styles
+                                    .get_style_with_format(new_style_index, &num_fmt);
+                            }
                         }
+                        worksheet.set_cell_with_number(row, column, v, new_style_index);
+                        return;
                     }
-                    worksheet.set_cell_with_number(row, column, v, new_style_index);
-                    return;
-                }
-                // We try to parse as boolean
-                if let Ok(v) = value.to_lowercase().parse::<bool>() {
-                    worksheet.set_cell_with_boolean(row, column, v, new_style_index);
-                    return;
-                }
-                // Check is it is error value
-                let upper = value.to_uppercase();
-                match get_error_by_name(&upper, &self.language) {
-                    Some(error) => {
-                        worksheet.set_cell_with_error(row, column, error, new_style_index);
+                    // We try to parse as boolean
+                    if let Ok(v) = value.to_lowercase().parse::<bool>() {
+                        worksheet.set_cell_with_boolean(row, column, v, new_style_index);
+                        return;
                     }
-                    None => {
-                        self.set_cell_with_string(sheet, row, column, &value, new_style_index);
+                    // Check is it is error value
+                    let upper = value.to_uppercase();
+                    match get_error_by_name(&upper, &model.language) {
+                        Some(error) => {
+                            worksheet.set_cell_with_error(row, column, error, new_style_index);
+                        }
+                        None => {
+                            model.set_cell_with_string(sheet, row, column, &value, new_style_index);
+                        }
                     }
                 }
             }
-        }
+        })
+    }
+
+    /// Sets a legacy array formula (Ctrl+Shift+Enter) in the block of `rows` x `columns` cells
+    /// with the top left cell at (`row`, `column`). The formula is evaluated as an array and the
+    /// result is written in the whole block.
+    pub fn set_array_formula(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        rows: i32,
+        columns: i32,
+        formula: &str,
+    ) -> Result<(), String> {
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            model.workbook.worksheet(sheet)?;
+            if rows < 1
+                || columns < 1
+                || !(1..=constants::LAST_ROW).contains(&row)
+                || !is_valid_column_number(column)
+                || row + rows - 1 > constants::LAST_ROW
+                || !is_valid_column_number(column + columns - 1)
+            {
+                return Err("Invalid array formula range".to_string());
+            }
+            let formula = formula.strip_prefix('=').unwrap_or(formula);
+            model.mark_cell_dirty(sheet, row, column);
+            let style = model.get_cell_style_index(sheet, row, column);
+            let formula_index = model.set_cell_with_formula(sheet, row, column, formula, style)?;
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_cell_with_array_formula(row, column, formula_index, (rows, columns), style);
+            // The block belongs to the formula right away, even before it is evaluated
+            model.spill_areas.insert(
+                (sheet, row, column),
+                SpillArea {
+                    rows,
+                    columns,
+                    blocked: false,
+                },
+            );
+            Ok(())
+        })
     }
 
     fn set_cell_with_formula(
@@ -1262,7 +1521,8 @@ impl Model {
             .unwrap_or_default();
         let formatted_value =
             cell.formatted_value(&self.workbook.shared_strings, &self.language, |value| {
-                format_number(value, &format, &self.locale).text
+                let date1904 = self.workbook.settings.date1904;
+                format_number_with_date_system(value, &format, &self.locale, date1904).text
             });
         Ok(formatted_value)
     }
@@ -1291,6 +1551,21 @@ impl Model {
 
     /// Evaluates the model with a top-down recursive algorithm
     pub fn evaluate(&mut self) {
+        self.rebuild_spill_areas();
+        // A formula might have read a cell before an array was spilled over it,
+        // we evaluate again until the spill ranges do not change.
+        for _ in 0..MAX_SPILL_PASSES {
+            let layout = self.spill_layout();
+            self.evaluate_all_cells();
+            if self.spill_layout() == layout {
+                break;
+            }
+        }
+        self.iterate_circular_references();
+    }
+
+    /// Evaluates every cell of the workbook once, from scratch
+    pub(crate) fn evaluate_all_cells(&mut self) {
         // clear all computation artifacts
         self.cells.clear();
 
@@ -1308,9 +1583,9 @@ impl Model {
     /// Evaluates the model with a top-down recursive algorithm
     /// Returns a list of errors instead of using #N/IMPL!, #CIRC! or #ERROR! values.
     pub fn evaluate_with_error_check(&mut self) -> Result<(), Vec<String>> {
-        // clear all computation artifacts
-        self.cells.clear();
+        self.evaluate();
 
+        // All cells are evaluated at this point, this just collects the results
         let cells = self.get_all_cells();
 
         let mut errors = Vec::new();
@@ -1350,21 +1625,32 @@ impl Model {
 
     /// Sets cell to empty. Can be used to delete value without affecting style.
     pub fn set_cell_empty(&mut self, sheet: u32, row: i32, column: i32) -> Result<(), String> {
-        let worksheet = self.workbook.worksheet_mut(sheet)?;
-        worksheet.set_cell_empty(row, column);
-        Ok(())
+        if self.is_in_array_formula_block(sheet, row, column) {
+            return Err("Cannot change part of an array formula".to_string());
+        }
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            let worksheet = model.workbook.worksheet_mut(sheet)?;
+            worksheet.set_cell_empty(row, column);
+            model.mark_cell_dirty(sheet, row, column);
+            Ok(())
+        })
     }
 
     /// Deletes a cell by removing it from worksheet data.
     pub fn delete_cell(&mut self, sheet: u32, row: i32, column: i32) -> Result<(), String> {
-        let worksheet = self.workbook.worksheet_mut(sheet)?;
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            let worksheet = model.workbook.worksheet_mut(sheet)?;
 
-        let sheet_data = &mut worksheet.sheet_data;
-        if let Some(row_data) = sheet_data.get_mut(&row) {
-            row_data.remove(&column);
-        }
+            let sheet_data = &mut worksheet.sheet_data;
+            if let Some(row_data) = sheet_data.get_mut(&row) {
+                row_data.remove(&column);
+            }
+            model.mark_cell_dirty(sheet, row, column);
 
-        Ok(())
+            Ok(())
+        })
     }
 
     // FIXME: expect
@@ -1465,6 +1751,14 @@ impl Model {
         };
         Ok(())
     }
+
+    /// Switches between the 1900 and the 1904 (Mac) date systems. Like in Excel the numbers in
+    /// the cells are not changed, so existing dates move by 1462 days.
+    pub fn set_date1904(&mut self, date1904: bool) {
+        self.workbook.settings.date1904 = date1904;
+        // Every date formula needs to be recomputed
+        self.invalidate_dependency_graph();
+    }
 }
 
 #[cfg(test)]
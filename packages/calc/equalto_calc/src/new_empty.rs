@@ -1,19 +1,23 @@
 use chrono::NaiveDateTime;
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{
     calc_result::Range,
+    diffs::{Diff, History},
+    environment::{default_environment, Environment},
     expressions::{
         lexer::LexerMode,
-        parser::stringify::{rename_sheet_in_node, to_rc_format},
-        parser::Parser,
+        parser::stringify::{rename_sheet_in_node, to_rc_format, DisplaceData},
+        parser::{Node, Parser},
         types::CellReferenceRC,
     },
+    functions::Function,
     language::get_language,
     locale::get_locale,
-    model::{get_milliseconds_since_epoch, Model, ParsedDefinedName},
-    types::{Metadata, SheetState, Workbook, WorkbookSettings, Worksheet},
+    model::{Model, ParsedDefinedName},
+    types::{IterativeCalculation, Metadata, SheetState, Workbook, WorkbookSettings, Worksheet},
     utils::ParsedReference,
 };
 
@@ -83,6 +87,17 @@ impl Model {
     pub(crate) fn parse_defined_names(&mut self) {
         let mut parsed_defined_names = HashMap::new();
         for defined_name in &self.workbook.defined_names {
+            let local_sheet_index = if let Some(sheet_id) = defined_name.sheet_id {
+                if let Some(sheet_index) = self.get_sheet_index_by_sheet_id(sheet_id) {
+                    Some(sheet_index)
+                } else {
+                    // TODO: Error: Sheet with given sheet_id not found.
+                    continue;
+                }
+            } else {
+                None
+            };
+
             let parsed_defined_name_formula = if let Ok(reference) =
                 ParsedReference::parse_reference_formula(
                     None,
@@ -99,18 +114,16 @@ impl Model {
                     }
                 }
             } else {
-                ParsedDefinedName::InvalidDefinedNameFormula
-            };
-
-            let local_sheet_index = if let Some(sheet_id) = defined_name.sheet_id {
-                if let Some(sheet_index) = self.get_sheet_index_by_sheet_id(sheet_id) {
-                    Some(sheet_index)
-                } else {
-                    // TODO: Error: Sheet with given sheet_id not found.
-                    continue;
+                // Relative references in the formula are relative to the calling cell
+                let context = Some(self.get_defined_name_context(local_sheet_index));
+                match self.parser.parse(&defined_name.formula, &context) {
+                    node @ Node::FunctionKind {
+                        kind: Function::Lambda,
+                        ..
+                    } => ParsedDefinedName::Lambda(node),
+                    Node::ParseErrorKind { .. } => ParsedDefinedName::InvalidDefinedNameFormula,
+                    node => ParsedDefinedName::Formula(node),
                 }
-            } else {
-                None
             };
 
             parsed_defined_names.insert(
@@ -120,16 +133,18 @@ impl Model {
         }
 
         self.parsed_defined_names = parsed_defined_names;
+        self.invalidate_dependency_graph();
     }
 
     // Reparses all formulas and defined names
-    fn reset_parsed_structures(&mut self) {
+    pub(crate) fn reset_parsed_structures(&mut self) {
         self.parser
             .set_worksheets(self.workbook.get_worksheet_names());
         self.parsed_formulas = vec![];
         self.parse_formulas();
         self.parsed_defined_names = HashMap::new();
         self.parse_defined_names();
+        self.invalidate_dependency_graph();
         self.evaluate();
     }
 
@@ -154,6 +169,10 @@ impl Model {
         // Now we need a sheet_id
         let sheet_id = self.get_new_sheet_id();
         let worksheet = Model::new_empty_worksheet(&sheet_name, sheet_id);
+        self.push_diff(Diff::InsertSheet {
+            sheet: self.workbook.worksheets.len() as u32,
+            worksheet: Box::new(worksheet.clone()),
+        });
         self.workbook.worksheets.push(worksheet);
         self.reset_parsed_structures();
     }
@@ -187,6 +206,10 @@ impl Model {
         if sheet_index as usize > self.workbook.worksheets.len() {
             return Err("Sheet index out of range".to_string());
         }
+        self.push_diff(Diff::InsertSheet {
+            sheet: sheet_index,
+            worksheet: Box::new(worksheet.clone()),
+        });
         self.workbook
             .worksheets
             .insert(sheet_index as usize, worksheet);
@@ -233,6 +256,11 @@ impl Model {
         if sheet_index >= sheet_count {
             return Err("Sheet index out of bounds".to_string());
         }
+        self.push_diff(Diff::RenameSheet {
+            sheet: sheet_index,
+            old_name: worksheets[sheet_index as usize].get_name(),
+            new_name: new_name.to_string(),
+        });
         // Parse all formulas with the old name
         // All internal formulas are R1C1
         self.parser.set_lexer_mode(LexerMode::R1C1);
@@ -261,7 +289,8 @@ impl Model {
         Ok(())
     }
 
-    /// Deletes a sheet by index. Fails if:
+    /// Deletes a sheet by index and replaces all references to that sheet with #REF!.
+    /// Fails if:
     ///   * The sheet does not exists
     ///   * It is the last sheet
     pub fn delete_sheet(&mut self, sheet_index: u32) -> Result<(), String> {
@@ -270,11 +299,23 @@ impl Model {
         if sheet_count == 1 {
             return Err("Cannot delete only sheet".to_string());
         };
-        if sheet_index > sheet_count {
+        if sheet_index >= sheet_count {
             return Err("Sheet index too large".to_string());
         }
-        self.workbook.worksheets.remove(sheet_index as usize);
-        self.reset_parsed_structures();
+        self.transaction(|model| {
+            let displace_data = DisplaceData::DeletedSheet { sheet: sheet_index };
+            for cell in model.get_all_cells() {
+                if cell.index != sheet_index {
+                    model.shift_cell_formula(cell.index, cell.row, cell.column, &displace_data);
+                }
+            }
+            model.push_diff(Diff::DeleteSheet {
+                sheet: sheet_index,
+                worksheet: Box::new(model.workbook.worksheets[sheet_index as usize].clone()),
+            });
+            model.workbook.worksheets.remove(sheet_index as usize);
+            model.reset_parsed_structures();
+        });
         Ok(())
     }
 
@@ -321,7 +362,8 @@ impl Model {
             Err(_) => return Err(format!("Invalid locale: {}", locale_id)),
         };
 
-        let milliseconds = get_milliseconds_since_epoch();
+        let environment = default_environment();
+        let milliseconds = environment.milliseconds_since_epoch();
         let seconds = milliseconds / 1000;
         let dt = match NaiveDateTime::from_timestamp_opt(seconds, 0) {
             Some(s) => s,
@@ -340,6 +382,8 @@ impl Model {
             settings: WorkbookSettings {
                 tz: timezone.to_string(),
                 locale: locale_id.to_string(),
+                iterative_calculation: IterativeCalculation::default(),
+                date1904: false,
             },
             metadata: Metadata {
                 application: APPLICATION.to_string(),
@@ -366,6 +410,16 @@ impl Model {
             parsed_defined_names: HashMap::new(),
             parser,
             cells,
+            dependency_graph: None,
+            spill_areas: HashMap::new(),
+            array_context: false,
+            locals: Vec::new(),
+            lambda_depth: 0,
+            evaluating_names: Vec::new(),
+            history: History::default(),
+            evaluation_trace: None,
+            user_functions: HashMap::new(),
+            environment: Arc::new(environment),
             locale,
             language,
             tz,
@@ -0,0 +1,600 @@
+//! Serializable edits to a model, transformed against concurrent edits to keep several replicas
+//! of a workbook in sync.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    expressions::{
+        parser::{
+            stringify::{rename_sheet_in_node, to_string, to_string_displaced, DisplaceData},
+            Node,
+        },
+        types::CellReferenceRC,
+    },
+    model::{Model, Style},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Operation {
+    SetUserInput {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        value: String,
+    },
+    SetCellEmpty {
+        sheet: u32,
+        row: i32,
+        column: i32,
+    },
+    DeleteCell {
+        sheet: u32,
+        row: i32,
+        column: i32,
+    },
+    SetCellStyle {
+        sheet: u32,
+        row: i32,
+        column: i32,
+        style: Box<Style>,
+    },
+    InsertRows {
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+    },
+    DeleteRows {
+        sheet: u32,
+        row: i32,
+        row_count: i32,
+    },
+    InsertColumns {
+        sheet: u32,
+        column: i32,
+        column_count: i32,
+    },
+    DeleteColumns {
+        sheet: u32,
+        column: i32,
+        column_count: i32,
+    },
+    /// The sheet id is part of the operation so that all replicas use the same one
+    InsertSheet {
+        sheet: u32,
+        name: String,
+        sheet_id: u32,
+    },
+    /// The old name is part of the operation to update the formulas typed concurrently
+    RenameSheet {
+        sheet: u32,
+        old_name: String,
+        new_name: String,
+    },
+    /// The name is part of the operation to update the formulas typed concurrently
+    DeleteSheet {
+        sheet: u32,
+        name: String,
+    },
+    SetSheetColor {
+        sheet: u32,
+        color: String,
+    },
+}
+
+/// Rows or columns inserted (`delta` > 0) or deleted (`delta` < 0) at `index`
+struct Displacement {
+    sheet: u32,
+    is_row: bool,
+    index: i32,
+    delta: i32,
+}
+
+impl Displacement {
+    fn from_operation(operation: &Operation) -> Option<Displacement> {
+        let (sheet, is_row, index, delta) = match *operation {
+            Operation::InsertRows {
+                sheet,
+                row,
+                row_count,
+            } => (sheet, true, row, row_count),
+            Operation::DeleteRows {
+                sheet,
+                row,
+                row_count,
+            } => (sheet, true, row, -row_count),
+            Operation::InsertColumns {
+                sheet,
+                column,
+                column_count,
+            } => (sheet, false, column, column_count),
+            Operation::DeleteColumns {
+                sheet,
+                column,
+                column_count,
+            } => (sheet, false, column, -column_count),
+            _ => return None,
+        };
+        Some(Displacement {
+            sheet,
+            is_row,
+            index,
+            delta,
+        })
+    }
+
+    fn displace_data(&self) -> DisplaceData {
+        if self.is_row {
+            DisplaceData::Row {
+                sheet: self.sheet,
+                row: self.index,
+                delta: self.delta,
+            }
+        } else {
+            DisplaceData::Column {
+                sheet: self.sheet,
+                column: self.index,
+                delta: self.delta,
+            }
+        }
+    }
+
+    /// New position of row (or column) `index`, `None` if it was deleted
+    fn displace_index(&self, index: i32) -> Option<i32> {
+        if index < self.index {
+            Some(index)
+        } else if self.delta < 0 && index < self.index - self.delta {
+            None
+        } else {
+            Some(index + self.delta)
+        }
+    }
+
+    /// New position of an insertion at `index`. Two insertions at the same place are ordered
+    /// by `priority`. An insertion at the first deleted row goes before the deletion, one inside
+    /// deleted rows is dropped and the deletion removes the inserted rows as well (see
+    /// [`Displacement::transform_deletion`]).
+    fn transform_insertion(&self, index: i32, priority: bool) -> Option<i32> {
+        if self.delta > 0 {
+            if index > self.index || (index == self.index && !priority) {
+                Some(index + self.delta)
+            } else {
+                Some(index)
+            }
+        } else if index == self.index {
+            Some(index)
+        } else if index > self.index && index < self.index - self.delta {
+            None
+        } else {
+            self.displace_index(index)
+        }
+    }
+
+    /// New position and size of the deletion of `count` rows at `index`, `None` if all of them
+    /// were already deleted
+    fn transform_deletion(&self, index: i32, count: i32) -> Option<(i32, i32)> {
+        if self.delta > 0 {
+            if self.index <= index {
+                Some((index + self.delta, count))
+            } else if self.index < index + count {
+                Some((index, count + self.delta))
+            } else {
+                Some((index, count))
+            }
+        } else {
+            let start = self.index;
+            let end = self.index - self.delta;
+            let overlap = (end.min(index + count) - start.max(index)).max(0);
+            if overlap == count {
+                return None;
+            }
+            let new_index = if index >= end {
+                index + self.delta
+            } else if index >= start {
+                start
+            } else {
+                index
+            };
+            Some((new_index, count - overlap))
+        }
+    }
+}
+
+/// Operations that change the content of a cell, the one that ranks higher wins when both
+/// change the same cell. Deleting wins over everything so that the result does not depend on
+/// the order they are applied.
+fn content_rank(operation: &Operation) -> Option<u8> {
+    match operation {
+        Operation::SetCellEmpty { .. } => Some(0),
+        Operation::SetUserInput { .. } => Some(1),
+        Operation::DeleteCell { .. } => Some(2),
+        _ => None,
+    }
+}
+
+/// Whether `operation` is still applied after a concurrent `applied` operation on the same cell
+fn wins_cell_conflict(operation: &Operation, applied: &Operation, priority: bool) -> bool {
+    match (content_rank(operation), content_rank(applied)) {
+        (Some(rank), Some(applied_rank)) => {
+            rank > applied_rank || (rank == applied_rank && priority)
+        }
+        _ => match (operation, applied) {
+            (Operation::SetCellStyle { .. }, Operation::SetCellStyle { .. }) => priority,
+            (Operation::SetCellStyle { .. }, Operation::DeleteCell { .. }) => false,
+            _ => true,
+        },
+    }
+}
+
+impl Operation {
+    pub fn sheet(&self) -> u32 {
+        match *self {
+            Operation::SetUserInput { sheet, .. }
+            | Operation::SetCellEmpty { sheet, .. }
+            | Operation::DeleteCell { sheet, .. }
+            | Operation::SetCellStyle { sheet, .. }
+            | Operation::InsertRows { sheet, .. }
+            | Operation::DeleteRows { sheet, .. }
+            | Operation::InsertColumns { sheet, .. }
+            | Operation::DeleteColumns { sheet, .. }
+            | Operation::InsertSheet { sheet, .. }
+            | Operation::RenameSheet { sheet, .. }
+            | Operation::DeleteSheet { sheet, .. }
+            | Operation::SetSheetColor { sheet, .. } => sheet,
+        }
+    }
+
+    fn sheet_mut(&mut self) -> &mut u32 {
+        match self {
+            Operation::SetUserInput { sheet, .. }
+            | Operation::SetCellEmpty { sheet, .. }
+            | Operation::DeleteCell { sheet, .. }
+            | Operation::SetCellStyle { sheet, .. }
+            | Operation::InsertRows { sheet, .. }
+            | Operation::DeleteRows { sheet, .. }
+            | Operation::InsertColumns { sheet, .. }
+            | Operation::DeleteColumns { sheet, .. }
+            | Operation::InsertSheet { sheet, .. }
+            | Operation::RenameSheet { sheet, .. }
+            | Operation::DeleteSheet { sheet, .. }
+            | Operation::SetSheetColor { sheet, .. } => sheet,
+        }
+    }
+
+    /// The cell changed by the operation, if any
+    fn cell_mut(&mut self) -> Option<(u32, &mut i32, &mut i32)> {
+        match self {
+            Operation::SetUserInput {
+                sheet, row, column, ..
+            }
+            | Operation::SetCellEmpty { sheet, row, column }
+            | Operation::DeleteCell { sheet, row, column }
+            | Operation::SetCellStyle {
+                sheet, row, column, ..
+            } => Some((*sheet, row, column)),
+            _ => None,
+        }
+    }
+}
+
+impl Model {
+    /// Applies an operation. Like any other edit, the model needs to be evaluated afterwards.
+    pub fn apply_operation(&mut self, operation: &Operation) -> Result<(), String> {
+        match operation {
+            Operation::SetUserInput {
+                sheet,
+                row,
+                column,
+                value,
+            } => {
+                self.workbook.worksheet(*sheet)?;
+                self.set_user_input(*sheet, *row, *column, value.clone());
+                Ok(())
+            }
+            Operation::SetCellEmpty { sheet, row, column } => {
+                self.set_cell_empty(*sheet, *row, *column)
+            }
+            Operation::DeleteCell { sheet, row, column } => self.delete_cell(*sheet, *row, *column),
+            Operation::SetCellStyle {
+                sheet,
+                row,
+                column,
+                style,
+            } => self.set_cell_style(*sheet, *row, *column, style),
+            Operation::InsertRows {
+                sheet,
+                row,
+                row_count,
+            } => self.insert_rows(*sheet, *row, *row_count),
+            Operation::DeleteRows {
+                sheet,
+                row,
+                row_count,
+            } => self.delete_rows(*sheet, *row, *row_count),
+            Operation::InsertColumns {
+                sheet,
+                column,
+                column_count,
+            } => self.insert_columns(*sheet, *column, *column_count),
+            Operation::DeleteColumns {
+                sheet,
+                column,
+                column_count,
+            } => self.delete_columns(*sheet, *column, *column_count),
+            Operation::InsertSheet {
+                sheet,
+                name,
+                sheet_id,
+            } => self.insert_sheet(name, *sheet, Some(*sheet_id)),
+            Operation::RenameSheet {
+                sheet, new_name, ..
+            } => self.rename_sheet_by_index(*sheet, new_name),
+            Operation::DeleteSheet { sheet, .. } => self.delete_sheet(*sheet),
+            Operation::SetSheetColor { sheet, color } => self.set_sheet_color(*sheet, color),
+        }
+    }
+
+    /// Transforms `operation` so that it can be applied after `applied`, both operations having
+    /// been made concurrently on the same state. Returns `None` if the operation has no effect
+    /// anymore, for instance if it changes a cell in a deleted row.
+    ///
+    /// `priority` decides between two operations that conflict, like two values typed in the
+    /// same cell or two insertions of rows at the same place. The two replicas must pass
+    /// opposite values.
+    ///
+    /// Formulas in `SetUserInput` operations are updated with the same rules used when inserting
+    /// or deleting rows and columns and when renaming or deleting sheets.
+    pub fn transform_operation(
+        &mut self,
+        operation: &Operation,
+        applied: &Operation,
+        priority: bool,
+    ) -> Option<Operation> {
+        let mut operation = operation.clone();
+        if let Some(displacement) = Displacement::from_operation(applied) {
+            return self.transform_displaced(operation, &displacement, priority);
+        }
+        match applied {
+            Operation::InsertSheet { sheet, .. } => {
+                let index = operation.sheet();
+                let shift = match operation {
+                    Operation::InsertSheet { .. } => {
+                        index > *sheet || (index == *sheet && !priority)
+                    }
+                    _ => index >= *sheet,
+                };
+                if shift {
+                    *operation.sheet_mut() += 1;
+                }
+            }
+            Operation::DeleteSheet { sheet, name } => {
+                let index = operation.sheet();
+                match operation {
+                    Operation::InsertSheet { .. } if index == *sheet => {}
+                    _ if index == *sheet => return None,
+                    _ => {}
+                }
+                if index > *sheet {
+                    *operation.sheet_mut() -= 1;
+                }
+                self.delete_sheet_in_input(&mut operation, *sheet, name);
+            }
+            Operation::RenameSheet {
+                sheet,
+                old_name,
+                new_name,
+            } => {
+                match &mut operation {
+                    Operation::RenameSheet {
+                        sheet: index,
+                        old_name: name,
+                        ..
+                    } if *index == *sheet => {
+                        if !priority {
+                            return None;
+                        }
+                        *name = new_name.clone();
+                    }
+                    Operation::DeleteSheet { sheet: index, name } if *index == *sheet => {
+                        *name = new_name.clone();
+                    }
+                    _ => {}
+                }
+                self.rename_sheet_in_input(&mut operation, *sheet, old_name, new_name);
+            }
+            Operation::SetSheetColor { sheet, .. }
+                if !priority
+                    && matches!(operation, Operation::SetSheetColor { sheet: index, .. } if index == *sheet) =>
+            {
+                return None;
+            }
+            Operation::SetUserInput {
+                sheet, row, column, ..
+            }
+            | Operation::SetCellEmpty { sheet, row, column }
+            | Operation::DeleteCell { sheet, row, column }
+            | Operation::SetCellStyle {
+                sheet, row, column, ..
+            } => {
+                let same_cell = match operation.cell_mut() {
+                    Some((index, r, c)) => index == *sheet && *r == *row && *c == *column,
+                    None => false,
+                };
+                if same_cell && !wins_cell_conflict(&operation, applied, priority) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+        Some(operation)
+    }
+
+    fn transform_displaced(
+        &mut self,
+        mut operation: Operation,
+        displacement: &Displacement,
+        priority: bool,
+    ) -> Option<Operation> {
+        let same_sheet = operation.sheet() == displacement.sheet;
+        if let Some((_, row, column)) = operation.cell_mut() {
+            if same_sheet {
+                let index = if displacement.is_row { row } else { column };
+                *index = displacement.displace_index(*index)?;
+            }
+        }
+        match &mut operation {
+            Operation::SetUserInput {
+                sheet,
+                row,
+                column,
+                value,
+            } => {
+                *value = self.displace_input(*sheet, *row, *column, value, displacement);
+            }
+            Operation::InsertRows { row: index, .. } if same_sheet && displacement.is_row => {
+                *index = displacement.transform_insertion(*index, priority)?;
+            }
+            Operation::InsertColumns { column: index, .. }
+                if same_sheet && !displacement.is_row =>
+            {
+                *index = displacement.transform_insertion(*index, priority)?;
+            }
+            Operation::DeleteRows {
+                row: index,
+                row_count: count,
+                ..
+            } if same_sheet && displacement.is_row => {
+                (*index, *count) = displacement.transform_deletion(*index, *count)?;
+            }
+            Operation::DeleteColumns {
+                column: index,
+                column_count: count,
+                ..
+            } if same_sheet && !displacement.is_row => {
+                (*index, *count) = displacement.transform_deletion(*index, *count)?;
+            }
+            _ => {}
+        }
+        Some(operation)
+    }
+
+    /// Updates the references of a formula typed in (`sheet`, `row`, `column`)
+    fn displace_input(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        value: &str,
+        displacement: &Displacement,
+    ) -> String {
+        let formula = match value.strip_prefix('=') {
+            Some(formula) => formula,
+            None => return value.to_string(),
+        };
+        let context = match self.workbook.worksheet(sheet) {
+            Ok(worksheet) => CellReferenceRC {
+                sheet: worksheet.get_name(),
+                row,
+                column,
+            },
+            Err(_) => return value.to_string(),
+        };
+        let node = self.parser.parse(formula, &Some(context.clone()));
+        format!(
+            "={}",
+            to_string_displaced(&node, &context, &displacement.displace_data())
+        )
+    }
+
+    /// Updates the references of a formula in a `SetUserInput` operation to the sheet `renamed`,
+    /// that was called `old_name` when the formula was typed
+    fn rename_sheet_in_input(
+        &mut self,
+        operation: &mut Operation,
+        renamed: u32,
+        old_name: &str,
+        new_name: &str,
+    ) {
+        if let Operation::SetUserInput {
+            sheet,
+            row,
+            column,
+            value,
+        } = operation
+        {
+            let mut sheet_names = self.workbook.get_worksheet_names();
+            if let Some(name) = sheet_names.get_mut(renamed as usize) {
+                *name = old_name.to_string();
+                *value = self.reparse_input(
+                    *sheet,
+                    *row,
+                    *column,
+                    value,
+                    sheet_names,
+                    |node, context| {
+                        rename_sheet_in_node(node, renamed, new_name);
+                        to_string(node, context)
+                    },
+                );
+            }
+        }
+    }
+
+    /// Replaces with #REF! the references of a formula in a `SetUserInput` operation to the
+    /// sheet `deleted`, called `name`
+    fn delete_sheet_in_input(&mut self, operation: &mut Operation, deleted: u32, name: &str) {
+        if let Operation::SetUserInput {
+            sheet,
+            row,
+            column,
+            value,
+        } = operation
+        {
+            let mut sheet_names = self.workbook.get_worksheet_names();
+            if deleted as usize > sheet_names.len() {
+                return;
+            }
+            sheet_names.insert(deleted as usize, name.to_string());
+            // Index of the sheet of the cell before the deletion
+            let sheet = if *sheet >= deleted {
+                *sheet + 1
+            } else {
+                *sheet
+            };
+            let displace_data = DisplaceData::DeletedSheet { sheet: deleted };
+            *value =
+                self.reparse_input(sheet, *row, *column, value, sheet_names, |node, context| {
+                    to_string_displaced(node, context, &displace_data)
+                });
+        }
+    }
+
+    /// Parses a formula typed in (`sheet`, `row`, `column`) when the sheets of the workbook were
+    /// `sheet_names` and writes it back with `write`
+    fn reparse_input(
+        &mut self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        value: &str,
+        sheet_names: Vec<String>,
+        write: impl FnOnce(&mut Node, &CellReferenceRC) -> String,
+    ) -> String {
+        let formula = match value.strip_prefix('=') {
+            Some(formula) => formula,
+            None => return value.to_string(),
+        };
+        let context = match sheet_names.get(sheet as usize) {
+            Some(name) => CellReferenceRC {
+                sheet: name.clone(),
+                row,
+                column,
+            },
+            None => return value.to_string(),
+        };
+        self.parser.set_worksheets(sheet_names);
+        let mut node = self.parser.parse(formula, &Some(context.clone()));
+        self.parser
+            .set_worksheets(self.workbook.get_worksheet_names());
+        format!("={}", write(&mut node, &context))
+    }
+}
@@ -0,0 +1,296 @@
+//! Parallel evaluation of independent regions of the workbook, with the same results as
+//! [`Model::evaluate`].
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    calc_result::CellReference, dependencies::CellKey, model::CellState, spill::SpillArea,
+    types::Cell,
+};
+use crate::{model::Model, spill::MAX_SPILL_PASSES};
+
+/// Disjoint sets of cells
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Components {
+    parent: HashMap<CellKey, CellKey>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Components {
+    fn find(&mut self, key: CellKey) -> CellKey {
+        let mut root = key;
+        while let Some(&parent) = self.parent.get(&root) {
+            if parent == root {
+                break;
+            }
+            root = parent;
+        }
+        let mut current = key;
+        while current != root {
+            current = self.parent.insert(current, root).unwrap_or(root);
+        }
+        root
+    }
+
+    fn union(&mut self, a: CellKey, b: CellKey) {
+        self.parent.entry(a).or_insert(a);
+        self.parent.entry(b).or_insert(b);
+        let root_a = self.find(a);
+        let root_b = self.find(b);
+        if root_a != root_b {
+            self.parent.insert(root_a, root_b);
+        }
+    }
+
+    /// All the cells grouped by component, each group sorted
+    fn groups(mut self) -> Vec<Vec<CellKey>> {
+        let keys: Vec<CellKey> = self.parent.keys().copied().collect();
+        let mut groups: HashMap<CellKey, Vec<CellKey>> = HashMap::new();
+        for key in keys {
+            let root = self.find(key);
+            groups.entry(root).or_default().push(key);
+        }
+        let mut groups: Vec<Vec<CellKey>> = groups.into_values().collect();
+        for group in &mut groups {
+            group.sort_unstable();
+        }
+        groups.sort_unstable();
+        groups
+    }
+}
+
+/// What a thread sends back after evaluating its cells
+#[cfg(not(target_arch = "wasm32"))]
+struct TaskResult {
+    /// The new content of every cell the thread might have written, `None` if the cell was removed
+    cells: Vec<(CellKey, Option<Cell>)>,
+    /// The spill ranges of the anchors evaluated by the thread
+    spill_areas: Vec<(CellKey, Option<SpillArea>)>,
+    /// The formulas evaluated by the thread
+    evaluated: Vec<CellKey>,
+}
+
+impl Model {
+    /// Evaluates the model like [`Model::evaluate`] using up to `threads` threads. The results
+    /// are the same, `std::thread::available_parallelism()` is a sensible number of threads.
+    ///
+    /// Each thread works on a copy of the model, so memory usage grows with the number of
+    /// threads. On wasm32 the evaluation is always sequential.
+    pub fn evaluate_parallel(&mut self, threads: usize) {
+        self.rebuild_spill_areas();
+        for _ in 0..MAX_SPILL_PASSES {
+            let layout = self.spill_layout();
+            self.evaluate_all_cells_parallel(threads);
+            if self.spill_layout() == layout {
+                break;
+            }
+        }
+        self.iterate_circular_references();
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn evaluate_all_cells_parallel(&mut self, _threads: usize) {
+        self.evaluate_all_cells();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn evaluate_all_cells_parallel(&mut self, threads: usize) {
+        let tasks = self.split_in_tasks(threads);
+        if tasks.len() < 2 {
+            self.evaluate_all_cells();
+            return;
+        }
+        self.cells.clear();
+        // The threads do not need the runtime structures that are not used in the evaluation
+        let history = std::mem::take(&mut self.history);
+        let dependency_graph = self.dependency_graph.take();
+        let results: Vec<TaskResult> = std::thread::scope(|scope| {
+            let handles: Vec<_> = tasks
+                .into_iter()
+                .map(|task| {
+                    let model = self.clone();
+                    scope.spawn(move || model.run_task(task))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|error| std::panic::resume_unwind(error))
+                })
+                .collect()
+        });
+        self.history = history;
+        self.dependency_graph = dependency_graph;
+
+        if self.has_spill_conflicts(&results) {
+            self.evaluate_all_cells();
+            return;
+        }
+        for result in results {
+            for ((sheet, row, column), cell) in result.cells {
+                let sheet_data = &mut self.workbook.worksheets[sheet as usize].sheet_data;
+                match cell {
+                    Some(cell) => {
+                        sheet_data.entry(row).or_default().insert(column, cell);
+                    }
+                    None => {
+                        if let Some(row_data) = sheet_data.get_mut(&row) {
+                            row_data.remove(&column);
+                        }
+                    }
+                }
+            }
+            for (anchor, area) in result.spill_areas {
+                match area {
+                    Some(area) => self.spill_areas.insert(anchor, area),
+                    None => self.spill_areas.remove(&anchor),
+                };
+            }
+            for key in result.evaluated {
+                self.cells.insert(key, CellState::Evaluated);
+            }
+        }
+    }
+
+    /// Returns true if a task spilled into cells outside of its previous spill ranges that
+    /// another task also wrote or evaluated. Which anchor gets those cells depends on the
+    /// evaluation order, so the pass has to be sequential.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn has_spill_conflicts(&self, results: &[TaskResult]) -> bool {
+        let mut task_count: HashMap<CellKey, usize> = HashMap::new();
+        for result in results {
+            let touched: HashSet<CellKey> = result
+                .cells
+                .iter()
+                .map(|(key, _)| *key)
+                .chain(result.evaluated.iter().copied())
+                .collect();
+            for key in touched {
+                *task_count.entry(key).or_default() += 1;
+            }
+        }
+        results.iter().any(|result| {
+            result.spill_areas.iter().any(|(anchor, area)| {
+                let previous = self.spill_areas.get(anchor);
+                area.iter().flat_map(|area| area.cells(anchor)).any(|key| {
+                    !previous.is_some_and(|previous| previous.contains(anchor, &key))
+                        && task_count.get(&key).is_some_and(|&count| count > 1)
+                })
+            })
+        })
+    }
+
+    /// Evaluates the cells of a task, sorted like in the sequential evaluation
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_task(mut self, task: Vec<CellKey>) -> TaskResult {
+        for &(sheet, row, column) in &task {
+            self.evaluate_cell(CellReference { sheet, row, column });
+        }
+        let evaluated: Vec<CellKey> = self.cells.keys().copied().collect();
+        let mut written = task;
+        let mut spill_areas = Vec::new();
+        for anchor in &evaluated {
+            let area = self.spill_areas.get(anchor).cloned();
+            if let Some(area) = &area {
+                written.extend(area.cells(anchor));
+            }
+            spill_areas.push((*anchor, area));
+        }
+        let cells = written
+            .into_iter()
+            .map(|key| {
+                let (sheet, row, column) = key;
+                let cell = self.workbook.worksheets[sheet as usize]
+                    .cell(row, column)
+                    .cloned();
+                (key, cell)
+            })
+            .collect();
+        TaskResult {
+            cells,
+            spill_areas,
+            evaluated,
+        }
+    }
+
+    /// Splits the cells of the workbook in up to `threads` groups that can be evaluated
+    /// independently. Returns an empty list if the workbook needs to be evaluated sequentially.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn split_in_tasks(&self, threads: usize) -> Vec<Vec<CellKey>> {
+        if threads < 2 {
+            return Vec::new();
+        }
+        let graph = self.build_dependency_graph();
+        if graph.has_volatile() {
+            return Vec::new();
+        }
+        let mut components = Components::default();
+        for cell in self.get_all_cells() {
+            let key = (cell.index, cell.row, cell.column);
+            components.union(key, key);
+        }
+        for (anchor, area) in &self.spill_areas {
+            for key in area.cells(anchor) {
+                components.union(*anchor, key);
+            }
+        }
+        // Formulas reading the same multi-cell range are joined first so that the cells of
+        // each range are only visited once
+        let mut ranges: HashMap<(CellKey, CellKey), CellKey> = HashMap::new();
+        for (key, precedents) in graph.precedents() {
+            let (sheet, row, column) = *key;
+            if let Some((rows, columns)) = self.workbook.worksheets[sheet as usize]
+                .cell(row, column)
+                .and_then(|cell| cell.get_array_formula_size())
+            {
+                let block = SpillArea {
+                    rows,
+                    columns,
+                    blocked: false,
+                };
+                for cell in block.cells(key) {
+                    components.union(*key, cell);
+                }
+            }
+            for range in precedents {
+                let left = (range.left.sheet, range.left.row, range.left.column);
+                if range.left == range.right {
+                    components.union(*key, left);
+                    continue;
+                }
+                let right = (range.right.sheet, range.right.row, range.right.column);
+                match ranges.get(&(left, right)) {
+                    Some(&other) => components.union(*key, other),
+                    None => {
+                        ranges.insert((left, right), *key);
+                        for cell in self.cells_in_range(range) {
+                            components.union(*key, cell);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut groups = components.groups();
+        if groups.len() < 2 {
+            return Vec::new();
+        }
+        // Biggest components first, each one to the task with fewer cells
+        groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+        let mut tasks: Vec<Vec<CellKey>> = vec![Vec::new(); threads.min(groups.len())];
+        for group in groups {
+            if let Some(task) = tasks.iter_mut().min_by_key(|task| task.len()) {
+                task.extend(group);
+            }
+        }
+        for task in &mut tasks {
+            task.sort_unstable();
+        }
+        tasks
+    }
+}
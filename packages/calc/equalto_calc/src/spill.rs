@@ -0,0 +1,334 @@
+//! Spilling of dynamic array results into the cells below and to the right of the anchor.
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    constants::{LAST_COLUMN, LAST_ROW},
+    dependencies::CellKey,
+    expressions::{parser::Node, token::Error},
+    model::{CellState, Model},
+    types::Cell,
+};
+
+/// Number of times a full evaluation is repeated while the spill ranges keep changing.
+/// A spilled array might cover cells that were read before in the same evaluation.
+pub(crate) const MAX_SPILL_PASSES: usize = 5;
+
+/// The size of the array returned by an anchor formula
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SpillArea {
+    pub(crate) rows: i32,
+    pub(crate) columns: i32,
+    /// The spill range is not empty and the anchor evaluates to #SPILL!
+    pub(crate) blocked: bool,
+}
+
+impl SpillArea {
+    /// Returns true if `key` is in the spill range of the anchor
+    pub(crate) fn contains(&self, anchor: &CellKey, key: &CellKey) -> bool {
+        let (sheet, row, column) = *anchor;
+        key.0 == sheet
+            && row <= key.1
+            && key.1 < row + self.rows
+            && column <= key.2
+            && key.2 < column + self.columns
+    }
+
+    /// All the cells of the spill range but the anchor
+    pub(crate) fn cells(&self, anchor: &CellKey) -> Vec<CellKey> {
+        let (sheet, row, column) = *anchor;
+        let mut cells = Vec::new();
+        for r in row..(row + self.rows).min(LAST_ROW + 1) {
+            for c in column..(column + self.columns).min(LAST_COLUMN + 1) {
+                if r != row || c != column {
+                    cells.push((sheet, r, c));
+                }
+            }
+        }
+        cells
+    }
+}
+
+/// The cell that stores `value` spilled from an anchor `dr` rows above and `dc` columns to
+/// the left
+pub(crate) fn spill_cell(value: &CalcResult, s: i32, dr: i32, dc: i32) -> Cell {
+    match value {
+        CalcResult::Number(v) => {
+            if v.is_nan() || v.is_infinite() {
+                Cell::SpillError {
+                    ei: Error::NUM,
+                    s,
+                    dr,
+                    dc,
+                }
+            } else {
+                Cell::SpillNumber { v: *v, s, dr, dc }
+            }
+        }
+        CalcResult::String(v) => Cell::SpillString {
+            v: v.clone(),
+            s,
+            dr,
+            dc,
+        },
+        CalcResult::Boolean(v) => Cell::SpillBoolean { v: *v, s, dr, dc },
+        CalcResult::Error { error, .. } => Cell::SpillError {
+            ei: error.clone(),
+            s,
+            dr,
+            dc,
+        },
+        CalcResult::EmptyCell | CalcResult::EmptyArg => Cell::SpillNumber { v: 0.0, s, dr, dc },
+        CalcResult::Range { .. } | CalcResult::Array(_) => Cell::SpillError {
+            ei: Error::VALUE,
+            s,
+            dr,
+            dc,
+        },
+    }
+}
+
+impl Model {
+    /// Rebuilds the spill ranges from the spilled values stored in the workbook. Values whose
+    /// anchor is not a formula anymore are removed.
+    pub(crate) fn rebuild_spill_areas(&mut self) {
+        self.spill_areas.clear();
+        let mut orphans = Vec::new();
+        for cell in self.get_all_cells() {
+            let (sheet, row, column) = (cell.index, cell.row, cell.column);
+            let worksheet = &self.workbook.worksheets[sheet as usize];
+            let anchor = match worksheet
+                .cell(row, column)
+                .and_then(|c| c.get_spill_anchor(row, column))
+            {
+                Some(anchor) => anchor,
+                None => continue,
+            };
+            if !worksheet
+                .cell(anchor.0, anchor.1)
+                .is_some_and(|c| c.has_formula())
+            {
+                orphans.push((sheet, row, column));
+                continue;
+            }
+            let area = self
+                .spill_areas
+                .entry((sheet, anchor.0, anchor.1))
+                .or_insert(SpillArea {
+                    rows: 1,
+                    columns: 1,
+                    blocked: false,
+                });
+            area.rows = area.rows.max(row - anchor.0 + 1);
+            area.columns = area.columns.max(column - anchor.1 + 1);
+        }
+        for key in orphans {
+            self.remove_spilled_value(key);
+        }
+    }
+
+    /// The spill ranges that are not blocked, sorted by anchor
+    pub(crate) fn spill_layout(&self) -> Vec<(CellKey, i32, i32)> {
+        let mut layout: Vec<(CellKey, i32, i32)> = self
+            .spill_areas
+            .iter()
+            .filter(|(_, area)| !area.blocked)
+            .map(|(anchor, area)| (*anchor, area.rows, area.columns))
+            .collect();
+        layout.sort_unstable();
+        layout
+    }
+
+    /// Anchors whose spill range (blocked or not) contains the cell
+    pub(crate) fn spill_anchors_containing(&self, key: &CellKey) -> Vec<CellKey> {
+        self.spill_areas
+            .iter()
+            .filter(|(anchor, area)| area.contains(anchor, key))
+            .map(|(anchor, _)| *anchor)
+            .collect()
+    }
+
+    /// Removes a spilled value keeping the style of the cell
+    fn remove_spilled_value(&mut self, key: CellKey) {
+        let (sheet, row, column) = key;
+        if let Some(row_data) = self.workbook.worksheets[sheet as usize]
+            .sheet_data
+            .get_mut(&row)
+        {
+            if let Some(cell) = row_data.get(&column) {
+                let s = cell.get_style();
+                if s == 0 {
+                    row_data.remove(&column);
+                } else {
+                    row_data.insert(column, Cell::EmptyCell { s });
+                }
+            }
+        }
+    }
+
+    /// Removes the values spilled by the formula in `anchor`, if any
+    pub(crate) fn clear_spill(&mut self, anchor: CellReference) {
+        let key = (anchor.sheet, anchor.row, anchor.column);
+        let area = match self.spill_areas.remove(&key) {
+            Some(area) => area,
+            None => return,
+        };
+        for (sheet, row, column) in area.cells(&key) {
+            let is_own_value = self
+                .workbook
+                .worksheet(sheet)
+                .ok()
+                .and_then(|worksheet| worksheet.cell(row, column))
+                .and_then(|cell| cell.get_spill_anchor(row, column))
+                == Some((anchor.row, anchor.column));
+            if is_own_value {
+                self.remove_spilled_value((sheet, row, column));
+            }
+        }
+    }
+
+    /// Returns true if the array spilled from `anchor` can be written in `key`
+    fn is_free_for_spill(&self, anchor: &CellReference, key: &CellKey) -> bool {
+        let (sheet, row, column) = *key;
+        match self.workbook.worksheets[sheet as usize].cell(row, column) {
+            None | Some(Cell::EmptyCell { .. }) => true,
+            Some(cell) => cell.get_spill_anchor(row, column) == Some((anchor.row, anchor.column)),
+        }
+    }
+
+    /// Writes the values of `array` in the spill range of `anchor` and returns the value of the
+    /// anchor cell itself (the top left value or #SPILL!)
+    pub(crate) fn spill_array(
+        &mut self,
+        anchor: CellReference,
+        array: Vec<Vec<CalcResult>>,
+    ) -> CalcResult {
+        let rows = array.len() as i32;
+        let columns = array.first().map_or(0, |row| row.len()) as i32;
+        self.clear_spill(anchor);
+        if rows == 0 || columns == 0 {
+            return CalcResult::new_error(Error::CALC, anchor, "Empty array".to_string());
+        }
+        if rows == 1 && columns == 1 {
+            return array[0][0].clone();
+        }
+        let key = (anchor.sheet, anchor.row, anchor.column);
+        let mut area = SpillArea {
+            rows,
+            columns,
+            blocked: false,
+        };
+        if anchor.row + rows - 1 > LAST_ROW || anchor.column + columns - 1 > LAST_COLUMN {
+            area.blocked = true;
+            self.spill_areas.insert(key, area);
+            return CalcResult::new_error(
+                Error::SPILL,
+                anchor,
+                "Spill range extends beyond the edge of the sheet".to_string(),
+            );
+        }
+        if !area
+            .cells(&key)
+            .iter()
+            .all(|cell| self.is_free_for_spill(&anchor, cell))
+        {
+            area.blocked = true;
+            self.spill_areas.insert(key, area);
+            return CalcResult::new_error(
+                Error::SPILL,
+                anchor,
+                "Spill range isn't blank".to_string(),
+            );
+        }
+        for (dr, values) in array.iter().enumerate() {
+            for (dc, value) in values.iter().enumerate() {
+                if dr == 0 && dc == 0 {
+                    continue;
+                }
+                let row = anchor.row + dr as i32;
+                let column = anchor.column + dc as i32;
+                let s = self.get_cell_style_index(anchor.sheet, row, column);
+                let cell = spill_cell(value, s, dr as i32, dc as i32);
+                self.workbook.worksheets[anchor.sheet as usize]
+                    .sheet_data
+                    .entry(row)
+                    .or_default()
+                    .insert(column, cell);
+            }
+        }
+        self.spill_areas.insert(key, area);
+        array[0][0].clone()
+    }
+
+    /// Evaluates a cell with a value spilled from `anchor`. The anchor is evaluated first, it
+    /// might have changed or removed the value.
+    pub(crate) fn evaluate_spilled_cell(
+        &mut self,
+        cell: CellReference,
+        anchor: CellReference,
+    ) -> CalcResult {
+        match self.cells.get(&(anchor.sheet, anchor.row, anchor.column)) {
+            Some(CellState::Evaluating) => {
+                return CalcResult::new_error(
+                    Error::CIRC,
+                    cell,
+                    "Circular reference detected".to_string(),
+                );
+            }
+            Some(CellState::Evaluated) => {}
+            None => {
+                self.evaluate_cell(anchor);
+            }
+        }
+        match self.workbook.worksheets[cell.sheet as usize].cell(cell.row, cell.column) {
+            Some(c) => self.get_cell_value(c, cell),
+            None => CalcResult::EmptyCell,
+        }
+    }
+
+    /// Evaluates `A1#`, the range with the array spilled by the formula in A1
+    pub(crate) fn get_spill_range(&mut self, reference: &Node, cell: CellReference) -> CalcResult {
+        let anchor = match reference {
+            Node::ReferenceKind {
+                sheet_index,
+                absolute_row,
+                absolute_column,
+                row,
+                column,
+                ..
+            } => CellReference {
+                sheet: *sheet_index,
+                row: if *absolute_row { *row } else { *row + cell.row },
+                column: if *absolute_column {
+                    *column
+                } else {
+                    *column + cell.column
+                },
+            },
+            _ => return CalcResult::new_error(Error::REF, cell, "Wrong reference".to_string()),
+        };
+        let value = self.evaluate_cell(anchor);
+        match self
+            .spill_areas
+            .get(&(anchor.sheet, anchor.row, anchor.column))
+        {
+            Some(SpillArea {
+                rows,
+                columns,
+                blocked: false,
+            }) => CalcResult::Range {
+                left: anchor,
+                right: CellReference {
+                    sheet: anchor.sheet,
+                    row: anchor.row + rows - 1,
+                    column: anchor.column + columns - 1,
+                },
+            },
+            _ => {
+                if value.is_error() {
+                    return value;
+                }
+                CalcResult::new_error(Error::REF, cell, "Not a spill range".to_string())
+            }
+        }
+    }
+}
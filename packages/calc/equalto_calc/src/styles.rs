@@ -218,11 +218,15 @@ impl Model {
         column: i32,
         style: &Style,
     ) -> Result<(), String> {
-        let style_index = self.workbook.styles.get_style_index_or_create(style);
-        self.workbook
-            .worksheet_mut(sheet)?
-            .set_cell_style(row, column, style_index);
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            let style_index = model.workbook.styles.get_style_index_or_create(style);
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_cell_style(row, column, style_index);
+            Ok(())
+        })
     }
 
     pub fn copy_cell_style(
@@ -230,16 +234,20 @@ impl Model {
         source_cell: (u32, i32, i32),
         destination_cell: (u32, i32, i32),
     ) -> Result<(), String> {
-        let source_style_index = self
-            .workbook
-            .worksheet(source_cell.0)?
-            .get_style(source_cell.1, source_cell.2);
+        self.try_transaction(|model| {
+            model.save_cell(destination_cell.0, destination_cell.1, destination_cell.2);
+            let source_style_index = model
+                .workbook
+                .worksheet(source_cell.0)?
+                .get_style(source_cell.1, source_cell.2);
 
-        self.workbook
-            .worksheet_mut(destination_cell.0)?
-            .set_cell_style(destination_cell.1, destination_cell.2, source_style_index);
+            model
+                .workbook
+                .worksheet_mut(destination_cell.0)?
+                .set_cell_style(destination_cell.1, destination_cell.2, source_style_index);
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Sets the style "style_name" in cell
@@ -250,17 +258,27 @@ impl Model {
         column: i32,
         style_name: &str,
     ) -> Result<(), String> {
-        let style_index = self.workbook.styles.get_style_index_by_name(style_name)?;
-        self.workbook
-            .worksheet_mut(sheet)?
-            .set_cell_style(row, column, style_index);
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_cell(sheet, row, column);
+            let style_index = model.workbook.styles.get_style_index_by_name(style_name)?;
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_cell_style(row, column, style_index);
+            Ok(())
+        })
     }
 
     pub fn set_sheet_style(&mut self, sheet: u32, style_name: &str) -> Result<(), String> {
-        let style_index = self.workbook.styles.get_style_index_by_name(style_name)?;
-        self.workbook.worksheet_mut(sheet)?.set_style(style_index)?;
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_columns(sheet);
+            let style_index = model.workbook.styles.get_style_index_by_name(style_name)?;
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_style(style_index)?;
+            Ok(())
+        })
     }
 
     pub fn set_sheet_row_style(
@@ -269,11 +287,15 @@ impl Model {
         row: i32,
         style_name: &str,
     ) -> Result<(), String> {
-        let style_index = self.workbook.styles.get_style_index_by_name(style_name)?;
-        self.workbook
-            .worksheet_mut(sheet)?
-            .set_row_style(row, style_index)?;
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_rows(sheet);
+            let style_index = model.workbook.styles.get_style_index_by_name(style_name)?;
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_row_style(row, style_index)?;
+            Ok(())
+        })
     }
 
     pub fn set_sheet_column_style(
@@ -282,10 +304,14 @@ impl Model {
         column: i32,
         style_name: &str,
     ) -> Result<(), String> {
-        let style_index = self.workbook.styles.get_style_index_by_name(style_name)?;
-        self.workbook
-            .worksheet_mut(sheet)?
-            .set_column_style(column, style_index)?;
-        Ok(())
+        self.try_transaction(|model| {
+            model.save_columns(sheet);
+            let style_index = model.workbook.styles.get_style_index_by_name(style_name)?;
+            model
+                .workbook
+                .worksheet_mut(sheet)?
+                .set_column_style(column, style_index)?;
+            Ok(())
+        })
     }
 }
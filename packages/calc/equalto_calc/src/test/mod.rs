@@ -1,25 +1,43 @@
 mod test_actions;
+mod test_auditing;
 mod test_binary_search;
+mod test_calendar_functions;
 mod test_cell;
 mod test_circular_references;
 mod test_column_width;
 mod test_criteria;
 mod test_currency;
+mod test_date1904;
 mod test_date_and_time;
+mod test_defined_names;
+mod test_environment;
 mod test_error_propagation;
+mod test_evaluate_incremental;
+mod test_evaluate_parallel;
 mod test_evaluate_with_error_check;
+mod test_evaluation_trace;
+mod test_fn_aggregate;
 mod test_fn_average;
 mod test_fn_averageifs;
 mod test_fn_choose;
+mod test_fn_combinatorics;
 mod test_fn_concatenate;
 mod test_fn_count;
+mod test_fn_descriptive_statistics;
+mod test_fn_distributions;
+mod test_fn_dynamic_arrays;
 mod test_fn_exact;
 mod test_fn_financial;
 mod test_fn_if;
+mod test_fn_lambda;
+mod test_fn_matrix;
 mod test_fn_maxifs;
 mod test_fn_minifs;
 mod test_fn_product;
+mod test_fn_regression;
 mod test_fn_rept;
+mod test_fn_rounding;
+mod test_fn_securities;
 mod test_fn_sum;
 mod test_fn_sumifs;
 mod test_fn_textbefore;
@@ -27,18 +45,23 @@ mod test_fn_textjoin;
 mod test_forward_references;
 mod test_frozen_rows_columns;
 mod test_general;
+mod test_iterative_calculation;
 mod test_math;
 mod test_metadata;
 mod test_model_delete_cell;
 mod test_model_is_empty_cell;
 mod test_model_set_cell_empty;
 mod test_move_formula;
+mod test_operations;
 mod test_quote_prefix;
 mod test_set_user_input;
 mod test_sheet_markup;
 mod test_sheets;
 mod test_styles;
+mod test_time_functions;
 mod test_trigonometric;
+mod test_undo_redo;
+mod test_user_functions;
 mod test_worksheet;
 pub(crate) mod util;
 
@@ -46,6 +69,8 @@ mod engineering;
 mod test_fn_offset;
 mod test_number_format;
 
+mod test_array_formulas;
+mod test_dynamic_arrays;
 mod test_escape_quotes;
 mod test_fn_type;
 mod test_percentage;
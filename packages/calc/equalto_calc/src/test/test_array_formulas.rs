@@ -0,0 +1,211 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::{assert_same_as_full_evaluation, new_empty_model};
+use crate::types::{Cell, FormulaValue};
+
+fn new_model_with_data() -> Model {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("B1", "10");
+    model._set("B2", "20");
+    model._set("B3", "30");
+    model
+}
+
+#[test]
+fn ranges_are_arrays() {
+    let mut model = new_model_with_data();
+    model
+        .set_array_formula(0, 1, 4, 1, 1, "=SUM(A1:A3*B1:B3)")
+        .unwrap();
+    // The same formula without Ctrl+Shift+Enter uses implicit intersection
+    model._set("E1", "=SUM(A1:A3*B1:B3)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"140");
+    assert_eq!(model._get_text("E1"), *"10");
+    assert_eq!(model._get_formula("D1"), *"=SUM(A1:A3*B1:B3)");
+    assert!(matches!(
+        model._get_cell("D1"),
+        Cell::ArrayFormula {
+            r: (1, 1),
+            v: FormulaValue::Number { .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn block_of_cells() {
+    let mut model = new_model_with_data();
+    model.set_array_formula(0, 1, 4, 3, 2, "=A1:B3*2").unwrap();
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"2");
+    assert_eq!(model._get_text("E1"), *"20");
+    assert_eq!(model._get_text("D2"), *"4");
+    assert_eq!(model._get_text("E3"), *"60");
+    assert!(!model._has_formula("E3"));
+}
+
+#[test]
+fn result_fitted_to_the_block() {
+    let mut model = new_model_with_data();
+    // A single value is repeated
+    model.set_array_formula(0, 1, 4, 2, 2, "=A1+A2").unwrap();
+    // A single column is repeated to the right, extra rows are #N/A
+    model.set_array_formula(0, 5, 1, 4, 2, "=A1:A3").unwrap();
+    // A range without operators
+    model.set_array_formula(0, 10, 1, 1, 3, "={1,2}").unwrap();
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"3");
+    assert_eq!(model._get_text("E2"), *"3");
+
+    assert_eq!(model._get_text("A5"), *"1");
+    assert_eq!(model._get_text("B5"), *"1");
+    assert_eq!(model._get_text("B7"), *"3");
+    assert_eq!(model._get_text("A8"), *"#N/A");
+    assert_eq!(model._get_text("B8"), *"#N/A");
+
+    assert_eq!(model._get_text("A10"), *"1");
+    assert_eq!(model._get_text("B10"), *"2");
+    assert_eq!(model._get_text("C10"), *"#N/A");
+}
+
+#[test]
+fn block_overwrites_cells() {
+    let mut model = new_model_with_data();
+    model._set("D2", "x");
+    model.set_array_formula(0, 1, 4, 2, 1, "=A1:A2").unwrap();
+    model.evaluate();
+
+    // Unlike dynamic arrays there is no #SPILL!
+    assert_eq!(model._get_text("D1"), *"1");
+    assert_eq!(model._get_text("D2"), *"2");
+}
+
+#[test]
+fn cells_of_the_block_cannot_be_edited() {
+    let mut model = new_model_with_data();
+    model.set_array_formula(0, 1, 4, 2, 1, "=A1:A2*10").unwrap();
+    // Before and after the first evaluation
+    model.set_user_input(0, 2, 4, "hello".to_string());
+    model.evaluate();
+    assert_eq!(model._get_text("D2"), *"20");
+
+    model.set_user_input(0, 2, 4, "hello".to_string());
+    model.update_cell_with_number(0, 2, 4, 3.0);
+    assert!(model.set_cell_empty(0, 2, 4).is_err());
+    assert!(model
+        .update_cell_with_formula(0, 2, 4, "=1".to_string())
+        .is_err());
+    assert_eq!(model._get_text("D2"), *"20");
+
+    // The anchor can be changed
+    model._set("D1", "5");
+    model.evaluate();
+    assert_eq!(model._get_text("D1"), *"5");
+    model._set("D2", "hello");
+    assert_eq!(model._get_text("D2"), *"hello");
+}
+
+#[test]
+fn referenced_formulas_are_not_arrays() {
+    let mut model = new_model_with_data();
+    model._set("C1", "=A1:A3*2");
+    model.set_array_formula(0, 1, 4, 1, 1, "=C1+1").unwrap();
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"2");
+    assert_eq!(model._get_text("D1"), *"3");
+}
+
+#[test]
+fn dependents_of_the_block() {
+    let mut model = new_model_with_data();
+    model._set("F1", "=D3+1");
+    model
+        .set_array_formula(0, 1, 4, 3, 1, "=B1:B3+A1:A3")
+        .unwrap();
+    model.evaluate();
+
+    assert_eq!(model._get_text("F1"), *"34");
+}
+
+#[test]
+fn resize_and_remove() {
+    let mut model = new_model_with_data();
+    model.set_array_formula(0, 1, 4, 3, 1, "=A1:A3").unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("D3"), *"3");
+
+    model.set_array_formula(0, 1, 4, 2, 1, "=A1:A3").unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("D2"), *"2");
+    assert_eq!(model._get_text("D3"), *"");
+
+    model._set("D1", "5");
+    model.evaluate();
+    assert_eq!(model._get_text("D1"), *"5");
+    assert_eq!(model._get_text("D2"), *"");
+}
+
+#[test]
+fn incremental_evaluation() {
+    let mut model = new_model_with_data();
+    model
+        .set_array_formula(0, 1, 4, 3, 1, "=A1:A3*B1:B3")
+        .unwrap();
+    model._set("F1", "=SUM(D1:D3)");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("F1"), *"140");
+
+    model._set("A3", "4");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("D3"), *"120");
+    assert_eq!(model._get_text("F1"), *"170");
+
+    // Editing a cell of the block is ignored
+    model._set("D2", "7");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("D2"), *"40");
+}
+
+#[test]
+fn invalid_range() {
+    let mut model = new_empty_model();
+    assert_eq!(
+        model.set_array_formula(0, 1, 1, 0, 1, "=1"),
+        Err("Invalid array formula range".to_string())
+    );
+    assert_eq!(
+        model.set_array_formula(0, 1_048_576, 1, 2, 1, "=1"),
+        Err("Invalid array formula range".to_string())
+    );
+    assert!(model.set_array_formula(7, 1, 1, 1, 1, "=1").is_err());
+}
+
+#[test]
+fn save_and_load() {
+    let mut model = new_model_with_data();
+    model
+        .set_array_formula(0, 1, 4, 2, 1, "=A1:A2&\"x\"")
+        .unwrap();
+    model
+        .set_array_formula(0, 1, 5, 1, 1, "=SUM(1/(A1:A3-2))")
+        .unwrap();
+    model.evaluate();
+
+    let mut model = Model::from_json(&model.to_json_str()).unwrap();
+    assert_eq!(model._get_text("D1"), *"1x");
+    assert_eq!(model._get_text("D2"), *"2x");
+    assert_eq!(model._get_text("E1"), *"#DIV/0!");
+
+    model._set("A2", "5");
+    model.evaluate();
+    assert_eq!(model._get_text("D2"), *"5x");
+}
@@ -0,0 +1,192 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::auditing::TraceArrow;
+use crate::expressions::types::{Area, CellReferenceIndex};
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+use crate::types::{Table, TableColumn, TableStyleInfo};
+
+fn arrow(precedent: (u32, i32, i32, i32, i32), dependent: (u32, i32, i32)) -> TraceArrow {
+    let (sheet, row, column, width, height) = precedent;
+    TraceArrow {
+        precedent: Area {
+            sheet,
+            row,
+            column,
+            width,
+            height,
+        },
+        dependent: CellReferenceIndex {
+            sheet: dependent.0,
+            row: dependent.1,
+            column: dependent.2,
+        },
+    }
+}
+
+fn chain_model() -> Model {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("B1", "=A1*2");
+    model._set("C1", "=SUM(A1:A2)+B1");
+    model._set("Other!A1", "=Sheet1!C1+1");
+    model._set("D1", "=Other!A1");
+    model.evaluate();
+    model
+}
+
+#[test]
+fn direct_precedents() {
+    let model = chain_model();
+    assert_eq!(
+        model.get_cell_precedents(0, 1, 3, false),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 2), (0, 1, 3)),
+            arrow((0, 1, 2, 1, 1), (0, 1, 3)),
+        ])
+    );
+    // Not a formula
+    assert_eq!(model.get_cell_precedents(0, 1, 1, false), Ok(vec![]));
+    assert_eq!(
+        model.get_cell_precedents(5, 1, 1, false),
+        Err("Invalid sheet index".to_string())
+    );
+}
+
+#[test]
+fn transitive_precedents() {
+    let model = chain_model();
+    assert_eq!(
+        model.get_cell_precedents(0, 1, 4, true),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 1), (0, 1, 2)),
+            arrow((0, 1, 1, 1, 2), (0, 1, 3)),
+            arrow((0, 1, 2, 1, 1), (0, 1, 3)),
+            // Cross sheet arrows
+            arrow((1, 1, 1, 1, 1), (0, 1, 4)),
+            arrow((0, 1, 3, 1, 1), (1, 1, 1)),
+        ])
+    );
+}
+
+#[test]
+fn direct_and_transitive_dependents() {
+    let model = chain_model();
+    assert_eq!(
+        model.get_cell_dependents(0, 1, 1, false),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 1), (0, 1, 2)),
+            arrow((0, 1, 1, 1, 1), (0, 1, 3)),
+        ])
+    );
+    assert_eq!(
+        model.get_cell_dependents(0, 2, 1, true),
+        Ok(vec![
+            arrow((0, 2, 1, 1, 1), (0, 1, 3)),
+            arrow((1, 1, 1, 1, 1), (0, 1, 4)),
+            arrow((0, 1, 3, 1, 1), (1, 1, 1)),
+        ])
+    );
+    // Nothing reads D1
+    assert_eq!(model.get_cell_dependents(0, 1, 4, true), Ok(vec![]));
+}
+
+#[test]
+fn defined_names() {
+    let mut model = new_empty_model();
+    model._set("A1", "10");
+    model._set("A2", "20");
+    model.new_defined_name("Rate", None, "Sheet1!$A$1").unwrap();
+    model
+        .new_defined_name("Data", None, "Sheet1!$A$1:$A$2")
+        .unwrap();
+    model.new_defined_name("Double", None, "Rate*2").unwrap();
+    model._set("B1", "=Double+SUM(Data)");
+    model.evaluate();
+    assert_eq!(
+        model.get_cell_precedents(0, 1, 2, false),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 1), (0, 1, 2)),
+            arrow((0, 1, 1, 1, 2), (0, 1, 2)),
+        ])
+    );
+    assert_eq!(
+        model.get_cell_dependents(0, 2, 1, false),
+        Ok(vec![arrow((0, 2, 1, 1, 1), (0, 1, 2))])
+    );
+}
+
+#[test]
+fn structured_references() {
+    let mut model = new_empty_model();
+    model.workbook.tables.insert(
+        "Sales".to_string(),
+        Table {
+            name: "Sales".to_string(),
+            display_name: "Sales".to_string(),
+            sheet_name: "Sheet1".to_string(),
+            reference: "A1:B3".to_string(),
+            totals_row_count: 0,
+            header_row_count: 1,
+            header_row_dxf_id: None,
+            data_dxf_id: None,
+            totals_row_dxf_id: None,
+            columns: vec![
+                TableColumn {
+                    id: 1,
+                    name: "Item".to_string(),
+                    ..Default::default()
+                },
+                TableColumn {
+                    id: 2,
+                    name: "Amount".to_string(),
+                    ..Default::default()
+                },
+            ],
+            style_info: TableStyleInfo::default(),
+            has_filters: false,
+        },
+    );
+    let mut model = Model::from_json(&model.to_json_str()).unwrap();
+    model._set("A1", "Item");
+    model._set("B1", "Amount");
+    model._set("B2", "5");
+    model._set("B3", "=B2*2");
+    model._set("D1", "=SUM(Sales[Amount])");
+    model.evaluate();
+    assert_eq!(model._get_text("D1"), *"15");
+    assert_eq!(
+        model.get_cell_precedents(0, 1, 4, true),
+        Ok(vec![
+            arrow((0, 2, 2, 1, 2), (0, 1, 4)),
+            arrow((0, 2, 2, 1, 1), (0, 3, 2)),
+        ])
+    );
+}
+
+#[test]
+fn spilled_values() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "={1;2;3}*A1");
+    model._set("C1", "=B3+1");
+    model.evaluate();
+    assert_eq!(model._get_text("C1"), *"4");
+    // The spilled value is traced to its anchor
+    assert_eq!(
+        model.get_cell_precedents(0, 1, 3, true),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 1), (0, 1, 2)),
+            arrow((0, 3, 2, 1, 1), (0, 1, 3)),
+        ])
+    );
+    assert_eq!(
+        model.get_cell_dependents(0, 1, 1, true),
+        Ok(vec![
+            arrow((0, 1, 1, 1, 1), (0, 1, 2)),
+            arrow((0, 3, 2, 1, 1), (0, 1, 3)),
+        ])
+    );
+}
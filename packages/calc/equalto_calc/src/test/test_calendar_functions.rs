@@ -0,0 +1,282 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+// 1 January 2023 is a Sunday
+
+#[test]
+fn test_fn_networkdays() {
+    let mut model = new_empty_model();
+    model._set("B1", "=DATE(2023,1,2)");
+    model._set("B2", "=DATE(2023,1,16)");
+    // A Saturday, it doesn't count
+    model._set("B3", "=DATE(2023,1,7)");
+
+    model._set("A1", "=NETWORKDAYS(DATE(2023,1,1), DATE(2023,1,31))");
+    model._set("A2", "=NETWORKDAYS(DATE(2023,1,1), DATE(2023,1,31), B1:B5)");
+    model._set("A3", "=NETWORKDAYS(DATE(2023,1,31), DATE(2023,1,1))");
+    model._set("A4", "=NETWORKDAYS(DATE(2023,1,2), DATE(2023,1,2))");
+    model._set(
+        "A5",
+        "=NETWORKDAYS(DATE(2023,1,1), DATE(2023,1,31), DATE(2023,1,2))",
+    );
+    model._set("A6", "=NETWORKDAYS(DATE(2023,1,1))");
+    model._set("A7", "=NETWORKDAYS(DATE(2023,1,1), -1)");
+    model._set(
+        "A8",
+        "=NETWORKDAYS(DATE(2023,1,1), DATE(2023,1,31), \"holiday\")",
+    );
+    model._set("A9", "=NETWORKDAYS(DATE(2023,1,1), DATE(2024,12,31))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"22");
+    assert_eq!(model._get_text("A2"), *"20");
+    assert_eq!(model._get_text("A3"), *"-22");
+    assert_eq!(model._get_text("A4"), *"1");
+    assert_eq!(model._get_text("A5"), *"21");
+    assert_eq!(model._get_text("A6"), *"#ERROR!");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"#VALUE!");
+    assert_eq!(model._get_text("A9"), *"522");
+}
+
+#[test]
+fn test_fn_networkdays_intl() {
+    let mut model = new_empty_model();
+    // Only Sundays
+    model._set(
+        "A1",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), 11)",
+    );
+    // Fridays and Saturdays
+    model._set(
+        "A2",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), 7)",
+    );
+    model._set(
+        "A3",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), \"0000011\")",
+    );
+    model._set("A4", "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31))");
+    model._set(
+        "A5",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), \"1000001\", DATE(2023,1,3))",
+    );
+    model._set(
+        "A6",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), \"1111111\")",
+    );
+    model._set(
+        "A7",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), \"000001\")",
+    );
+    model._set(
+        "A8",
+        "=NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), 8)",
+    );
+    model._set(
+        "A9",
+        "=_xlfn.NETWORKDAYS.INTL(DATE(2023,1,1), DATE(2023,1,31), 1)",
+    );
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"26");
+    assert_eq!(model._get_text("A2"), *"23");
+    assert_eq!(model._get_text("A3"), *"22");
+    assert_eq!(model._get_text("A4"), *"22");
+    assert_eq!(model._get_text("A5"), *"20");
+    assert_eq!(model._get_text("A6"), *"#VALUE!");
+    assert_eq!(model._get_text("A7"), *"#VALUE!");
+    assert_eq!(model._get_text("A8"), *"#NUM!");
+    assert_eq!(model._get_text("A9"), *"22");
+    assert_eq!(
+        model._get_formula("A9"),
+        *"=NETWORKDAYS.INTL(DATE(2023,1,1),DATE(2023,1,31),1)"
+    );
+}
+
+#[test]
+fn test_fn_workday() {
+    let mut model = new_empty_model();
+    model._set("B1", "=DATE(2023,1,9)");
+
+    // The Friday before is followed by the Monday
+    model._set("A1", "=WORKDAY(DATE(2023,1,6), 1)");
+    model._set("A2", "=WORKDAY(DATE(2023,1,6), 5, B1:B3)");
+    model._set("A3", "=WORKDAY(DATE(2023,1,9), -1)");
+    model._set("A4", "=WORKDAY(DATE(2023,1,7), 0)");
+    // Fridays and Saturdays are the weekend
+    model._set("A5", "=WORKDAY.INTL(DATE(2023,1,6), 1, 7)");
+    model._set("A6", "=WORKDAY.INTL(DATE(2023,1,6), 1, \"0000000\")");
+    model._set("A7", "=WORKDAY.INTL(DATE(2023,1,6), 1, \"1111111\")");
+    model._set("A8", "=WORKDAY(1, -10)");
+    model._set("A9", "=WORKDAY(DATE(2023,1,6), 1.9)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"09/01/2023");
+    assert_eq!(model._get_text("A2"), *"16/01/2023");
+    assert_eq!(model._get_text("A3"), *"06/01/2023");
+    assert_eq!(model._get_text("A4"), *"07/01/2023");
+    assert_eq!(model._get_text("A5"), *"08/01/2023");
+    assert_eq!(model._get_text("A6"), *"07/01/2023");
+    assert_eq!(model._get_text("A7"), *"#VALUE!");
+    assert_eq!(model._get_text("A8"), *"#NUM!");
+    assert_eq!(model._get_text("A9"), *"09/01/2023");
+}
+
+#[test]
+fn test_fn_datedif() {
+    let mut model = new_empty_model();
+    model._set("B1", "=DATE(2001,6,1)");
+    model._set("B2", "=DATE(2002,8,15)");
+
+    model._set("A1", "=DATEDIF(B1, B2, \"Y\")");
+    model._set("A2", "=DATEDIF(B1, B2, \"M\")");
+    model._set("A3", "=DATEDIF(B1, B2, \"D\")");
+    model._set("A4", "=DATEDIF(B1, B2, \"MD\")");
+    model._set("A5", "=DATEDIF(B1, B2, \"YM\")");
+    model._set("A6", "=DATEDIF(B1, B2, \"YD\")");
+    model._set("A7", "=DATEDIF(B1, B2, \"y\")");
+    model._set("A8", "=DATEDIF(B2, B1, \"Y\")");
+    model._set("A9", "=DATEDIF(B1, B2, \"W\")");
+    // Like Excel, February is shorter than 31 days
+    model._set("A10", "=DATEDIF(DATE(2015,1,31), DATE(2015,3,1), \"MD\")");
+    model._set("A11", "=DATEDIF(DATE(2000,3,15), DATE(2001,3,14), \"Y\")");
+    model._set("A12", "=DATEDIF(DATE(2000,3,15), DATE(2001,3,14), \"YD\")");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("A2"), *"14");
+    assert_eq!(model._get_text("A3"), *"440");
+    assert_eq!(model._get_text("A4"), *"14");
+    assert_eq!(model._get_text("A5"), *"2");
+    assert_eq!(model._get_text("A6"), *"75");
+    assert_eq!(model._get_text("A7"), *"1");
+    assert_eq!(model._get_text("A8"), *"#NUM!");
+    assert_eq!(model._get_text("A9"), *"#NUM!");
+    assert_eq!(model._get_text("A10"), *"-2");
+    assert_eq!(model._get_text("A11"), *"0");
+    assert_eq!(model._get_text("A12"), *"364");
+}
+
+#[test]
+fn test_fn_weekday() {
+    let mut model = new_empty_model();
+    model._set("A1", "=WEEKDAY(DATE(2023,1,1))");
+    model._set("A2", "=WEEKDAY(DATE(2023,1,1), 2)");
+    model._set("A3", "=WEEKDAY(DATE(2023,1,1), 3)");
+    model._set("A4", "=WEEKDAY(DATE(2023,1,1), 11)");
+    model._set("A5", "=WEEKDAY(DATE(2023,1,1), 12)");
+    model._set("A6", "=WEEKDAY(DATE(2023,1,1), 17)");
+    model._set("A7", "=WEEKDAY(DATE(2023,1,1), 4)");
+    model._set("A8", "=WEEKDAY(0)");
+    model._set("A9", "=WEEKDAY(-1)");
+    model._set("A10", "=WEEKDAY()");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("A2"), *"7");
+    assert_eq!(model._get_text("A3"), *"6");
+    assert_eq!(model._get_text("A4"), *"7");
+    assert_eq!(model._get_text("A5"), *"6");
+    assert_eq!(model._get_text("A6"), *"1");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"7");
+    assert_eq!(model._get_text("A9"), *"#NUM!");
+    assert_eq!(model._get_text("A10"), *"#ERROR!");
+}
+
+#[test]
+fn test_fn_weeknum() {
+    let mut model = new_empty_model();
+    model._set("A1", "=WEEKNUM(DATE(2023,1,1))");
+    model._set("A2", "=WEEKNUM(DATE(2023,1,8))");
+    model._set("A3", "=WEEKNUM(DATE(2023,1,1), 2)");
+    model._set("A4", "=WEEKNUM(DATE(2023,1,2), 2)");
+    model._set("A5", "=WEEKNUM(DATE(2023,12,31))");
+    model._set("A6", "=WEEKNUM(DATE(2023,1,1), 21)");
+    model._set("A7", "=WEEKNUM(DATE(2023,1,1), 3)");
+    model._set("A8", "=ISOWEEKNUM(DATE(2023,1,1))");
+    model._set("A9", "=ISOWEEKNUM(DATE(2023,1,2))");
+    model._set("A10", "=_xlfn.ISOWEEKNUM(DATE(2020,12,31))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("A2"), *"2");
+    assert_eq!(model._get_text("A3"), *"1");
+    assert_eq!(model._get_text("A4"), *"2");
+    assert_eq!(model._get_text("A5"), *"53");
+    assert_eq!(model._get_text("A6"), *"52");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"52");
+    assert_eq!(model._get_text("A9"), *"1");
+    assert_eq!(model._get_text("A10"), *"53");
+}
+
+#[test]
+fn test_fn_yearfrac() {
+    let mut model = new_empty_model();
+    model._set("B1", "=DATE(2012,1,1)");
+    model._set("B2", "=DATE(2012,7,30)");
+
+    model._set("A1", "=YEARFRAC(B1, B2)");
+    model._set("A2", "=YEARFRAC(B1, B2, 1)");
+    model._set("A3", "=YEARFRAC(B1, B2, 2)");
+    model._set("A4", "=YEARFRAC(B1, B2, 3)");
+    model._set("A5", "=YEARFRAC(B1, B2, 4)");
+    model._set("A6", "=YEARFRAC(B1, B2, 5)");
+    model._set("A7", "=YEARFRAC(B2, B1, 3)");
+    model._set("A8", "=YEARFRAC(DATE(2011,1,1), DATE(2013,1,1), 1)");
+    model._set("A9", "=YEARFRAC(DATE(2011,2,28), DATE(2012,2,29))");
+    model._set("A10", "=YEARFRAC(DATE(2011,1,31), DATE(2011,3,31))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"0.580555556");
+    assert_eq!(model._get_text("A2"), *"0.576502732");
+    assert_eq!(model._get_text("A3"), *"0.586111111");
+    assert_eq!(model._get_text("A4"), *"0.578082192");
+    assert_eq!(model._get_text("A5"), *"0.580555556");
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"0.578082192");
+    assert_eq!(model._get_text("A8"), *"2.000912409");
+    assert_eq!(model._get_text("A9"), *"1");
+    assert_eq!(model._get_text("A10"), *"0.166666667");
+}
+
+#[test]
+fn test_fn_days360() {
+    let mut model = new_empty_model();
+    model._set("A1", "=DAYS360(DATE(2011,1,30), DATE(2011,12,31))");
+    model._set("A2", "=DAYS360(DATE(2011,1,1), DATE(2011,1,31))");
+    model._set("A3", "=DAYS360(DATE(2011,1,1), DATE(2011,1,31), TRUE)");
+    model._set("A4", "=DAYS360(DATE(2011,2,28), DATE(2011,3,31))");
+    model._set("A5", "=DAYS360(DATE(2011,2,28), DATE(2011,3,31), TRUE)");
+    model._set("A6", "=DAYS360(DATE(2011,12,31), DATE(2011,1,30))");
+    model._set("A7", "=DAYS360(DATE(2011,1,1))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"330");
+    assert_eq!(model._get_text("A2"), *"30");
+    assert_eq!(model._get_text("A3"), *"29");
+    assert_eq!(model._get_text("A4"), *"30");
+    assert_eq!(model._get_text("A5"), *"32");
+    assert_eq!(model._get_text("A6"), *"-330");
+    assert_eq!(model._get_text("A7"), *"#ERROR!");
+}
+
+#[test]
+fn test_calendar_functions_date1904() {
+    let mut model = new_empty_model();
+    model.set_date1904(true);
+    // 1 January 1904 was a Friday
+    model._set("A1", "=WEEKDAY(0)");
+    model._set("A2", "=NETWORKDAYS(0, 6)");
+    model._set("A3", "=WORKDAY(0, 1)");
+    model._set("A4", "=DATEDIF(0, DATE(1905,3,1), \"M\")");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"6");
+    assert_eq!(model._get_text("A2"), *"5");
+    assert_eq!(model._get_text("A3"), *"04/01/1904");
+    assert_eq!(model._get_text("A4"), *"14");
+}
@@ -0,0 +1,121 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+
+fn new_1904_model() -> Model {
+    let mut model = new_empty_model();
+    model.set_date1904(true);
+    model
+}
+
+#[test]
+fn date_functions() {
+    let mut model = new_1904_model();
+    model._set("A1", "=DATE(2023, 1, 9)");
+    model._set("A2", "=DATE(1904, 1, 1)");
+    model._set("A3", "=YEAR(0)");
+    model._set("A4", "=MONTH(0)");
+    model._set("A5", "=DAY(0)");
+    model._set("A6", "=EDATE(A1, 1)");
+    model._set("A7", "=EOMONTH(A1, 1)");
+    model._set("A8", "=TODAY()");
+    model._set("A9", "=VALUE(\"2023-01-09\")");
+    model._set("A10", "=TEXT(A1, \"yyyy-mm-dd\")");
+    model.evaluate();
+
+    // 44935 in the 1900 date system
+    assert_eq!(model._get_text("A1"), *"09/01/2023");
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(43473.0.into()));
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A2"), Ok(0.0.into()));
+    assert_eq!(model._get_text("A3"), *"1904");
+    assert_eq!(model._get_text("A4"), *"1");
+    assert_eq!(model._get_text("A5"), *"1");
+    // 9 February 2023 and 28 February 2023
+    assert_eq!(model._get_text("A6"), *"43504");
+    assert_eq!(model._get_text("A7"), *"43523");
+    // 8 November 2022
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A8"), Ok(43411.0.into()));
+    assert_eq!(model._get_text("A9"), *"43473");
+    assert_eq!(model._get_text("A10"), *"2023-01-09");
+}
+
+#[test]
+fn date_out_of_range() {
+    let mut model = new_1904_model();
+    model._set("A1", "=DATE(1903, 1, 1)");
+    model._set("A2", "=DATE(1904, 1, 0)");
+    model._set("A3", "=DATE(9999, 12, 31)");
+    model._set("A4", "=DATE(10000, 1, 1)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#NUM!");
+    assert_eq!(model._get_text("A2"), *"#NUM!");
+    assert_eq!(
+        model.get_cell_value_by_ref("Sheet1!A3"),
+        Ok(2957003.0.into())
+    );
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+}
+
+#[test]
+fn user_input_and_formatting() {
+    let mut model = new_1904_model();
+    model.set_user_input(0, 1, 1, "09/01/2023".to_string());
+    model.set_user_input(0, 2, 1, "0".to_string());
+    model.set_user_input(0, 3, 1, "-1".to_string());
+    model.set_user_input(0, 4, 1, "2957003".to_string());
+    model.set_user_input(0, 5, 1, "2957004".to_string());
+    for row in 2..=5 {
+        model
+            .set_cell_style(0, row, 1, &model.get_style_for_cell(0, 1, 1))
+            .unwrap();
+    }
+    model.evaluate();
+
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(43473.0.into()));
+    assert_eq!(model._get_text("A1"), *"09/01/2023");
+    assert_eq!(model._get_text("A2"), *"01/01/1904");
+    assert_eq!(model._get_text("A3"), *"#VALUE!");
+    assert_eq!(model._get_text("A4"), *"31/12/9999");
+    assert_eq!(model._get_text("A5"), *"#VALUE!");
+}
+
+#[test]
+fn switching_date_system() {
+    let mut model = new_empty_model();
+    model._set("A1", "=DATE(2023, 1, 9)");
+    model._set("A2", "=YEAR(44935)");
+    model.evaluate();
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(44935.0.into()));
+    assert_eq!(model._get_text("A2"), *"2023");
+
+    model.set_date1904(true);
+    model.evaluate();
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(43473.0.into()));
+    assert_eq!(model._get_text("A2"), *"2027");
+}
+
+#[test]
+fn switching_date_system_incremental() {
+    let mut model = new_empty_model();
+    model._set("A1", "=YEAR(1000)");
+    model._set("B1", "=DATE(2000, 1, 1)");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("A1"), *"1902");
+
+    model.set_date1904(true);
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("A1"), *"1906");
+    assert_eq!(model._get_text("B1"), *"01/01/2000");
+}
+
+#[test]
+fn json_round_trip() {
+    let model = new_empty_model();
+    assert!(!model.to_json_str().contains("date1904"));
+
+    let model = new_1904_model();
+    let model = Model::from_json(&model.to_json_str()).unwrap();
+    assert!(model.workbook.settings.date1904);
+}
@@ -0,0 +1,251 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+use crate::types::DefinedName;
+
+fn add_defined_name(model: &mut Model, name: &str, formula: &str, sheet_id: Option<u32>) {
+    model.workbook.defined_names.push(DefinedName {
+        name: name.to_string(),
+        formula: formula.to_string(),
+        sheet_id,
+    });
+    model.parse_defined_names();
+}
+
+#[test]
+fn constants() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "TaxRate", "0.21", None);
+    add_defined_name(&mut model, "Greeting", "\"Hello\"", None);
+    add_defined_name(&mut model, "Broken", "#REF!", None);
+    model._set("A1", "100");
+    model._set("B1", "=A1*TaxRate");
+    model._set("B2", "=Greeting&\" world\"");
+    model._set("B3", "=Broken");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"21");
+    assert_eq!(model._get_text("B2"), *"Hello world");
+    assert_eq!(model._get_text("B3"), *"#REF!");
+}
+
+#[test]
+fn formulas() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "LastRow", "COUNTA(Sheet1!$A:$A)", None);
+    add_defined_name(&mut model, "Total", "SUM(Sheet1!$A$1:$A$10)*TaxRate", None);
+    add_defined_name(&mut model, "TaxRate", "0.5", None);
+    model._set("A1", "2");
+    model._set("A2", "4");
+    model._set("A3", "6");
+    model._set("C1", "=LastRow");
+    model._set("C2", "=Total");
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"3");
+    assert_eq!(model._get_text("C2"), *"6");
+}
+
+#[test]
+fn relative_references_follow_the_calling_cell() {
+    let mut model = new_empty_model();
+    // Relative to A1, so it is the cell to the right of the calling cell
+    add_defined_name(&mut model, "Twice", "Sheet1!B1*2", None);
+    model._set("B1", "1");
+    model._set("B2", "2");
+    model._set("A1", "=Twice");
+    model._set("A2", "=Twice");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"2");
+    assert_eq!(model._get_text("A2"), *"4");
+    assert_eq!(model._get_formula("A2"), *"=Twice");
+}
+
+#[test]
+fn local_names() {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    let sheet_id = model.workbook.worksheets[1].sheet_id;
+    add_defined_name(&mut model, "Rate", "1", None);
+    add_defined_name(&mut model, "Rate", "2", Some(sheet_id));
+    model._set("A1", "=Rate");
+    model._set("Other!A1", "=Rate");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("Other!A1"), *"2");
+}
+
+#[test]
+fn circular_names() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "Loop", "Loop+1", None);
+    add_defined_name(&mut model, "Ping", "Pong", None);
+    add_defined_name(&mut model, "Pong", "Ping*2", None);
+    model._set("A1", "=Loop");
+    model._set("A2", "=Ping");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#CIRC!");
+    assert_eq!(model._get_text("A2"), *"#CIRC!");
+}
+
+#[test]
+fn let_names_are_not_visible_in_defined_names() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "Plus", "x+1", None);
+    model._set("A1", "=LET(x, 1, Plus)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#NAME?");
+}
+
+#[test]
+fn invalid_formula() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "Bad", "SUM(1,", None);
+    model._set("A1", "=Bad");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#ERROR!");
+}
+
+#[test]
+fn incremental_evaluation() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "Total", "SUM(Sheet1!$A$1:$A$3)", None);
+    add_defined_name(&mut model, "Double", "Total*2", None);
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("B1", "=Double");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("B1"), *"6");
+
+    model._set("A3", "3");
+    assert_eq!(model.cells_to_recompute(), [(0, 1, 2), (0, 3, 1)]);
+    model._set("A3", "3");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("B1"), *"12");
+}
+
+#[test]
+fn new_defined_name() {
+    let mut model = new_empty_model();
+    model._set("A1", "=Rate*10");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"#NAME?");
+
+    model.new_defined_name("Rate", None, "=0.5").unwrap();
+    assert_eq!(model._get_text("A1"), *"5");
+    assert_eq!(
+        model.get_defined_name_list(),
+        [("Rate".to_string(), None, "0.5".to_string())]
+    );
+
+    assert_eq!(
+        model.new_defined_name("rate", None, "1"),
+        Err("Defined name already exists: 'rate'".to_string())
+    );
+    assert_eq!(
+        model.new_defined_name("A1", None, "1"),
+        Err("Invalid defined name: 'A1'".to_string())
+    );
+    assert_eq!(
+        model.new_defined_name("Other", Some(3), "1"),
+        Err("Sheet index out of bounds".to_string())
+    );
+    assert!(model.new_defined_name("Other", None, "SUM(1,").is_err());
+    // The same name local to a sheet is fine
+    model.new_defined_name("Rate", Some(0), "2").unwrap();
+    assert_eq!(model._get_text("A1"), *"20");
+}
+
+#[test]
+fn rename_defined_name() {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    model.new_defined_name("Rate", None, "2").unwrap();
+    model.new_defined_name("Rate", Some(1), "3").unwrap();
+    model.new_defined_name("Twice", None, "Rate*2").unwrap();
+    model
+        .new_defined_name("Tax", None, "LAMBDA(x, x*Rate)")
+        .unwrap();
+    model.new_defined_name("Half", Some(0), "Rate/2").unwrap();
+    model.new_defined_name("Double", Some(1), "Rate*2").unwrap();
+    model._set("A1", "=Rate+1");
+    model._set("A2", "=Tax(10)");
+    model._set("A3", "=Twice");
+    model._set("Other!A1", "=Rate+1");
+
+    model
+        .update_defined_name("Rate", None, "Factor", None, "2")
+        .unwrap();
+    assert_eq!(model._get_formula("A1"), *"=Factor+1");
+    assert_eq!(model._get_text("A1"), *"3");
+    assert_eq!(model._get_text("A2"), *"20");
+    assert_eq!(model._get_text("A3"), *"4");
+    // The local name in Other hides the global one
+    assert_eq!(model._get_formula("Other!A1"), *"=Rate+1");
+    assert_eq!(model._get_text("Other!A1"), *"4");
+    assert_eq!(
+        model.get_defined_name_list(),
+        [
+            ("Factor".to_string(), None, "2".to_string()),
+            ("Rate".to_string(), Some(1), "3".to_string()),
+            ("Twice".to_string(), None, "Factor*2".to_string()),
+            ("Tax".to_string(), None, "LAMBDA(x,x*Factor)".to_string()),
+            ("Half".to_string(), Some(0), "Factor/2".to_string()),
+            ("Double".to_string(), Some(1), "Rate*2".to_string()),
+        ]
+    );
+
+    // Renaming a LAMBDA renames the calls
+    model
+        .update_defined_name("Tax", None, "Levy", None, "LAMBDA(x, x*Factor)")
+        .unwrap();
+    assert_eq!(model._get_formula("A2"), *"=Levy(10)");
+    assert_eq!(model._get_text("A2"), *"20");
+
+    assert_eq!(
+        model.update_defined_name("Factor", None, "Twice", None, "2"),
+        Err("Defined name already exists: 'Twice'".to_string())
+    );
+    assert_eq!(
+        model.update_defined_name("Missing", None, "Other", None, "2"),
+        Err("Defined name not found: 'Missing'".to_string())
+    );
+}
+
+#[test]
+fn change_scope_and_formula() {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    model.new_defined_name("Rate", None, "2").unwrap();
+    model._set("A1", "=Rate");
+    model._set("Other!A1", "=Rate");
+
+    model
+        .update_defined_name("Rate", None, "Rate", Some(1), "5")
+        .unwrap();
+    assert_eq!(model._get_text("A1"), *"#NAME?");
+    assert_eq!(model._get_text("Other!A1"), *"5");
+}
+
+#[test]
+fn delete_defined_name() {
+    let mut model = new_empty_model();
+    model.new_defined_name("Rate", None, "2").unwrap();
+    model._set("A1", "=Rate");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"2");
+
+    model.delete_defined_name("RATE", None).unwrap();
+    assert_eq!(model._get_text("A1"), *"#NAME?");
+    assert!(model.get_defined_name_list().is_empty());
+    assert_eq!(
+        model.delete_defined_name("Rate", None),
+        Err("Defined name not found: 'Rate'".to_string())
+    );
+}
@@ -0,0 +1,296 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::{assert_same_as_full_evaluation, new_empty_model};
+use crate::types::Cell;
+
+#[test]
+fn array_literal_spills() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,2,3;4,5,6}");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("C1"), *"3");
+    assert_eq!(model._get_text("A2"), *"4");
+    assert_eq!(model._get_text("C2"), *"6");
+    assert_eq!(model._get_text("D1"), *"");
+    assert_eq!(model._get_text("A3"), *"");
+
+    // Spilled values are not formulas
+    assert_eq!(model._get_formula("A1"), *"={1,2,3;4,5,6}");
+    assert!(!model._has_formula("B1"));
+    assert!(matches!(
+        model._get_cell("C2"),
+        Cell::SpillNumber { dr: 1, dc: 2, .. }
+    ));
+}
+
+#[test]
+fn array_literal_values() {
+    let mut model = new_empty_model();
+    model._set("A1", "={\"a\",TRUE;#N/A,-1.5}");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"a");
+    assert_eq!(model._get_text("B1"), *"TRUE");
+    assert_eq!(model._get_text("A2"), *"#N/A");
+    assert_eq!(model._get_text("B2"), *"-1.5");
+}
+
+#[test]
+fn single_element_array_does_not_spill() {
+    let mut model = new_empty_model();
+    model._set("A1", "={7}");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"7");
+    assert!(model.spill_areas.is_empty());
+}
+
+#[test]
+fn broadcasting() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,2,3}*10");
+    model._set("A2", "={1;2}+{10,20}");
+    model._set("A4", "={1,2,3}+{1,2}");
+    model._set("A5", "=-{1,2}");
+    model._set("A6", "={1,2}&\"x\"");
+    model._set("A7", "={1,2,3}>1");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"10");
+    assert_eq!(model._get_text("B1"), *"20");
+    assert_eq!(model._get_text("C1"), *"30");
+
+    assert_eq!(model._get_text("A2"), *"11");
+    assert_eq!(model._get_text("B2"), *"21");
+    assert_eq!(model._get_text("A3"), *"12");
+    assert_eq!(model._get_text("B3"), *"22");
+
+    // Positions outside of the smaller array are #N/A
+    assert_eq!(model._get_text("A4"), *"2");
+    assert_eq!(model._get_text("B4"), *"4");
+    assert_eq!(model._get_text("C4"), *"#N/A");
+
+    assert_eq!(model._get_text("A5"), *"-1");
+    assert_eq!(model._get_text("B5"), *"-2");
+
+    assert_eq!(model._get_text("A6"), *"1x");
+    assert_eq!(model._get_text("B6"), *"2x");
+
+    assert_eq!(model._get_text("A7"), *"FALSE");
+    assert_eq!(model._get_text("B7"), *"TRUE");
+    assert_eq!(model._get_text("C7"), *"TRUE");
+}
+
+#[test]
+fn ranges_keep_implicit_intersection() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("B2", "=A1:A2*3");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B2"), *"6");
+    assert_eq!(model._get_text("B3"), *"");
+}
+
+#[test]
+fn functions_with_arrays() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SUM({1,2;3,4})");
+    model._set("A2", "=MAX({1,5,3})");
+    model._set("A3", "=COUNT({1,\"a\",TRUE,2})");
+    model._set("A4", "=TYPE({1,2})");
+    model._set("A5", "=SUM({1,2,3}*2)");
+    model._set("A6", "=AND({TRUE,FALSE})");
+    model._set("A7", "=TEXTJOIN(\"-\",TRUE,{\"a\",\"b\"})");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"10");
+    assert_eq!(model._get_text("A2"), *"5");
+    assert_eq!(model._get_text("A3"), *"2");
+    assert_eq!(model._get_text("A4"), *"64");
+    assert_eq!(model._get_text("A5"), *"12");
+    assert_eq!(model._get_text("A6"), *"FALSE");
+    assert_eq!(model._get_text("A7"), *"a-b");
+}
+
+#[test]
+fn lookup_functions_with_arrays() {
+    let mut model = new_empty_model();
+    model._set("A1", "=INDEX({1,2,3},2)");
+    model._set("A2", "=INDEX(SEQUENCE(3),2)");
+    model._set("A3", "=INDEX({1,2;3,4},2,1)");
+    model._set("A4", "=INDEX({1,2,3},4)");
+    model._set("A5", "=COLUMNS({1,2,3})");
+    model._set("A6", "=ROWS({1;2;3;4})");
+    model._set("A7", "=MATCH(2,{1,2,3},0)");
+    model._set("A8", "=MATCH(2.5,{1;2;3})");
+    model._set("A9", "=MATCH(2.5,{3,2,1},-1)");
+    model._set("A10", "=MATCH(5,{1,2,3},0)");
+    model._set("A11", "=VLOOKUP(\"b\",{\"a\",1;\"b\",2},2,FALSE)");
+    model._set("A12", "=VLOOKUP(2.5,{1,\"x\";2,\"y\";3,\"z\"},2)");
+    model._set("A13", "=VLOOKUP(\"b\",{\"a\",1;\"b\",2},3,FALSE)");
+    model._set("A14", "=HLOOKUP(\"b\",{\"a\",\"b\";1,2},2,FALSE)");
+    model._set("A15", "=LOOKUP(2,{1,2,3},{\"x\",\"y\",\"z\"})");
+    model._set("A16", "=XLOOKUP(2,{1;2},{\"x\";\"y\"})");
+    model._set("A17", "=XLOOKUP(3,{1;2},{\"x\";\"y\"},\"none\")");
+    model._set("B1", "={5;6;7}");
+    model._set("A18", "=XLOOKUP(6,B1#,{\"x\";\"y\";\"z\"})");
+    model._set("A19", "=ISREF(B1#)");
+    model._set("A20", "=ISREF({1,2})");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"2");
+    assert_eq!(model._get_text("A2"), *"2");
+    assert_eq!(model._get_text("A3"), *"3");
+    assert_eq!(model._get_text("A4"), *"#REF!");
+    assert_eq!(model._get_text("A5"), *"3");
+    assert_eq!(model._get_text("A6"), *"4");
+    assert_eq!(model._get_text("A7"), *"2");
+    assert_eq!(model._get_text("A8"), *"2");
+    assert_eq!(model._get_text("A9"), *"1");
+    assert_eq!(model._get_text("A10"), *"#N/A");
+    assert_eq!(model._get_text("A11"), *"2");
+    assert_eq!(model._get_text("A12"), *"y");
+    assert_eq!(model._get_text("A13"), *"#REF!");
+    assert_eq!(model._get_text("A14"), *"2");
+    assert_eq!(model._get_text("A15"), *"y");
+    assert_eq!(model._get_text("A16"), *"y");
+    assert_eq!(model._get_text("A17"), *"none");
+    assert_eq!(model._get_text("A18"), *"y");
+    assert_eq!(model._get_text("A19"), *"TRUE");
+    assert_eq!(model._get_text("A20"), *"FALSE");
+}
+
+#[test]
+fn blocked_spill() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,2,3}");
+    model._set("C1", "x");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#SPILL!");
+    assert_eq!(model._get_text("B1"), *"");
+    assert_eq!(model._get_text("C1"), *"x");
+
+    // Clearing the blocking cell lets the array spill
+    model.set_cell_empty(0, 1, 3).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("C1"), *"3");
+}
+
+#[test]
+fn spill_beyond_the_edge_of_the_sheet() {
+    let mut model = new_empty_model();
+    model._set("XFD1", "={1,2}");
+    model.evaluate();
+
+    assert_eq!(model._get_text("XFD1"), *"#SPILL!");
+}
+
+#[test]
+fn spill_shrinks() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,2,3}");
+    model.evaluate();
+    assert_eq!(model._get_text("C1"), *"3");
+
+    model._set("A1", "={1,2}");
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("C1"), *"");
+
+    model._set("A1", "=5");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"5");
+    assert_eq!(model._get_text("B1"), *"");
+}
+
+#[test]
+fn spill_reference() {
+    let mut model = new_empty_model();
+    model._set("B1", "={1;2;3}");
+    model._set("C1", "=SUM(B1#)");
+    model._set("D1", "=B1#*2");
+    model._set("E1", "=ROWS(B1#)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"6");
+    assert_eq!(model._get_text("D1"), *"2");
+    assert_eq!(model._get_text("D2"), *"4");
+    assert_eq!(model._get_text("D3"), *"6");
+    assert_eq!(model._get_text("E1"), *"3");
+}
+
+#[test]
+fn spill_reference_without_spill() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "={1,2}");
+    model._set("B2", "x");
+    model._set("C1", "=A1#");
+    model._set("C2", "=A2#");
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"#REF!");
+    assert_eq!(model._get_text("C2"), *"#SPILL!");
+}
+
+#[test]
+fn reading_spilled_cells() {
+    let mut model = new_empty_model();
+    // The dependent comes before the anchor in evaluation order
+    model._set("A1", "=C2*10");
+    model._set("B1", "={1,2;3,4}");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"40");
+}
+
+#[test]
+fn incremental_evaluation() {
+    let mut model = new_empty_model();
+    model._set("A1", "3");
+    model._set("B1", "={1,2,3}*A1");
+    model._set("A2", "=D1+1");
+    model._set("A3", "=SUM(B1#)");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A2"), *"10");
+    assert_eq!(model._get_text("A3"), *"18");
+
+    model._set("A1", "2");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A2"), *"7");
+    assert_eq!(model._get_text("A3"), *"12");
+
+    // Blocking the spill range
+    model._set("C1", "x");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"#SPILL!");
+    assert_eq!(model._get_text("A2"), *"1");
+
+    model.set_cell_empty(0, 1, 3).unwrap();
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("D1"), *"6");
+}
+
+#[test]
+fn save_and_load() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,\"two\";TRUE,#DIV/0!}");
+    model.evaluate();
+
+    let mut model = Model::from_json(&model.to_json_str()).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("B1"), *"two");
+    assert_eq!(model._get_text("A2"), *"TRUE");
+    assert_eq!(model._get_text("B2"), *"#DIV/0!");
+}
@@ -0,0 +1,74 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::environment::{Environment, FixedEnvironment};
+use crate::test::util::new_empty_model;
+
+#[test]
+fn fixed_environment_is_deterministic() {
+    let first = FixedEnvironment::new(0, 42);
+    let second = FixedEnvironment::new(0, 42);
+    let other = FixedEnvironment::new(0, 43);
+    for _ in 0..100 {
+        let value = first.random();
+        assert!((0.0..1.0).contains(&value));
+        assert_eq!(value, second.random());
+        assert_ne!(value, other.random());
+    }
+}
+
+#[test]
+fn rand_is_reproducible() {
+    let mut model = new_empty_model();
+    model._set("A1", "=RAND()");
+    model._set("A2", "=RANDBETWEEN(1, 6)");
+    model._set("A3", "=RANDBETWEEN(-10, 10)");
+
+    model.set_environment(FixedEnvironment::new(0, 7));
+    model.evaluate();
+    let values = [
+        model._get_text("A1"),
+        model._get_text("A2"),
+        model._get_text("A3"),
+    ];
+    let a1: f64 = values[0].parse().unwrap();
+    assert!((0.0..1.0).contains(&a1));
+    let a2: f64 = values[1].parse().unwrap();
+    assert!((1.0..=6.0).contains(&a2) && a2.fract() == 0.0);
+    let a3: f64 = values[2].parse().unwrap();
+    assert!((-10.0..=10.0).contains(&a3) && a3.fract() == 0.0);
+
+    // A new evaluation draws new numbers
+    model.evaluate();
+    assert_ne!(model._get_text("A1"), values[0]);
+
+    // The same seed gives the same numbers again
+    model.set_environment(FixedEnvironment::new(0, 7));
+    model.evaluate();
+    assert_eq!(
+        [
+            model._get_text("A1"),
+            model._get_text("A2"),
+            model._get_text("A3"),
+        ],
+        values
+    );
+}
+
+#[test]
+fn clock_is_injected() {
+    let mut model = new_empty_model();
+    model._set("A1", "=NOW()");
+    model._set("A2", "=TODAY()");
+    model.evaluate();
+    assert_eq!(
+        model.environment().milliseconds_since_epoch(),
+        1667906008578
+    );
+    assert_eq!(model._get_text("A2"), *"08/11/2022");
+
+    // 1 January 2000 01:00:00 UTC
+    model.set_environment(FixedEnvironment::new(946688400000, 0));
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"36526.041666667");
+    assert_eq!(model._get_text("A2"), *"01/01/2000");
+}
@@ -0,0 +1,167 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::{assert_same_as_full_evaluation, new_empty_model};
+use crate::types::DefinedName;
+
+#[test]
+fn first_call_is_a_full_evaluation() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "=A1+1");
+    model._set("A3", "=SUM(A1:A2)");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A3"), *"3");
+}
+
+#[test]
+fn chain_of_dependents() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "=A1*2");
+    model._set("A3", "=A2*2");
+    model._set("B1", "=A3+A1");
+    model._set("C1", "=7");
+    model.evaluate_incremental();
+
+    model._set("A1", "5");
+    assert_eq!(
+        model.cells_to_recompute(),
+        [(0, 1, 1), (0, 1, 2), (0, 2, 1), (0, 3, 1)]
+    );
+    // cells_to_recompute consumed the edit, repeat it
+    model._set("A1", "5");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"25");
+    assert_eq!(model._get_text("C1"), *"7");
+}
+
+#[test]
+fn range_dependents() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("B1", "=SUM(A:A)");
+    model._set("B2", "=SUM(A1:A2)");
+    model._set("B3", "=SUM(C1:C3)");
+    model.evaluate_incremental();
+
+    model._set("A100", "10");
+    assert_eq!(model.cells_to_recompute(), [(0, 1, 2), (0, 100, 1)]);
+
+    model._set("A2", "4");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"15");
+    assert_eq!(model._get_text("B2"), *"5");
+}
+
+#[test]
+fn new_and_deleted_formulas() {
+    let mut model = new_empty_model();
+    model._set("A1", "3");
+    model._set("A2", "=A1*A1");
+    model.evaluate_incremental();
+
+    // a new formula
+    model._set("A3", "=A2+1");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A3"), *"10");
+
+    // A formula that stops depending on A1
+    model._set("A2", "=100");
+    assert_same_as_full_evaluation(&mut model);
+    model._set("A1", "4");
+    assert_eq!(model.cells_to_recompute(), [(0, 1, 1)]);
+
+    // deleting a precedent
+    model._set("A2", "=A1*A1");
+    model.delete_cell(0, 1, 1).unwrap();
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A3"), *"1");
+}
+
+#[test]
+fn cross_sheet_and_defined_names() {
+    let mut model = new_empty_model();
+    model.add_sheet("Data").unwrap();
+    model.workbook.defined_names.push(DefinedName {
+        name: "rate".to_string(),
+        formula: "Data!$B$1".to_string(),
+        sheet_id: None,
+    });
+    model.parse_defined_names();
+    model._set("Data!A1", "2");
+    model._set("Data!B1", "0.5");
+    model._set("A1", "=Data!A1*10");
+    model._set("A2", "=A1*rate");
+    model.evaluate_incremental();
+
+    model._set("Data!A1", "3");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A1"), *"30");
+
+    model._set("Data!B1", "2");
+    assert_eq!(model.cells_to_recompute(), [(0, 2, 1), (1, 1, 2)]);
+    model._set("Data!B1", "2");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A2"), *"60");
+}
+
+#[test]
+fn volatile_functions() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "=INDIRECT(\"A1\")");
+    model._set("A3", "=OFFSET(A1,0,0)+1");
+    model._set("B1", "=A1+1");
+    model._set("B2", "=5");
+    model.evaluate_incremental();
+
+    model._set("B2", "=6");
+    assert_eq!(
+        model.cells_to_recompute(),
+        [(0, 2, 1), (0, 2, 2), (0, 3, 1)]
+    );
+    model._set("B2", "=6");
+
+    model._set("A1", "7");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A2"), *"7");
+    assert_eq!(model._get_text("A3"), *"8");
+}
+
+#[test]
+fn circular_references() {
+    let mut model = new_empty_model();
+    model._set("A1", "=B1");
+    model._set("B1", "=A1");
+    model._set("C1", "=B1+1");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("C1"), *"#CIRC!");
+
+    // break the cycle
+    model._set("B1", "2");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A1"), *"2");
+    assert_eq!(model._get_text("C1"), *"3");
+}
+
+#[test]
+fn structural_changes() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "=A1+1");
+    model.evaluate_incremental();
+
+    model.insert_rows(0, 1, 2).unwrap();
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_formula("A4"), *"=A3+1");
+
+    model._set("A3", "10");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("A4"), *"11");
+
+    model.rename_sheet("Sheet1", "Main").unwrap();
+    model._set("Main!A3", "20");
+    assert_same_as_full_evaluation(&mut model);
+    assert_eq!(model._get_text("Main!A4"), *"21");
+}
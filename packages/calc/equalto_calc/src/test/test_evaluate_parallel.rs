@@ -0,0 +1,113 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+
+/// Evaluates `model` in parallel and checks the result against a sequential evaluation
+fn assert_same_as_sequential_evaluation(model: &mut Model) {
+    let mut expected = model.clone();
+    expected.evaluate();
+    model.evaluate_parallel(4);
+    assert_eq!(model.workbook, expected.workbook);
+}
+
+#[test]
+fn independent_regions() {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    for row in 1..=20 {
+        model._set(&format!("A{row}"), &row.to_string());
+        model._set(&format!("B{row}"), &format!("=A{row}*2"));
+        model._set(&format!("D{row}"), &format!("={row}/3"));
+        model._set(&format!("Other!A{row}"), &format!("=Sheet1!D{row}+1"));
+    }
+    model._set("C1", "=SUM(B1:B20)");
+    model._set("E1", "=CONCATENATE(\"x\", D2)");
+    model._set("F1", "=SUM(Other!A1:A3)");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("C1"), *"420");
+    assert_eq!(model._get_text("F1"), *"5");
+}
+
+#[test]
+fn circular_references() {
+    let mut model = new_empty_model();
+    model._set("A1", "=B1+1");
+    model._set("B1", "=A1+1");
+    model._set("D1", "=D2");
+    model._set("D2", "=D3");
+    model._set("D3", "=D1");
+    model._set("F1", "=5");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("A1"), *"#CIRC!");
+    assert_eq!(model._get_text("F1"), *"5");
+}
+
+#[test]
+fn spills_and_array_formulas() {
+    let mut model = new_empty_model();
+    model._set("A1", "={1,2,3;4,5,6}");
+    model._set("A5", "=B2*10");
+    model._set("E1", "1");
+    model._set("E2", "2");
+    model.set_array_formula(0, 1, 6, 2, 1, "=E1:E2*3").unwrap();
+    model._set("G1", "=F2+1");
+    // Blocked spill range
+    model._set("H1", "={1,2}");
+    model._set("I1", "x");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("A5"), *"50");
+    assert_eq!(model._get_text("G1"), *"7");
+    assert_eq!(model._get_text("H1"), *"#SPILL!");
+
+    // A second evaluation starts from the spilled values
+    model._set("A1", "={1;2}");
+    model._set("I1", "");
+    assert_same_as_sequential_evaluation(&mut model);
+}
+
+#[test]
+fn spills_into_the_same_cells() {
+    let mut model = new_empty_model();
+    model._set("B1", "=SEQUENCE(3)");
+    model._set("A2", "=SEQUENCE(1,3)");
+    model._set("E1", "=1+1");
+    model._set("F1", "=2+2");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("B2"), *"2");
+    assert_eq!(model._get_text("A2"), *"#SPILL!");
+}
+
+#[test]
+fn defined_names() {
+    let mut model = new_empty_model();
+    model._set("A1", "10");
+    model.new_defined_name("Rate", None, "Sheet1!$A$1").unwrap();
+    model.new_defined_name("Double", None, "Rate*2").unwrap();
+    model._set("B1", "=Double+1");
+    model._set("C1", "=3");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"21");
+}
+
+#[test]
+fn volatile_formulas_are_sequential() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "=INDIRECT(\"A\"&1)+1");
+    model._set("C1", "=OFFSET(A1,0,0)");
+    model._set("D1", "=4");
+    assert_same_as_sequential_evaluation(&mut model);
+    assert_eq!(model._get_text("B1"), *"2");
+}
+
+#[test]
+fn single_thread() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "=A1+1");
+    model._set("C1", "=7");
+    model.evaluate_parallel(1);
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("C1"), *"7");
+}
@@ -15,13 +15,13 @@ fn test_circ() {
 }
 
 #[test]
-fn test_nimpl() {
+fn test_nested_arrays() {
     let mut model = new_empty_model();
     model._set("B2", "={{1}}");
     assert_eq!(
         model.evaluate_with_error_check(),
         Err(vec![
-            "Sheet1!B2 ('={{1}}'): Arrays not implemented".to_string()
+            "Sheet1!B2 ('={{1}}'): Error parsing {{1}}: Arrays cannot be nested".to_string()
         ]),
     )
 }
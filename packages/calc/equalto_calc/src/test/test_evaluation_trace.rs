@@ -0,0 +1,190 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::evaluation_trace::{EvaluationStep, StepValue};
+use crate::expressions::types::{Area, CellReferenceIndex};
+use crate::test::util::new_empty_model;
+
+fn number(value: f64) -> StepValue {
+    StepValue::Number { value }
+}
+
+/// The expressions of the children of a step
+fn expressions(step: &EvaluationStep) -> Vec<&str> {
+    step.children
+        .iter()
+        .map(|child| child.expression.as_str())
+        .collect()
+}
+
+#[test]
+fn if_branch() {
+    let mut model = new_empty_model();
+    model._set("A1", "5");
+    model._set("B1", "=IF(A1>3, A1*2, 0)");
+    model._set("B2", "=IF(A1>30, A1*2, 0)");
+    model.evaluate();
+
+    let trace = model.get_evaluation_trace(0, 1, 2).unwrap();
+    assert_eq!(trace.expression, "IF(A1>3,A1*2,0)");
+    assert_eq!(trace.value, number(10.0));
+    assert_eq!(trace.branch, Some(1));
+    assert_eq!(expressions(&trace), vec!["A1>3", "A1*2"]);
+    let condition = &trace.children[0];
+    assert_eq!(condition.value, StepValue::Boolean { value: true });
+    assert_eq!(expressions(condition), vec!["A1", "3"]);
+    assert_eq!(condition.children[0].value, number(5.0));
+
+    let trace = model.get_evaluation_trace(0, 2, 2).unwrap();
+    assert_eq!(trace.branch, Some(2));
+    assert_eq!(expressions(&trace), vec!["A1>30", "0"]);
+}
+
+#[test]
+fn ifs_and_switch() {
+    let mut model = new_empty_model();
+    model._set("A1", "2");
+    model._set("B1", "=IFS(A1=1, \"one\", A1=2, \"two\")");
+    model._set("B2", "=SWITCH(A1, 1, \"one\", 2, \"two\", \"many\")");
+    model._set("B3", "=SWITCH(A1, 1, \"one\", \"many\")");
+    model._set("B4", "=IFERROR(1/0, \"oops\")");
+    model.evaluate();
+
+    let trace = model.get_evaluation_trace(0, 1, 2).unwrap();
+    assert_eq!(trace.branch, Some(3));
+    assert_eq!(
+        trace.value,
+        StepValue::String {
+            value: "two".to_string()
+        }
+    );
+    assert_eq!(model.get_evaluation_trace(0, 2, 2).unwrap().branch, Some(4));
+    assert_eq!(model.get_evaluation_trace(0, 3, 2).unwrap().branch, Some(3));
+
+    let trace = model.get_evaluation_trace(0, 4, 2).unwrap();
+    assert_eq!(trace.branch, Some(1));
+    assert_eq!(
+        trace.children[0].value,
+        StepValue::Error {
+            error: "#DIV/0!".to_string(),
+            message: "Divide by Zero".to_string()
+        }
+    );
+}
+
+#[test]
+fn lookup_match() {
+    let mut model = new_empty_model();
+    for (row, (name, price)) in [("apple", 3), ("banana", 1), ("cherry", 7)]
+        .iter()
+        .enumerate()
+    {
+        model._set(&format!("A{}", row + 1), name);
+        model._set(&format!("B{}", row + 1), &price.to_string());
+    }
+    model._set("D1", "=VLOOKUP(\"banana\", A1:B3, 2, FALSE)");
+    model._set("D2", "=MATCH(\"cherry\", A1:A3, 0)");
+    model._set("D3", "=XLOOKUP(\"apple\", A1:A3, B1:B3)");
+    model._set("D4", "=VLOOKUP(\"kiwi\", A1:B3, 2, FALSE)");
+    // Approximate matches outside of the table are #REF! errors, not matches
+    model._set("D5", "=VLOOKUP(\"banana\", A1:B3, 3)");
+    model._set("D6", "=HLOOKUP(3, B1:B3, 4)");
+    model.evaluate();
+
+    let trace = model.get_evaluation_trace(0, 1, 4).unwrap();
+    assert_eq!(trace.value, number(1.0));
+    assert_eq!(
+        trace.lookup_match,
+        Some(CellReferenceIndex {
+            sheet: 0,
+            row: 2,
+            column: 1
+        })
+    );
+    // The table is read after the column index and the sorted flag
+    assert_eq!(
+        expressions(&trace),
+        vec!["\"banana\"", "2", "FALSE", "A1:B3"]
+    );
+    assert_eq!(
+        trace.children[3].value,
+        StepValue::Range(Area {
+            sheet: 0,
+            row: 1,
+            column: 1,
+            width: 2,
+            height: 3
+        })
+    );
+    let trace = model.get_evaluation_trace(0, 2, 4).unwrap();
+    assert_eq!(trace.lookup_match.unwrap().row, 3);
+    let trace = model.get_evaluation_trace(0, 3, 4).unwrap();
+    assert_eq!(trace.lookup_match.unwrap().row, 1);
+    assert_eq!(
+        model.get_evaluation_trace(0, 4, 4).unwrap().lookup_match,
+        None
+    );
+    for row in [5, 6] {
+        assert_eq!(model._get_text(&format!("D{row}")), *"#REF!");
+        assert_eq!(
+            model.get_evaluation_trace(0, row, 4).unwrap().lookup_match,
+            None
+        );
+    }
+}
+
+#[test]
+fn referenced_formulas_and_names() {
+    let mut model = new_empty_model();
+    model._set("A1", "=2+3");
+    model
+        .new_defined_name("Double", None, "Sheet1!$A$1*2")
+        .unwrap();
+    model._set("B1", "=Double+A1");
+    model.evaluate();
+
+    let trace = model.get_evaluation_trace(0, 1, 2).unwrap();
+    assert_eq!(trace.value, number(15.0));
+    assert_eq!(expressions(&trace), vec!["Double", "A1"]);
+    // The formula of a defined name is part of the evaluation of the cell
+    assert_eq!(expressions(&trace.children[0]), vec!["Sheet1!$A$1*2"]);
+    // The formula in a referenced cell is not
+    assert_eq!(trace.children[1].value, number(5.0));
+    assert!(trace.children[1].children.is_empty());
+}
+
+#[test]
+fn model_is_not_modified() {
+    let mut model = new_empty_model();
+    model._set("A1", "=RAND()");
+    model._set("A2", "=ROW()");
+    model.evaluate();
+    let value = model._get_text("A1");
+
+    model.get_evaluation_trace(0, 1, 1).unwrap();
+    assert_eq!(model._get_text("A1"), value);
+
+    assert_eq!(
+        model.get_evaluation_trace(0, 3, 1),
+        Err("Cell does not contain a formula".to_string())
+    );
+    assert_eq!(
+        model.get_evaluation_trace(3, 1, 1),
+        Err("Invalid sheet index".to_string())
+    );
+}
+
+#[test]
+fn serialization() {
+    let mut model = new_empty_model();
+    model._set("A1", "=CHOOSE(2, 10, 20)");
+    model.evaluate();
+    let trace = model.get_evaluation_trace(0, 1, 1).unwrap();
+    assert_eq!(
+        serde_json::to_string(&trace).unwrap(),
+        concat!(
+            r#"{"expression":"CHOOSE(2,10,20)","value":{"type":"number","value":20.0},"branch":2,"#,
+            r#""children":[{"expression":"2","value":{"type":"number","value":2.0},"children":[]},"#,
+            r#"{"expression":"20","value":{"type":"number","value":20.0},"children":[]}]}"#
+        )
+    );
+}
@@ -0,0 +1,162 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::{model::Model, test::util::new_empty_model, types::Row};
+
+// Example from the Excel documentation
+fn new_aggregate_model() -> Model {
+    let mut model = new_empty_model();
+    let a = [
+        "=1/0",
+        "72",
+        "30",
+        "=SQRT(-1)",
+        "31",
+        "96",
+        "32",
+        "81",
+        "33",
+        "53",
+        "34",
+    ];
+    let b = [
+        "82", "65", "95", "63", "53", "71", "55", "83", "100", "91", "89",
+    ];
+    for (row, (a, b)) in a.iter().zip(b).enumerate() {
+        model._set(&format!("A{}", row + 1), a);
+        model._set(&format!("B{}", row + 1), b);
+    }
+    model
+}
+
+fn hide_row(model: &mut Model, row: i32) {
+    model.workbook.worksheets[0].rows.push(Row {
+        r: row,
+        height: 15.0,
+        custom_format: false,
+        custom_height: false,
+        s: 0,
+        hidden: true,
+    });
+}
+
+#[test]
+fn fn_aggregate_arguments() {
+    let mut model = new_aggregate_model();
+    model._set("C1", "=AGGREGATE(4,6)");
+    model._set("C2", "=AGGREGATE(15,6,A1:A11)");
+    model._set("C3", "=AGGREGATE(14,6,A1:A11,1,2)");
+    model._set("C4", "=AGGREGATE(20,6,A1:A11)");
+    model._set("C5", "=AGGREGATE(4,8,A1:A11)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"#ERROR!");
+    assert_eq!(model._get_text("C2"), *"#ERROR!");
+    assert_eq!(model._get_text("C3"), *"#ERROR!");
+    assert_eq!(model._get_text("C4"), *"#VALUE!");
+    assert_eq!(model._get_text("C5"), *"#VALUE!");
+}
+
+#[test]
+fn fn_aggregate_ignore_errors() {
+    let mut model = new_aggregate_model();
+    model._set("C1", "=AGGREGATE(4,6,A1:A11)");
+    model._set("C2", "=AGGREGATE(14,6,A1:A11,3)");
+    model._set("C3", "=AGGREGATE(12,6,A1:A11,B1:B11)");
+    model._set("C4", "=AGGREGATE(4,4,A1:A11)");
+    model._set("C5", "=AGGREGATE(12,6,A1:A11)");
+    model._set("C6", "=AGGREGATE(1,6,A1:A11)");
+    model._set("C7", "=AGGREGATE(2,6,A1:A11)");
+    model._set("C8", "=AGGREGATE(3,6,A1:A11)");
+    model._set("C9", "=AGGREGATE(3,4,A1:A11)");
+    model._set("C10", "=AGGREGATE(5,6,A1:A11)");
+    model._set("C11", "=AGGREGATE(6,6,A2:A3)");
+    model._set("C12", "=AGGREGATE(7,6,A1:A11)");
+    model._set("C13", "=AGGREGATE(8,6,A1:A11)");
+    model._set("C14", "=AGGREGATE(9,6,A1:A11)");
+    model._set("C15", "=AGGREGATE(10,6,A2:A3)");
+    model._set("C16", "=AGGREGATE(11,6,A2:A3)");
+    model._set("C17", "=AGGREGATE(13,6,A1:A11,B1:B11)");
+    model._set("C18", "=AGGREGATE(15,6,A1:A11,2)");
+    model._set("C19", "=AGGREGATE(16,6,A1:A11,0.9)");
+    model._set("C20", "=AGGREGATE(17,6,A1:A11,3)");
+    model._set("C21", "=AGGREGATE(18,6,A1:A11,0.9)");
+    model._set("C22", "=AGGREGATE(19,6,A1:A11,3)");
+    model._set("C23", "=AGGREGATE(14,6,A1:A11,10)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"96");
+    assert_eq!(model._get_text("C2"), *"72");
+    assert_eq!(model._get_text("C3"), *"68");
+    assert_eq!(model._get_text("C4"), *"#DIV/0!");
+    assert_eq!(model._get_text("C5"), *"34");
+    assert_eq!(model._get_text("C6"), *"51.333333333");
+    assert_eq!(model._get_text("C7"), *"9");
+    assert_eq!(model._get_text("C8"), *"9");
+    assert_eq!(model._get_text("C9"), *"11");
+    assert_eq!(model._get_text("C10"), *"30");
+    assert_eq!(model._get_text("C11"), *"2160");
+    assert_eq!(model._get_text("C12"), *"25.455844123");
+    assert_eq!(model._get_text("C13"), *"24");
+    assert_eq!(model._get_text("C14"), *"462");
+    assert_eq!(model._get_text("C15"), *"882");
+    assert_eq!(model._get_text("C16"), *"441");
+    assert_eq!(model._get_text("C17"), *"53");
+    assert_eq!(model._get_text("C18"), *"31");
+    assert_eq!(model._get_text("C19"), *"84");
+    assert_eq!(model._get_text("C20"), *"72");
+    assert_eq!(model._get_text("C21"), *"96");
+    assert_eq!(model._get_text("C22"), *"76.5");
+    assert_eq!(model._get_text("C23"), *"#NUM!");
+}
+
+#[test]
+fn fn_aggregate_array_form() {
+    let mut model = new_aggregate_model();
+    model._set("C1", "=AGGREGATE(14,6,A1:A11/(A1:A11>50),1)");
+    model._set("C2", "=AGGREGATE(14,6,A1:A11/(A1:A11>50),4)");
+    model._set("C3", "=AGGREGATE(15,6,A1:A11/(A1:A11>50),1)");
+    model._set("C4", "=AGGREGATE(14,4,A1:A11/(A1:A11>50),1)");
+    model._set("C5", "=AGGREGATE(9,4,{1,2,3})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"96");
+    assert_eq!(model._get_text("C2"), *"53");
+    assert_eq!(model._get_text("C3"), *"53");
+    assert_eq!(model._get_text("C4"), *"#DIV/0!");
+    assert_eq!(model._get_text("C5"), *"6");
+}
+
+#[test]
+fn fn_aggregate_hidden_rows_and_subtotals() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("A4", "=SUBTOTAL(9,A1:A3)");
+    model._set("A5", "=AGGREGATE(9,4,A1:A3)");
+    hide_row(&mut model, 2);
+
+    model._set("B1", "=AGGREGATE(9,0,A1:A5)");
+    model._set("B2", "=AGGREGATE(9,1,A1:A5)");
+    model._set("B3", "=AGGREGATE(9,4,A1:A5)");
+    model._set("B4", "=AGGREGATE(9,5,A1:A5)");
+    model._set("B5", "=AGGREGATE(9,,A1:A3,SUBTOTAL(9,A1:A3))");
+    model._set("B6", "=AGGREGATE(9,4,A1:A3,SUBTOTAL(9,A1:A3))");
+    // Nested aggregates are ignored by SUBTOTAL as well
+    model._set("B7", "=SUBTOTAL(9,A1:A5)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A4"), *"6");
+    assert_eq!(model._get_text("A5"), *"6");
+    assert_eq!(model._get_text("B1"), *"6");
+    assert_eq!(model._get_text("B2"), *"4");
+    assert_eq!(model._get_text("B3"), *"18");
+    assert_eq!(model._get_text("B4"), *"16");
+    assert_eq!(model._get_text("B5"), *"6");
+    assert_eq!(model._get_text("B6"), *"12");
+    assert_eq!(model._get_text("B7"), *"6");
+}
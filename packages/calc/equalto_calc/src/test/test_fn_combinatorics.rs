@@ -0,0 +1,139 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=FACT()");
+    model._set("A2", "=FACTDOUBLE(1,2)");
+    model._set("A3", "=COMBIN(1)");
+    model._set("A4", "=COMBINA(1,2,3)");
+    model._set("A5", "=PERMUT(1)");
+    model._set("A6", "=PERMUTATIONA()");
+    model._set("A7", "=MULTINOMIAL()");
+    model._set("A8", "=SERIESSUM(1,2,3)");
+    model._set("A9", "=SUMSQ()");
+
+    model.evaluate();
+
+    for row in 1..=9 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_fact_factdouble() {
+    let mut model = new_empty_model();
+    model._set("A1", "=FACT(5)");
+    model._set("A2", "=FACT(1.9)");
+    model._set("A3", "=FACT(0)");
+    model._set("A4", "=FACT(-1)");
+    model._set("A5", "=FACT(171)");
+
+    model._set("B1", "=FACTDOUBLE(6)");
+    model._set("B2", "=FACTDOUBLE(7)");
+    model._set("B3", "=FACTDOUBLE(-1)");
+    model._set("B4", "=FACTDOUBLE(-2)");
+    model._set("B5", "=FACTDOUBLE(0)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"120");
+    assert_eq!(model._get_text("A2"), *"1");
+    assert_eq!(model._get_text("A3"), *"1");
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_eq!(model._get_text("A5"), *"#NUM!");
+
+    assert_eq!(model._get_text("B1"), *"48");
+    assert_eq!(model._get_text("B2"), *"105");
+    assert_eq!(model._get_text("B3"), *"1");
+    assert_eq!(model._get_text("B4"), *"#NUM!");
+    assert_eq!(model._get_text("B5"), *"1");
+}
+
+#[test]
+fn fn_combin_permut() {
+    let mut model = new_empty_model();
+    model._set("A1", "=COMBIN(8,2)");
+    model._set("A2", "=COMBIN(10.9,3.2)");
+    model._set("A3", "=COMBIN(4,5)");
+    model._set("A4", "=COMBIN(-1,0)");
+    model._set("A5", "=COMBIN(5,0)");
+    model._set("A6", "=COMBIN(60,30)");
+
+    model._set("B1", "=COMBINA(4,3)");
+    model._set("B2", "=COMBINA(10,3)");
+    model._set("B3", "=COMBINA(0,0)");
+    model._set("B4", "=COMBINA(3,-1)");
+
+    model._set("C1", "=PERMUT(100,3)");
+    model._set("C2", "=PERMUT(3,2)");
+    model._set("C3", "=PERMUT(2,3)");
+    model._set("C4", "=PERMUT(4,0)");
+
+    model._set("D1", "=PERMUTATIONA(3,2)");
+    model._set("D2", "=PERMUTATIONA(2,2)");
+    model._set("D3", "=PERMUTATIONA(-2,2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"28");
+    assert_eq!(model._get_text("A2"), *"120");
+    assert_eq!(model._get_text("A3"), *"#NUM!");
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_eq!(model._get_text("A5"), *"1");
+    assert_eq!(model._get_text("A6"), *"1.18265E+17");
+
+    assert_eq!(model._get_text("B1"), *"20");
+    assert_eq!(model._get_text("B2"), *"220");
+    assert_eq!(model._get_text("B3"), *"1");
+    assert_eq!(model._get_text("B4"), *"#NUM!");
+
+    assert_eq!(model._get_text("C1"), *"970200");
+    assert_eq!(model._get_text("C2"), *"6");
+    assert_eq!(model._get_text("C3"), *"#NUM!");
+    assert_eq!(model._get_text("C4"), *"1");
+
+    assert_eq!(model._get_text("D1"), *"9");
+    assert_eq!(model._get_text("D2"), *"4");
+    assert_eq!(model._get_text("D3"), *"#NUM!");
+}
+
+#[test]
+fn fn_multinomial_seriessum_sumsq() {
+    let mut model = new_empty_model();
+    model._set("A1", "3");
+    model._set("A2", "4");
+    model._set("A3", "text");
+
+    model._set("B1", "=MULTINOMIAL(2,3,4)");
+    model._set("B2", "=MULTINOMIAL(A1:A3)");
+    model._set("B3", "=MULTINOMIAL(-1,2)");
+
+    model._set(
+        "C1",
+        "=SERIESSUM(PI()/4,0,2,{1,-0.5,0.041666666666666664,-0.001388888888888889})",
+    );
+    model._set("C2", "=SERIESSUM(2,1,1,A1:A2)");
+    model._set("C3", "=SERIESSUM(2,1,1,A1:A3)");
+
+    model._set("D1", "=SUMSQ(3,4)");
+    model._set("D2", "=SUMSQ(A1:A3,-1)");
+    model._set("D3", "=SUMSQ(TRUE,\"2\")");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"1260");
+    assert_eq!(model._get_text("B2"), *"35");
+    assert_eq!(model._get_text("B3"), *"#NUM!");
+
+    assert_eq!(model._get_text("C1"), *"0.707103215");
+    // 3*2 + 4*2^2
+    assert_eq!(model._get_text("C2"), *"22");
+    assert_eq!(model._get_text("C3"), *"#VALUE!");
+
+    assert_eq!(model._get_text("D1"), *"25");
+    assert_eq!(model._get_text("D2"), *"26");
+    assert_eq!(model._get_text("D3"), *"5");
+}
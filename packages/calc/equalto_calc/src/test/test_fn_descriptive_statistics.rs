@@ -0,0 +1,277 @@
+#![allow(clippy::unwrap_used)]
+
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::test::util::{assert_number, new_empty_model, set_column};
+
+// Most of the examples are taken from the Excel documentation
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=STDEV.S()");
+    model._set("A2", "=MEDIAN()");
+    model._set("A3", "=PERCENTILE.INC(1)");
+    model._set("A4", "=RANK.EQ(1)");
+    model._set("A5", "=LARGE(1,2,3)");
+    model._set("A6", "=TRIMMEAN(1)");
+    model._set("A7", "=PERCENTRANK(1,2,3,4)");
+
+    model.evaluate();
+
+    for row in 1..=7 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_variance_and_standard_deviation() {
+    let mut model = new_empty_model();
+    set_column(
+        &mut model,
+        "A",
+        &[
+            "1345", "1301", "1368", "1322", "1310", "1370", "1318", "1350", "1303", "1299",
+        ],
+    );
+    model._set("B1", "=STDEV.S(A1:A10)");
+    model._set("B2", "=STDEV(A1:A10)");
+    model._set("B3", "=STDEV.P(A1:A10)");
+    model._set("B4", "=STDEVP(A1:A10)");
+    model._set("B5", "=VAR.S(A1:A10)");
+    model._set("B6", "=VAR(A1:A10)");
+    model._set("B7", "=VAR.P(A1:A10)");
+    model._set("B8", "=VARP(A1:A10)");
+    model._set("B9", "=STDEV.S(A:A)");
+
+    // text and booleans in ranges only count in the A variants
+    set_column(&mut model, "C", &["1", "2", "TRUE", "a"]);
+    model._set("D1", "=STDEVA(C1:C4)");
+    model._set("D2", "=STDEVPA(C1:C4)");
+    model._set("D3", "=STDEV.S(C1:C4)");
+    model._set("D4", "=VARA(C1:C4)");
+    model._set("D5", "=VARPA(C1:C4)");
+
+    model._set("E1", "=STDEV.S(1)");
+    model._set("E2", "=VAR.P(C4)");
+    model._set("E3", "=STDEV.P(1)");
+
+    model.evaluate();
+
+    assert_number(&model, "B1", 27.463915719843495);
+    assert_number(&model, "B2", 27.463915719843495);
+    assert_number(&model, "B3", 26.054558142482477);
+    assert_number(&model, "B4", 26.054558142482477);
+    assert_number(&model, "B5", 754.2666666666667);
+    assert_number(&model, "B6", 754.2666666666667);
+    assert_number(&model, "B7", 678.84);
+    assert_number(&model, "B8", 678.84);
+    assert_number(&model, "B9", 27.463915719843495);
+
+    assert_number(&model, "D1", 0.816496580927726);
+    assert_number(&model, "D2", FRAC_1_SQRT_2);
+    assert_number(&model, "D3", FRAC_1_SQRT_2);
+    assert_number(&model, "D4", 0.6666666666666666);
+    assert_number(&model, "D5", 0.5);
+
+    assert_eq!(model._get_text("E1"), *"#DIV/0!");
+    assert_eq!(model._get_text("E2"), *"#DIV/0!");
+    assert_number(&model, "E3", 0.0);
+}
+
+#[test]
+fn fn_median_and_mode() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MEDIAN(1,2,3,4,5)");
+    model._set("A2", "=MEDIAN({1,2,3,4,5,6})");
+    model._set("A3", "=MEDIAN(\"a\")");
+    model._set("A4", "=MODE.SNGL(5.6,4,4,3,2,4)");
+    model._set("A5", "=MODE(1,2,3)");
+    model._set("A6", "=MODE.SNGL(3,1,1,3)");
+    model._set("B1", "=MODE.MULT({1,2,3,4,3,2,1,2,3})");
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 3.0);
+    assert_number(&model, "A2", 3.5);
+    assert_eq!(model._get_text("A3"), *"#VALUE!");
+    assert_number(&model, "A4", 4.0);
+    assert_eq!(model._get_text("A5"), *"#N/A");
+    // ties are resolved by the first value
+    assert_number(&model, "A6", 3.0);
+    assert_number(&model, "B1", 2.0);
+    assert_number(&model, "B2", 3.0);
+    assert_eq!(model._get_text("B3"), *"");
+}
+
+#[test]
+fn fn_percentile_and_quartile() {
+    let mut model = new_empty_model();
+    set_column(
+        &mut model,
+        "A",
+        &["1", "2", "3", "6", "6", "6", "7", "8", "9"],
+    );
+    set_column(
+        &mut model,
+        "B",
+        &[
+            "6", "7", "15", "36", "39", "40", "41", "42", "43", "47", "49",
+        ],
+    );
+    model._set("C1", "=PERCENTILE.INC({1,3,2,4},0.3)");
+    model._set("C2", "=PERCENTILE({1,3,2,4},0.3)");
+    model._set("C3", "=PERCENTILE.INC({1,3,2,4},1.1)");
+    model._set("C4", "=PERCENTILE.EXC(A1:A9,0.25)");
+    model._set("C5", "=PERCENTILE.EXC(A1:A9,0)");
+    model._set("C6", "=PERCENTILE.EXC(A1:A9,0.01)");
+    model._set("C7", "=QUARTILE.INC({1,2,4,7,8,9,10,12},1)");
+    model._set("C8", "=QUARTILE({1,2,4,7,8,9,10,12},4.5)");
+    model._set("C9", "=QUARTILE.INC({1,2,4,7,8,9,10,12},5)");
+    model._set("C10", "=QUARTILE.EXC(B1:B11,1)");
+    model._set("C11", "=QUARTILE.EXC(B1:B11,3)");
+    model._set("C12", "=QUARTILE.EXC(B1:B11,0)");
+
+    model.evaluate();
+
+    assert_number(&model, "C1", 1.9);
+    assert_number(&model, "C2", 1.9);
+    assert_eq!(model._get_text("C3"), *"#NUM!");
+    assert_number(&model, "C4", 2.5);
+    assert_eq!(model._get_text("C5"), *"#NUM!");
+    assert_eq!(model._get_text("C6"), *"#NUM!");
+    assert_number(&model, "C7", 3.5);
+    assert_number(&model, "C8", 12.0);
+    assert_eq!(model._get_text("C9"), *"#NUM!");
+    assert_number(&model, "C10", 15.0);
+    assert_number(&model, "C11", 43.0);
+    assert_eq!(model._get_text("C12"), *"#NUM!");
+}
+
+#[test]
+fn fn_percentrank() {
+    let mut model = new_empty_model();
+    set_column(
+        &mut model,
+        "A",
+        &["13", "12", "11", "8", "4", "3", "2", "1", "1", "1"],
+    );
+    set_column(
+        &mut model,
+        "B",
+        &["1", "2", "3", "6", "6", "6", "7", "8", "9"],
+    );
+    model._set("C1", "=PERCENTRANK.INC(A1:A10,2)");
+    model._set("C2", "=PERCENTRANK.INC(A1:A10,4)");
+    model._set("C3", "=PERCENTRANK.INC(A1:A10,8)");
+    model._set("C4", "=PERCENTRANK.INC(A1:A10,5)");
+    model._set("C5", "=PERCENTRANK(A1:A10,5,1)");
+    model._set("C6", "=PERCENTRANK.INC(A1:A10,14)");
+    model._set("C7", "=PERCENTRANK.EXC(B1:B9,7)");
+    model._set("C8", "=PERCENTRANK.EXC(B1:B9,5.43)");
+    model._set("C9", "=PERCENTRANK.EXC(B1:B9,5.43,1)");
+    model._set("C10", "=PERCENTRANK.EXC(B1:B9,5.43,0)");
+
+    model.evaluate();
+
+    assert_number(&model, "C1", 0.333);
+    assert_number(&model, "C2", 0.555);
+    assert_number(&model, "C3", 0.666);
+    assert_number(&model, "C4", 0.583);
+    assert_number(&model, "C5", 0.5);
+    assert_eq!(model._get_text("C6"), *"#N/A");
+    assert_number(&model, "C7", 0.7);
+    assert_number(&model, "C8", 0.381);
+    assert_number(&model, "C9", 0.3);
+    assert_eq!(model._get_text("C10"), *"#NUM!");
+}
+
+#[test]
+fn fn_rank_large_small() {
+    let mut model = new_empty_model();
+    set_column(&mut model, "A", &["7", "3.5", "3.5", "1", "2"]);
+    set_column(
+        &mut model,
+        "B",
+        &["89", "88", "92", "101", "94", "97", "95"],
+    );
+    model._set("C1", "=RANK.EQ(A1,A1:A5,1)");
+    model._set("C2", "=RANK.EQ(A5,A1:A5)");
+    model._set("C3", "=RANK.EQ(A2,A1:A5,1)");
+    model._set("C4", "=RANK(A2,A1:A5)");
+    model._set("C5", "=RANK.AVG(A2,A1:A5)");
+    model._set("C6", "=RANK.AVG(94,B1:B7)");
+    model._set("C7", "=RANK.EQ(5,A1:A5)");
+
+    model._set("D1", "=LARGE({3,5,3,5,4,4,2,4,6,7},3)");
+    model._set("D2", "=LARGE({3,5,3,5,4,4,2,4,6,7},7)");
+    model._set("D3", "=SMALL({1,4,8,3,7,12,54,8,23},2)");
+    model._set("D4", "=SMALL({1,4,8,3,7,12,54,8,23},1.2)");
+    model._set("D5", "=SMALL({1,4,8},0)");
+    model._set("D6", "=LARGE({1,4,8},4)");
+
+    model.evaluate();
+
+    assert_number(&model, "C1", 5.0);
+    assert_number(&model, "C2", 4.0);
+    assert_number(&model, "C3", 3.0);
+    assert_number(&model, "C4", 2.0);
+    assert_number(&model, "C5", 2.5);
+    assert_number(&model, "C6", 4.0);
+    assert_eq!(model._get_text("C7"), *"#N/A");
+
+    assert_number(&model, "D1", 5.0);
+    assert_number(&model, "D2", 4.0);
+    assert_number(&model, "D3", 3.0);
+    assert_number(&model, "D4", 3.0);
+    assert_eq!(model._get_text("D5"), *"#NUM!");
+    assert_eq!(model._get_text("D6"), *"#NUM!");
+}
+
+#[test]
+fn fn_means_and_moments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=GEOMEAN(4,5,8,7,11,4,3)");
+    model._set("A2", "=HARMEAN(4,5,8,7,11,4,3)");
+    model._set("A3", "=GEOMEAN(4,0)");
+    model._set("A4", "=HARMEAN(-1,2)");
+    model._set("A5", "=AVEDEV(4,5,6,7,5,4,3)");
+    model._set("A6", "=DEVSQ(4,5,8,7,11,4,3)");
+    model._set("A7", "=KURT(3,4,5,2,3,4,5,6,4,7)");
+    model._set("A8", "=SKEW(3,4,5,2,3,4,5,6,4,7)");
+    model._set("A9", "=KURT(1,2,3)");
+    model._set("A10", "=SKEW(1,1,1)");
+    model._set("A11", "=TRIMMEAN({4,5,6,7,2,3,4,5,1,2,3},0.2)");
+    model._set("A12", "=TRIMMEAN({4,5,6},1)");
+    model._set("A13", "=DEVSQ(\"a\")");
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 5.476986969656962);
+    assert_number(&model, "A2", 5.028375962061728);
+    assert_eq!(model._get_text("A3"), *"#NUM!");
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_number(&model, "A5", 1.0204081632653061);
+    assert_number(&model, "A6", 48.0);
+    assert_number(&model, "A7", -0.15179963720841272);
+    assert_number(&model, "A8", 0.3595430714067978);
+    assert_eq!(model._get_text("A9"), *"#DIV/0!");
+    assert_eq!(model._get_text("A10"), *"#DIV/0!");
+    assert_number(&model, "A11", 3.7777777777777777);
+    assert_eq!(model._get_text("A12"), *"#NUM!");
+    assert_eq!(model._get_text("A13"), *"#VALUE!");
+}
+
+#[test]
+fn fn_descriptive_statistics_formulas() {
+    let mut model = new_empty_model();
+    model._set("A1", "=stdev.s(1,2)");
+    model._set("A2", "=_xlfn.RANK.EQ(1,B1:B2)");
+    model._set("A3", "=percentile(B1:B2,0.5)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_formula("A1"), *"=STDEV.S(1,2)");
+    assert_eq!(model._get_formula("A2"), *"=RANK.EQ(1,B1:B2)");
+    assert_eq!(model._get_formula("A3"), *"=PERCENTILE(B1:B2,0.5)");
+}
@@ -0,0 +1,192 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::{cell::CellValue, test::util::new_empty_model};
+
+// Reference values are the examples in the Excel documentation, recomputed with mpmath in
+// Python to full precision
+const EPS: f64 = 1e-10;
+
+fn assert_cases_are_close(cases: &[(&str, f64)]) {
+    let mut model = new_empty_model();
+    for (index, (formula, _)) in cases.iter().enumerate() {
+        model._set(&format!("A{}", index + 1), &format!("={formula}"));
+    }
+    model.evaluate();
+    for (index, (formula, expected)) in cases.iter().enumerate() {
+        let value = model
+            .get_cell_value_by_ref(&format!("Sheet1!A{}", index + 1))
+            .unwrap();
+        match value {
+            CellValue::Number(f) => assert!(
+                (f - expected).abs() <= EPS * expected.abs().max(1.0),
+                "Got: {f}, expected: {expected} for {formula}"
+            ),
+            _ => panic!("Got: {value:?}, expected: {expected} for {formula}"),
+        }
+    }
+}
+
+#[test]
+fn fn_normal_distribution() {
+    assert_cases_are_close(&[
+        ("NORM.DIST(42,40,1.5,TRUE)", 0.9087887802741321),
+        ("NORM.DIST(42,40,1.5,FALSE)", 0.10934004978399575),
+        ("NORMDIST(42,40,1.5,TRUE)", 0.9087887802741321),
+        ("NORM.INV(0.908789,40,1.5)", 42.00000200956616),
+        ("NORMINV(0.908789,40,1.5)", 42.00000200956616),
+        ("NORM.S.DIST(1.333333,TRUE)", 0.9087887256040951),
+        ("NORM.S.DIST(1.333333,FALSE)", 0.16401014756936724),
+        ("NORMSDIST(1.333333)", 0.9087887256040951),
+        ("NORM.S.INV(0.908789)", 1.3333346730441076),
+        ("NORMSINV(0.908789)", 1.3333346730441076),
+        ("NORM.S.INV(1E-300)", -37.0470962993612),
+        ("LOGNORM.DIST(4,3.5,1.2,TRUE)", 0.039083555706800464),
+        ("LOGNORM.DIST(4,3.5,1.2,FALSE)", 0.017617596681819222),
+        ("LOGNORMDIST(4,3.5,1.2)", 0.039083555706800464),
+        ("LOGNORM.INV(0.039084,3.5,1.2)", 4.000025218680635),
+        ("LOGINV(0.039084,3.5,1.2)", 4.000025218680635),
+        ("CONFIDENCE.NORM(0.05,2.5,50)", 0.692951912174839),
+        ("CONFIDENCE(0.05,2.5,50)", 0.692951912174839),
+    ]);
+}
+
+#[test]
+fn fn_t_distribution() {
+    assert_cases_are_close(&[
+        ("T.DIST(60,1,TRUE)", 0.9946953263673767),
+        ("T.DIST(8,3,FALSE)", 0.0007369065209469263),
+        // degrees of freedom are truncated
+        ("T.DIST(8,3.9,FALSE)", 0.0007369065209469263),
+        ("T.DIST.RT(1.959999998,60)", 0.027322464987960437),
+        ("T.DIST.2T(1.959999998,60)", 0.054644929975920874),
+        ("TDIST(1.959999998,60,1)", 0.027322464987960437),
+        ("TDIST(1.959999998,60,2)", 0.054644929975920874),
+        ("T.INV(0.75,2)", 0.816496580927726),
+        ("T.INV(0.1,10)", -1.3721836411103356),
+        ("T.INV.2T(0.546449,60)", 0.606533075825755),
+        ("TINV(0.546449,60)", 0.606533075825755),
+        ("CONFIDENCE.T(0.05,1,50)", 0.2841968554957299),
+    ]);
+}
+
+#[test]
+fn fn_chi_squared_and_f_distributions() {
+    assert_cases_are_close(&[
+        ("CHISQ.DIST(0.5,1,TRUE)", 0.5204998778130465),
+        ("CHISQ.DIST(2,3,FALSE)", 0.20755374871029736),
+        ("CHISQ.DIST.RT(18.307,10)", 0.050000589091398095),
+        ("CHIDIST(18.307,10)", 0.050000589091398095),
+        ("CHISQ.INV(0.93,1)", 3.2830202867595357),
+        ("CHISQ.INV.RT(0.050001,10)", 18.306973456961057),
+        ("CHIINV(0.050001,10)", 18.306973456961057),
+        ("F.DIST(15.2069,6,4,TRUE)", 0.9900000430027627),
+        ("F.DIST(15.2069,6,4,FALSE)", 0.0012237917087831731),
+        ("F.DIST.RT(15.2069,6,4)", 0.009999956997237311),
+        ("FDIST(15.2069,6,4)", 0.009999956997237311),
+        ("F.INV(0.01,6,4)", 0.10930991412457854),
+        ("F.INV.RT(0.01,6,4)", 15.20686486115753),
+        ("FINV(0.01,6,4)", 15.20686486115753),
+    ]);
+}
+
+#[test]
+fn fn_discrete_distributions() {
+    assert_cases_are_close(&[
+        ("BINOM.DIST(6,10,0.5,FALSE)", 0.205078125),
+        ("BINOM.DIST(6,10,0.5,TRUE)", 0.828125),
+        ("BINOMDIST(6.7,10,0.5,FALSE)", 0.205078125),
+        ("BINOM.INV(6,0.5,0.75)", 4.0),
+        ("CRITBINOM(6,0.5,0.75)", 4.0),
+        ("BINOM.INV(6,0.5,0)", 0.0),
+        ("BINOM.INV(6,0.5,1)", 6.0),
+        ("POISSON.DIST(2,5,TRUE)", 0.12465201948308115),
+        ("POISSON.DIST(2,5,FALSE)", 0.08422433748856833),
+        ("POISSON(2,5,TRUE)", 0.12465201948308115),
+    ]);
+}
+
+#[test]
+fn fn_gamma_and_beta_distributions() {
+    assert_cases_are_close(&[
+        ("EXPON.DIST(0.2,10,TRUE)", 0.8646647167633873),
+        ("EXPON.DIST(0.2,10,FALSE)", 1.353352832366127),
+        ("EXPONDIST(0.2,10,TRUE)", 0.8646647167633873),
+        ("GAMMA.DIST(10.00001131,9,2,FALSE)", 0.032639130418294),
+        ("GAMMA.DIST(10.00001131,9,2,TRUE)", 0.06809400386978733),
+        ("GAMMADIST(10.00001131,9,2,TRUE)", 0.06809400386978733),
+        ("GAMMA.INV(0.068094,9,2)", 10.000011191437178),
+        ("GAMMAINV(0.068094,9,2)", 10.000011191437178),
+        ("GAMMALN(4)", 1.791759469228055),
+        ("GAMMALN.PRECISE(4.5)", 2.4537365708424423),
+        ("GAMMA(2.5)", 1.329340388179137),
+        ("GAMMA(-3.75)", 0.2678661288614166),
+        ("BETA.DIST(2,8,10,TRUE,1,3)", 0.6854705810546875),
+        ("BETA.DIST(2,8,10,FALSE,1,3)", 1.4837646484375),
+        ("BETA.DIST(0.5,8,10,TRUE)", 0.6854705810546875),
+        ("BETADIST(2,8,10,1,3)", 0.6854705810546875),
+        ("BETA.INV(0.685470581,8,10,1,3)", 1.9999999999631428),
+        ("BETAINV(0.685470581,8,10,1,3)", 1.9999999999631428),
+    ]);
+}
+
+#[test]
+fn fn_distributions_errors() {
+    let mut model = new_empty_model();
+    model._set("A1", "=NORM.DIST(1,0,0,TRUE)");
+    model._set("A2", "=NORM.INV(1,0,1)");
+    model._set("A3", "=NORM.S.INV(0)");
+    model._set("A4", "=LOGNORM.DIST(0,1,1,TRUE)");
+    model._set("A5", "=T.DIST(1,0,TRUE)");
+    model._set("A6", "=T.DIST.2T(-1,3)");
+    model._set("A7", "=TDIST(1,3,3)");
+    model._set("A8", "=CHISQ.DIST(-1,3,TRUE)");
+    model._set("A9", "=CHISQ.INV.RT(0,3)");
+    model._set("A10", "=F.DIST(1,0,3,TRUE)");
+    model._set("A11", "=BINOM.DIST(11,10,0.5,TRUE)");
+    model._set("A12", "=POISSON.DIST(1,-1,TRUE)");
+    model._set("A13", "=EXPON.DIST(1,0,TRUE)");
+    model._set("A14", "=GAMMA.DIST(1,0,1,TRUE)");
+    model._set("A15", "=GAMMA(0)");
+    model._set("A16", "=GAMMA(-2)");
+    model._set("A17", "=GAMMALN(0)");
+    model._set("A18", "=BETA.DIST(4,8,10,TRUE,1,3)");
+    model._set("A19", "=BETA.INV(0.5,8,10,3,3)");
+    model._set("A20", "=CONFIDENCE.T(0.05,1,1)");
+    model._set("A21", "=GAMMA(172)");
+
+    model._set("B1", "=NORM.DIST(1,0,1)");
+    model._set("B2", "=T.INV(0.5)");
+    model._set("B3", "=BETA.DIST(1,2,3,TRUE,0,1,2)");
+    model._set("B4", "=GAMMALN()");
+
+    model._set("C1", "=NORM.DIST(\"a\",0,1,TRUE)");
+
+    model.evaluate();
+
+    for row in 1..=19 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#NUM!");
+    }
+    assert_eq!(model._get_text("A20"), *"#DIV/0!");
+    assert_eq!(model._get_text("A21"), *"#NUM!");
+
+    assert_eq!(model._get_text("B1"), *"#ERROR!");
+    assert_eq!(model._get_text("B2"), *"#ERROR!");
+    assert_eq!(model._get_text("B3"), *"#ERROR!");
+    assert_eq!(model._get_text("B4"), *"#ERROR!");
+
+    assert_eq!(model._get_text("C1"), *"#VALUE!");
+}
+
+#[test]
+fn fn_distributions_formulas() {
+    let mut model = new_empty_model();
+    model._set("A1", "=norm.s.dist(1,true)");
+    model._set("A2", "=_xlfn.NORM.S.DIST(1,TRUE)");
+    model._set("A3", "=normsdist(1)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_formula("A1"), *"=NORM.S.DIST(1,TRUE)");
+    assert_eq!(model._get_formula("A2"), *"=NORM.S.DIST(1,TRUE)");
+    assert_eq!(model._get_formula("A3"), *"=NORMSDIST(1)");
+}
@@ -0,0 +1,352 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::environment::FixedEnvironment;
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+
+/// Checks the values spilled from the cell at (row, column) of the first sheet
+fn assert_array(model: &Model, row: i32, column: i32, expected: &[&[&str]]) {
+    for (dr, values) in expected.iter().enumerate() {
+        for (dc, value) in values.iter().enumerate() {
+            assert_eq!(
+                model._get_text_at(0, row + dr as i32, column + dc as i32),
+                *value,
+                "at ({}, {})",
+                row + dr as i32,
+                column + dc as i32
+            );
+        }
+    }
+}
+
+/// Model with a small table in A1:C5: name, region and sales
+fn new_model_with_table() -> Model {
+    let mut model = new_empty_model();
+    let table = [
+        ["Ana", "East", "30"],
+        ["Bob", "West", "10"],
+        ["Carl", "East", "20"],
+        ["Dana", "North", "10"],
+        ["Bob", "West", "10"],
+    ];
+    for (row, values) in table.iter().enumerate() {
+        for (column, value) in ["A", "B", "C"].iter().zip(values) {
+            model._set(&format!("{column}{}", row + 1), value);
+        }
+    }
+    model
+}
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=FILTER({1,2})");
+    model._set("A2", "=SORT()");
+    model._set("A3", "=SORTBY({1,2})");
+    model._set("A4", "=UNIQUE({1},1,1,1)");
+    model._set("A5", "=SEQUENCE()");
+    model._set("A6", "=RANDARRAY(1,1,1,1,1,1)");
+    model._set("A7", "=TAKE({1})");
+    model._set("A8", "=DROP({1},1,1,1)");
+    model._set("A9", "=CHOOSEROWS({1})");
+    model._set("A10", "=CHOOSECOLS({1})");
+    model._set("A11", "=VSTACK()");
+    model._set("A12", "=HSTACK()");
+    model._set("A13", "=TOCOL()");
+    model._set("A14", "=TOROW({1},1,1,1)");
+    model._set("A15", "=WRAPROWS({1})");
+    model._set("A16", "=WRAPCOLS({1},1,1,1)");
+
+    model.evaluate();
+
+    for row in 1..=16 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_filter() {
+    let mut model = new_model_with_table();
+    model._set("E1", "=FILTER(A1:C5,B1:B5=\"East\")");
+    model._set("E4", "=FILTER(A1:C5,C1:C5>100,\"None\")");
+    model._set("E5", "=FILTER(A1:C5,C1:C5>100)");
+    model._set("E6", "=FILTER({1,2,3;4,5,6},{1,0,1})");
+    model._set("E8", "=FILTER(A1:C5,{1;0})");
+
+    model.evaluate();
+
+    assert_array(
+        &model,
+        1,
+        5,
+        &[&["Ana", "East", "30"], &["Carl", "East", "20"]],
+    );
+    assert_eq!(model._get_text("E3"), *"");
+    assert_eq!(model._get_text("E4"), *"None");
+    assert_eq!(model._get_text("E5"), *"#CALC!");
+    assert_array(&model, 6, 5, &[&["1", "3"], &["4", "6"]]);
+    assert_eq!(model._get_text("E8"), *"#VALUE!");
+}
+
+#[test]
+fn fn_sort_and_sortby() {
+    let mut model = new_model_with_table();
+    model._set("E1", "=SORT(A1:C5)");
+    // by sales descending, then by name
+    model._set("I1", "=SORT(A1:C5,{3,1},{-1,1})");
+    model._set("E7", "=SORT({3,1,2;\"c\",\"a\",\"b\"},1,1,TRUE)");
+    model._set("E9", "=SORTBY(A1:A5,C1:C5,1,B1:B5,-1)");
+    model._set("F9", "=SORT({2;\"b\";TRUE;1;\"a\"})");
+    model._set("G9", "=SORT(A1:C5,4)");
+    model._set("G10", "=SORT(A1:C5,1,2)");
+    model._set("G11", "=SORTBY(A1:A5,{1,2})");
+
+    model.evaluate();
+
+    assert_array(
+        &model,
+        1,
+        5,
+        &[
+            &["Ana", "East", "30"],
+            &["Bob", "West", "10"],
+            &["Bob", "West", "10"],
+            &["Carl", "East", "20"],
+            &["Dana", "North", "10"],
+        ],
+    );
+    assert_array(
+        &model,
+        1,
+        9,
+        &[
+            &["Ana", "East", "30"],
+            &["Carl", "East", "20"],
+            &["Bob", "West", "10"],
+            &["Bob", "West", "10"],
+            &["Dana", "North", "10"],
+        ],
+    );
+    assert_array(&model, 7, 5, &[&["1", "2", "3"], &["a", "b", "c"]]);
+    // sales ascending and ties by region descending, the sort is stable
+    assert_array(
+        &model,
+        9,
+        5,
+        &[&["Bob"], &["Bob"], &["Dana"], &["Carl"], &["Ana"]],
+    );
+    assert_array(&model, 9, 6, &[&["1"], &["2"], &["a"], &["b"], &["TRUE"]]);
+    assert_eq!(model._get_text("G9"), *"#VALUE!");
+    assert_eq!(model._get_text("G10"), *"#VALUE!");
+    assert_eq!(model._get_text("G11"), *"#VALUE!");
+}
+
+#[test]
+fn fn_unique() {
+    let mut model = new_model_with_table();
+    model._set("E1", "=UNIQUE(A1:A5)");
+    model._set("F1", "=UNIQUE(A1:C5,FALSE,TRUE)");
+    model._set("I1", "=UNIQUE({1,1,2;3,3,4},TRUE)");
+    model._set("I3", "=UNIQUE({\"a\",\"A\",\"b\"},TRUE)");
+    model._set("I4", "=UNIQUE({1,1},TRUE,TRUE)");
+
+    model.evaluate();
+
+    assert_array(&model, 1, 5, &[&["Ana"], &["Bob"], &["Carl"], &["Dana"]]);
+    assert_eq!(model._get_text("E5"), *"");
+    assert_array(
+        &model,
+        1,
+        6,
+        &[
+            &["Ana", "East", "30"],
+            &["Carl", "East", "20"],
+            &["Dana", "North", "10"],
+        ],
+    );
+    assert_eq!(model._get_text("F4"), *"");
+    assert_array(&model, 1, 9, &[&["1", "2"], &["3", "4"]]);
+    assert_eq!(model._get_text("K1"), *"");
+    // comparisons are case insensitive
+    assert_array(&model, 3, 9, &[&["a", "b"]]);
+    assert_eq!(model._get_text("I4"), *"#CALC!");
+}
+
+#[test]
+fn fn_sequence() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SEQUENCE(2,3)");
+    model._set("A3", "=SEQUENCE(3,,10,-5)");
+    model._set("B3", "=SEQUENCE(0)");
+    model._set("C3", "=SEQUENCE(-1)");
+    model._set("D3", "=SUM(SEQUENCE(100))");
+    // Too many elements
+    model._set("E3", "=ROWS(SEQUENCE(1048576,2))");
+    model._set("F3", "=ROWS(RANDARRAY(1000,16384))");
+
+    model.evaluate();
+
+    assert_array(&model, 1, 1, &[&["1", "2", "3"], &["4", "5", "6"]]);
+    assert_array(&model, 3, 1, &[&["10"], &["5"], &["0"]]);
+    assert_eq!(model._get_text("B3"), *"#CALC!");
+    assert_eq!(model._get_text("C3"), *"#VALUE!");
+    assert_eq!(model._get_text("D3"), *"5050");
+    assert_eq!(model._get_text("E3"), *"#VALUE!");
+    assert_eq!(model._get_text("F3"), *"#VALUE!");
+}
+
+#[test]
+fn fn_randarray() {
+    let mut model = new_empty_model();
+    model._set("A1", "=RANDARRAY(3,2,-5,5,TRUE)");
+    model._set("C1", "=RANDARRAY(2)");
+    model._set("D1", "=RANDARRAY(1,1,2,1)");
+    model._set("D2", "=RANDARRAY(1,1,0.5,3,TRUE)");
+
+    model.set_environment(FixedEnvironment::new(0, 7));
+    model.evaluate();
+
+    for row in 1..=3 {
+        for column in 1..=2 {
+            let value: f64 = model._get_text_at(0, row, column).parse().unwrap();
+            assert!((-5.0..=5.0).contains(&value) && value.fract() == 0.0);
+        }
+    }
+    for row in 1..=2 {
+        let value: f64 = model._get_text_at(0, row, 3).parse().unwrap();
+        assert!((0.0..1.0).contains(&value));
+    }
+    assert_eq!(model._get_text("D1"), *"#VALUE!");
+    assert_eq!(model._get_text("D2"), *"#VALUE!");
+}
+
+#[test]
+fn fn_take_and_drop() {
+    let mut model = new_empty_model();
+    model._set("A1", "=TAKE({1,2,3;4,5,6;7,8,9},2)");
+    model._set("E1", "=TAKE({1,2,3;4,5,6;7,8,9},-1,-2)");
+    model._set("E2", "=TAKE({1,2,3;4,5,6;7,8,9},,1)");
+    model._set("A5", "=DROP({1,2,3;4,5,6;7,8,9},1,1)");
+    model._set("E5", "=DROP({1,2,3;4,5,6;7,8,9},-2)");
+    model._set("E7", "=DROP({1,2,3},1)");
+    model._set("E8", "=TAKE({1,2,3},0)");
+    model._set("E9", "=TAKE({1,2},5)");
+
+    model.evaluate();
+
+    assert_array(&model, 1, 1, &[&["1", "2", "3"], &["4", "5", "6"]]);
+    assert_eq!(model._get_text("A3"), *"");
+    assert_array(&model, 1, 5, &[&["8", "9"]]);
+    assert_array(&model, 2, 5, &[&["1"], &["4"], &["7"]]);
+    assert_array(&model, 5, 1, &[&["5", "6"], &["8", "9"]]);
+    assert_array(&model, 5, 5, &[&["1", "2", "3"]]);
+    assert_eq!(model._get_text("E7"), *"#CALC!");
+    assert_eq!(model._get_text("E8"), *"#CALC!");
+    assert_array(&model, 9, 5, &[&["1", "2"]]);
+}
+
+#[test]
+fn fn_chooserows_and_choosecols() {
+    let mut model = new_empty_model();
+    model._set("A1", "=CHOOSEROWS({1,2;3,4;5,6},3,{1,-1})");
+    model._set("D1", "=CHOOSECOLS({1,2,3;4,5,6},-1,1)");
+    model._set("D3", "=CHOOSEROWS({1,2},2)");
+    model._set("D4", "=CHOOSECOLS({1,2},0)");
+
+    model.evaluate();
+
+    assert_array(&model, 1, 1, &[&["5", "6"], &["1", "2"], &["5", "6"]]);
+    assert_array(&model, 1, 4, &[&["3", "1"], &["6", "4"]]);
+    assert_eq!(model._get_text("D3"), *"#VALUE!");
+    assert_eq!(model._get_text("D4"), *"#VALUE!");
+}
+
+#[test]
+fn fn_vstack_and_hstack() {
+    let mut model = new_empty_model();
+    model._set("A1", "=VSTACK({1,2},{3;4},5)");
+    model._set("D1", "=HSTACK({1;2},{3,4},\"x\")");
+    model._set("D4", "=VSTACK(SEQUENCE(2),{\"a\"})");
+
+    model.evaluate();
+
+    assert_array(
+        &model,
+        1,
+        1,
+        &[&["1", "2"], &["3", "#N/A"], &["4", "#N/A"], &["5", "#N/A"]],
+    );
+    assert_array(
+        &model,
+        1,
+        4,
+        &[&["1", "3", "4", "x"], &["2", "#N/A", "#N/A", "#N/A"]],
+    );
+    assert_array(&model, 4, 4, &[&["1"], &["2"], &["a"]]);
+}
+
+#[test]
+fn fn_tocol_and_torow() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "2");
+    model._set("A2", "=1/0");
+    model._set("C2", "4");
+    model._set("E1", "=TOCOL(A1:C2)");
+    model._set("F1", "=TOCOL(A1:C2,3)");
+    model._set("G1", "=TOCOL(A1:C2,1,TRUE)");
+    model._set("A8", "=TOROW(A1:C2,2)");
+    model._set("A9", "=TOROW(A1:C2,4)");
+    model._set("A10", "=TOROW(C1)");
+
+    model.evaluate();
+
+    assert_array(
+        &model,
+        1,
+        5,
+        &[&["1"], &["2"], &["0"], &["#DIV/0!"], &["0"], &["4"]],
+    );
+    assert_array(&model, 1, 6, &[&["1"], &["2"], &["4"]]);
+    assert_eq!(model._get_text("F4"), *"");
+    assert_array(&model, 1, 7, &[&["1"], &["#DIV/0!"], &["2"], &["4"]]);
+    assert_array(&model, 8, 1, &[&["1", "2", "0", "0", "4"]]);
+    assert_eq!(model._get_text("A9"), *"#VALUE!");
+    assert_eq!(model._get_text("A10"), *"0");
+}
+
+#[test]
+fn whole_rows_and_columns() {
+    let mut model = new_empty_model();
+    model.add_sheet("Other").unwrap();
+    model._set("Other!A1", "1");
+    model._set("Other!B3", "2");
+    // Clipped to the used part of the sheet, A1:B3
+    model._set("A1", "=ROWS(TOCOL(Other!A:XFD))");
+    model._set("A2", "=COLUMNS(TOROW(Other!1:1048576))");
+    model._set("A3", "=SUM(TOCOL(Other!A:A))");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"6");
+    assert_eq!(model._get_text("A2"), *"6");
+    assert_eq!(model._get_text("A3"), *"1");
+}
+
+#[test]
+fn fn_wraprows_and_wrapcols() {
+    let mut model = new_empty_model();
+    model._set("A1", "=WRAPROWS({1,2,3,4,5},2)");
+    model._set("D1", "=WRAPCOLS({1;2;3;4;5},3,0)");
+    model._set("D4", "=WRAPROWS({1,2,3},5)");
+    model._set("D5", "=WRAPROWS({1,2;3,4},2)");
+    model._set("D6", "=WRAPCOLS({1,2},0)");
+
+    model.evaluate();
+
+    assert_array(&model, 1, 1, &[&["1", "2"], &["3", "4"], &["5", "#N/A"]]);
+    assert_array(&model, 1, 4, &[&["1", "4"], &["2", "5"], &["3", "0"]]);
+    assert_array(&model, 4, 4, &[&["1", "2", "3", "#N/A", "#N/A"]]);
+    assert_eq!(model._get_text("D5"), *"#VALUE!");
+    assert_eq!(model._get_text("D6"), *"#NUM!");
+}
@@ -0,0 +1,211 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+use crate::types::DefinedName;
+
+fn add_defined_name(model: &mut Model, name: &str, formula: &str) {
+    model.workbook.defined_names.push(DefinedName {
+        name: name.to_string(),
+        formula: formula.to_string(),
+        sheet_id: None,
+    });
+    model.parse_defined_names();
+}
+
+#[test]
+fn fn_let() {
+    let mut model = new_empty_model();
+    model._set("A1", "3");
+    model._set("B1", "=LET(x, A1, y, x*2, x+y)");
+    // Names are case insensitive and shadow outer names
+    model._set("B2", "=LET(x, 1, LET(X, x+10, x*2))");
+    // A LET name shadows a defined name
+    model._set("B3", "=LET(A, 5, A+1)");
+    model._set("B4", "=LET(x, 1, y)");
+    model._set("B5", "=LET(x, 1)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"9");
+    assert_eq!(model._get_text("B2"), *"22");
+    assert_eq!(model._get_text("B3"), *"6");
+    assert_eq!(model._get_text("B4"), *"#NAME?");
+    assert_eq!(model._get_text("B5"), *"#ERROR!");
+    assert_eq!(model._get_formula("B1"), *"=LET(x,A1,y,x*2,x+y)");
+}
+
+#[test]
+fn fn_let_invalid_name() {
+    let mut model = new_empty_model();
+    model._set("A1", "=LET(1, 2, 3)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#ERROR!");
+}
+
+#[test]
+fn fn_lambda() {
+    let mut model = new_empty_model();
+    model._set("A1", "4");
+    model._set("B1", "=LAMBDA(x, x*x)(A1)");
+    model._set("B2", "=LAMBDA(x, y, x-y)(10, 3)");
+    // Not called
+    model._set("B3", "=LAMBDA(x, x*x)");
+    // Wrong number of arguments
+    model._set("B4", "=LAMBDA(x, y, x-y)(10)");
+    // Parameters are only in scope in the body
+    model._set("B5", "=LAMBDA(x, x)(1)+x");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"16");
+    assert_eq!(model._get_text("B2"), *"7");
+    assert_eq!(model._get_text("B3"), *"#CALC!");
+    assert_eq!(model._get_text("B4"), *"#VALUE!");
+    assert_eq!(model._get_text("B5"), *"#NAME?");
+    assert_eq!(model._get_formula("B1"), *"=LAMBDA(x,x*x)(A1)");
+}
+
+#[test]
+fn lambda_bound_with_let() {
+    let mut model = new_empty_model();
+    model._set("A1", "=LET(k, 2, f, LAMBDA(x, x*k), f(3))");
+    // The LAMBDA captures `k` where it is defined
+    model._set("A2", "=LET(k, 2, f, LAMBDA(x, x*k), LET(k, 100, f(3)))");
+    // Lambdas can be passed to lambdas
+    model._set(
+        "A3",
+        "=LET(twice, LAMBDA(g, x, g(g(x))), inc, LAMBDA(n, n+1), twice(inc, 5))",
+    );
+    // A LAMBDA bound to a name is not a value
+    model._set("A4", "=LET(f, LAMBDA(x, x), f)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"6");
+    assert_eq!(model._get_text("A2"), *"6");
+    assert_eq!(model._get_text("A3"), *"7");
+    assert_eq!(model._get_text("A4"), *"#CALC!");
+}
+
+#[test]
+fn named_lambda() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "MyTax", "LAMBDA(amount, amount*0.2)");
+    add_defined_name(
+        &mut model,
+        "Fact",
+        "_xlfn.LAMBDA(_xlpm.n, IF(_xlpm.n<=1, 1, _xlpm.n*FACT(_xlpm.n-1)))",
+    );
+    add_defined_name(&mut model, "Forever", "LAMBDA(n, Forever(n+1))");
+    model._set("A1", "50");
+    model._set("B1", "=MYTAX(A1)");
+    model._set("B2", "=Fact(5)");
+    model._set("B3", "=MAP({1,2,3}, MyTax)");
+    model._set("B4", "=MyTax");
+    model._set("B5", "=Forever(1)");
+    model._set("B6", "=Unknown(1)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"10");
+    assert_eq!(model._get_text("B2"), *"120");
+    assert_eq!(model._get_text("C3"), *"0.4");
+    assert_eq!(model._get_text("B4"), *"#CALC!");
+    assert_eq!(model._get_text("B5"), *"#NUM!");
+    assert_eq!(model._get_text("B6"), *"#ERROR!");
+}
+
+#[test]
+fn named_lambda_is_recomputed() {
+    let mut model = new_empty_model();
+    add_defined_name(&mut model, "Double", "LAMBDA(x, x*2+$C$1)");
+    model._set("A1", "5");
+    model._set("B1", "=Double(A1)");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("B1"), *"10");
+
+    model._set("C1", "1");
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("B1"), *"11");
+}
+
+#[test]
+fn fn_map() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("C1", "=MAP(A1:A3, LAMBDA(x, x*10))");
+    model._set("D1", "=MAP(A1:A3, {1;2;3}, LAMBDA(x, y, x+y))");
+    // The LAMBDA must return a single value
+    model._set("E1", "=MAP(A1:A2, LAMBDA(x, {1,2}))");
+    model._set("F1", "=MAP(A1:A3, 5)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"10");
+    assert_eq!(model._get_text("C3"), *"30");
+    assert_eq!(model._get_text("D2"), *"4");
+    assert_eq!(model._get_text("E1"), *"#CALC!");
+    assert_eq!(model._get_text("F1"), *"#VALUE!");
+}
+
+#[test]
+fn fn_reduce_and_scan() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("B1", "=REDUCE(0, A1:A3, LAMBDA(acc, x, acc+x*x))");
+    model._set("B2", "=REDUCE(, A1:A3, LAMBDA(acc, x, acc+x))");
+    model._set("C1", "=SCAN(1, A1:A3, LAMBDA(acc, x, acc*x))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"14");
+    assert_eq!(model._get_text("B2"), *"6");
+    assert_eq!(model._get_text("C1"), *"1");
+    assert_eq!(model._get_text("C2"), *"2");
+    assert_eq!(model._get_text("C3"), *"6");
+}
+
+#[test]
+fn fn_byrow_and_bycol() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "2");
+    model._set("A2", "3");
+    model._set("B2", "4");
+    model._set("D1", "=BYROW(A1:B2, LAMBDA(row, SUM(row)))");
+    model._set("E1", "=BYCOL(A1:B2, LAMBDA(column, MAX(column)))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("D1"), *"3");
+    assert_eq!(model._get_text("D2"), *"7");
+    assert_eq!(model._get_text("E1"), *"3");
+    assert_eq!(model._get_text("F1"), *"4");
+}
+
+#[test]
+fn fn_makearray() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MAKEARRAY(2, 3, LAMBDA(i, j, i*j))");
+    model._set("A5", "=MAKEARRAY(0, 3, LAMBDA(i, j, i*j))");
+    model._set("A6", "=MAKEARRAY(2, 2, 1)");
+    // Too many elements
+    model._set("A7", "=ROWS(MAKEARRAY(100000, 16384, LAMBDA(x, y, 1)))");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("C1"), *"3");
+    assert_eq!(model._get_text("C2"), *"6");
+    assert_eq!(model._get_text("A5"), *"#VALUE!");
+    assert_eq!(model._get_text("A6"), *"#VALUE!");
+    assert_eq!(model._get_text("A7"), *"#VALUE!");
+}
+
+#[test]
+fn xlsx_names() {
+    let mut model = new_empty_model();
+    model._set("A1", "=_xlfn.LET(_xlpm.x,2,_xlpm.x+1)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"3");
+    assert_eq!(model._get_formula("A1"), *"=LET(x,2,x+1)");
+}
@@ -0,0 +1,187 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::{assert_number, new_empty_model};
+
+// Most of the examples are taken from the Excel documentation
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SUMPRODUCT()");
+    model._set("A2", "=MMULT({1})");
+    model._set("A3", "=MINVERSE({1},{1})");
+    model._set("A4", "=MDETERM()");
+    model._set("A5", "=MUNIT()");
+    model._set("A6", "=TRANSPOSE({1},{2})");
+    model._set("A7", "=SUMX2MY2({1})");
+    model._set("A8", "=SUMX2PY2({1},{2},{3})");
+    model._set("A9", "=SUMXMY2()");
+
+    model.evaluate();
+
+    for row in 1..=9 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_sumproduct() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("B1", "10");
+    model._set("B2", "20");
+    model._set("B3", "30");
+    model._set("C1", "=SUMPRODUCT({3,4;8,6;1,9},{2,7;6,7;5,3})");
+    model._set("C2", "=SUMPRODUCT(A1:A3,B1:B3)");
+    model._set("C3", "=SUMPRODUCT((A1:A3>1)*B1:B3)");
+    // booleans are not numbers
+    model._set("C4", "=SUMPRODUCT(A1:A3>1)");
+    model._set("C5", "=SUMPRODUCT(--(A1:A3>1))");
+    model._set("C6", "=SUMPRODUCT({1,\"a\"},{2,3})");
+    model._set("C7", "=SUMPRODUCT({1,2},{1;2})");
+    model._set("C8", "=SUMPRODUCT({1,2},{1,#N/A})");
+    model._set("C9", "=SUMPRODUCT(A1:A3)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("C1"), *"156");
+    assert_eq!(model._get_text("C2"), *"140");
+    assert_eq!(model._get_text("C3"), *"50");
+    assert_eq!(model._get_text("C4"), *"0");
+    assert_eq!(model._get_text("C5"), *"2");
+    assert_eq!(model._get_text("C6"), *"2");
+    assert_eq!(model._get_text("C7"), *"#VALUE!");
+    assert_eq!(model._get_text("C8"), *"#N/A");
+    assert_eq!(model._get_text("C9"), *"6");
+}
+
+#[test]
+fn fn_mmult() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MMULT({1,3;7,2},{2,0;0,2})");
+    model._set("C1", "=MMULT({1,2,3},{1;2;3})");
+    model._set("C2", "=MMULT({1,2},{1,2})");
+    model._set("C3", "=MMULT({1,\"a\"},{1;2})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"2");
+    assert_eq!(model._get_text("B1"), *"6");
+    assert_eq!(model._get_text("A2"), *"14");
+    assert_eq!(model._get_text("B2"), *"4");
+    assert_eq!(model._get_text("C1"), *"14");
+    assert_eq!(model._get_text("C2"), *"#VALUE!");
+    assert_eq!(model._get_text("C3"), *"#VALUE!");
+}
+
+#[test]
+fn fn_minverse() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MINVERSE({4,-1;2,0})");
+    model._set("A3", "=MINVERSE({1,2,1;3,4,-1;0,2,0})");
+    model._set("A6", "=MINVERSE({1,2;2,4})");
+    model._set("A7", "=MINVERSE({1,2})");
+    model._set("A8", "=MINVERSE({1,2;3,\"\"})");
+    // the product of a matrix and its inverse is the identity
+    model._set(
+        "A9",
+        "=MMULT({2,1,1;1,3,2;1,0,0},MINVERSE({2,1,1;1,3,2;1,0,0}))",
+    );
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 0.0);
+    assert_number(&model, "B1", 0.5);
+    assert_number(&model, "A2", -1.0);
+    assert_number(&model, "B2", 2.0);
+    let expected = [[0.25, 0.25, -0.75], [0.0, 0.0, 0.5], [0.75, -0.25, -0.25]];
+    for (row, values) in expected.iter().enumerate() {
+        for (column, value) in ["A", "B", "C"].iter().zip(values) {
+            assert_number(&model, &format!("{column}{}", row + 3), *value);
+        }
+    }
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"#VALUE!");
+    assert_eq!(model._get_text("A8"), *"#VALUE!");
+    for (row, values) in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        .iter()
+        .enumerate()
+    {
+        for (column, value) in ["A", "B", "C"].iter().zip(values) {
+            assert_number(&model, &format!("{column}{}", row + 9), *value);
+        }
+    }
+}
+
+#[test]
+fn fn_mdeterm() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MDETERM({1,3,8,5;1,3,6,1;1,1,1,0;7,3,10,2})");
+    model._set("A2", "=MDETERM({3,6;1,1})");
+    model._set("A3", "=MDETERM({1,2;2,4})");
+    model._set("A4", "=MDETERM({5})");
+    model._set("A5", "=MDETERM({1,2,3})");
+    model._set("A6", "=MDETERM({0,1;1,0})");
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 88.0);
+    assert_number(&model, "A2", -3.0);
+    assert_number(&model, "A3", 0.0);
+    assert_number(&model, "A4", 5.0);
+    assert_eq!(model._get_text("A5"), *"#VALUE!");
+    assert_number(&model, "A6", -1.0);
+}
+
+#[test]
+fn fn_munit_and_transpose() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MUNIT(3)");
+    model._set("A4", "=MUNIT(0)");
+    model._set("B4", "=ROWS(MUNIT(16384))");
+    model._set("E1", "=TRANSPOSE({1,2,3;\"a\",TRUE,4})");
+    model._set("H1", "1");
+    model._set("H2", "2");
+    model._set("I1", "=TRANSPOSE(H1:H2)");
+
+    model.evaluate();
+
+    for row in 1..=3 {
+        for column in 1..=3 {
+            let expected = if row == column { "1" } else { "0" };
+            assert_eq!(model._get_text_at(0, row, column), *expected);
+        }
+    }
+    assert_eq!(model._get_text("A4"), *"#VALUE!");
+    assert_eq!(model._get_text("B4"), *"#VALUE!");
+    assert_eq!(model._get_text("E1"), *"1");
+    assert_eq!(model._get_text("F1"), *"a");
+    assert_eq!(model._get_text("E2"), *"2");
+    assert_eq!(model._get_text("F2"), *"TRUE");
+    assert_eq!(model._get_text("E3"), *"3");
+    assert_eq!(model._get_text("F3"), *"4");
+    assert_eq!(model._get_text("I1"), *"1");
+    assert_eq!(model._get_text("J1"), *"2");
+}
+
+#[test]
+fn fn_sums_of_squares() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SUMX2MY2({2,3,9,1,8,7,5},{6,5,11,7,5,4,4})");
+    model._set("A2", "=SUMX2PY2({2,3,9,1,8,7,5},{6,5,11,7,5,4,4})");
+    model._set("A3", "=SUMXMY2({2,3,9,1,8,7,5},{6,5,11,7,5,4,4})");
+    model._set("A4", "=SUMXMY2({1,2},{1})");
+    model._set("A5", "=SUMXMY2({1,\"a\"},{3,4})");
+    model._set("A6", "=SUMX2PY2({\"a\"},{\"b\"})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"-55");
+    assert_eq!(model._get_text("A2"), *"521");
+    assert_eq!(model._get_text("A3"), *"79");
+    assert_eq!(model._get_text("A4"), *"#N/A");
+    assert_eq!(model._get_text("A5"), *"4");
+    assert_eq!(model._get_text("A6"), *"#DIV/0!");
+}
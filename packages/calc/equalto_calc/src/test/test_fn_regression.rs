@@ -0,0 +1,307 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::{assert_number, new_empty_model, set_column};
+
+// Expected values of the regressions were computed with 40 digit arithmetic, several examples are
+// taken from the Excel documentation
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SLOPE(1)");
+    model._set("A2", "=CORREL(1,2,3)");
+    model._set("A3", "=FORECAST.LINEAR(1,2)");
+    model._set("A4", "=LINEST()");
+    model._set("A5", "=TREND(1,2,3,4,5)");
+    model._set("A6", "=COVARIANCE.S(1)");
+
+    model.evaluate();
+
+    for row in 1..=6 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_pair_statistics() {
+    let mut model = new_empty_model();
+    set_column(
+        &mut model,
+        "A",
+        &["2", "3", "9", "1", "8", "7", "5", "text", "4"],
+    );
+    set_column(&mut model, "B", &["6", "5", "11", "7", "5", "4", "4", "3"]);
+    model._set("C1", "=SLOPE(A1:A9,B1:B9)");
+    model._set("C2", "=INTERCEPT(A1:A9,B1:B9)");
+    model._set("C3", "=RSQ(A1:A9,B1:B9)");
+    model._set("C4", "=CORREL(A1:A9,B1:B9)");
+    model._set("C5", "=PEARSON(A1:A9,B1:B9)");
+    model._set("C6", "=STEYX(A1:A9,B1:B9)");
+    model._set("C7", "=COVARIANCE.P(A1:A9,B1:B9)");
+    model._set("C8", "=COVAR(A1:A9,B1:B9)");
+    model._set("C9", "=COVARIANCE.S(A1:A9,B1:B9)");
+    model._set("C10", "=FORECAST.LINEAR(30,A1:A9,B1:B9)");
+    model._set("C11", "=FORECAST(30,A1:A9,B1:B9)");
+
+    model.evaluate();
+
+    // the pairs with text or an empty cell are ignored
+    assert_number(&model, "C1", 0.305_555_555_555_555_6);
+    assert_number(&model, "C2", 3.1666666666666667);
+    assert_number(&model, "C3", 0.057_950_191_570_881_23);
+    assert_number(&model, "C4", 0.24072846024282469);
+    assert_number(&model, "C5", 0.24072846024282469);
+    assert_number(&model, "C6", 3.3057189502100413);
+    assert_number(&model, "C7", 1.5714285714285714);
+    assert_number(&model, "C8", 1.5714285714285714);
+    assert_number(&model, "C9", 1.8333333333333333);
+    assert_number(&model, "C10", 12.333333333333333);
+    assert_number(&model, "C11", 12.333333333333333);
+}
+
+#[test]
+fn fn_pair_statistics_errors() {
+    let mut model = new_empty_model();
+    model._set("A1", "=SLOPE({1,2,3},{1,2})");
+    model._set("A2", "=SLOPE({1,2,3},{2,2,2})");
+    model._set("A3", "=CORREL({1,1,1},{1,2,3})");
+    model._set("A4", "=STEYX({1,2},{1,2})");
+    model._set("A5", "=COVARIANCE.S({1},{2})");
+    model._set("B1", "1");
+    model._set("C1", "=1/0");
+    model._set("A6", "=COVARIANCE.P({1,2},B1:C1)");
+    model._set("A7", "=INTERCEPT({\"a\"},{\"b\"})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#N/A");
+    assert_eq!(model._get_text("A2"), *"#DIV/0!");
+    assert_eq!(model._get_text("A3"), *"#DIV/0!");
+    assert_eq!(model._get_text("A4"), *"#DIV/0!");
+    assert_eq!(model._get_text("A5"), *"#DIV/0!");
+    assert_eq!(model._get_text("A6"), *"#DIV/0!");
+    assert_eq!(model._get_text("A7"), *"#DIV/0!");
+}
+
+#[test]
+fn fn_linest_multiple_variables() {
+    let mut model = new_empty_model();
+    // floor space, offices, entrances, age and assessed value of office buildings
+    let rows = [
+        ["2310", "2", "2", "20", "142000"],
+        ["2333", "2", "2", "12", "144000"],
+        ["2356", "3", "1.5", "33", "151000"],
+        ["2379", "3", "2", "43", "150000"],
+        ["2402", "2", "3", "53", "139000"],
+        ["2425", "4", "2", "23", "169000"],
+        ["2448", "2", "1.5", "99", "126000"],
+        ["2471", "2", "2", "34", "142900"],
+        ["2494", "3", "3", "23", "163000"],
+        ["2517", "4", "4", "55", "169000"],
+        ["2540", "2", "3", "22", "149000"],
+    ];
+    for (row, values) in rows.iter().enumerate() {
+        for (column, value) in ["A", "B", "C", "D", "E"].iter().zip(values) {
+            model._set(&format!("{column}{}", row + 1), value);
+        }
+    }
+    model._set("G1", "=LINEST(E1:E11,A1:D11,TRUE,TRUE)");
+    model._set("G7", "=TREND(E1:E11,A1:D11,{2500,3,2,25})");
+
+    model.evaluate();
+
+    let expected = [
+        [
+            -234.23716447120242,
+            2_553.210_660_391_538,
+            12529.768167086751,
+            27.641387366020284,
+            52_317.830_507_291_32,
+        ],
+        [
+            13.268011475500364,
+            530.669_151_930_378_3,
+            400.066_838_193_953_1,
+            5.429_374_041_545_316,
+            12237.361602862353,
+        ],
+    ];
+    for (row, values) in expected.iter().enumerate() {
+        for (column, value) in ["G", "H", "I", "J", "K"].iter().zip(values) {
+            assert_number(&model, &format!("{column}{}", row + 1), *value);
+        }
+    }
+    assert_number(&model, "G3", 0.9967479933845101);
+    assert_number(&model, "H3", 970.578_462_928_506_3);
+    assert_number(&model, "G4", 459.75367422539247);
+    assert_number(&model, "H4", 6.0);
+    assert_number(&model, "G5", 1732393319.2292506);
+    assert_number(&model, "H5", 5_652_135.316_203_97);
+    for row in 3..=5 {
+        for column in ["I", "J", "K"] {
+            assert_eq!(model._get_text(&format!("{column}{row}")), *"#N/A");
+        }
+    }
+    assert_number(&model, "G7", 158261.0956326053);
+}
+
+#[test]
+fn fn_linest_single_variable() {
+    let mut model = new_empty_model();
+    model._set("A1", "=LINEST({3,5,4,7,9},{1,2,3,4,5},TRUE,TRUE)");
+    // without known x's we use {1,2,3,...}
+    model._set("D1", "=LINEST({3;5;4;7;9})");
+    model._set("A7", "=LINEST({1,9,5,8},{0,4,2,3},FALSE,TRUE)");
+    // exact fit
+    model._set("D7", "=LINEST({1,9,5,7},{0,4,2,3},,TRUE)");
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 1.4);
+    assert_number(&model, "B1", 1.4);
+    assert_number(&model, "A2", 0.34641016151377546);
+    assert_number(&model, "B2", 1.1489125293076057);
+    assert_number(&model, "A3", 0.844_827_586_206_896_6);
+    assert_number(&model, "B3", 1.0954451150103322);
+    assert_number(&model, "A4", 16.333333333333333);
+    assert_number(&model, "B4", 3.0);
+    assert_number(&model, "A5", 19.6);
+    assert_number(&model, "B5", 3.6);
+
+    assert_number(&model, "D1", 1.4);
+    assert_number(&model, "E1", 1.4);
+    assert_eq!(model._get_text("D2"), *"");
+
+    assert_number(&model, "A7", 2.413_793_103_448_276);
+    assert_number(&model, "B7", 0.0);
+    assert_number(&model, "A8", 0.15292108845574794);
+    assert_eq!(model._get_text("B8"), *"#N/A");
+    assert_number(&model, "A9", 0.988_102_440_008_066_1);
+    assert_number(&model, "B9", 0.823_505_263_820_596_3);
+    assert_number(&model, "A10", 249.15254237288136);
+    assert_number(&model, "B10", 3.0);
+    assert_number(&model, "A11", 168.965_517_241_379_3);
+    assert_number(&model, "B11", 2.0344827586206897);
+
+    assert_number(&model, "D7", 2.0);
+    assert_number(&model, "E7", 1.0);
+    assert_number(&model, "D8", 0.0);
+    assert_number(&model, "D9", 1.0);
+    assert_eq!(model._get_text("D10"), *"#NUM!");
+    assert_number(&model, "E10", 2.0);
+}
+
+#[test]
+fn fn_logest_and_growth() {
+    let mut model = new_empty_model();
+    set_column(&mut model, "A", &["11", "12", "13", "14", "15", "16"]);
+    set_column(
+        &mut model,
+        "B",
+        &["33100", "47300", "69000", "102000", "150000", "220000"],
+    );
+    model._set("C1", "=LOGEST(B1:B6,A1:A6,TRUE,TRUE)");
+    model._set("C7", "=GROWTH(B1:B6,A1:A6,{17;18})");
+
+    model.evaluate();
+
+    assert_number(&model, "C1", 1.4632756281161759);
+    assert_number(&model, "D1", 495.30477015872754);
+    assert_number(&model, "C2", 0.0026334028914250613);
+    assert_number(&model, "D2", 0.035834282435717627);
+    assert_number(&model, "C3", 0.999_808_619_775_817_6);
+    assert_number(&model, "D3", 0.011016314665073015);
+    assert_number(&model, "C4", 20_896.801_099_420_32);
+    assert_number(&model, "D4", 4.0);
+    assert_number(&model, "C5", 2.5360188299385667);
+    assert_number(&model, "D5", 0.00048543675519961111);
+    assert_number(&model, "C7", 320_196.718_363_473_2);
+    assert_number(&model, "C8", 468_536.054_184_049_5);
+}
+
+#[test]
+fn fn_regression_statistics_with_index() {
+    let mut model = new_empty_model();
+    set_column(&mut model, "A", &["1", "2", "3", "4", "5"]);
+    set_column(&mut model, "B", &["3", "5", "4", "7", "9"]);
+    set_column(&mut model, "C", &["11", "12", "13", "14", "15", "16"]);
+    set_column(
+        &mut model,
+        "D",
+        &["33100", "47300", "69000", "102000", "150000", "220000"],
+    );
+    // slope, standard error of the slope, r², F and the sum of squares of the residuals
+    model._set("E1", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),1,1)");
+    model._set("E2", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),2,1)");
+    model._set("E3", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),3,1)");
+    model._set("E4", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),4,1)");
+    model._set("E5", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),5,2)");
+    // standard error of the intercept and of the estimate
+    model._set("E6", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),2,2)");
+    model._set("E7", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),3,2)");
+    // without statistics LINEST returns a single row
+    model._set("E8", "=INDEX(LINEST(B1:B5,A1:A5),2)");
+    model._set("E9", "=INDEX(LINEST(B1:B5,A1:A5,TRUE,TRUE),6,1)");
+    model._set("F1", "=INDEX(LOGEST(D1:D6,C1:C6),1,1)");
+    model._set("F2", "=INDEX(LOGEST(D1:D6,C1:C6,TRUE,TRUE),3,1)");
+    model._set("F3", "=INDEX(LOGEST(D1:D6,C1:C6,TRUE,TRUE),2,2)");
+    model._set("F4", "=INDEX(LOGEST(D1:D6,C1:C6,TRUE,TRUE),4,1)");
+
+    model.evaluate();
+
+    assert_number(&model, "E1", 1.4);
+    assert_number(&model, "E2", 0.34641016151377546);
+    assert_number(&model, "E3", 0.844_827_586_206_896_6);
+    assert_number(&model, "E4", 16.333333333333333);
+    assert_number(&model, "E5", 3.6);
+    assert_number(&model, "E6", 1.1489125293076057);
+    assert_number(&model, "E7", 1.0954451150103322);
+    assert_number(&model, "E8", 1.4);
+    assert_eq!(model._get_text("E9"), *"#REF!");
+    assert_number(&model, "F1", 1.4632756281161759);
+    assert_number(&model, "F2", 0.999_808_619_775_817_6);
+    assert_number(&model, "F3", 0.035834282435717627);
+    assert_number(&model, "F4", 20_896.801_099_420_32);
+}
+
+#[test]
+fn fn_trend() {
+    let mut model = new_empty_model();
+    model._set("A1", "=TREND({3,5,4,7,9})");
+    model._set("A2", "=TREND({3,5,4,7,9},{1,2,3,4,5},{6,7})");
+    model._set("A3", "=TREND({3,5,4,7,9},,{6;7})");
+    model._set("A5", "=TREND({1,9,5,8},{0,4,2,3},5,FALSE)");
+
+    model.evaluate();
+
+    assert_number(&model, "A1", 2.8);
+    assert_number(&model, "B1", 4.2);
+    assert_number(&model, "C1", 5.6);
+    assert_number(&model, "D1", 7.0);
+    assert_number(&model, "E1", 8.4);
+    assert_number(&model, "A2", 9.8);
+    assert_number(&model, "B2", 11.2);
+    assert_number(&model, "A3", 9.8);
+    assert_number(&model, "A4", 11.2);
+    assert_number(&model, "A5", 12.068965517241379);
+}
+
+#[test]
+fn fn_regression_errors() {
+    let mut model = new_empty_model();
+    model._set("A1", "=LOGEST({1,-2,3})");
+    model._set("A2", "=GROWTH({1,0,3})");
+    model._set("A3", "=LINEST({1,2,3},{1,2})");
+    model._set("A4", "=LINEST({1;2;3},{1,2;3,4})");
+    model._set("A5", "=LINEST({1,\"a\",3})");
+    model._set("A6", "=TREND({1;2;3},{1,2;3,4;5,7},{1,2,3})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"#NUM!");
+    assert_eq!(model._get_text("A2"), *"#NUM!");
+    assert_eq!(model._get_text("A3"), *"#REF!");
+    assert_eq!(model._get_text("A4"), *"#REF!");
+    assert_eq!(model._get_text("A5"), *"#VALUE!");
+    assert_eq!(model._get_text("A6"), *"#REF!");
+}
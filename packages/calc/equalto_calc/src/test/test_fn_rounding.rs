@@ -0,0 +1,276 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+// Most of the examples are taken from the Excel documentation
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=CEILING(1)");
+    model._set("A2", "=FLOOR(1,2,3)");
+    model._set("A3", "=CEILING.MATH()");
+    model._set("A4", "=FLOOR.MATH(1,2,3,4)");
+    model._set("A5", "=CEILING.PRECISE(1,2,3)");
+    model._set("A6", "=FLOOR.PRECISE()");
+    model._set("A7", "=MROUND(1)");
+    model._set("A8", "=INT()");
+    model._set("A9", "=TRUNC(1,2,3)");
+    model._set("A10", "=MOD(1)");
+    model._set("A11", "=QUOTIENT(1,2,3)");
+    model._set("A12", "=GCD()");
+    model._set("A13", "=LCM()");
+    model._set("A14", "=EVEN()");
+    model._set("A15", "=ODD(1,2)");
+    model._set("A16", "=SIGN()");
+
+    model.evaluate();
+
+    for row in 1..=16 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_ceiling_floor() {
+    let mut model = new_empty_model();
+    model._set("A1", "=CEILING(2.5,1)");
+    model._set("A2", "=CEILING(-2.5,-2)");
+    model._set("A3", "=CEILING(-2.5,2)");
+    model._set("A4", "=CEILING(1.5,0.1)");
+    model._set("A5", "=CEILING(0.234,0.01)");
+    model._set("A6", "=CEILING(2.5,-2)");
+    model._set("A7", "=CEILING(2.5,0)");
+    model._set("A8", "=CEILING(1.1,0.1)");
+
+    model._set("B1", "=FLOOR(3.7,2)");
+    model._set("B2", "=FLOOR(-2.5,-2)");
+    model._set("B3", "=FLOOR(2.5,-2)");
+    model._set("B4", "=FLOOR(1.58,0.1)");
+    model._set("B5", "=FLOOR(0.234,0.01)");
+    model._set("B6", "=FLOOR(-2.5,2)");
+    model._set("B7", "=FLOOR(2.5,0)");
+    model._set("B8", "=FLOOR(0,0)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"3");
+    assert_eq!(model._get_text("A2"), *"-4");
+    assert_eq!(model._get_text("A3"), *"-2");
+    assert_eq!(model._get_text("A4"), *"1.5");
+    assert_eq!(model._get_text("A5"), *"0.24");
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"0");
+    assert_eq!(model._get_text("A8"), *"1.1");
+
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("B2"), *"-2");
+    assert_eq!(model._get_text("B3"), *"#NUM!");
+    assert_eq!(model._get_text("B4"), *"1.5");
+    assert_eq!(model._get_text("B5"), *"0.23");
+    assert_eq!(model._get_text("B6"), *"-4");
+    assert_eq!(model._get_text("B7"), *"#DIV/0!");
+    assert_eq!(model._get_text("B8"), *"0");
+}
+
+#[test]
+fn fn_ceiling_floor_math_and_precise() {
+    let mut model = new_empty_model();
+    model._set("A1", "=CEILING.MATH(24.3,5)");
+    model._set("A2", "=CEILING.MATH(6.7)");
+    model._set("A3", "=CEILING.MATH(-8.1,2)");
+    model._set("A4", "=CEILING.MATH(-5.5,2,-1)");
+    model._set("A5", "=CEILING.MATH(4.3,-2)");
+    model._set("A6", "=CEILING.MATH(4.3,0)");
+
+    model._set("B1", "=FLOOR.MATH(24.3,5)");
+    model._set("B2", "=FLOOR.MATH(6.7)");
+    model._set("B3", "=FLOOR.MATH(-8.1,2)");
+    model._set("B4", "=FLOOR.MATH(-5.5,2,-1)");
+
+    model._set("C1", "=CEILING.PRECISE(4.3)");
+    model._set("C2", "=CEILING.PRECISE(-4.3)");
+    model._set("C3", "=CEILING.PRECISE(4.3,2)");
+    model._set("C4", "=CEILING.PRECISE(4.3,-2)");
+    model._set("C5", "=CEILING.PRECISE(-4.3,2)");
+    model._set("C6", "=CEILING.PRECISE(-4.3,-2)");
+
+    model._set("D1", "=FLOOR.PRECISE(-3.2,-1)");
+    model._set("D2", "=FLOOR.PRECISE(3.2,1)");
+    model._set("D3", "=FLOOR.PRECISE(-3.2,1)");
+    model._set("D4", "=FLOOR.PRECISE(3.2,-1)");
+    model._set("D5", "=FLOOR.PRECISE(3.2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"25");
+    assert_eq!(model._get_text("A2"), *"7");
+    assert_eq!(model._get_text("A3"), *"-8");
+    assert_eq!(model._get_text("A4"), *"-6");
+    assert_eq!(model._get_text("A5"), *"6");
+    assert_eq!(model._get_text("A6"), *"0");
+
+    assert_eq!(model._get_text("B1"), *"20");
+    assert_eq!(model._get_text("B2"), *"6");
+    assert_eq!(model._get_text("B3"), *"-10");
+    assert_eq!(model._get_text("B4"), *"-4");
+
+    assert_eq!(model._get_text("C1"), *"5");
+    assert_eq!(model._get_text("C2"), *"-4");
+    assert_eq!(model._get_text("C3"), *"6");
+    assert_eq!(model._get_text("C4"), *"6");
+    assert_eq!(model._get_text("C5"), *"-4");
+    assert_eq!(model._get_text("C6"), *"-4");
+
+    assert_eq!(model._get_text("D1"), *"-4");
+    assert_eq!(model._get_text("D2"), *"3");
+    assert_eq!(model._get_text("D3"), *"-4");
+    assert_eq!(model._get_text("D4"), *"3");
+    assert_eq!(model._get_text("D5"), *"3");
+}
+
+#[test]
+fn fn_mround_int_trunc() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MROUND(10,3)");
+    model._set("A2", "=MROUND(-10,-3)");
+    model._set("A3", "=MROUND(1.3,0.2)");
+    model._set("A4", "=MROUND(5,-2)");
+    model._set("A5", "=MROUND(5,0)");
+
+    model._set("B1", "=INT(8.9)");
+    model._set("B2", "=INT(-8.9)");
+
+    model._set("C1", "=TRUNC(8.9)");
+    model._set("C2", "=TRUNC(-8.9)");
+    model._set("C3", "=TRUNC(0.45)");
+    model._set("C4", "=TRUNC(3.14159,2)");
+    model._set("C5", "=TRUNC(1234.5,-2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"9");
+    assert_eq!(model._get_text("A2"), *"-9");
+    assert_eq!(model._get_text("A3"), *"1.4");
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_eq!(model._get_text("A5"), *"0");
+
+    assert_eq!(model._get_text("B1"), *"8");
+    assert_eq!(model._get_text("B2"), *"-9");
+
+    assert_eq!(model._get_text("C1"), *"8");
+    assert_eq!(model._get_text("C2"), *"-8");
+    assert_eq!(model._get_text("C3"), *"0");
+    assert_eq!(model._get_text("C4"), *"3.14");
+    assert_eq!(model._get_text("C5"), *"1200");
+}
+
+#[test]
+fn fn_mod_quotient() {
+    let mut model = new_empty_model();
+    model._set("A1", "=MOD(3,2)");
+    model._set("A2", "=MOD(-3,2)");
+    model._set("A3", "=MOD(3,-2)");
+    model._set("A4", "=MOD(-3,-2)");
+    model._set("A5", "=MOD(3,0)");
+    model._set("A6", "=MOD(5.5,2)");
+
+    model._set("B1", "=QUOTIENT(5,2)");
+    model._set("B2", "=QUOTIENT(4.5,3.1)");
+    model._set("B3", "=QUOTIENT(-10,3)");
+    model._set("B4", "=QUOTIENT(1,0)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("A2"), *"1");
+    assert_eq!(model._get_text("A3"), *"-1");
+    assert_eq!(model._get_text("A4"), *"-1");
+    assert_eq!(model._get_text("A5"), *"#DIV/0!");
+    assert_eq!(model._get_text("A6"), *"1.5");
+
+    assert_eq!(model._get_text("B1"), *"2");
+    assert_eq!(model._get_text("B2"), *"1");
+    assert_eq!(model._get_text("B3"), *"-3");
+    assert_eq!(model._get_text("B4"), *"#DIV/0!");
+}
+
+#[test]
+fn fn_gcd_lcm() {
+    let mut model = new_empty_model();
+    model._set("A1", "24");
+    model._set("A2", "36");
+    model._set("A3", "text");
+
+    model._set("B1", "=GCD(5,2)");
+    model._set("B2", "=GCD(24,36)");
+    model._set("B3", "=GCD(7,1)");
+    model._set("B4", "=GCD(5,0)");
+    model._set("B5", "=GCD(A1:A3,18.9)");
+    model._set("B6", "=GCD(-1,2)");
+    model._set("B7", "=GCD(0)");
+
+    model._set("C1", "=LCM(5,2)");
+    model._set("C2", "=LCM(24,36)");
+    model._set("C3", "=LCM(A1:A3,5)");
+    model._set("C4", "=LCM(3,0)");
+    model._set("C5", "=LCM(-3,2)");
+    model._set("C6", "=LCM({2,3,4})");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("B1"), *"1");
+    assert_eq!(model._get_text("B2"), *"12");
+    assert_eq!(model._get_text("B3"), *"1");
+    assert_eq!(model._get_text("B4"), *"5");
+    assert_eq!(model._get_text("B5"), *"6");
+    assert_eq!(model._get_text("B6"), *"#NUM!");
+    assert_eq!(model._get_text("B7"), *"0");
+
+    assert_eq!(model._get_text("C1"), *"10");
+    assert_eq!(model._get_text("C2"), *"72");
+    assert_eq!(model._get_text("C3"), *"360");
+    assert_eq!(model._get_text("C4"), *"0");
+    assert_eq!(model._get_text("C5"), *"#NUM!");
+    assert_eq!(model._get_text("C6"), *"12");
+}
+
+#[test]
+fn fn_even_odd_sign() {
+    let mut model = new_empty_model();
+    model._set("A1", "=EVEN(1.5)");
+    model._set("A2", "=EVEN(3)");
+    model._set("A3", "=EVEN(2)");
+    model._set("A4", "=EVEN(-1)");
+    model._set("A5", "=EVEN(0)");
+
+    model._set("B1", "=ODD(1.5)");
+    model._set("B2", "=ODD(3)");
+    model._set("B3", "=ODD(2)");
+    model._set("B4", "=ODD(-1)");
+    model._set("B5", "=ODD(-2)");
+    model._set("B6", "=ODD(0)");
+
+    model._set("C1", "=SIGN(10)");
+    model._set("C2", "=SIGN(4-4)");
+    model._set("C3", "=SIGN(-0.00001)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"2");
+    assert_eq!(model._get_text("A2"), *"4");
+    assert_eq!(model._get_text("A3"), *"2");
+    assert_eq!(model._get_text("A4"), *"-2");
+    assert_eq!(model._get_text("A5"), *"0");
+
+    assert_eq!(model._get_text("B1"), *"3");
+    assert_eq!(model._get_text("B2"), *"3");
+    assert_eq!(model._get_text("B3"), *"3");
+    assert_eq!(model._get_text("B4"), *"-1");
+    assert_eq!(model._get_text("B5"), *"-3");
+    assert_eq!(model._get_text("B6"), *"1");
+
+    assert_eq!(model._get_text("C1"), *"1");
+    assert_eq!(model._get_text("C2"), *"0");
+    assert_eq!(model._get_text("C3"), *"-1");
+}
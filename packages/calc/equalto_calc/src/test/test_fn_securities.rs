@@ -0,0 +1,251 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::{assert_number_with_tolerance, new_empty_model};
+
+// The examples are taken from the Excel documentation
+
+#[test]
+fn fn_arguments() {
+    let mut model = new_empty_model();
+    model._set("A1", "=ACCRINT(1,2,3,4,5)");
+    model._set("A2", "=ACCRINTM(1,2,3)");
+    model._set("A3", "=COUPDAYBS(1,2)");
+    model._set("A4", "=COUPDAYS(1,2,3,4,5)");
+    model._set("A5", "=COUPDAYSNC(1,2)");
+    model._set("A6", "=COUPNCD(1,2)");
+    model._set("A7", "=COUPNUM(1,2)");
+    model._set("A8", "=COUPPCD(1,2)");
+    model._set("A9", "=DISC(1,2,3)");
+    model._set("A10", "=DURATION(1,2,3,4)");
+    model._set("A11", "=INTRATE(1,2,3)");
+    model._set("A12", "=MDURATION(1,2,3,4,5,6,7)");
+    model._set("A13", "=PRICE(1,2,3,4,5)");
+    model._set("A14", "=PRICEDISC(1,2,3)");
+    model._set("A15", "=PRICEMAT(1,2,3,4)");
+    model._set("A16", "=RECEIVED(1,2,3)");
+    model._set("A17", "=YIELD(1,2,3,4,5)");
+    model._set("A18", "=YIELDDISC(1,2,3)");
+    model._set("A19", "=YIELDMAT(1,2,3,4)");
+
+    model.evaluate();
+
+    for row in 1..=19 {
+        assert_eq!(model._get_text(&format!("A{row}")), *"#ERROR!");
+    }
+}
+
+#[test]
+fn fn_coupon_functions() {
+    let mut model = new_empty_model();
+    model._set("A1", "=COUPDAYBS(DATE(2011,1,25),DATE(2011,11,15),2,1)");
+    model._set("A2", "=COUPDAYS(DATE(2011,1,25),DATE(2011,11,15),2,1)");
+    model._set("A3", "=COUPDAYSNC(DATE(2011,1,25),DATE(2011,11,15),2,1)");
+    model._set("A4", "=COUPNCD(DATE(2011,1,25),DATE(2011,11,15),2,1)");
+    model._set("A5", "=COUPNUM(DATE(2007,1,25),DATE(2008,11,15),2,1)");
+    model._set("A6", "=COUPPCD(DATE(2011,1,25),DATE(2011,11,15),2,1)");
+    // 30/360
+    model._set("B1", "=COUPDAYBS(DATE(2011,1,25),DATE(2011,11,15),2,0)");
+    model._set("B2", "=COUPDAYS(DATE(2011,1,25),DATE(2011,11,15),2,0)");
+    model._set("B3", "=COUPDAYSNC(DATE(2011,1,25),DATE(2011,11,15),2,0)");
+    model._set("B4", "=COUPDAYS(DATE(2011,1,25),DATE(2011,11,15),4,3)");
+    // maturity at the end of the month
+    model._set("C1", "=COUPPCD(DATE(2011,4,15),DATE(2011,11,30),4)");
+    model._set("C2", "=COUPNCD(DATE(2011,4,15),DATE(2011,11,30),4)");
+    model._set("C3", "=COUPNUM(DATE(2011,5,31),DATE(2011,11,30),4)");
+    // errors
+    model._set("D1", "=COUPNUM(DATE(2011,11,15),DATE(2011,11,15),2)");
+    model._set("D2", "=COUPNUM(DATE(2011,1,25),DATE(2011,11,15),3)");
+    model._set("D3", "=COUPNUM(DATE(2011,1,25),DATE(2011,11,15),2,5)");
+    model._set("D4", "=COUPNUM(-1,DATE(2011,11,15),2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"71");
+    assert_eq!(model._get_text("A2"), *"181");
+    assert_eq!(model._get_text("A3"), *"110");
+    assert_eq!(model._get_text("A4"), *"40678");
+    assert_eq!(model._get_text("A5"), *"4");
+    assert_eq!(model._get_text("A6"), *"40497");
+
+    assert_eq!(model._get_text("B1"), *"70");
+    assert_eq!(model._get_text("B2"), *"180");
+    assert_eq!(model._get_text("B3"), *"110");
+    assert_eq!(model._get_text("B4"), *"91.25");
+
+    // DATE(2011,2,28) and DATE(2011,5,31)
+    assert_eq!(model._get_text("C1"), *"40602");
+    assert_eq!(model._get_text("C2"), *"40694");
+    assert_eq!(model._get_text("C3"), *"2");
+
+    assert_eq!(model._get_text("D1"), *"#NUM!");
+    assert_eq!(model._get_text("D2"), *"#NUM!");
+    assert_eq!(model._get_text("D3"), *"#NUM!");
+    assert_eq!(model._get_text("D4"), *"#NUM!");
+}
+
+#[test]
+fn fn_price_yield() {
+    let mut model = new_empty_model();
+    model._set(
+        "A1",
+        "=PRICE(DATE(2008,2,15),DATE(2017,11,15),0.0575,0.065,100,2,0)",
+    );
+    model._set(
+        "A2",
+        "=YIELD(DATE(2008,2,15),DATE(2016,11,15),0.0575,95.04287,100,2,0)",
+    );
+    // the yield of the price is the original yield
+    model._set(
+        "A3",
+        "=YIELD(DATE(2008,2,15),DATE(2017,11,15),0.0575,A1,100,2,0)",
+    );
+    // a single coupon period left
+    model._set(
+        "A4",
+        "=PRICE(DATE(2008,2,15),DATE(2008,6,1),0.05,0.04,100,2,1)",
+    );
+    model._set(
+        "A5",
+        "=YIELD(DATE(2008,2,15),DATE(2008,6,1),0.05,A4,100,2,1)",
+    );
+    model._set(
+        "A6",
+        "=PRICE(DATE(2008,2,15),DATE(2017,11,15),-0.0575,0.065,100,2)",
+    );
+    model._set(
+        "A7",
+        "=YIELD(DATE(2008,2,15),DATE(2016,11,15),0.0575,0,100,2)",
+    );
+
+    model.evaluate();
+
+    assert_number_with_tolerance(&model, "A1", 94.63436162, 1e-8);
+    assert_number_with_tolerance(&model, "A2", 0.065, 1e-6);
+    assert_number_with_tolerance(&model, "A3", 0.065, 1e-9);
+    assert_number_with_tolerance(&model, "A5", 0.04, 1e-9);
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+}
+
+#[test]
+fn fn_duration() {
+    let mut model = new_empty_model();
+    model._set(
+        "A1",
+        "=DURATION(DATE(2018,7,1),DATE(2048,1,1),0.08,0.09,2,1)",
+    );
+    model._set(
+        "A2",
+        "=DURATION(DATE(2008,1,1),DATE(2016,1,1),0.08,0.09,2,1)",
+    );
+    model._set(
+        "A3",
+        "=MDURATION(DATE(2008,1,1),DATE(2016,1,1),0.08,0.09,2,1)",
+    );
+    model._set(
+        "A4",
+        "=DURATION(DATE(2008,1,1),DATE(2016,1,1),0.08,0.09,5,1)",
+    );
+
+    model.evaluate();
+
+    assert_number_with_tolerance(&model, "A1", 10.9191453, 1e-7);
+    assert_number_with_tolerance(&model, "A2", 5.993775, 1e-6);
+    assert_number_with_tolerance(&model, "A3", 5.73567, 1e-5);
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+}
+
+#[test]
+fn fn_accrued_interest() {
+    let mut model = new_empty_model();
+    model._set(
+        "A1",
+        "=ACCRINT(DATE(2008,3,1),DATE(2008,8,31),DATE(2008,5,1),0.1,1000,2,0)",
+    );
+    model._set(
+        "A2",
+        "=ACCRINT(DATE(2008,3,5),DATE(2008,8,31),DATE(2008,5,1),0.1,1000,2,0,TRUE)",
+    );
+    model._set(
+        "A3",
+        "=ACCRINT(DATE(2008,3,5),DATE(2008,3,31),DATE(2008,5,1),0.1,1000,2,0,FALSE)",
+    );
+    model._set(
+        "A4",
+        "=ACCRINT(DATE(2008,5,1),DATE(2008,8,31),DATE(2008,3,1),0.1,1000,2)",
+    );
+    model._set("A5", "=ACCRINTM(DATE(2008,4,1),DATE(2008,6,15),0.1,1000,3)");
+    model._set("A6", "=ACCRINTM(DATE(2008,4,1),DATE(2008,6,15),0,1000,3)");
+
+    model.evaluate();
+
+    assert_number_with_tolerance(&model, "A1", 16.66666667, 1e-8);
+    assert_number_with_tolerance(&model, "A2", 15.55555556, 1e-8);
+    // 31 days (30/360) at 10%
+    assert_number_with_tolerance(&model, "A3", 8.611111111, 1e-8);
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_number_with_tolerance(&model, "A5", 20.54794521, 1e-8);
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+}
+
+#[test]
+fn fn_discounted_securities() {
+    let mut model = new_empty_model();
+    model._set("A1", "=DISC(DATE(2007,1,25),DATE(2007,6,15),97.975,100,1)");
+    model._set(
+        "A2",
+        "=INTRATE(DATE(2008,2,15),DATE(2008,5,15),1000000,1014420,2)",
+    );
+    model._set(
+        "A3",
+        "=PRICEDISC(DATE(2008,2,16),DATE(2008,3,1),0.0525,100,2)",
+    );
+    model._set(
+        "A4",
+        "=RECEIVED(DATE(2008,2,15),DATE(2008,5,15),1000000,0.0575,2)",
+    );
+    model._set(
+        "A5",
+        "=YIELDDISC(DATE(2008,2,16),DATE(2008,3,1),99.795,100,2)",
+    );
+    model._set(
+        "A6",
+        "=RECEIVED(DATE(2008,2,15),DATE(2008,5,15),1000000,5,2)",
+    );
+    model._set("A7", "=DISC(DATE(2007,6,15),DATE(2007,1,25),97.975,100,1)");
+    model._set("A8", "=DISC(DATE(2007,1,25),DATE(2007,6,15),0,100,1)");
+
+    model.evaluate();
+
+    assert_number_with_tolerance(&model, "A1", 0.052420213, 1e-9);
+    assert_number_with_tolerance(&model, "A2", 0.05768, 1e-12);
+    assert_number_with_tolerance(&model, "A3", 99.79583333, 1e-8);
+    assert_number_with_tolerance(&model, "A4", 1014584.654, 1e-3);
+    assert_number_with_tolerance(&model, "A5", 0.052822572, 1e-9);
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"#NUM!");
+}
+
+#[test]
+fn fn_securities_at_maturity() {
+    let mut model = new_empty_model();
+    model._set(
+        "A1",
+        "=PRICEMAT(DATE(2008,2,15),DATE(2008,4,13),DATE(2007,11,11),0.061,0.061,0)",
+    );
+    model._set(
+        "A2",
+        "=YIELDMAT(DATE(2008,3,15),DATE(2008,11,3),DATE(2007,11,8),0.0625,100.0123,0)",
+    );
+    model._set(
+        "A3",
+        "=PRICEMAT(DATE(2008,2,15),DATE(2008,4,13),DATE(2008,3,11),0.061,0.061,0)",
+    );
+
+    model.evaluate();
+
+    assert_number_with_tolerance(&model, "A1", 99.98449888, 1e-8);
+    assert_number_with_tolerance(&model, "A2", 0.060954334, 1e-9);
+    assert_eq!(model._get_text("A3"), *"#NUM!");
+}
@@ -0,0 +1,138 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::cell::CellValue;
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+use crate::types::IterativeCalculation;
+
+fn get_number(model: &Model, row: i32, column: i32) -> f64 {
+    match model.get_cell_value_by_index(0, row, column).unwrap() {
+        CellValue::Number(value) => value,
+        value => panic!("Expected a number, got {value:?}"),
+    }
+}
+
+/// Interest computed on the average of the opening and closing balances
+fn loan_model() -> Model {
+    let mut model = new_empty_model();
+    model._set("A1", "1000");
+    model._set("B1", "=A1+C1");
+    model._set("C1", "=(A1+B1)/2*0.1");
+    model._set("D1", "=B1*2");
+    model
+}
+
+#[test]
+fn disabled_by_default() {
+    let mut model = loan_model();
+    assert_eq!(
+        model.workbook.settings.iterative_calculation,
+        IterativeCalculation::default()
+    );
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"#CIRC!");
+    assert!(model.evaluate_with_error_check().is_err());
+}
+
+#[test]
+fn converges() {
+    let mut model = loan_model();
+    model
+        .set_iterative_calculation(true, 100, 0.000001)
+        .unwrap();
+    model.evaluate();
+    // B1 = 1000 + 0.05 * (1000 + B1)
+    let expected = 1050.0 / 0.95;
+    assert!((get_number(&model, 1, 2) - expected).abs() < 0.00001);
+    assert!((get_number(&model, 1, 3) - (expected - 1000.0)).abs() < 0.00001);
+    // Dependents of the cycle use the final values
+    assert_eq!(get_number(&model, 1, 4), 2.0 * get_number(&model, 1, 2));
+    assert_eq!(model.evaluate_with_error_check(), Ok(()));
+}
+
+#[test]
+fn maximum_number_of_iterations() {
+    let mut model = new_empty_model();
+    model.set_iterative_calculation(true, 10, 0.001).unwrap();
+    model._set("A1", "=A1+1");
+    model._set("B1", "=A1*2");
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"10");
+    assert_eq!(model._get_text("B1"), *"20");
+
+    // Every evaluation starts from the previous values
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"20");
+
+    model.set_iterative_calculation(true, 1, 0.001).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"21");
+}
+
+#[test]
+fn chained_cycles() {
+    let mut model = new_empty_model();
+    model
+        .set_iterative_calculation(true, 100, 0.000001)
+        .unwrap();
+    // First cycle converges to A1 = 2, B1 = 1
+    model._set("A1", "=B1+1");
+    model._set("B1", "=A1/2");
+    // Reads the first cycle through a formula outside of any cycle
+    model._set("C1", "=A1*10");
+    // Second cycle converges to D1 = 40, E1 = 20
+    model._set("D1", "=E1+C1");
+    model._set("E1", "=D1/2");
+    model.evaluate();
+    assert!((get_number(&model, 1, 1) - 2.0).abs() < 0.00001);
+    assert!((get_number(&model, 1, 4) - 40.0).abs() < 0.0001);
+    assert!((get_number(&model, 1, 5) - 20.0).abs() < 0.0001);
+}
+
+#[test]
+fn incremental_and_parallel_evaluation() {
+    let mut model = loan_model();
+    model._set("F1", "=7");
+    model.set_iterative_calculation(true, 100, 0.001).unwrap();
+    let mut expected = model.clone();
+    expected.evaluate();
+
+    let mut incremental = model.clone();
+    incremental.evaluate_incremental();
+    assert_eq!(incremental.workbook, expected.workbook);
+
+    model.evaluate_parallel(2);
+    assert_eq!(model.workbook, expected.workbook);
+}
+
+#[test]
+fn invalid_settings() {
+    let mut model = new_empty_model();
+    assert_eq!(
+        model.set_iterative_calculation(true, 0, 0.001),
+        Err("Invalid maximum number of iterations: 0".to_string())
+    );
+    assert_eq!(
+        model.set_iterative_calculation(true, 100, -1.0),
+        Err("Invalid maximum change: -1".to_string())
+    );
+    assert!(!model.workbook.settings.iterative_calculation.enabled);
+}
+
+#[test]
+fn settings_are_saved() {
+    let mut model = new_empty_model();
+    let json = model.to_json_str();
+    assert!(!json.contains("iterative_calculation"));
+
+    model.set_iterative_calculation(true, 50, 0.01).unwrap();
+    let model = Model::from_json(&model.to_json_str()).unwrap();
+    assert_eq!(
+        model.workbook.settings.iterative_calculation,
+        IterativeCalculation {
+            enabled: true,
+            max_iterations: 50,
+            max_change: 0.01,
+        }
+    );
+}
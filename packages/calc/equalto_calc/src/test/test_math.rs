@@ -27,3 +27,61 @@ fn test_fn_sqrtpi_arguments() {
     assert_eq!(model._get_text("A1"), *"#ERROR!");
     assert_eq!(model._get_text("A2"), *"#ERROR!");
 }
+
+#[test]
+fn test_fn_exp_and_logarithms() {
+    let mut model = new_empty_model();
+    model._set("A1", "=EXP(1)");
+    model._set("A2", "=EXP(2)");
+    model._set("A3", "=EXP(1000)");
+    model._set("A4", "=LN(86)");
+    model._set("A5", "=LN(EXP(3))");
+    model._set("A6", "=LN(0)");
+    model._set("A7", "=LOG(10)");
+    model._set("A8", "=LOG(8,2)");
+    model._set("A9", "=LOG(86,2.7182818)");
+    model._set("A10", "=LOG(10,1)");
+    model._set("A11", "=LOG(-1)");
+    model._set("A12", "=LOG10(86)");
+    model._set("A13", "=LOG10(1E5)");
+    model._set("A14", "=LOG10(0)");
+    model._set("A15", "=LOG()");
+    model._set("A16", "=LN(1,2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"2.718281828");
+    assert_eq!(model._get_text("A2"), *"7.389056099");
+    assert_eq!(model._get_text("A3"), *"#NUM!");
+    assert_eq!(model._get_text("A4"), *"4.454347296");
+    assert_eq!(model._get_text("A5"), *"3");
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"1");
+    assert_eq!(model._get_text("A8"), *"3");
+    assert_eq!(model._get_text("A9"), *"4.454347343");
+    assert_eq!(model._get_text("A10"), *"#DIV/0!");
+    assert_eq!(model._get_text("A11"), *"#NUM!");
+    assert_eq!(model._get_text("A12"), *"1.934498451");
+    assert_eq!(model._get_text("A13"), *"5");
+    assert_eq!(model._get_text("A14"), *"#NUM!");
+    assert_eq!(model._get_text("A15"), *"#ERROR!");
+    assert_eq!(model._get_text("A16"), *"#ERROR!");
+}
+
+#[test]
+fn test_fn_degrees_radians() {
+    let mut model = new_empty_model();
+    model._set("A1", "=DEGREES(PI())");
+    model._set("A2", "=RADIANS(270)");
+    model._set("A3", "=DEGREES(RADIANS(30))");
+    model._set("A4", "=DEGREES()");
+    model._set("A5", "=RADIANS(1,2)");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"180");
+    assert_eq!(model._get_text("A2"), *"4.71238898");
+    assert_eq!(model._get_text("A3"), *"30");
+    assert_eq!(model._get_text("A4"), *"#ERROR!");
+    assert_eq!(model._get_text("A5"), *"#ERROR!");
+}
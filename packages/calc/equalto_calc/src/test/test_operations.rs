@@ -0,0 +1,547 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::operations::Operation;
+use crate::test::util::new_empty_model;
+
+/// The formula or value and the formatted value of every non empty cell, sheet by sheet
+fn contents(model: &Model) -> Vec<(u32, i32, i32, String, String)> {
+    model
+        .get_all_cells()
+        .iter()
+        .filter(|cell| {
+            !model
+                .is_empty_cell(cell.index, cell.row, cell.column)
+                .unwrap()
+        })
+        .map(|cell| {
+            let content = model
+                .cell_formula(cell.index, cell.row, cell.column)
+                .unwrap()
+                .unwrap_or_else(|| model._get_text_at(cell.index, cell.row, cell.column));
+            let value = model._get_text_at(cell.index, cell.row, cell.column);
+            (cell.index, cell.row, cell.column, content, value)
+        })
+        .collect()
+}
+
+/// Applies two concurrent operations in both orders and checks that the replicas converge.
+/// Returns the resulting model.
+fn converge(base: &Model, a: Operation, b: Operation) -> Model {
+    let mut first = base.clone();
+    first.apply_operation(&a).unwrap();
+    if let Some(b) = first.transform_operation(&b, &a, false) {
+        first.apply_operation(&b).unwrap();
+    }
+    first.evaluate();
+
+    let mut second = base.clone();
+    second.apply_operation(&b).unwrap();
+    if let Some(a) = second.transform_operation(&a, &b, true) {
+        second.apply_operation(&a).unwrap();
+    }
+    second.evaluate();
+
+    assert_eq!(contents(&first), contents(&second));
+    assert_eq!(
+        first.workbook.get_worksheet_names(),
+        second.workbook.get_worksheet_names()
+    );
+    first
+}
+
+#[test]
+fn serialize_operations() {
+    let operations = vec![
+        Operation::SetUserInput {
+            sheet: 0,
+            row: 1,
+            column: 2,
+            value: "=A1*2".to_string(),
+        },
+        Operation::InsertRows {
+            sheet: 0,
+            row: 3,
+            row_count: 2,
+        },
+        Operation::InsertSheet {
+            sheet: 1,
+            name: "Data".to_string(),
+            sheet_id: 7,
+        },
+    ];
+    let json = serde_json::to_string(&operations).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<Operation>>(&json).unwrap(),
+        operations
+    );
+
+    let mut model = new_empty_model();
+    for operation in &operations {
+        model.apply_operation(operation).unwrap();
+    }
+    model.evaluate();
+    assert_eq!(model._get_formula("B1"), *"=A1*2");
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1", "Data"]);
+    assert_eq!(model.workbook.worksheet(1).unwrap().sheet_id, 7);
+}
+
+#[test]
+fn apply_to_missing_sheet() {
+    let mut model = new_empty_model();
+    assert!(model
+        .apply_operation(&Operation::SetUserInput {
+            sheet: 3,
+            row: 1,
+            column: 1,
+            value: "1".to_string(),
+        })
+        .is_err());
+}
+
+#[test]
+fn concurrent_row_insertions() {
+    let mut base = new_empty_model();
+    base._set("A3", "3");
+    let model = converge(
+        &base,
+        Operation::InsertRows {
+            sheet: 0,
+            row: 2,
+            row_count: 1,
+        },
+        Operation::InsertRows {
+            sheet: 0,
+            row: 2,
+            row_count: 2,
+        },
+    );
+    assert_eq!(model._get_text("A6"), *"3");
+}
+
+#[test]
+fn formula_input_and_row_insertion() {
+    let mut base = new_empty_model();
+    base._set("A1", "1");
+    base._set("A3", "3");
+    let model = converge(
+        &base,
+        Operation::InsertRows {
+            sheet: 0,
+            row: 2,
+            row_count: 2,
+        },
+        Operation::SetUserInput {
+            sheet: 0,
+            row: 5,
+            column: 3,
+            value: "=A3+A1".to_string(),
+        },
+    );
+    assert_eq!(model._get_formula("C7"), *"=A5+A1");
+    assert_eq!(model._get_text("C7"), *"4");
+}
+
+#[test]
+fn input_in_deleted_row() {
+    let mut base = new_empty_model();
+    base._set("A1", "1");
+    base._set("A2", "2");
+    let mut model = base.clone();
+    let input = Operation::SetUserInput {
+        sheet: 0,
+        row: 2,
+        column: 2,
+        value: "5".to_string(),
+    };
+    let deletion = Operation::DeleteRows {
+        sheet: 0,
+        row: 2,
+        row_count: 1,
+    };
+    model.apply_operation(&deletion).unwrap();
+    assert_eq!(model.transform_operation(&input, &deletion, true), None);
+
+    let model = converge(&base, deletion, input);
+    assert!(model.is_empty_cell(0, 2, 2).unwrap());
+}
+
+#[test]
+fn formula_referencing_deleted_row() {
+    let mut base = new_empty_model();
+    base._set("A3", "3");
+    let model = converge(
+        &base,
+        Operation::DeleteRows {
+            sheet: 0,
+            row: 3,
+            row_count: 1,
+        },
+        Operation::SetUserInput {
+            sheet: 0,
+            row: 1,
+            column: 2,
+            value: "=A3*2".to_string(),
+        },
+    );
+    assert_eq!(model._get_formula("B1"), *"=#REF!*2");
+}
+
+#[test]
+fn insertion_inside_deleted_rows() {
+    let mut base = new_empty_model();
+    for row in 1..=8 {
+        base._set(&format!("A{row}"), &row.to_string());
+    }
+    let model = converge(
+        &base,
+        Operation::DeleteRows {
+            sheet: 0,
+            row: 2,
+            row_count: 4,
+        },
+        Operation::InsertRows {
+            sheet: 0,
+            row: 4,
+            row_count: 3,
+        },
+    );
+    assert_eq!(model._get_text("A2"), *"6");
+}
+
+#[test]
+fn insertion_at_the_edges_of_deleted_rows() {
+    let mut base = new_empty_model();
+    for row in 1..=8 {
+        base._set(&format!("A{row}"), &row.to_string());
+    }
+    let deletion = Operation::DeleteRows {
+        sheet: 0,
+        row: 3,
+        row_count: 4,
+    };
+    // Rows (3, 4) survive, inserted before the deleted rows
+    let model = converge(
+        &base,
+        Operation::InsertRows {
+            sheet: 0,
+            row: 3,
+            row_count: 2,
+        },
+        deletion.clone(),
+    );
+    assert_eq!(model._get_text("A2"), *"2");
+    assert_eq!(model._get_text("A3"), *"");
+    assert_eq!(model._get_text("A4"), *"");
+    assert_eq!(model._get_text("A5"), *"7");
+    assert_eq!(model._get_text("A6"), *"8");
+
+    // Removed together with the deleted rows
+    let model = converge(
+        &base,
+        Operation::InsertRows {
+            sheet: 0,
+            row: 5,
+            row_count: 2,
+        },
+        deletion.clone(),
+    );
+    assert_eq!(model._get_text("A3"), *"7");
+    assert_eq!(model._get_text("A4"), *"8");
+
+    // Rows (7, 8) survive, inserted after the deleted rows
+    let model = converge(
+        &base,
+        Operation::InsertRows {
+            sheet: 0,
+            row: 7,
+            row_count: 2,
+        },
+        deletion,
+    );
+    assert_eq!(model._get_text("A2"), *"2");
+    assert_eq!(model._get_text("A3"), *"");
+    assert_eq!(model._get_text("A4"), *"");
+    assert_eq!(model._get_text("A5"), *"7");
+}
+
+#[test]
+fn overlapping_row_deletions() {
+    let mut base = new_empty_model();
+    for row in 1..=10 {
+        base._set(&format!("A{row}"), &row.to_string());
+    }
+    let a = Operation::DeleteRows {
+        sheet: 0,
+        row: 2,
+        row_count: 4,
+    };
+    let b = Operation::DeleteRows {
+        sheet: 0,
+        row: 4,
+        row_count: 4,
+    };
+    let mut model = base.clone();
+    assert_eq!(
+        model.transform_operation(&b, &a, false),
+        Some(Operation::DeleteRows {
+            sheet: 0,
+            row: 2,
+            row_count: 2,
+        })
+    );
+    let model = converge(&base, a, b);
+    assert_eq!(model._get_text("A2"), *"8");
+    assert_eq!(model._get_text("A4"), *"10");
+
+    // A deletion contained in another one is dropped
+    let mut model = base.clone();
+    let outer = Operation::DeleteRows {
+        sheet: 0,
+        row: 2,
+        row_count: 5,
+    };
+    let inner = Operation::DeleteRows {
+        sheet: 0,
+        row: 3,
+        row_count: 2,
+    };
+    assert_eq!(model.transform_operation(&inner, &outer, false), None);
+    converge(&base, outer, inner);
+}
+
+#[test]
+fn column_insertion_and_style() {
+    let mut base = new_empty_model();
+    base._set("B2", "2");
+    let mut style = base.get_style_for_cell(0, 2, 2);
+    style.font.b = true;
+    let model = converge(
+        &base,
+        Operation::SetCellStyle {
+            sheet: 0,
+            row: 2,
+            column: 2,
+            style: Box::new(style),
+        },
+        Operation::InsertColumns {
+            sheet: 0,
+            column: 1,
+            column_count: 1,
+        },
+    );
+    assert_eq!(model._get_text("C2"), *"2");
+    assert!(model.get_style_for_cell(0, 2, 3).font.b);
+}
+
+#[test]
+fn same_cell_inputs() {
+    let base = new_empty_model();
+    let first = Operation::SetUserInput {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        value: "first".to_string(),
+    };
+    let second = Operation::SetUserInput {
+        sheet: 0,
+        row: 1,
+        column: 1,
+        value: "second".to_string(),
+    };
+    // The operation with priority wins
+    let model = converge(&base, first.clone(), second);
+    assert_eq!(model._get_text("A1"), *"first");
+
+    // Deleting wins regardless of the priority
+    let model = converge(
+        &base,
+        first,
+        Operation::DeleteCell {
+            sheet: 0,
+            row: 1,
+            column: 1,
+        },
+    );
+    assert!(model.is_empty_cell(0, 1, 1).unwrap());
+}
+
+#[test]
+fn sheet_operations() {
+    let mut base = new_empty_model();
+    base.add_sheet("Data").unwrap();
+    base._set("Data!A1", "1");
+
+    let model = converge(
+        &base,
+        Operation::DeleteSheet {
+            sheet: 1,
+            name: "Data".to_string(),
+        },
+        Operation::SetUserInput {
+            sheet: 1,
+            row: 2,
+            column: 1,
+            value: "2".to_string(),
+        },
+    );
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1"]);
+
+    let model = converge(
+        &base,
+        Operation::InsertSheet {
+            sheet: 0,
+            name: "First".to_string(),
+            sheet_id: 10,
+        },
+        Operation::SetUserInput {
+            sheet: 1,
+            row: 2,
+            column: 1,
+            value: "2".to_string(),
+        },
+    );
+    assert_eq!(
+        model.workbook.get_worksheet_names(),
+        ["First", "Sheet1", "Data"]
+    );
+    assert_eq!(model._get_text("Data!A2"), *"2");
+
+    let model = converge(
+        &base,
+        Operation::InsertSheet {
+            sheet: 1,
+            name: "Left".to_string(),
+            sheet_id: 10,
+        },
+        Operation::InsertSheet {
+            sheet: 1,
+            name: "Right".to_string(),
+            sheet_id: 11,
+        },
+    );
+    assert_eq!(
+        model.workbook.get_worksheet_names(),
+        ["Sheet1", "Left", "Right", "Data"]
+    );
+
+    let model = converge(
+        &base,
+        Operation::RenameSheet {
+            sheet: 1,
+            old_name: "Data".to_string(),
+            new_name: "Mine".to_string(),
+        },
+        Operation::RenameSheet {
+            sheet: 1,
+            old_name: "Data".to_string(),
+            new_name: "Theirs".to_string(),
+        },
+    );
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1", "Mine"]);
+}
+
+#[test]
+fn formulas_and_sheet_renames() {
+    let mut base = new_empty_model();
+    base.add_sheet("Other").unwrap();
+    base._set("A1", "5");
+    let rename = Operation::RenameSheet {
+        sheet: 0,
+        old_name: "Sheet1".to_string(),
+        new_name: "Data".to_string(),
+    };
+
+    let model = converge(
+        &base,
+        rename.clone(),
+        Operation::SetUserInput {
+            sheet: 1,
+            row: 1,
+            column: 1,
+            value: "=Sheet1!A1+SUM(Sheet1!A1:A2)".to_string(),
+        },
+    );
+    assert_eq!(
+        model.cell_formula(1, 1, 1).unwrap(),
+        Some("=Data!A1+SUM(Data!A1:A2)".to_string())
+    );
+    assert_eq!(model._get_text("Other!A1"), *"10");
+
+    // A formula typed in the renamed sheet
+    let model = converge(
+        &base,
+        Operation::SetUserInput {
+            sheet: 0,
+            row: 2,
+            column: 1,
+            value: "=Sheet1!A1*2".to_string(),
+        },
+        rename.clone(),
+    );
+    assert_eq!(
+        model.cell_formula(0, 2, 1).unwrap(),
+        Some("=Data!A1*2".to_string())
+    );
+    assert_eq!(model._get_text("Data!A2"), *"10");
+
+    // Deleting the renamed sheet
+    let model = converge(
+        &base,
+        rename,
+        Operation::DeleteSheet {
+            sheet: 0,
+            name: "Sheet1".to_string(),
+        },
+    );
+    assert_eq!(model.workbook.get_worksheet_names(), ["Other"]);
+}
+
+#[test]
+fn formulas_and_sheet_deletions() {
+    let mut base = new_empty_model();
+    base.add_sheet("Data").unwrap();
+    base.add_sheet("Other").unwrap();
+    base._set("Data!A1", "5");
+    base._set("Other!A1", "7");
+
+    let model = converge(
+        &base,
+        Operation::DeleteSheet {
+            sheet: 1,
+            name: "Data".to_string(),
+        },
+        Operation::SetUserInput {
+            sheet: 2,
+            row: 2,
+            column: 1,
+            value: "=Data!A1+Other!A1+SUM(Data!A1:A2)".to_string(),
+        },
+    );
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1", "Other"]);
+    assert_eq!(
+        model.cell_formula(1, 2, 1).unwrap(),
+        Some("=#REF!+Other!A1+SUM(#REF!)".to_string())
+    );
+    assert_eq!(model._get_text("Other!A2"), *"#REF!");
+
+    // References to other sheets are kept
+    let model = converge(
+        &base,
+        Operation::DeleteSheet {
+            sheet: 1,
+            name: "Data".to_string(),
+        },
+        Operation::SetUserInput {
+            sheet: 0,
+            row: 1,
+            column: 1,
+            value: "=Other!A1*2".to_string(),
+        },
+    );
+    assert_eq!(
+        model.cell_formula(0, 1, 1).unwrap(),
+        Some("=Other!A1*2".to_string())
+    );
+    assert_eq!(model._get_text("A1"), *"14");
+}
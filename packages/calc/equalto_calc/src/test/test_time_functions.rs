@@ -0,0 +1,109 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::test::util::new_empty_model;
+
+#[test]
+fn test_fn_time() {
+    let mut model = new_empty_model();
+    model._set("A1", "=TIME(13, 45, 0)");
+    model._set("A2", "=TIME(25, 0, 0)");
+    model._set("A3", "=TIME(0, 0, 90)");
+    model._set("A4", "=TIME(0, -1, 0)");
+    model._set("A5", "=TIME(1, -1, 0)");
+    model._set("A6", "=TIME(32768, 0, 0)");
+    model._set("A7", "=TIME(13, 45)");
+    model._set("A8", "=TIME(12, 0, 0)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"1:45 PM");
+    assert_eq!(model._get_text("A2"), *"1:00 AM");
+    assert_eq!(model._get_text("A3"), *"12:01 AM");
+    assert_eq!(model._get_text("A4"), *"#NUM!");
+    assert_eq!(model._get_text("A5"), *"12:59 AM");
+    assert_eq!(model._get_text("A6"), *"#NUM!");
+    assert_eq!(model._get_text("A7"), *"#ERROR!");
+    assert_eq!(model._get_text("A8"), *"12:00 PM");
+}
+
+#[test]
+fn test_fn_hour_minute_second() {
+    let mut model = new_empty_model();
+    model._set("A1", "=HOUR(0.75)");
+    model._set("A2", "=HOUR(\"13:45\")");
+    model._set("A3", "=MINUTE(\"13:45:30\")");
+    model._set("A4", "=SECOND(\"13:45:30\")");
+    model._set("A5", "=HOUR(DATE(2023,4,1)+TIME(8,30,0))");
+    model._set("A6", "=MINUTE(DATE(2023,4,1)+TIME(8,30,0))");
+    model._set("A7", "=HOUR(-1)");
+    model._set("A8", "=HOUR(\"noon\")");
+    // Rounded to the nearest second
+    model._set("A9", "=HOUR(0.99999999)");
+    model._set("A10", "=SECOND(TIME(0,0,59)+0.4/86400)");
+    model._set("A11", "=HOUR(\"2023-04-01 8:30 PM\")");
+    model._set("A12", "=SECOND()");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"18");
+    assert_eq!(model._get_text("A2"), *"13");
+    assert_eq!(model._get_text("A3"), *"45");
+    assert_eq!(model._get_text("A4"), *"30");
+    assert_eq!(model._get_text("A5"), *"8");
+    assert_eq!(model._get_text("A6"), *"30");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"#VALUE!");
+    assert_eq!(model._get_text("A9"), *"0");
+    assert_eq!(model._get_text("A10"), *"59");
+    assert_eq!(model._get_text("A11"), *"20");
+    assert_eq!(model._get_text("A12"), *"#ERROR!");
+}
+
+#[test]
+fn test_fn_timevalue_datevalue() {
+    let mut model = new_empty_model();
+    model._set("A1", "=TIMEVALUE(\"13:45\")");
+    model._set("A2", "=TIMEVALUE(\"2023-04-01 08:30\")");
+    model._set("A3", "=TIMEVALUE(\"2:24 AM\")");
+    model._set("A4", "=TIMEVALUE(0.5)");
+    model._set("A5", "=TIMEVALUE(\"noon\")");
+    model._set("B1", "=DATEVALUE(\"2023-04-01\")");
+    model._set("B2", "=DATEVALUE(\"2023-04-01 08:30\")");
+    model._set("B3", "=DATEVALUE(\"01/04/2023\")");
+    model._set("B4", "=DATEVALUE(\"13:45\")");
+    model._set("B5", "=DATEVALUE(45017)");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"0.572916667");
+    assert_eq!(model._get_text("A2"), *"0.354166667");
+    assert_eq!(model._get_text("A3"), *"0.1");
+    assert_eq!(model._get_text("A4"), *"#VALUE!");
+    assert_eq!(model._get_text("A5"), *"#VALUE!");
+    assert_eq!(model._get_text("B1"), *"45017");
+    assert_eq!(model._get_text("B2"), *"45017");
+    assert_eq!(model._get_text("B3"), *"45017");
+    assert_eq!(model._get_text("B4"), *"#VALUE!");
+    assert_eq!(model._get_text("B5"), *"#VALUE!");
+}
+
+#[test]
+fn test_time_user_input() {
+    let mut model = new_empty_model();
+    model._set("A1", "13:45");
+    model._set("A2", "2023-04-01 08:30");
+    model._set("A3", "1:45 PM");
+    model._set("A4", "=A1+A2");
+    model._set("A5", "25:30");
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"13:45");
+    assert_eq!(model._get_text("A2"), *"2023-04-01 8:30");
+    assert_eq!(model._get_text("A3"), *"1:45 PM");
+    assert_eq!(model._get_text("A5"), *"25:30");
+    assert_eq!(
+        model.get_cell_value_by_ref("Sheet1!A1"),
+        Ok((49500.0 / 86400.0).into())
+    );
+    assert_eq!(
+        model.get_cell_value_by_ref("Sheet1!A4"),
+        Ok((45017.0 + 30600.0 / 86400.0 + 49500.0 / 86400.0).into())
+    );
+}
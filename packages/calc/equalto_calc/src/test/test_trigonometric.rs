@@ -96,3 +96,31 @@ fn test_fn_tan_pi2() {
     // This is consistent with IEEE 754 but inconsistent with Excel
     assert_eq!(model._get_text("A1"), *"1.63312E+16");
 }
+
+#[test]
+fn test_fn_reciprocal_trigonometric() {
+    let mut model = new_empty_model();
+    model._set("A1", "=COT(30)");
+    model._set("A2", "=COT(0)");
+    model._set("A3", "=SEC(45)");
+    model._set("A4", "=SEC(0)");
+    model._set("A5", "=CSC(15)");
+    model._set("A6", "=CSC(0)");
+    model._set("A7", "=COT(2^27)");
+    model._set("A8", "=COT()");
+    model._set("A9", "=SEC(1,2)");
+    model._set("A10", "=CSC()");
+
+    model.evaluate();
+
+    assert_eq!(model._get_text("A1"), *"-0.156119952");
+    assert_eq!(model._get_text("A2"), *"#DIV/0!");
+    assert_eq!(model._get_text("A3"), *"1.903594407");
+    assert_eq!(model._get_text("A4"), *"1");
+    assert_eq!(model._get_text("A5"), *"1.537780562");
+    assert_eq!(model._get_text("A6"), *"#DIV/0!");
+    assert_eq!(model._get_text("A7"), *"#NUM!");
+    assert_eq!(model._get_text("A8"), *"#ERROR!");
+    assert_eq!(model._get_text("A9"), *"#ERROR!");
+    assert_eq!(model._get_text("A10"), *"#ERROR!");
+}
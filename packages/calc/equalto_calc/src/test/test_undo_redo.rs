@@ -0,0 +1,283 @@
+#![allow(clippy::unwrap_used)]
+
+use crate::model::Model;
+use crate::test::util::new_empty_model;
+use crate::types::Worksheet;
+
+/// The parts of the worksheets that undo and redo restore
+fn worksheets(model: &Model) -> Vec<Worksheet> {
+    model
+        .workbook
+        .worksheets
+        .iter()
+        .map(|worksheet| Worksheet {
+            // Formulas are only appended, undo does not remove them
+            shared_formulas: vec![],
+            // Deleting cells might leave empty rows behind
+            sheet_data: worksheet
+                .sheet_data
+                .iter()
+                .filter(|(_, row_data)| !row_data.is_empty())
+                .map(|(row, row_data)| (*row, row_data.clone()))
+                .collect(),
+            ..worksheet.clone()
+        })
+        .collect()
+}
+
+#[test]
+fn undo_and_redo_user_input() {
+    let mut model = new_empty_model();
+    assert!(!model.can_undo());
+    assert_eq!(model.undo(), Err("Nothing to undo".to_string()));
+
+    model._set("A1", "1");
+    model._set("A2", "=A1*2");
+    model._set("A1", "5");
+    model.evaluate();
+    assert_eq!(model._get_text("A2"), *"10");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"1");
+    assert_eq!(model._get_text("A2"), *"2");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert!(model.is_empty_cell(0, 2, 1).unwrap());
+    assert!(model.can_redo());
+
+    model.redo().unwrap();
+    model.redo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A2"), *"10");
+    assert!(!model.can_redo());
+    assert_eq!(model.redo(), Err("Nothing to redo".to_string()));
+}
+
+#[test]
+fn new_edit_clears_redo() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A1", "2");
+    model.undo().unwrap();
+    assert!(model.can_redo());
+
+    model._set("A1", "3");
+    assert!(!model.can_redo());
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"1");
+}
+
+#[test]
+fn transactions() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+
+    model.begin_transaction();
+    model._set("A1", "2");
+    model._set("B1", "=A1+1");
+    // Cells changed twice are restored to the value before the transaction
+    model._set("A1", "3");
+    assert_eq!(
+        model.undo(),
+        Err("Cannot undo with an open transaction".to_string())
+    );
+    model.commit_transaction();
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"4");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("A1"), *"1");
+    assert!(model.is_empty_cell(0, 1, 2).unwrap());
+
+    // An empty transaction is not recorded
+    model.begin_transaction();
+    model.commit_transaction();
+    model.undo().unwrap();
+    assert!(!model.can_undo());
+}
+
+#[test]
+fn failed_actions() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model.undo().unwrap();
+    assert!(!model.can_undo());
+
+    assert!(model.delete_rows(0, 1, 0).is_err());
+    assert!(model.insert_columns(0, 1, -1).is_err());
+    assert!(model.move_column_action(0, 0, 1).is_err());
+    assert!(model
+        .update_cell_with_formula(0, 1, 1, "1+1".to_string())
+        .is_err());
+    assert!(!model.can_undo());
+    assert!(model.can_redo());
+
+    // Changes made before the failure are reverted
+    let result: Result<(), String> = model.try_transaction(|model| {
+        model._set("A1", "2");
+        model._set("B1", "=A1");
+        Err("Failed".to_string())
+    });
+    assert!(result.is_err());
+    assert!(!model.can_undo());
+    assert!(model.is_empty_cell(0, 1, 1).unwrap());
+    assert!(model.is_empty_cell(0, 1, 2).unwrap());
+}
+
+#[test]
+fn undo_delete_rows() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("A3", "3");
+    model._set("B1", "=SUM(A1:A3)");
+    model._set("C1", "=A2");
+    model._set("C5", "=A3*10");
+    let mut style = model.get_style_for_cell(0, 1, 1);
+    style.font.b = true;
+    model
+        .workbook
+        .styles
+        .create_named_style("bold", &style)
+        .unwrap();
+    model.set_sheet_row_style(0, 3, "bold").unwrap();
+    model.evaluate();
+    let before = worksheets(&model);
+
+    model.delete_rows(0, 2, 1).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("C1"), *"=#REF!");
+    assert_eq!(model._get_formula("C4"), *"=A2*10");
+    let after = worksheets(&model);
+
+    // The whole action is a single transaction
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(worksheets(&model), before);
+    assert_eq!(model._get_formula("C1"), *"=A2");
+    assert_eq!(model._get_text("B1"), *"6");
+
+    model.redo().unwrap();
+    model.evaluate();
+    assert_eq!(worksheets(&model), after);
+}
+
+#[test]
+fn undo_insert_columns() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    model._set("B1", "2");
+    model._set("C1", "=A1+B1");
+    model.evaluate();
+    let before = worksheets(&model);
+
+    model.insert_columns(0, 2, 2).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("E1"), *"=A1+D1");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(worksheets(&model), before);
+}
+
+#[test]
+fn undo_styles() {
+    let mut model = new_empty_model();
+    model._set("A1", "1");
+    let style = model.get_style_for_cell(0, 1, 1);
+    let mut bold = style.clone();
+    bold.font.b = true;
+    model.set_cell_style(0, 1, 1, &bold).unwrap();
+    model
+        .workbook
+        .styles
+        .create_named_style("bold", &bold)
+        .unwrap();
+    model.set_sheet_column_style(0, 3, "bold").unwrap();
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(model.get_style_for_cell(0, 1, 1), style);
+    assert!(model.workbook.worksheet(0).unwrap().cols.is_empty());
+
+    model.redo().unwrap();
+    assert!(model.get_style_for_cell(0, 1, 1).font.b);
+}
+
+#[test]
+fn undo_sheet_operations() {
+    let mut model = new_empty_model();
+    model.add_sheet("Data").unwrap();
+    model._set("Data!A1", "42");
+    model._set("A1", "=Data!A1");
+    model.rename_sheet("Data", "Numbers").unwrap();
+    model.set_sheet_color(1, "#FF0000").unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("A1"), *"=Numbers!A1");
+
+    model.delete_sheet(1).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("A1"), *"=#REF!");
+    assert_eq!(model._get_text("A1"), *"#REF!");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("A1"), *"=Numbers!A1");
+    assert_eq!(model._get_text("A1"), *"42");
+    assert_eq!(
+        model.workbook.worksheet(1).unwrap().color,
+        Some("#FF0000".to_string())
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.workbook.worksheet(1).unwrap().color, None);
+
+    model.undo().unwrap();
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1", "Data"]);
+    assert_eq!(model._get_formula("A1"), *"=Data!A1");
+
+    model.undo().unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
+    assert_eq!(model.workbook.get_worksheet_names(), ["Sheet1"]);
+
+    model.redo().unwrap();
+    model.redo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("Data!A1"), *"42");
+}
+
+#[test]
+fn undo_defined_names() {
+    let mut model = new_empty_model();
+    model._set("A1", "10");
+    model.new_defined_name("Rate", None, "Sheet1!$A$1").unwrap();
+    model._set("B1", "=Rate*2");
+    model
+        .update_defined_name("Rate", None, "Tax", Some(0), "Sheet1!$A$1/2")
+        .unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("B1"), *"=Tax*2");
+    assert_eq!(model._get_text("B1"), *"10");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_formula("B1"), *"=Rate*2");
+    assert_eq!(model._get_text("B1"), *"20");
+
+    model.delete_defined_name("Rate", None).unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"#NAME?");
+
+    model.undo().unwrap();
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"20");
+    assert_eq!(
+        model.get_defined_name_list(),
+        [("Rate".to_string(), None, "Sheet1!$A$1".to_string())]
+    );
+}
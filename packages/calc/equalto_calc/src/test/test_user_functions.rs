@@ -0,0 +1,182 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::expressions::token::Error;
+use crate::test::util::new_empty_model;
+use crate::user_functions::{FunctionValue, UserFunction};
+
+/// Exchange rates from an in-memory table
+fn fx_rate(args: &[FunctionValue]) -> Result<FunctionValue, String> {
+    let rates = HashMap::from([("EUR", 1.1), ("GBP", 1.25)]);
+    match args {
+        [FunctionValue::String(currency)] => match rates.get(currency.as_str()) {
+            Some(rate) => Ok(FunctionValue::Number(*rate)),
+            None => Ok(FunctionValue::Error(Error::NA)),
+        },
+        _ => Err("Expected a currency".to_string()),
+    }
+}
+
+/// Sum of all the numbers in the arguments
+fn total(args: &[FunctionValue]) -> Result<FunctionValue, String> {
+    fn add(value: &FunctionValue) -> f64 {
+        match value {
+            FunctionValue::Number(value) => *value,
+            FunctionValue::Array(rows) => rows.iter().flatten().map(add).sum(),
+            _ => 0.0,
+        }
+    }
+    Ok(FunctionValue::Number(args.iter().map(add).sum()))
+}
+
+/// Counts how many times it is called
+struct Counter {
+    calls: Arc<AtomicUsize>,
+    volatile: bool,
+}
+
+impl UserFunction for Counter {
+    fn call(&self, _args: &[FunctionValue]) -> Result<FunctionValue, String> {
+        let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(FunctionValue::Number(calls as f64))
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.volatile
+    }
+}
+
+#[test]
+fn register_and_unregister() {
+    let mut model = new_empty_model();
+    model._set("A1", "100");
+    model._set("B1", "=fxrate(\"EUR\")*A1");
+    model._set("B2", "=FXRATE(\"JPY\")");
+    model._set("B3", "=FXRATE(1)");
+    model.evaluate();
+    assert_eq!(model._get_text("B1"), *"#ERROR!");
+
+    model.register_function("FxRate", fx_rate).unwrap();
+    assert_eq!(model._get_formula("B1"), *"=FXRATE(\"EUR\")*A1");
+    assert_eq!(model._get_text("B1"), *"110");
+    assert_eq!(model._get_text("B2"), *"#N/A");
+    assert_eq!(model._get_text("B3"), *"#VALUE!");
+
+    // New formulas are parsed with the function
+    model._set("C1", "=FXRATE(\"GBP\")");
+    model.evaluate();
+    assert_eq!(model._get_text("C1"), *"1.25");
+
+    model.unregister_function("fxrate").unwrap();
+    assert_eq!(model._get_text("B1"), *"#ERROR!");
+    assert_eq!(
+        model.unregister_function("fxrate"),
+        Err("Function not found: 'fxrate'".to_string())
+    );
+}
+
+#[test]
+fn invalid_names() {
+    let mut model = new_empty_model();
+    assert_eq!(
+        model.register_function("SUM", total),
+        Err("Function already exists: 'SUM'".to_string())
+    );
+    assert_eq!(
+        model.register_function("A1", total),
+        Err("Invalid function name: 'A1'".to_string())
+    );
+    assert_eq!(
+        model.register_function("MY FUNCTION", total),
+        Err("Invalid function name: 'MY FUNCTION'".to_string())
+    );
+}
+
+#[test]
+fn ranges_arrays_and_errors() {
+    let mut model = new_empty_model();
+    model.register_function("TOTAL", total).unwrap();
+    model
+        .register_function("ONE_TWO_THREE", |_: &[FunctionValue]| {
+            Ok(FunctionValue::Array(vec![
+                vec![FunctionValue::Number(1.0)],
+                vec![FunctionValue::Number(2.0)],
+                vec![FunctionValue::Number(3.0)],
+            ]))
+        })
+        .unwrap();
+    model._set("A1", "1");
+    model._set("A2", "2");
+    model._set("B1", "x");
+    model._set("B2", "3");
+    model._set("C1", "=TOTAL(A1:B2, 10, {1,2})");
+    model._set("C2", "=TOTAL(A:A)");
+    model._set("C3", "=TOTAL(A1, 1/0)");
+    model._set("D1", "=ONE_TWO_THREE()");
+    model._set("E1", "=TOTAL(D1#)");
+    model._set("F1", "=ISFORMULA(D1)");
+    model.evaluate();
+    assert_eq!(model._get_text("C1"), *"19");
+    assert_eq!(model._get_text("C2"), *"3");
+    assert_eq!(model._get_text("C3"), *"#DIV/0!");
+    assert_eq!(model._get_text("D3"), *"3");
+    assert_eq!(model._get_text("E1"), *"6");
+    assert_eq!(model._get_text("F1"), *"TRUE");
+}
+
+#[test]
+fn dependency_tracking() {
+    let mut model = new_empty_model();
+    let calls = Arc::new(AtomicUsize::new(0));
+    model.register_function("TOTAL", total).unwrap();
+    model
+        .register_function(
+            "COUNTER",
+            Counter {
+                calls: calls.clone(),
+                volatile: false,
+            },
+        )
+        .unwrap();
+    model._set("A1", "1");
+    model._set("B1", "=TOTAL(A1, 1)");
+    model._set("C1", "=COUNTER(B1)");
+    model._set("D1", "=COUNTER(5)");
+    model.evaluate_incremental();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(model._get_text("B1"), *"2");
+
+    // Only the formulas that depend on A1 are recomputed
+    model.set_user_input(0, 1, 1, "5".to_string());
+    model.evaluate_incremental();
+    assert_eq!(model._get_text("B1"), *"6");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    let arrows = model.get_cell_dependents(0, 1, 1, true).unwrap();
+    assert_eq!(arrows.len(), 2);
+}
+
+#[test]
+fn volatile_functions() {
+    let mut model = new_empty_model();
+    let calls = Arc::new(AtomicUsize::new(0));
+    model
+        .register_function(
+            "TICK",
+            Counter {
+                calls: calls.clone(),
+                volatile: true,
+            },
+        )
+        .unwrap();
+    model._set("A1", "=TICK()");
+    model._set("B1", "7");
+    model.evaluate_incremental();
+    let first = calls.load(Ordering::SeqCst);
+    model.set_user_input(0, 1, 2, "8".to_string());
+    model.evaluate_incremental();
+    assert_eq!(calls.load(Ordering::SeqCst), first + 1);
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::unwrap_used)]
 
 use crate::calc_result::CellReference;
+use crate::cell::CellValue;
 use crate::model::Model;
 use crate::types::Cell;
 
@@ -8,6 +9,40 @@ pub fn new_empty_model() -> Model {
     Model::new_empty("model", "en", "UTC").unwrap()
 }
 
+/// Evaluates `model` incrementally and checks the result against a full evaluation
+pub fn assert_same_as_full_evaluation(model: &mut Model) {
+    model.evaluate_incremental();
+    let mut expected = model.clone();
+    expected.evaluate();
+    assert_eq!(model.workbook, expected.workbook);
+}
+
+/// Checks that `cell` in Sheet1 is a number equal to `expected` up to a relative error of 1e-12
+pub fn assert_number(model: &Model, cell: &str, expected: f64) {
+    assert_number_with_tolerance(model, cell, expected, 1e-12 * expected.abs().max(1.0));
+}
+
+/// Checks that `cell` in Sheet1 is a number that differs from `expected` by at most `tolerance`
+pub fn assert_number_with_tolerance(model: &Model, cell: &str, expected: f64, tolerance: f64) {
+    match model
+        .get_cell_value_by_ref(&format!("Sheet1!{cell}"))
+        .unwrap()
+    {
+        CellValue::Number(f) => assert!(
+            (f - expected).abs() <= tolerance,
+            "Got: {f}, expected: {expected} in {cell}"
+        ),
+        value => panic!("Got: {value:?}, expected: {expected} in {cell}"),
+    }
+}
+
+/// Writes `values` in consecutive rows of `column` in Sheet1, starting at row 1
+pub fn set_column(model: &mut Model, column: &str, values: &[&str]) {
+    for (index, value) in values.iter().enumerate() {
+        model._set(&format!("{column}{}", index + 1), value);
+    }
+}
+
 impl Model {
     fn _parse_reference(&self, cell: &str) -> CellReference {
         if cell.contains('!') {
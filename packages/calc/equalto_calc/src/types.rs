@@ -43,10 +43,39 @@ pub struct Metadata {
     pub last_modified: String, //"2020-11-20T16:24:35"
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+fn is_default_iterative_calculation(settings: &IterativeCalculation) -> bool {
+    *settings == IterativeCalculation::default()
+}
+
+/// Excel's iterative calculation settings. When enabled circular references are solved by
+/// recomputing the cells in the cycle until the values change less than `max_change` or
+/// `max_iterations` are reached, instead of evaluating to #CIRC!
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct IterativeCalculation {
+    pub enabled: bool,
+    pub max_iterations: i32,
+    pub max_change: f64,
+}
+
+impl Default for IterativeCalculation {
+    fn default() -> Self {
+        IterativeCalculation {
+            enabled: false,
+            max_iterations: 100,
+            max_change: 0.001,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct WorkbookSettings {
     pub tz: String,
     pub locale: String,
+    #[serde(default, skip_serializing_if = "is_default_iterative_calculation")]
+    pub iterative_calculation: IterativeCalculation,
+    /// Dates are counted from 1 January 1904 instead of 1 January 1900 (Mac workbooks)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub date1904: bool,
 }
 /// An internal representation of an EqualTo Workbook
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -199,7 +228,50 @@ pub enum Cell {
         // Error Message: "Not implemented function"
         m: String,
     },
-    // TODO: Array formulas
+    // Legacy array formula (Ctrl+Shift+Enter) entered in the block of `r` = (rows, columns) cells
+    // that starts in this cell. `v` is the value of this cell, the rest of the block holds Spill*
+    // values
+    #[serde(rename = "af")]
+    ArrayFormula {
+        f: i32,
+        r: (i32, i32),
+        v: FormulaValue,
+        s: i32,
+    },
+    // Values spilled by a dynamic array formula or computed by a legacy array formula. The formula
+    // (the anchor) is `dr` rows above and `dc` columns to the left of the cell
+    #[serde(rename = "sb")]
+    SpillBoolean { v: bool, s: i32, dr: i32, dc: i32 },
+    #[serde(rename = "sn")]
+    SpillNumber { v: f64, s: i32, dr: i32, dc: i32 },
+    // always inline string
+    #[serde(rename = "sstr")]
+    SpillString { v: String, s: i32, dr: i32, dc: i32 },
+    #[serde(rename = "se")]
+    SpillError { ei: Error, s: i32, dr: i32, dc: i32 },
+}
+
+/// The value of the anchor cell of an array formula
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "t", deny_unknown_fields)]
+pub enum FormulaValue {
+    // Non evaluated formula
+    #[serde(rename = "u")]
+    Unevaluated,
+    #[serde(rename = "b")]
+    Boolean { v: bool },
+    #[serde(rename = "n")]
+    Number { v: f64 },
+    #[serde(rename = "str")]
+    String { v: String },
+    #[serde(rename = "e")]
+    Error {
+        ei: Error,
+        // Origin: Sheet3!C4
+        o: String,
+        // Error Message: "Not implemented function"
+        m: String,
+    },
 }
 
 impl Default for Cell {
@@ -283,8 +283,12 @@ impl Model {
             Node::ParseErrorKind { .. } => None,
             Node::EmptyArgKind => None,
             Node::InvalidFunctionKind { .. } => None,
+            Node::UserFunctionKind { .. } => None,
             Node::ArrayKind(_) => None,
+            Node::SpillRefKind(_) => None,
             Node::VariableKind(_) => None,
+            Node::LocalVariableKind(_) => None,
+            Node::LambdaCallKind { .. } => None,
             Node::CompareKind { .. } => None,
             Node::OpPowerKind { .. } => None,
         }
@@ -299,6 +303,24 @@ impl Model {
         match kind {
             Function::Sum => self.units_fn_sum_like(args, cell),
             Function::Average => self.units_fn_sum_like(args, cell),
+            Function::Median => self.units_fn_sum_like(args, cell),
+            Function::Mode => self.units_fn_sum_like(args, cell),
+            Function::ModeSngl => self.units_fn_sum_like(args, cell),
+            Function::Large => self.units_fn_sum_like(args, cell),
+            Function::Small => self.units_fn_sum_like(args, cell),
+            Function::Percentile => self.units_fn_sum_like(args, cell),
+            Function::PercentileInc => self.units_fn_sum_like(args, cell),
+            Function::PercentileExc => self.units_fn_sum_like(args, cell),
+            Function::Quartile => self.units_fn_sum_like(args, cell),
+            Function::QuartileInc => self.units_fn_sum_like(args, cell),
+            Function::QuartileExc => self.units_fn_sum_like(args, cell),
+            Function::Trimmean => self.units_fn_sum_like(args, cell),
+            Function::Geomean => self.units_fn_sum_like(args, cell),
+            Function::Harmean => self.units_fn_sum_like(args, cell),
+            Function::Avedev => self.units_fn_sum_like(args, cell),
+            Function::Stdev => self.units_fn_sum_like(args, cell),
+            Function::StdevS => self.units_fn_sum_like(args, cell),
+            Function::StdevP => self.units_fn_sum_like(args, cell),
             Function::Pmt => self.units_fn_currency(args, cell),
             Function::Nper => self.units_fn_currency(args, cell),
             Function::Npv => self.units_fn_currency(args, cell),
@@ -315,6 +337,9 @@ impl Model {
             Function::Tbillyield => self.units_fn_percentage_2(args, cell),
             Function::Date => self.units_fn_dates(args, cell),
             Function::Today => self.units_fn_dates(args, cell),
+            Function::Workday => self.units_fn_dates(args, cell),
+            Function::WorkdayIntl => self.units_fn_dates(args, cell),
+            Function::Time => self.units_fn_times(args, cell),
             _ => None,
         }
     }
@@ -361,4 +386,8 @@ impl Model {
         // TODO: update locale and use it here
         Some(Units::Date("dd/mm/yyyy".to_string()))
     }
+
+    fn units_fn_times(&self, _args: &[Node], _cell: &CellReference) -> Option<Units> {
+        Some(Units::Date("h:mm AM/PM".to_string()))
+    }
 }
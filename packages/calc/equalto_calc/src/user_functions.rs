@@ -0,0 +1,180 @@
+//! Functions defined by the user of the library, registered with [`Model::register_function`].
+//! They are not saved with the workbook.
+
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    calc_result::{CalcResult, CellReference},
+    expressions::{parser::Node, token::Error, utils::is_valid_identifier},
+    functions::Function,
+    model::Model,
+};
+
+/// A value passed to or returned by a user defined function
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionValue {
+    Empty,
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Error(Error),
+    /// A list of rows of values. All the rows have the same length
+    Array(Vec<Vec<FunctionValue>>),
+}
+
+/// A function that can be called from formulas
+pub trait UserFunction: Send + Sync {
+    /// Computes the value of the function. An `Err` evaluates to #VALUE! with the message.
+    fn call(&self, args: &[FunctionValue]) -> Result<FunctionValue, String>;
+
+    /// Volatile functions are recomputed on every evaluation, like RAND or NOW
+    fn is_volatile(&self) -> bool {
+        false
+    }
+}
+
+impl<F> UserFunction for F
+where
+    F: Fn(&[FunctionValue]) -> Result<FunctionValue, String> + Send + Sync,
+{
+    fn call(&self, args: &[FunctionValue]) -> Result<FunctionValue, String> {
+        self(args)
+    }
+}
+
+impl Model {
+    /// Registers `function` with the given name, replacing any function registered with the
+    /// same name. The name cannot be the name of a built-in function.
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        function: impl UserFunction + 'static,
+    ) -> Result<(), String> {
+        if !is_valid_identifier(name) {
+            return Err(format!("Invalid function name: '{name}'"));
+        }
+        if Function::get_function(name).is_some() {
+            return Err(format!("Function already exists: '{name}'"));
+        }
+        self.user_functions
+            .insert(name.to_uppercase(), Arc::new(function));
+        self.reset_user_functions();
+        Ok(())
+    }
+
+    /// Removes a function registered with [`Model::register_function`]
+    pub fn unregister_function(&mut self, name: &str) -> Result<(), String> {
+        if self.user_functions.remove(&name.to_uppercase()).is_none() {
+            return Err(format!("Function not found: '{name}'"));
+        }
+        self.reset_user_functions();
+        Ok(())
+    }
+
+    /// Formulas need to be parsed again, a name might be a call to a user defined function now
+    fn reset_user_functions(&mut self) {
+        let names: HashSet<String> = self.user_functions.keys().cloned().collect();
+        self.parser.set_user_functions(names);
+        self.reset_parsed_structures();
+    }
+
+    pub(crate) fn evaluate_user_function(
+        &mut self,
+        name: &str,
+        args: &[Node],
+        cell: CellReference,
+    ) -> CalcResult {
+        let function = match self.user_functions.get(name) {
+            Some(function) => function.clone(),
+            None => {
+                return CalcResult::new_error(
+                    Error::ERROR,
+                    cell,
+                    format!("Invalid function: {}", name),
+                )
+            }
+        };
+        let mut values = Vec::new();
+        for arg in args {
+            let value = match self.evaluate_node_in_context(arg, cell) {
+                CalcResult::Range { left, right } => self.range_to_array(left, right),
+                value => value,
+            };
+            match to_function_value(value, cell) {
+                Ok(value) => values.push(value),
+                Err(error) => return error,
+            }
+        }
+        match function.call(&values) {
+            Ok(value) => from_function_value(value, cell),
+            Err(message) => CalcResult::new_error(Error::VALUE, cell, message),
+        }
+    }
+}
+
+/// Converts an evaluated argument, returns the error if it is or contains one
+fn to_function_value(value: CalcResult, cell: CellReference) -> Result<FunctionValue, CalcResult> {
+    Ok(match value {
+        CalcResult::Number(value) => FunctionValue::Number(value),
+        CalcResult::String(value) => FunctionValue::String(value),
+        CalcResult::Boolean(value) => FunctionValue::Boolean(value),
+        CalcResult::EmptyCell | CalcResult::EmptyArg => FunctionValue::Empty,
+        CalcResult::Array(rows) => {
+            let mut array = Vec::new();
+            for row in rows {
+                let mut values = Vec::new();
+                for value in row {
+                    values.push(to_function_value(value, cell)?);
+                }
+                array.push(values);
+            }
+            FunctionValue::Array(array)
+        }
+        error @ CalcResult::Error { .. } => return Err(error),
+        CalcResult::Range { .. } => {
+            return Err(CalcResult::new_error(
+                Error::VALUE,
+                cell,
+                "Invalid argument".to_string(),
+            ))
+        }
+    })
+}
+
+fn from_function_value(value: FunctionValue, cell: CellReference) -> CalcResult {
+    match value {
+        FunctionValue::Empty => CalcResult::EmptyCell,
+        FunctionValue::Number(value) => CalcResult::Number(value),
+        FunctionValue::String(value) => CalcResult::String(value),
+        FunctionValue::Boolean(value) => CalcResult::Boolean(value),
+        FunctionValue::Error(error) => {
+            CalcResult::new_error(error, cell, "Error returned by function".to_string())
+        }
+        FunctionValue::Array(rows) => {
+            let columns = rows.first().map_or(0, |row| row.len());
+            if columns == 0 || rows.iter().any(|row| row.len() != columns) {
+                return CalcResult::new_error(
+                    Error::VALUE,
+                    cell,
+                    "Invalid array returned by function".to_string(),
+                );
+            }
+            CalcResult::Array(
+                rows.into_iter()
+                    .map(|row| {
+                        row.into_iter()
+                            .map(|value| match value {
+                                FunctionValue::Array(_) => CalcResult::new_error(
+                                    Error::VALUE,
+                                    cell,
+                                    "Nested arrays are not supported".to_string(),
+                                ),
+                                value => from_function_value(value, cell),
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
@@ -203,6 +203,18 @@ impl Worksheet {
         self.update_cell(row, column, cell);
     }
 
+    pub fn set_cell_with_array_formula(
+        &mut self,
+        row: i32,
+        column: i32,
+        index: i32,
+        size: (i32, i32),
+        style: i32,
+    ) {
+        let cell = Cell::new_array_formula(index, size, style);
+        self.update_cell(row, column, cell);
+    }
+
     pub fn set_cell_with_number(&mut self, row: i32, column: i32, value: f64, style: i32) {
         let cell = Cell::new_number(value, style);
         self.update_cell(row, column, cell);
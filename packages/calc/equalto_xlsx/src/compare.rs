@@ -140,6 +140,30 @@ pub fn compare(model1: &Model, model2: &Model) -> CompareResult<Vec<Diff>> {
                     });
                 }
             }
+            // Array formulas and the values they spill or compute. The values in a workbook
+            // imported from Excel are plain values.
+            (Cell::ArrayFormula { .. }, Cell::ArrayFormula { .. })
+            | (Cell::SpillNumber { .. }, Cell::SpillNumber { .. } | Cell::NumberCell { .. })
+            | (Cell::SpillBoolean { .. }, Cell::SpillBoolean { .. } | Cell::BooleanCell { .. })
+            | (Cell::SpillString { .. }, Cell::SpillString { .. } | Cell::SharedString { .. })
+            | (Cell::SpillError { .. }, Cell::SpillError { .. } | Cell::ErrorCell { .. }) => {
+                let value1 = cell1.value(&model1.workbook.shared_strings, &model1.language);
+                let value2 = cell2.value(&model2.workbook.shared_strings, &model2.language);
+                let are_equal = match (&value1, &value2) {
+                    (CellValue::Number(x), CellValue::Number(y)) => numbers_are_close(*x, *y, eps),
+                    _ => value1 == value2,
+                };
+                if !are_equal {
+                    diffs.push(Diff {
+                        sheet_name: ws1[cell.index as usize].clone(),
+                        row,
+                        column,
+                        value1: cell1.clone(),
+                        value2: cell2.clone(),
+                        reason: "Values are different".to_string(),
+                    });
+                }
+            }
             (_, _) => {
                 diffs.push(Diff {
                     sheet_name: ws1[cell.index as usize].clone(),
@@ -1,6 +1,7 @@
 mod _rels;
 mod doc_props;
 mod escape;
+mod metadata;
 mod shared_strings;
 mod styles;
 mod workbook;
@@ -15,7 +16,7 @@ use std::{
 };
 
 use equalto_calc::expressions::utils::number_to_column;
-use equalto_calc::model::{get_milliseconds_since_epoch, Model};
+use equalto_calc::model::Model;
 use equalto_calc::types::Workbook;
 
 use self::xml_constants::XML_DECLARATION;
@@ -25,7 +26,7 @@ use crate::error::XlsxError;
 #[cfg(test)]
 mod test;
 
-fn get_content_types_xml(workbook: &Workbook) -> String {
+fn get_content_types_xml(workbook: &Workbook, has_dynamic_arrays: bool) -> String {
     // A list of all files in the zip
     let mut content = vec![
         r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#.to_string(),
@@ -43,6 +44,9 @@ fn get_content_types_xml(workbook: &Workbook) -> String {
     // we skip the theme and calcChain
     // r#"<Override PartName="/xl/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#,
     // r#"<Override PartName="/xl/calcChain.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.calcChain+xml"/>"#,
+    if has_dynamic_arrays {
+        content.push(r#"<Override PartName="/xl/metadata.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheetMetadata+xml"/>"#.to_string());
+    }
     content.extend([
         r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#.to_string(),
         r#"<Override PartName="/xl/sharedStrings.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml"/>"#.to_string(),
@@ -68,6 +72,10 @@ pub fn save_to_xlsx(model: &Model, file_name: &str) -> Result<(), XlsxError> {
 
 pub fn save_xlsx_to_writer<W: Write + Seek>(model: &Model, writer: W) -> Result<W, XlsxError> {
     let workbook = &model.workbook;
+    let has_dynamic_arrays = workbook
+        .worksheets
+        .iter()
+        .any(|worksheet| !worksheets::get_dynamic_arrays(worksheet).is_empty());
     let mut zip = zip::ZipWriter::new(writer);
 
     let options =
@@ -75,13 +83,13 @@ pub fn save_xlsx_to_writer<W: Write + Seek>(model: &Model, writer: W) -> Result<
 
     // root folder
     zip.start_file("[Content_Types].xml", options)?;
-    zip.write_all(get_content_types_xml(workbook).as_bytes())?;
+    zip.write_all(get_content_types_xml(workbook, has_dynamic_arrays).as_bytes())?;
 
     zip.add_directory("docProps", options)?;
     zip.start_file("docProps/app.xml", options)?;
     zip.write_all(doc_props::get_app_xml(workbook).as_bytes())?;
     zip.start_file("docProps/core.xml", options)?;
-    let milliseconds = get_milliseconds_since_epoch();
+    let milliseconds = model.environment().milliseconds_since_epoch();
     zip.write_all(doc_props::get_core_xml(workbook, milliseconds)?.as_bytes())?;
 
     // Package-relationship item
@@ -94,12 +102,18 @@ pub fn save_xlsx_to_writer<W: Write + Seek>(model: &Model, writer: W) -> Result<
     zip.write_all(shared_strings::get_shared_strings_xml(workbook).as_bytes())?;
     zip.start_file("xl/styles.xml", options)?;
     zip.write_all(styles::get_styles_xml(workbook).as_bytes())?;
+    if has_dynamic_arrays {
+        zip.start_file("xl/metadata.xml", options)?;
+        zip.write_all(metadata::get_metadata_xml().as_bytes())?;
+    }
     zip.start_file("xl/workbook.xml", options)?;
-    zip.write_all(workbook::get_workbook_xml(workbook).as_bytes())?;
+    zip.write_all(workbook::get_workbook_xml(workbook, &model.parsed_defined_names).as_bytes())?;
 
     zip.add_directory("xl/_rels", options)?;
     zip.start_file("xl/_rels/workbook.xml.rels", options)?;
-    zip.write_all(workbook_xml_rels::get_workbook_xml_rels(workbook).as_bytes())?;
+    zip.write_all(
+        workbook_xml_rels::get_workbook_xml_rels(workbook, has_dynamic_arrays).as_bytes(),
+    )?;
 
     zip.add_directory("xl/worksheets", options)?;
     for (sheet_index, worksheet) in workbook.worksheets.iter().enumerate() {
@@ -29,12 +29,19 @@
 
 use std::collections::HashMap;
 
-use equalto_calc::types::{SheetState, Workbook};
+use equalto_calc::{
+    expressions::{parser::stringify::to_excel_string, types::CellReferenceRC},
+    model::ParsedDefinedName,
+    types::{IterativeCalculation, SheetState, Workbook},
+};
 
 use super::escape::escape_xml;
 use super::xml_constants::XML_DECLARATION;
 
-pub(crate) fn get_workbook_xml(workbook: &Workbook) -> String {
+pub(crate) fn get_workbook_xml(
+    workbook: &Workbook,
+    parsed_defined_names: &HashMap<(Option<u32>, String), ParsedDefinedName>,
+) -> String {
     // sheets
     // <sheet name="Sheet1" sheetId="1" r:id="rId1"/>
     let mut sheets_str: Vec<String> = vec![];
@@ -62,30 +69,71 @@ pub(crate) fn get_workbook_xml(workbook: &Workbook) -> String {
     let mut defined_names_str: Vec<String> = vec![];
     for defined_name in &workbook.defined_names {
         let name = &defined_name.name;
-        let name = escape_xml(name);
-        let local_sheet_id = if let Some(sheet_id) = defined_name.sheet_id {
+        let sheet_index = defined_name
+            .sheet_id
+            .map(|sheet_id| *sheet_id_to_sheet_index.get(&sheet_id).unwrap());
+        let local_sheet_id = if let Some(excel_local_sheet_id) = sheet_index {
             // In Excel the localSheetId is actually the index of the sheet.
-            let excel_local_sheet_id = sheet_id_to_sheet_index.get(&sheet_id).unwrap();
             format!(" localSheetId=\"{excel_local_sheet_id}\"")
         } else {
             "".to_string()
         };
-        let formula = escape_xml(&defined_name.formula);
+        // Formulas are written with the xlsx names of the functions (`_xlfn.LET`)
+        let formula = match parsed_defined_names.get(&(sheet_index, name.to_lowercase())) {
+            Some(ParsedDefinedName::Formula(node)) | Some(ParsedDefinedName::Lambda(node)) => {
+                let context = CellReferenceRC {
+                    sheet: workbook.worksheets[sheet_index.unwrap_or(0) as usize].get_name(),
+                    row: 1,
+                    column: 1,
+                };
+                to_excel_string(node, &context)
+            }
+            _ => defined_name.formula.clone(),
+        };
+        let formula = escape_xml(&formula);
         defined_names_str.push(format!(
-            "<definedName name=\"{name}\"{local_sheet_id}>{formula}</definedName>"
+            "<definedName name=\"{}\"{local_sheet_id}>{formula}</definedName>",
+            escape_xml(name)
         ))
     }
 
+    // <calcPr iterate="1" iterateCount="100" iterateDelta="0.001"/>
+    let iterative_calculation = &workbook.settings.iterative_calculation;
+    let default_iterative_calculation = IterativeCalculation::default();
+    let mut calc_pr = String::new();
+    if iterative_calculation.enabled {
+        calc_pr.push_str(" iterate=\"1\"");
+    }
+    if iterative_calculation.max_iterations != default_iterative_calculation.max_iterations {
+        calc_pr.push_str(&format!(
+            " iterateCount=\"{}\"",
+            iterative_calculation.max_iterations
+        ));
+    }
+    if iterative_calculation.max_change != default_iterative_calculation.max_change {
+        calc_pr.push_str(&format!(
+            " iterateDelta=\"{}\"",
+            iterative_calculation.max_change
+        ));
+    }
+
+    let workbook_pr = if workbook.settings.date1904 {
+        "<workbookPr date1904=\"1\"/>"
+    } else {
+        ""
+    };
+
     let sheets = sheets_str.join("");
     let defined_names = defined_names_str.join("");
     format!("{XML_DECLARATION}\n\
     <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+      {workbook_pr}\
       <sheets>\
         {sheets}\
       </sheets>\
       <definedNames>\
         {defined_names}\
       </definedNames>\
-      <calcPr/>\
+      <calcPr{calc_pr}/>\
     </workbook>")
 }
@@ -2,7 +2,7 @@ use equalto_calc::types::Workbook;
 
 use super::xml_constants::{XML_DECLARATION, XML_WORKSHEET};
 
-pub(crate) fn get_workbook_xml_rels(workbook: &Workbook) -> String {
+pub(crate) fn get_workbook_xml_rels(workbook: &Workbook, has_dynamic_arrays: bool) -> String {
     let mut relationships_str: Vec<String> = vec![];
     let worksheet_count = workbook.worksheets.len() + 1;
     for id in 1..worksheet_count {
@@ -18,6 +18,12 @@ pub(crate) fn get_workbook_xml_rels(workbook: &Workbook) -> String {
     relationships_str.push(
         format!("<Relationship Id=\"rId{id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings\" Target=\"sharedStrings.xml\"/>")
     );
+    if has_dynamic_arrays {
+        id += 1;
+        relationships_str.push(
+            format!("<Relationship Id=\"rId{id}\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/sheetMetadata\" Target=\"metadata.xml\"/>")
+        );
+    }
     format!(
         "{XML_DECLARATION}\n<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">{}</Relationships>",
         relationships_str.join("")
@@ -23,7 +23,7 @@ use equalto_calc::{
         types::CellReferenceRC,
         utils::number_to_column,
     },
-    types::{Cell, Worksheet},
+    types::{Cell, FormulaValue, Worksheet},
 };
 
 use super::{escape::escape_xml, xml_constants::XML_DECLARATION};
@@ -51,6 +51,59 @@ fn get_formula_attribute(
     escape_xml(formula).to_string()
 }
 
+/// The (rows, columns) of the spill range of each dynamic array formula by (row, column).
+/// Legacy array formulas store the values of their block the same way, but the anchor is an
+/// `ArrayFormula` cell.
+pub(crate) fn get_dynamic_arrays(worksheet: &Worksheet) -> HashMap<(i32, i32), (i32, i32)> {
+    let mut dynamic_arrays = HashMap::new();
+    for (row, row_data) in &worksheet.sheet_data {
+        for (column, cell) in row_data {
+            let (anchor_row, anchor_column) = match cell.get_spill_anchor(*row, *column) {
+                Some(anchor) => anchor,
+                None => continue,
+            };
+            if matches!(
+                worksheet.cell(anchor_row, anchor_column),
+                Some(Cell::ArrayFormula { .. })
+            ) {
+                continue;
+            }
+            let size = dynamic_arrays
+                .entry((anchor_row, anchor_column))
+                .or_insert((1, 1));
+            size.0 = size.0.max(row - anchor_row + 1);
+            size.1 = size.1.max(column - anchor_column + 1);
+        }
+    }
+    dynamic_arrays
+}
+
+/// The cell metadata attribute and the opening formula tag of a formula cell. A dynamic array
+/// formula is saved like Excel does, as an array formula over its spill range flagged with the
+/// (only) cell metadata record:
+/// <c r="A1" cm="1">
+///   <f t="array" ref="A1:A3">SEQUENCE(3)</f>
+///   <v>1</v>
+/// </c>
+fn get_formula_tag(
+    cell_name: &str,
+    row: i32,
+    column: i32,
+    dynamic_arrays: &HashMap<(i32, i32), (i32, i32)>,
+) -> (&'static str, String) {
+    match dynamic_arrays.get(&(row, column)) {
+        Some((rows, columns)) => {
+            let last_column = number_to_column(column + columns - 1).unwrap();
+            let last_row = row + rows - 1;
+            (
+                " cm=\"1\"",
+                format!("<f t=\"array\" ref=\"{cell_name}:{last_column}{last_row}\">"),
+            )
+        }
+        None => ("", "<f>".to_string()),
+    }
+}
+
 pub(crate) fn get_worksheet_xml(
     worksheet: &Worksheet,
     parsed_formulas: &[Node],
@@ -58,6 +111,7 @@ pub(crate) fn get_worksheet_xml(
 ) -> String {
     let mut sheet_data_str: Vec<String> = vec![];
     let mut cols_str: Vec<String> = vec![];
+    let dynamic_arrays = get_dynamic_arrays(worksheet);
 
     for col in &worksheet.cols {
         // <col min="4" max="4" width="12" customWidth="1"/>
@@ -151,9 +205,12 @@ pub(crate) fn get_worksheet_xml(
                         &parsed_formulas[*f as usize],
                     );
 
+                    let (metadata, formula_tag) =
+                        get_formula_tag(&cell_name, *row_index, *column_index, &dynamic_arrays);
+
                     let b = i32::from(*v);
                     row_data_str.push(format!(
-                        "<c r=\"{cell_name}\" t=\"b\"{style}><f>{formula}</f><v>{b}</v></c>"
+                        "<c r=\"{cell_name}\" t=\"b\"{style}{metadata}>{formula_tag}{formula}</f><v>{b}</v></c>"
                     ));
                 }
                 Cell::CellFormulaNumber { f, v, s } => {
@@ -171,8 +228,11 @@ pub(crate) fn get_worksheet_xml(
                     );
                     let style = get_cell_style_attribute(*s);
 
+                    let (metadata, formula_tag) =
+                        get_formula_tag(&cell_name, *row_index, *column_index, &dynamic_arrays);
+
                     row_data_str.push(format!(
-                        "<c r=\"{cell_name}\"{style}><f>{formula}</f><v>{v}</v></c>"
+                        "<c r=\"{cell_name}\"{style}{metadata}>{formula_tag}{formula}</f><v>{v}</v></c>"
                     ));
                 }
                 Cell::CellFormulaString { f, v, s } => {
@@ -188,8 +248,11 @@ pub(crate) fn get_worksheet_xml(
                     );
                     let style = get_cell_style_attribute(*s);
 
+                    let (metadata, formula_tag) =
+                        get_formula_tag(&cell_name, *row_index, *column_index, &dynamic_arrays);
+
                     row_data_str.push(format!(
-                        "<c r=\"{cell_name}\" t=\"str\"{style}><f>{formula}</f><v>{v}</v></c>"
+                        "<c r=\"{cell_name}\" t=\"str\"{style}{metadata}>{formula_tag}{formula}</f><v>{v}</v></c>"
                     ));
                 }
                 Cell::CellFormulaError {
@@ -210,8 +273,65 @@ pub(crate) fn get_worksheet_xml(
                         &parsed_formulas[*f as usize],
                     );
                     let style = get_cell_style_attribute(*s);
+                    let (metadata, formula_tag) =
+                        get_formula_tag(&cell_name, *row_index, *column_index, &dynamic_arrays);
+                    row_data_str.push(format!(
+                        "<c r=\"{cell_name}\" t=\"e\"{style}{metadata}>{formula_tag}{formula}</f><v>{ei}</v></c>"
+                    ));
+                }
+                Cell::ArrayFormula { f, r, v, s } => {
+                    // <c r="B2" t="e" s="4">
+                    //   <f t="array" ref="B2:C3">A1:B2/A3</f>
+                    //   <v>#DIV/0!</v>
+                    // </c>
+                    // The rest of the block holds plain values
+                    let formula = get_formula_attribute(
+                        worksheet.get_name(),
+                        *row_index,
+                        *column_index,
+                        &parsed_formulas[*f as usize],
+                    );
+                    let last_column = number_to_column(*column_index + r.1 - 1).unwrap();
+                    let last_row = *row_index + r.0 - 1;
+                    let reference = format!("{cell_name}:{last_column}{last_row}");
+                    let style = get_cell_style_attribute(*s);
+                    let (cell_type, value) = match v {
+                        FormulaValue::Unevaluated => {
+                            panic!("Model needs to be evaluated before saving!");
+                        }
+                        FormulaValue::Boolean { v } => (" t=\"b\"", i32::from(*v).to_string()),
+                        FormulaValue::Number { v } => ("", v.to_string()),
+                        FormulaValue::String { v } => (" t=\"str\"", escape_xml(v).to_string()),
+                        FormulaValue::Error { ei, .. } => (" t=\"e\"", ei.to_string()),
+                    };
+                    row_data_str.push(format!(
+                        "<c r=\"{cell_name}\"{cell_type}{style}><f t=\"array\" ref=\"{reference}\">{formula}</f><v>{value}</v></c>"
+                    ));
+                }
+                // Values spilled by a dynamic array formula or computed by a legacy array formula
+                // are stored as plain values, the formula of the anchor has the size of the block
+                Cell::SpillBoolean { v, s, .. } => {
+                    let b = i32::from(*v);
+                    let style = get_cell_style_attribute(*s);
+                    row_data_str.push(format!(
+                        "<c r=\"{cell_name}\" t=\"b\"{style}><v>{b}</v></c>"
+                    ));
+                }
+                Cell::SpillNumber { v, s, .. } => {
+                    let style = get_cell_style_attribute(*s);
+                    row_data_str.push(format!("<c r=\"{cell_name}\"{style}><v>{v}</v></c>"));
+                }
+                Cell::SpillString { v, s, .. } => {
+                    let style = get_cell_style_attribute(*s);
+                    let v = escape_xml(v);
                     row_data_str.push(format!(
-                        "<c r=\"{cell_name}\" t=\"e\"{style}><f>{formula}</f><v>{ei}</v></c>"
+                        "<c r=\"{cell_name}\" t=\"str\"{style}><v>{v}</v></c>"
+                    ));
+                }
+                Cell::SpillError { ei, s, .. } => {
+                    let style = get_cell_style_attribute(*s);
+                    row_data_str.push(format!(
+                        "<c r=\"{cell_name}\" t=\"e\"{style}><v>{ei}</v></c>"
                     ));
                 }
             }
@@ -98,6 +98,8 @@ fn load_xlsx_from_reader<R: Read + std::io::Seek>(
         settings: WorkbookSettings {
             tz: tz.to_string(),
             locale: locale.to_string(),
+            iterative_calculation: workbook.iterative_calculation,
+            date1904: workbook.date1904,
         },
         metadata,
         tables,
@@ -1,6 +1,6 @@
 use std::io::Read;
 
-use equalto_calc::types::{DefinedName, SheetState};
+use equalto_calc::types::{DefinedName, IterativeCalculation, SheetState};
 use roxmltree::Node;
 
 use crate::error::XlsxError;
@@ -71,9 +71,29 @@ pub(super) fn load_workbook<R: Read + std::io::Seek>(
             sheet_id,
         })
     }
+    // Iterative calculation settings
+    // <calcPr iterate="1" iterateCount="100" iterateDelta="0.001"/>
+    let mut iterative_calculation = IterativeCalculation::default();
+    if let Some(calc_pr) = doc.descendants().find(|n| n.has_tag_name("calcPr")) {
+        iterative_calculation.enabled = matches!(calc_pr.attribute("iterate"), Some("1" | "true"));
+        if let Some(count) = calc_pr.attribute("iterateCount") {
+            iterative_calculation.max_iterations = count.parse::<i32>()?;
+        }
+        if let Some(delta) = calc_pr.attribute("iterateDelta") {
+            iterative_calculation.max_change = delta.parse::<f64>()?;
+        }
+    }
+    // Mac workbooks count dates from 1904
+    // <workbookPr date1904="1"/>
+    let date1904 = doc
+        .descendants()
+        .find(|n| n.has_tag_name("workbookPr"))
+        .is_some_and(|n| matches!(n.attribute("date1904"), Some("1" | "true")));
     // read the relationships file
     Ok(WorkbookXML {
         worksheets: sheets,
         defined_names,
+        iterative_calculation,
+        date1904,
     })
 }
@@ -5,9 +5,12 @@ use equalto_calc::{
         parser::{stringify::to_rc_format, Parser},
         token::{get_error_by_english_name, Error},
         types::CellReferenceRC,
-        utils::column_to_number,
+        utils::{column_to_number, parse_reference_a1},
+    },
+    types::{
+        Cell, Col, Comment, DefinedName, FormulaValue, IterativeCalculation, Row, SheetData,
+        SheetState, Table, Worksheet,
     },
-    types::{Cell, Col, Comment, DefinedName, Row, SheetData, SheetState, Table, Worksheet},
 };
 use roxmltree::Node;
 use serde::{Deserialize, Serialize};
@@ -32,6 +35,8 @@ pub(crate) struct Sheet {
 pub(crate) struct WorkbookXML {
     pub(crate) worksheets: Vec<Sheet>,
     pub(crate) defined_names: Vec<DefinedName>,
+    pub(crate) iterative_calculation: IterativeCalculation,
+    pub(crate) date1904: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -103,6 +108,74 @@ fn load_columns(ws: Node) -> Result<Vec<Col>, XlsxError> {
     Ok(cols)
 }
 
+/// Returns the (rows, columns) of a range like "B2:C3" or "B2"
+fn get_range_size(reference: &str) -> Result<(i32, i32), XlsxError> {
+    let (first, last) = reference.split_once(':').unwrap_or((reference, reference));
+    match (parse_reference_a1(first), parse_reference_a1(last)) {
+        (Some(first), Some(last)) if first.row <= last.row && first.column <= last.column => {
+            Ok((last.row - first.row + 1, last.column - first.column + 1))
+        }
+        _ => Err(XlsxError::Xml(format!("Invalid range: {}", reference))),
+    }
+}
+
+/// Converts a formula cell into the anchor of an array formula of `r` = (rows, columns)
+fn to_array_formula(cell: Cell, r: (i32, i32)) -> Cell {
+    match cell {
+        Cell::CellFormula { f, s } => Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::Unevaluated,
+            s,
+        },
+        Cell::CellFormulaBoolean { f, v, s } => Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::Boolean { v },
+            s,
+        },
+        Cell::CellFormulaNumber { f, v, s } => Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::Number { v },
+            s,
+        },
+        Cell::CellFormulaString { f, v, s } => Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::String { v },
+            s,
+        },
+        Cell::CellFormulaError { f, ei, s, o, m } => Cell::ArrayFormula {
+            f,
+            r,
+            v: FormulaValue::Error { ei, o, m },
+            s,
+        },
+        cell => cell,
+    }
+}
+
+/// Converts a value cached in the spill range of a dynamic array formula into a spilled value,
+/// `dr` rows below and `dc` columns to the right of the anchor
+fn to_spilled_value(cell: Cell, dr: i32, dc: i32, shared_strings: &[String]) -> Cell {
+    match cell {
+        Cell::BooleanCell { v, s } => Cell::SpillBoolean { v, s, dr, dc },
+        Cell::NumberCell { v, s } => Cell::SpillNumber { v, s, dr, dc },
+        Cell::ErrorCell { ei, s } => Cell::SpillError { ei, s, dr, dc },
+        Cell::SharedString { si, s } => match shared_strings.get(si as usize) {
+            Some(v) => Cell::SpillString {
+                v: v.clone(),
+                s,
+                dr,
+                dc,
+            },
+            None => Cell::SharedString { si, s },
+        },
+        cell => cell,
+    }
+}
+
 fn load_merge_cells(ws: Node) -> Result<Vec<String>, XlsxError> {
     // 18.3.1.55 Merge Cells
     // <mergeCells count="1">
@@ -614,6 +687,8 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
 
     // holds a map from the formula index in Excel to the index in EqualTo
     let mut index_map = HashMap::new();
+    // (rows, columns) of the spill range of each dynamic array formula by (row, column)
+    let mut dynamic_arrays: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
     for row in sheet_data_nodes.children() {
         // This is the row number 1-indexed
         let row_index = get_attribute(&row, "r")?.parse::<i32>()?;
@@ -668,8 +743,9 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
         // r: reference. A1 style
         // s: style index
         // t: cell type
+        // cm: cell metadata. Only used by Excel to flag dynamic array formulas
         // Unused attributes
-        // cm (cell metadata), ph (Show Phonetic), vm (value metadata)
+        // ph (Show Phonetic), vm (value metadata)
         for cell in row.children() {
             let cell_ref = get_attribute(&cell, "r")?;
             let column_letter = get_column_from_ref(cell_ref);
@@ -726,6 +802,7 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
             //       We could solve that with a second transversal.
             let fs: Vec<Node> = cell.children().filter(|n| n.has_tag_name("f")).collect();
             let mut formula_index = -1;
+            let mut array_formula_size = None;
             if fs.len() == 1 {
                 // formula types:
                 // 18.18.6 ST_CellFormulaType (Formula Type)
@@ -790,7 +867,36 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
                         }
                     }
                     "array" => {
-                        return Err(XlsxError::NotImplemented("array formulas".to_string()));
+                        // A legacy array formula entered in the block of cells `ref`:
+                        // <c r="B2">
+                        //   <f t="array" ref="B2:C3">A1:B2*2</f>
+                        //   <v>3</v>
+                        // </c>
+                        // The rest of the cells of the block only have values
+                        // A dynamic array formula looks the same but has cell metadata and `ref`
+                        // is the spill range:
+                        // <c r="B2" cm="1">
+                        //   <f t="array" ref="B2:B4">SEQUENCE(3)</f>
+                        //   <v>1</v>
+                        // </c>
+                        let reference = get_attribute(&fs[0], "ref")?;
+                        let size = get_range_size(reference)?;
+                        if cell.attribute("cm").is_some() {
+                            dynamic_arrays.insert((row_index, column), size);
+                        } else {
+                            array_formula_size = Some(size);
+                        }
+                        let formula = fs[0].text().unwrap_or("").to_string();
+                        let context = format!("{}!{}", sheet_name, cell_ref);
+                        let formula = from_a1_to_rc(formula, worksheets, context, tables.clone())?;
+
+                        match get_formula_index(&formula, &shared_formulas) {
+                            Some(index) => formula_index = index,
+                            None => {
+                                shared_formulas.push(formula);
+                                formula_index = shared_formulas.len() as i32 - 1;
+                            }
+                        }
                     }
                     "dataTable" => {
                         return Err(XlsxError::NotImplemented("data table formulas".to_string()));
@@ -827,6 +933,21 @@ pub(super) fn load_sheet<R: Read + std::io::Seek>(
                 cell_ref,
                 shared_strings,
             );
+            let cell = match array_formula_size {
+                Some(r) => to_array_formula(cell, r),
+                None => cell,
+            };
+            let spill_anchor = dynamic_arrays.iter().find(|((r, c), (rows, columns))| {
+                (*r..r + rows).contains(&row_index)
+                    && (*c..c + columns).contains(&column)
+                    && (*r, *c) != (row_index, column)
+            });
+            let cell = match spill_anchor {
+                Some(((r, c), _)) => {
+                    to_spilled_value(cell, row_index - r, column - c, shared_strings)
+                }
+                None => cell,
+            };
             data_row.insert(column, cell);
         }
         sheet_data.insert(row_index, data_row);
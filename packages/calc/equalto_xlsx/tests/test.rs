@@ -2,7 +2,7 @@ use std::{env, fs, io};
 use uuid::Uuid;
 
 use equalto_calc::model::Model;
-use equalto_calc::types::{HorizontalAlignment, VerticalAlignment, Workbook};
+use equalto_calc::types::{DefinedName, HorizontalAlignment, VerticalAlignment, Workbook};
 use equalto_xlsx::compare::{test_file, test_load_and_saving};
 use equalto_xlsx::error::XlsxError;
 use equalto_xlsx::export::save_to_xlsx;
@@ -253,15 +253,13 @@ fn test_evaluation_error_on_load() {
         err,
         XlsxError::Evaluation(vec![
             "Sheet1!A3 ('=_xlfn.DAYS(A2,A1)'): Invalid function: _xlfn.DAYS".to_string(),
-            "Sheet1!A4 ('=NETWORKDAYS(A1,A2)'): Invalid function: NETWORKDAYS".to_string(),
         ])
     );
     assert_eq!(
         err.to_string(),
         format!(
-            "Evaluation Error: {}; {}",
+            "Evaluation Error: {}",
             "Sheet1!A3 ('=_xlfn.DAYS(A2,A1)'): Invalid function: _xlfn.DAYS",
-            "Sheet1!A4 ('=NETWORKDAYS(A1,A2)'): Invalid function: NETWORKDAYS",
         ),
     )
 }
@@ -282,3 +280,165 @@ fn test_evaluation_discrepancy_on_load() {
         ))
     );
 }
+
+#[test]
+fn test_array_formulas_round_trip() {
+    let mut model = Model::new_empty("model", "en", "UTC").unwrap();
+    model.set_user_input(0, 1, 1, "1".to_string());
+    model.set_user_input(0, 2, 1, "2".to_string());
+    model.set_user_input(0, 3, 1, "3".to_string());
+    // B1:B3 {=A1:A3*10}
+    model.set_array_formula(0, 1, 2, 3, 1, "=A1:A3*10").unwrap();
+    // C1 {=SUM(A1:A3*A1:A3)}
+    model
+        .set_array_formula(0, 1, 3, 1, 1, "=SUM(A1:A3*A1:A3)")
+        .unwrap();
+    model.evaluate();
+
+    let temp_file_name = "temp_file_array_formulas.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let mut model = load_model_from_xlsx(temp_file_name, "en", "UTC").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(
+        model.cell_formula(0, 1, 2).unwrap(),
+        Some("=A1:A3*10".to_string())
+    );
+    assert_eq!(model.cell_formula(0, 2, 2).unwrap(), None);
+    assert_eq!(
+        model.cell_formula(0, 1, 3).unwrap(),
+        Some("=SUM(A1:A3*A1:A3)".to_string())
+    );
+
+    model.set_user_input(0, 3, 1, "4".to_string());
+    model.evaluate();
+    assert_eq!(model.formatted_cell_value(0, 1, 2).unwrap(), "10");
+    assert_eq!(model.formatted_cell_value(0, 2, 2).unwrap(), "20");
+    assert_eq!(model.formatted_cell_value(0, 3, 2).unwrap(), "40");
+    assert_eq!(model.formatted_cell_value(0, 1, 3).unwrap(), "21");
+}
+
+#[test]
+fn test_dynamic_arrays_round_trip() {
+    let mut model = Model::new_empty("model", "en", "UTC").unwrap();
+    model.set_user_input(0, 1, 1, "=SEQUENCE(3)".to_string());
+    model.set_user_input(0, 1, 2, "={\"a\",\"b\";TRUE,#N/A}".to_string());
+    model.set_user_input(0, 1, 4, "=SUM(A1#)".to_string());
+    model.evaluate();
+
+    let temp_file_name = "temp_file_dynamic_arrays.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let mut model = load_model_from_xlsx(temp_file_name, "en", "UTC").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert_eq!(
+        model.cell_formula(0, 1, 1).unwrap(),
+        Some("=SEQUENCE(3)".to_string())
+    );
+    assert_eq!(model.cell_formula(0, 2, 1).unwrap(), None);
+    assert_eq!(model.formatted_cell_value(0, 1, 1).unwrap(), "1");
+    assert_eq!(model.formatted_cell_value(0, 3, 1).unwrap(), "3");
+    assert_eq!(model.formatted_cell_value(0, 1, 3).unwrap(), "b");
+    assert_eq!(model.formatted_cell_value(0, 2, 3).unwrap(), "#N/A");
+    assert_eq!(model.formatted_cell_value(0, 1, 4).unwrap(), "6");
+
+    // The spill range follows the formula
+    model.set_user_input(0, 1, 1, "=SEQUENCE(2)".to_string());
+    model.evaluate();
+    assert_eq!(model.formatted_cell_value(0, 2, 1).unwrap(), "2");
+    assert_eq!(model.formatted_cell_value(0, 3, 1).unwrap(), "");
+    assert_eq!(model.formatted_cell_value(0, 1, 4).unwrap(), "3");
+}
+
+#[test]
+fn test_defined_name_formulas_round_trip() {
+    let mut workbook = Model::new_empty("model", "en", "UTC").unwrap().workbook;
+    workbook.defined_names.push(DefinedName {
+        name: "TaxRate".to_string(),
+        formula: "0.25".to_string(),
+        sheet_id: None,
+    });
+    workbook.defined_names.push(DefinedName {
+        name: "Total".to_string(),
+        formula: "LET(x,SUM(Sheet1!$A$1:$A$2),x*TaxRate)".to_string(),
+        sheet_id: None,
+    });
+    let mut model = Model::from_workbook(workbook).unwrap();
+    model.set_user_input(0, 1, 1, "10".to_string());
+    model.set_user_input(0, 2, 1, "30".to_string());
+    model.set_user_input(0, 1, 2, "=Total".to_string());
+    model.evaluate();
+
+    let temp_file_name = "temp_file_defined_name_formulas.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let mut model = load_model_from_xlsx(temp_file_name, "en", "UTC").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    let formulas: Vec<&str> = model
+        .workbook
+        .defined_names
+        .iter()
+        .map(|defined_name| defined_name.formula.as_str())
+        .collect();
+    assert_eq!(
+        formulas,
+        [
+            "0.25",
+            "_xlfn.LET(_xlpm.x,SUM(Sheet1!$A$1:$A$2),_xlpm.x*TaxRate)"
+        ]
+    );
+    // The API returns the display form
+    assert_eq!(
+        model.get_defined_name_list(),
+        [
+            ("TaxRate".to_string(), None, "0.25".to_string()),
+            (
+                "Total".to_string(),
+                None,
+                "LET(x,SUM(Sheet1!$A$1:$A$2),x*TaxRate)".to_string()
+            ),
+        ]
+    );
+    model.evaluate();
+    assert_eq!(model.formatted_cell_value(0, 1, 2).unwrap(), "10");
+}
+
+#[test]
+fn test_iterative_calculation_round_trip() {
+    let mut model = Model::new_empty("model", "en", "UTC").unwrap();
+    // Converges far enough that evaluating again after loading does not change the values
+    model.set_iterative_calculation(true, 80, 1e-14).unwrap();
+    // A1 converges to 2
+    model.set_user_input(0, 1, 1, "=B1+1".to_string());
+    model.set_user_input(0, 1, 2, "=A1/2".to_string());
+    model.evaluate();
+
+    let temp_file_name = "temp_file_iterative_calculation.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let model = load_model_from_xlsx(temp_file_name, "en", "UTC").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    let settings = &model.workbook.settings.iterative_calculation;
+    assert!(settings.enabled);
+    assert_eq!(settings.max_iterations, 80);
+    assert_eq!(settings.max_change, 1e-14);
+    assert_eq!(model.formatted_cell_value(0, 1, 1).unwrap(), "2");
+}
+
+#[test]
+fn test_date1904_round_trip() {
+    let mut model = Model::new_empty("model", "en", "UTC").unwrap();
+    model.set_date1904(true);
+    model.set_user_input(0, 1, 1, "=DATE(2023,1,9)".to_string());
+    model.evaluate();
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(43473.0.into()));
+
+    let temp_file_name = "temp_file_date1904.xlsx";
+    save_to_xlsx(&model, temp_file_name).unwrap();
+    let model = load_model_from_xlsx(temp_file_name, "en", "UTC").unwrap();
+    fs::remove_file(temp_file_name).unwrap();
+
+    assert!(model.workbook.settings.date1904);
+    assert_eq!(model.get_cell_value_by_ref("Sheet1!A1"), Ok(43473.0.into()));
+    assert_eq!(model.formatted_cell_value(0, 1, 1).unwrap(), "09/01/2023");
+}
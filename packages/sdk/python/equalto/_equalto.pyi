@@ -1,3 +1,5 @@
+from typing import Any, Callable
+
 class PyCalcModel:
     def get_name(self) -> str: ...
     def evaluate_with_error_check(self) -> list[str]: ...
@@ -15,10 +17,26 @@ class PyCalcModel:
     def add_sheet(self, name: str) -> None: ...
     def new_sheet(self) -> None: ...
     def delete_sheet_by_sheet_id(self, sheet_id: int) -> None: ...
+    def get_cell_precedents(self, sheet: int, row: int, column: int, transitive: bool) -> str: ...
+    def get_cell_dependents(self, sheet: int, row: int, column: int, transitive: bool) -> str: ...
+    def register_function(self, name: str, function: Callable[..., Any], volatile: bool = False) -> None: ...
+    def unregister_function(self, name: str) -> None: ...
+    def get_defined_name_list(self) -> list[tuple[str, int | None, str]]: ...
+    def new_defined_name(self, name: str, scope: int | None, formula: str) -> None: ...
+    def update_defined_name(
+        self,
+        name: str,
+        scope: int | None,
+        new_name: str,
+        new_scope: int | None,
+        new_formula: str,
+    ) -> None: ...
+    def delete_defined_name(self, name: str, scope: int | None) -> None: ...
     def rename_sheet(self, sheet: int, new_name: str) -> None: ...
     def set_cell_empty(self, sheet: int, row: int, column: int) -> None: ...
     def delete_cell(self, sheet: int, row: int, column: int) -> None: ...
     def get_timezone(self) -> str: ...
+    def is_date1904(self) -> bool: ...
     def save_to_xlsx(self, file: str) -> None: ...
     def get_style_for_cell(self, sheet: int, row: int, column: int) -> str: ...
     def set_cell_style(self, sheet: int, row: int, column: int, style: str) -> None: ...
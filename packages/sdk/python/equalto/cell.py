@@ -9,6 +9,7 @@ from typing import TYPE_CHECKING
 from equalto._equalto import number_to_column
 from equalto.exceptions import WorkbookValueError
 from equalto.style import Style
+from equalto.trace import TraceArrow
 
 if TYPE_CHECKING:
     from equalto._equalto import PyCalcModel
@@ -136,6 +137,23 @@ class Cell:
         self._model.set_user_input(*self.cell_ref, value)
         self.workbook.evaluate()
 
+    def precedents(self, transitive: bool = False) -> list[TraceArrow]:
+        """
+        Get an arrow for every area read by the formula in the cell.
+
+        Areas reached through defined names and structured table references are included. With
+        `transitive` the formulas in those areas are traced too, and so on.
+        """
+        return self._trace_arrows(self._model.get_cell_precedents(*self.cell_ref, transitive))
+
+    def dependents(self, transitive: bool = False) -> list[TraceArrow]:
+        """
+        Get an arrow to every formula that reads the cell.
+
+        With `transitive` the dependents of those formulas are traced too, and so on.
+        """
+        return self._trace_arrows(self._model.get_cell_dependents(*self.cell_ref, transitive))
+
     @property
     def style(self) -> Style:
         return Style(self)
@@ -161,7 +179,25 @@ class Cell:
     def _model(self) -> PyCalcModel:
         return self.workbook._model  # noqa: WPS437
 
-    _excel_base_dt = datetime(1899, 12, 30)
+    def _trace_arrows(self, arrows_json: str) -> list[TraceArrow]:
+        sheet_names = [sheet.name for sheet in self.workbook.sheets]
+
+        def text_ref(sheet: int, row: int, column: int, width: int = 1, height: int = 1) -> str:
+            text = f"{sheet_names[sheet]}!{number_to_column(column)}{row}"
+            if width > 1 or height > 1:
+                text += f":{number_to_column(column + width - 1)}{row + height - 1}"
+            return text
+
+        return [
+            TraceArrow(precedent=text_ref(**arrow["precedent"]), dependent=text_ref(**arrow["dependent"]))
+            for arrow in json.loads(arrows_json)
+        ]
+
+    @property
+    def _excel_base_dt(self) -> datetime:
+        if self.workbook.date1904:
+            return datetime(1904, 1, 1)
+        return datetime(1899, 12, 30)
 
     def _get_excel_date(self, dt: date | datetime) -> float:
         if isinstance(dt, datetime):
@@ -0,0 +1,10 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+
+
+@dataclass(frozen=True)
+class DefinedName:
+    name: str
+    formula: str
+    scope: int | None = None  # sheet index of names local to a sheet
@@ -0,0 +1,11 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+
+
+@dataclass(frozen=True)
+class TraceArrow:
+    """An arrow from an area to a formula that reads it, both as text references."""
+
+    precedent: str  # i.e. "Sheet1!A1:A3"
+    dependent: str  # i.e. "Sheet2!B1"
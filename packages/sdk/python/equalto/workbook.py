@@ -2,9 +2,10 @@ from __future__ import annotations
 
 import os
 from functools import cached_property
-from typing import TYPE_CHECKING
+from typing import TYPE_CHECKING, Any, Callable
 from zoneinfo import ZoneInfo
 
+from equalto.defined_name import DefinedName
 from equalto.exceptions import CellReferenceError, SuppressEvaluationErrors, WorkbookError, WorkbookEvaluationError
 from equalto.reference import parse_cell_reference
 from equalto.sheet import WorkbookSheets
@@ -41,6 +42,11 @@ class Workbook:
     def timezone(self) -> ZoneInfo:
         return ZoneInfo(self._model.get_timezone())
 
+    @cached_property
+    def date1904(self) -> bool:
+        """Dates are counted from 1 January 1904 instead of 1 January 1900 (Mac workbooks)."""
+        return self._model.is_date1904()
+
     def cell(self, sheet_index: int, row: int, column: int) -> Cell:
         return self.sheets[sheet_index].cell(row, column)
 
@@ -49,6 +55,51 @@ class Workbook:
         """Get container with workbook sheets."""
         return WorkbookSheets(self)
 
+    @property
+    def defined_names(self) -> list[DefinedName]:
+        return [
+            DefinedName(name=name, formula=formula, scope=scope)
+            for name, scope, formula in self._model.get_defined_name_list()
+        ]
+
+    def add_defined_name(self, name: str, formula: str, *, scope: int | None = None) -> None:
+        """
+        Add a defined name to the workbook.
+
+        `scope` is the index of the sheet the name is local to, or None for a workbook-wide name.
+        """
+        self._model.new_defined_name(name, scope, formula)
+
+    def update_defined_name(
+        self,
+        name: str,
+        new_name: str,
+        new_formula: str,
+        *,
+        scope: int | None = None,
+        new_scope: int | None = None,
+    ) -> None:
+        """Rename the defined name, change its scope or its formula."""
+        self._model.update_defined_name(name, scope, new_name, new_scope, new_formula)
+
+    def delete_defined_name(self, name: str, *, scope: int | None = None) -> None:
+        """Delete the defined name with the given scope, None for a workbook-wide name."""
+        self._model.delete_defined_name(name, scope)
+
+    def register_function(self, name: str, function: Callable[..., Any], volatile: bool = False) -> None:
+        """
+        Register a function that can be called from formulas.
+
+        The function is called with the evaluated arguments: None for empty values, numbers, strings,
+        booleans and ranges as lists of rows. It can return any of those, a list of rows spills. An
+        exception makes the formula evaluate to #VALUE!. Volatile functions are recomputed on every
+        evaluation, like RAND or NOW.
+        """
+        self._model.register_function(name, function, volatile)
+
+    def unregister_function(self, name: str) -> None:
+        self._model.unregister_function(name)
+
     def save(self, file: str) -> None:
         _, ext = os.path.splitext(file)
         if ext == ".xlsx":
@@ -1,10 +1,12 @@
 use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::types::{PyBool, PyList, PyTuple};
 use pyo3::{create_exception, prelude::*, wrap_pyfunction};
 
 use equalto_calc::expressions::utils;
 use equalto_calc::model::Model;
 use equalto_calc::types::CellType;
 use equalto_calc::types::Worksheet;
+use equalto_calc::user_functions::{FunctionValue, UserFunction};
 use equalto_xlsx::error::XlsxError;
 use equalto_xlsx::export::save_to_xlsx;
 use equalto_xlsx::import::{check_model_support, load_model_from_xlsx_without_support_check};
@@ -133,6 +135,86 @@ impl PyModel {
             .map_err(WorkbookError::new_err)
     }
 
+    pub fn get_cell_precedents(
+        &self,
+        sheet: i32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> PyResult<String> {
+        let arrows = self
+            .model
+            .get_cell_precedents(sheet.try_into().unwrap(), row, column, transitive)
+            .map_err(WorkbookError::new_err)?;
+        Ok(serde_json::to_string(&arrows).unwrap())
+    }
+
+    pub fn get_cell_dependents(
+        &self,
+        sheet: i32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> PyResult<String> {
+        let arrows = self
+            .model
+            .get_cell_dependents(sheet.try_into().unwrap(), row, column, transitive)
+            .map_err(WorkbookError::new_err)?;
+        Ok(serde_json::to_string(&arrows).unwrap())
+    }
+
+    #[args(volatile = "false")]
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        function: PyObject,
+        volatile: bool,
+    ) -> PyResult<()> {
+        self.model
+            .register_function(name, PyUserFunction { function, volatile })
+            .map_err(WorkbookError::new_err)
+    }
+
+    pub fn unregister_function(&mut self, name: &str) -> PyResult<()> {
+        self.model
+            .unregister_function(name)
+            .map_err(WorkbookError::new_err)
+    }
+
+    pub fn get_defined_name_list(&self) -> PyResult<Vec<(String, Option<u32>, String)>> {
+        Ok(self.model.get_defined_name_list())
+    }
+
+    pub fn new_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        formula: &str,
+    ) -> PyResult<()> {
+        self.model
+            .new_defined_name(name, scope, formula)
+            .map_err(WorkbookError::new_err)
+    }
+
+    pub fn update_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        new_name: &str,
+        new_scope: Option<u32>,
+        new_formula: &str,
+    ) -> PyResult<()> {
+        self.model
+            .update_defined_name(name, scope, new_name, new_scope, new_formula)
+            .map_err(WorkbookError::new_err)
+    }
+
+    pub fn delete_defined_name(&mut self, name: &str, scope: Option<u32>) -> PyResult<()> {
+        self.model
+            .delete_defined_name(name, scope)
+            .map_err(WorkbookError::new_err)
+    }
+
     pub fn update_cell_with_text(&mut self, sheet: i32, row: i32, column: i32, value: &str) {
         self.model
             .update_cell_with_text(sheet.try_into().unwrap(), row, column, value);
@@ -169,6 +251,10 @@ impl PyModel {
         Ok(self.model.tz.to_string())
     }
 
+    pub fn is_date1904(&self) -> PyResult<bool> {
+        Ok(self.model.workbook.settings.date1904)
+    }
+
     pub fn save_to_xlsx(&self, file: &str) -> PyResult<()> {
         save_to_xlsx(&self.model, file).map_err(|e| WorkbookError::new_err(e.to_string()))
     }
@@ -182,6 +268,79 @@ impl PyModel {
     }
 }
 
+/// A Python callable used as a user defined function
+struct PyUserFunction {
+    function: PyObject,
+    volatile: bool,
+}
+
+impl UserFunction for PyUserFunction {
+    fn call(&self, args: &[FunctionValue]) -> Result<FunctionValue, String> {
+        Python::with_gil(|py| {
+            let args: Vec<PyObject> = args.iter().map(|arg| to_python(py, arg)).collect();
+            let result = self
+                .function
+                .call1(py, PyTuple::new(py, args))
+                .map_err(|e| e.to_string())?;
+            from_python(result.as_ref(py))
+        })
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.volatile
+    }
+}
+
+fn to_python(py: Python, value: &FunctionValue) -> PyObject {
+    match value {
+        FunctionValue::Empty => py.None(),
+        FunctionValue::Number(value) => value.to_object(py),
+        FunctionValue::String(value) => value.to_object(py),
+        FunctionValue::Boolean(value) => value.to_object(py),
+        FunctionValue::Error(error) => error.to_string().to_object(py),
+        FunctionValue::Array(rows) => rows
+            .iter()
+            .map(|row| row.iter().map(|value| to_python(py, value)).collect())
+            .collect::<Vec<Vec<PyObject>>>()
+            .to_object(py),
+    }
+}
+
+fn from_python(value: &PyAny) -> Result<FunctionValue, String> {
+    if value.is_none() {
+        return Ok(FunctionValue::Empty);
+    }
+    // bool is a subclass of int, it has to be checked first
+    if let Ok(value) = value.downcast::<PyBool>() {
+        return Ok(FunctionValue::Boolean(value.is_true()));
+    }
+    if let Ok(value) = value.extract::<f64>() {
+        return Ok(FunctionValue::Number(value));
+    }
+    if let Ok(value) = value.extract::<String>() {
+        return Ok(FunctionValue::String(value));
+    }
+    if let Ok(list) = value.downcast::<PyList>() {
+        // A list of values is a single row
+        let is_row = list
+            .iter()
+            .all(|item| !item.is_instance_of::<PyList>().unwrap_or(false));
+        let rows = if is_row {
+            vec![list]
+        } else {
+            list.iter()
+                .map(|row| row.downcast::<PyList>().map_err(|e| e.to_string()))
+                .collect::<Result<_, _>>()?
+        };
+        let mut array = Vec::new();
+        for row in rows {
+            array.push(row.iter().map(from_python).collect::<Result<_, _>>()?);
+        }
+        return Ok(FunctionValue::Array(array));
+    }
+    Err(format!("Unsupported value: {value}"))
+}
+
 impl WorkbookError {
     fn from_xlsx_error(error: XlsxError) -> PyErr {
         WorkbookError::new_err(error.user_message())
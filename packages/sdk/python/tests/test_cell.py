@@ -1,3 +1,4 @@
+import json
 from datetime import date, datetime, timezone
 from typing import Any
 from zoneinfo import ZoneInfo
@@ -7,6 +8,7 @@ import pytest
 import equalto
 from equalto.cell import Cell, CellType
 from equalto.exceptions import WorkbookError, WorkbookEvaluationError, WorkbookValueError
+from equalto.trace import TraceArrow
 from equalto.workbook import Workbook
 
 
@@ -256,6 +258,21 @@ def test_date_value(workbook_timezone: str, date_value: date, raw_value: int) ->
     assert cell.date_value == date_value
 
 
+def test_date_value_1904() -> None:
+    workbook_json = json.loads(equalto.new(timezone=ZoneInfo("UTC")).json)
+    workbook_json["settings"]["date1904"] = True
+    workbook = equalto.loads(json.dumps(workbook_json))
+    assert workbook.date1904
+
+    cell = workbook["Sheet1!A1"]
+    cell.value = date(2023, 1, 9)
+    assert cell.value == 44935 - 1462
+    assert cell.date_value == date(2023, 1, 9)
+
+    workbook["Sheet1!A2"].formula = "=DATE(2023, 1, 9)"
+    assert workbook["Sheet1!A2"].value == 44935 - 1462
+
+
 @pytest.mark.parametrize(
     "value, error",
     [
@@ -322,3 +339,28 @@ def test_set_naive_datetime(cell: Cell) -> None:
 
 def _get_tz_cell(tz: str) -> Cell:
     return equalto.new(timezone=ZoneInfo(tz)).sheets[0]["A1"]
+
+
+def test_precedents_and_dependents(empty_workbook: Workbook) -> None:
+    empty_workbook.sheets.add("Other")
+    empty_workbook["Sheet1!A1"].value = 1
+    empty_workbook["Sheet1!A2"].value = 2
+    empty_workbook.add_defined_name("Data", "Sheet1!$A$1:$A$2")
+    empty_workbook["Sheet1!B1"].formula = "=SUM(Data)"
+    empty_workbook["Other!A1"].formula = "=Sheet1!B1*2"
+
+    assert empty_workbook["Other!A1"].precedents() == [
+        TraceArrow(precedent="Sheet1!B1", dependent="Other!A1"),
+    ]
+    assert empty_workbook["Other!A1"].precedents(transitive=True) == [
+        TraceArrow(precedent="Sheet1!A1:A2", dependent="Sheet1!B1"),
+        TraceArrow(precedent="Sheet1!B1", dependent="Other!A1"),
+    ]
+    assert empty_workbook["Sheet1!A2"].dependents() == [
+        TraceArrow(precedent="Sheet1!A2", dependent="Sheet1!B1"),
+    ]
+    assert empty_workbook["Sheet1!A2"].dependents(transitive=True) == [
+        TraceArrow(precedent="Sheet1!A2", dependent="Sheet1!B1"),
+        TraceArrow(precedent="Sheet1!B1", dependent="Other!A1"),
+    ]
+    assert not empty_workbook["Sheet1!A1"].precedents()
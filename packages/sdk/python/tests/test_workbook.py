@@ -6,7 +6,8 @@ import pytest
 
 import equalto
 from equalto.cell import Cell
-from equalto.exceptions import CellReferenceError, SuppressEvaluationErrors, WorkbookEvaluationError
+from equalto.defined_name import DefinedName
+from equalto.exceptions import CellReferenceError, SuppressEvaluationErrors, WorkbookError, WorkbookEvaluationError
 from equalto.workbook import Workbook
 
 
@@ -93,3 +94,67 @@ def test_suppress_evaluation_errors(cell: Cell) -> None:
     # the errors are normally raised after leaving the context
     with pytest.raises(WorkbookEvaluationError):
         cell.formula = "=INVALID()"
+
+
+def test_defined_names(empty_workbook: Workbook) -> None:
+    empty_workbook["Sheet1!A1"].value = 21
+    empty_workbook.add_defined_name("Answer", "Sheet1!$A$1*2")
+    empty_workbook["Sheet1!B1"].formula = "=Answer"
+    assert empty_workbook.defined_names == [DefinedName(name="Answer", formula="Sheet1!$A$1*2")]
+    assert empty_workbook["Sheet1!B1"].value == 42
+
+    empty_workbook.update_defined_name("Answer", "Result", "Sheet1!$A$1", new_scope=0)
+    assert empty_workbook.defined_names == [DefinedName(name="Result", formula="Sheet1!$A$1", scope=0)]
+    assert empty_workbook["Sheet1!B1"].formula == "=Result"
+    assert empty_workbook["Sheet1!B1"].value == 21
+
+    empty_workbook.delete_defined_name("Result", scope=0)
+    assert not empty_workbook.defined_names
+
+    empty_workbook.add_defined_name("Local", "2", scope=0)
+    assert empty_workbook.defined_names == [DefinedName(name="Local", formula="2", scope=0)]
+
+
+def test_invalid_defined_name(empty_workbook: Workbook) -> None:
+    with pytest.raises(WorkbookError, match="Invalid defined name: 'A1'"):
+        empty_workbook.add_defined_name("A1", "1")
+    with pytest.raises(WorkbookError, match="Defined name not found: 'Foo'"):
+        empty_workbook.delete_defined_name("Foo")
+
+
+def test_user_functions(empty_workbook: Workbook) -> None:
+    rates = {"EUR": 1.1, "GBP": 1.25}
+
+    def fx_rate(currency: str) -> float:
+        return rates[currency]
+
+    def total(values: list[list[object]]) -> float:
+        return sum(value for row in values for value in row if isinstance(value, float))
+
+    empty_workbook.register_function("FxRate", fx_rate)
+    empty_workbook.register_function("TOTAL", total)
+    empty_workbook.register_function("SEQUENCE_OF", lambda n: [[i] for i in range(1, int(n) + 1)])
+
+    sheet = empty_workbook.sheets[0]
+    sheet["A1"].value = 100
+    sheet["A2"].value = 50
+    sheet["B1"].formula = '=fxrate("EUR")*A1'
+    sheet["B2"].formula = '=FXRATE("JPY")'
+    sheet["B3"].formula = "=TOTAL(A1:A2)"
+    sheet["C1"].formula = "=SEQUENCE_OF(3)"
+    assert sheet["B1"].formula == '=FXRATE("EUR")*A1'
+    assert sheet["B1"].value == pytest.approx(110)
+    assert sheet["B2"].value == "#VALUE!"
+    assert sheet["B3"].value == 150
+    assert sheet["C3"].value == 3
+
+    sheet["A1"].value = 200
+    assert sheet["B1"].value == pytest.approx(220)
+
+    with pytest.raises(WorkbookError, match="Function already exists: 'SUM'"):
+        empty_workbook.register_function("SUM", total)
+
+    empty_workbook.unregister_function("FXRATE")
+    assert sheet["B1"].value == "#ERROR!"
+    with pytest.raises(WorkbookError, match="Function not found: 'FXRATE'"):
+        empty_workbook.unregister_function("FXRATE")
@@ -0,0 +1,53 @@
+use crate::error::WorkbookError;
+use crate::workbook::Workbook;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinedName {
+    pub name: String,
+    pub scope: Option<u32>, // sheet index of names local to a sheet
+    pub formula: String,
+}
+
+impl Workbook {
+    pub fn defined_names(&self) -> Vec<DefinedName> {
+        self.calc_model
+            .get_defined_name_list()
+            .into_iter()
+            .map(|(name, scope, formula)| DefinedName {
+                name,
+                scope,
+                formula,
+            })
+            .collect()
+    }
+
+    pub fn add_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        formula: &str,
+    ) -> Result<(), WorkbookError> {
+        Ok(self.calc_model.new_defined_name(name, scope, formula)?)
+    }
+
+    pub fn update_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        new_name: &str,
+        new_scope: Option<u32>,
+        new_formula: &str,
+    ) -> Result<(), WorkbookError> {
+        Ok(self
+            .calc_model
+            .update_defined_name(name, scope, new_name, new_scope, new_formula)?)
+    }
+
+    pub fn delete_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+    ) -> Result<(), WorkbookError> {
+        Ok(self.calc_model.delete_defined_name(name, scope)?)
+    }
+}
@@ -1,4 +1,5 @@
 pub mod cell;
+pub mod defined_name;
 pub mod error;
 pub mod workbook;
 
@@ -6,6 +7,7 @@ pub mod workbook;
 mod tests {
     use equalto_calc::cell::CellValue;
 
+    use crate::defined_name::DefinedName;
     use crate::workbook::Workbook;
 
     #[test]
@@ -42,6 +44,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_defined_names() {
+        let mut workbook = Workbook::new().unwrap();
+        workbook.set_formula("Sheet1!A1", "=Rate*10").unwrap();
+        workbook.add_defined_name("Rate", None, "0.5").unwrap();
+        assert_eq!(workbook.value("Sheet1!A1").unwrap(), CellValue::Number(5.0));
+
+        workbook
+            .update_defined_name("Rate", None, "Factor", Some(0), "2")
+            .unwrap();
+        assert_eq!(
+            workbook.formula("Sheet1!A1").unwrap(),
+            Some("=Factor*10".to_string()),
+        );
+        assert_eq!(
+            workbook.value("Sheet1!A1").unwrap(),
+            CellValue::Number(20.0),
+        );
+        assert_eq!(
+            workbook.defined_names(),
+            vec![DefinedName {
+                name: "Factor".to_string(),
+                scope: Some(0),
+                formula: "2".to_string(),
+            }],
+        );
+
+        workbook.delete_defined_name("Factor", Some(0)).unwrap();
+        assert!(workbook.defined_names().is_empty());
+        assert!(workbook.add_defined_name("A1", None, "1").is_err());
+    }
+
     #[test]
     fn test_load() {
         let workbook = Workbook::load("tests/example.xlsx").unwrap();
@@ -1,12 +1,14 @@
 use wasm_bindgen::{
     prelude::{wasm_bindgen, JsError},
-    JsValue,
+    JsCast, JsValue,
 };
 
 use equalto_calc::{
     cell::CellValue,
     expressions::types::{Area, CellReferenceIndex},
     model::Model,
+    operations::Operation,
+    user_functions::{FunctionValue, UserFunction},
     worksheet::NavigationDirection,
 };
 
@@ -18,6 +20,82 @@ use equalto_xlsx::export::save_xlsx_to_writer;
 
 use crate::error::WorkbookError;
 
+/// A JavaScript callback used as a user defined function
+struct JsUserFunction {
+    callback: js_sys::Function,
+    volatile: bool,
+}
+
+// WebAssembly is single threaded, the callback is never shared between threads
+unsafe impl Send for JsUserFunction {}
+unsafe impl Sync for JsUserFunction {}
+
+impl UserFunction for JsUserFunction {
+    fn call(&self, args: &[FunctionValue]) -> Result<FunctionValue, String> {
+        let args: js_sys::Array = args.iter().map(to_js_value).collect();
+        let result = self
+            .callback
+            .apply(&JsValue::NULL, &args)
+            .map_err(|error| match error.dyn_ref::<js_sys::Error>() {
+                Some(error) => String::from(error.message()),
+                None => error
+                    .as_string()
+                    .unwrap_or_else(|| "Function failed".to_string()),
+            })?;
+        from_js_value(&result)
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.volatile
+    }
+}
+
+fn to_js_value(value: &FunctionValue) -> JsValue {
+    match value {
+        FunctionValue::Empty => JsValue::NULL,
+        FunctionValue::Number(value) => JsValue::from_f64(*value),
+        FunctionValue::String(value) => JsValue::from_str(value),
+        FunctionValue::Boolean(value) => JsValue::from_bool(*value),
+        FunctionValue::Error(error) => JsValue::from_str(&error.to_string()),
+        FunctionValue::Array(rows) => rows
+            .iter()
+            .map(|row| row.iter().map(to_js_value).collect::<js_sys::Array>())
+            .collect::<js_sys::Array>()
+            .into(),
+    }
+}
+
+fn from_js_value(value: &JsValue) -> Result<FunctionValue, String> {
+    if value.is_null() || value.is_undefined() {
+        Ok(FunctionValue::Empty)
+    } else if let Some(value) = value.as_f64() {
+        Ok(FunctionValue::Number(value))
+    } else if let Some(value) = value.as_string() {
+        Ok(FunctionValue::String(value))
+    } else if let Some(value) = value.as_bool() {
+        Ok(FunctionValue::Boolean(value))
+    } else if js_sys::Array::is_array(value) {
+        let array = js_sys::Array::from(value);
+        // An array of values is a single row
+        let rows = if array.iter().any(|item| js_sys::Array::is_array(&item)) {
+            array.iter().map(|row| js_sys::Array::from(&row)).collect()
+        } else {
+            vec![array]
+        };
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(
+                row.iter()
+                    .map(|value| from_js_value(&value))
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+        Ok(FunctionValue::Array(values))
+    } else {
+        Err("Unsupported value returned by function".to_string())
+    }
+}
+
 #[wasm_bindgen]
 pub enum WasmNavigationDirection {
     Left,
@@ -115,6 +193,12 @@ pub struct WasmSheetDimension {
     pub max_row: i32,
 }
 
+fn parse_operation(operation: &str) -> Result<Operation, WorkbookError> {
+    serde_json::from_str(operation)
+        .map_err(|_| "Could not parse data transfer blob for operation.".to_string())
+        .map_err(WorkbookError::from)
+}
+
 #[wasm_bindgen]
 pub struct WasmWorkbook {
     model: Model,
@@ -168,6 +252,75 @@ impl WasmWorkbook {
         Ok(())
     }
 
+    pub fn undo(&mut self) -> Result<(), JsError> {
+        self.model
+            .undo()
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    pub fn redo(&mut self) -> Result<(), JsError> {
+        self.model
+            .redo()
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "canUndo")]
+    pub fn can_undo(&self) -> bool {
+        self.model.can_undo()
+    }
+
+    #[wasm_bindgen(js_name = "canRedo")]
+    pub fn can_redo(&self) -> bool {
+        self.model.can_redo()
+    }
+
+    #[wasm_bindgen(js_name = "isDate1904")]
+    pub fn is_date1904(&self) -> bool {
+        self.model.workbook.settings.date1904
+    }
+
+    #[wasm_bindgen(js_name = "beginTransaction")]
+    pub fn begin_transaction(&mut self) {
+        self.model.begin_transaction();
+    }
+
+    #[wasm_bindgen(js_name = "commitTransaction")]
+    pub fn commit_transaction(&mut self) {
+        self.model.commit_transaction();
+    }
+
+    #[wasm_bindgen(js_name = "applyOperation")]
+    pub fn apply_operation(&mut self, operation: &str) -> Result<(), JsError> {
+        self.model
+            .apply_operation(&parse_operation(operation)?)
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "transformOperation")]
+    pub fn transform_operation(
+        &mut self,
+        operation: &str,
+        applied: &str,
+        priority: bool,
+    ) -> Result<Option<String>, JsError> {
+        let operation = parse_operation(operation)?;
+        let applied = parse_operation(applied)?;
+        match self
+            .model
+            .transform_operation(&operation, &applied, priority)
+        {
+            Some(operation) => Ok(Some(
+                serde_json::to_string(&operation)
+                    .map_err(|_| "Could not stringify operation to JSON.".to_string())
+                    .map_err(WorkbookError::from)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     #[wasm_bindgen(js_name = "getWorksheetNames")]
     pub fn get_worksheet_names(&self) -> Result<js_sys::Array, JsError> {
         Ok(self
@@ -225,6 +378,104 @@ impl WasmWorkbook {
             .map_err(JsError::from)
     }
 
+    #[wasm_bindgen(js_name = "getCellPrecedents")]
+    pub fn get_cell_precedents(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> Result<String, JsError> {
+        let arrows = self
+            .model
+            .get_cell_precedents(sheet, row, column, transitive)
+            .map_err(WorkbookError::from)?;
+        Ok(serde_json::to_string(&arrows)
+            .map_err(|_| "Could not stringify trace arrows to JSON.".to_string())
+            .map_err(WorkbookError::from)?)
+    }
+
+    #[wasm_bindgen(js_name = "getCellDependents")]
+    pub fn get_cell_dependents(
+        &self,
+        sheet: u32,
+        row: i32,
+        column: i32,
+        transitive: bool,
+    ) -> Result<String, JsError> {
+        let arrows = self
+            .model
+            .get_cell_dependents(sheet, row, column, transitive)
+            .map_err(WorkbookError::from)?;
+        Ok(serde_json::to_string(&arrows)
+            .map_err(|_| "Could not stringify trace arrows to JSON.".to_string())
+            .map_err(WorkbookError::from)?)
+    }
+
+    #[wasm_bindgen(js_name = "registerFunction")]
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        callback: js_sys::Function,
+        volatile: bool,
+    ) -> Result<(), JsError> {
+        self.model
+            .register_function(name, JsUserFunction { callback, volatile })
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "unregisterFunction")]
+    pub fn unregister_function(&mut self, name: &str) -> Result<(), JsError> {
+        self.model
+            .unregister_function(name)
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "getDefinedNameList")]
+    pub fn get_defined_name_list(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.model.get_defined_name_list())
+            .map_err(|_| "Could not stringify defined names to JSON.".to_string())
+            .map_err(WorkbookError::from)?)
+    }
+
+    #[wasm_bindgen(js_name = "newDefinedName")]
+    pub fn new_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        formula: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .new_defined_name(name, scope, formula)
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "updateDefinedName")]
+    pub fn update_defined_name(
+        &mut self,
+        name: &str,
+        scope: Option<u32>,
+        new_name: &str,
+        new_scope: Option<u32>,
+        new_formula: &str,
+    ) -> Result<(), JsError> {
+        self.model
+            .update_defined_name(name, scope, new_name, new_scope, new_formula)
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
+    #[wasm_bindgen(js_name = "deleteDefinedName")]
+    pub fn delete_defined_name(&mut self, name: &str, scope: Option<u32>) -> Result<(), JsError> {
+        self.model
+            .delete_defined_name(name, scope)
+            .map_err(WorkbookError::from)
+            .map_err(JsError::from)
+    }
+
     #[wasm_bindgen(js_name = "getCellValueByIndex")]
     pub fn get_cell_value_by_index(
         &self,
@@ -50,6 +50,15 @@ describe('utils', () => {
         '2030-01-01T09:36:00.000Z',
       );
     });
+
+    test('converts dates in the 1904 date system', () => {
+      expect(convertSpreadsheetDateToDayjsUTC(0, true).toISOString()).toEqual(
+        '1904-01-01T00:00:00.000Z',
+      );
+      expect(convertSpreadsheetDateToDayjsUTC(46_022, true).toISOString()).toEqual(
+        '2030-01-01T00:00:00.000Z',
+      );
+    });
   });
 
   describe('convertDayjsUTCToSpreadsheetDate', () => {
@@ -80,5 +89,14 @@ describe('utils', () => {
         47_484.4,
       );
     });
+
+    test('converts dates in the 1904 date system', () => {
+      expect(convertDayjsUTCToSpreadsheetDate(dayjs.utc('1904-01-01T00:00:00.000Z'), true)).toEqual(
+        0,
+      );
+      expect(convertDayjsUTCToSpreadsheetDate(dayjs.utc('2030-01-01T00:00:00.000Z'), true)).toEqual(
+        46_022,
+      );
+    });
   });
 });
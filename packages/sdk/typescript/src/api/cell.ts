@@ -135,7 +135,7 @@ export class Cell implements ICell {
         this._wasmWorkbook.updateCellWithBool(this._sheet.index, this._row, this._column, value);
       } else if (value instanceof Date) {
         const date = dayjs.utc(value);
-        const excelDate = convertDayjsUTCToSpreadsheetDate(date);
+        const excelDate = convertDayjsUTCToSpreadsheetDate(date, this._wasmWorkbook.isDate1904());
         if (excelDate < 0) {
           throw new CalcError(`Date "${date.toISOString()}" is not representable in workbook.`);
         }
@@ -164,7 +164,7 @@ export class Cell implements ICell {
     if (value < 0) {
       throw new CalcError(`Number "${value}" cannot be converted to date.`);
     }
-    return convertSpreadsheetDateToDayjsUTC(value).toDate();
+    return convertSpreadsheetDateToDayjsUTC(value, this._wasmWorkbook.isDate1904()).toDate();
   }
 
   get stringValue(): string {
@@ -61,6 +61,30 @@ type Area = {
   height: number;
 };
 
+export type DefinedName = {
+  name: string;
+  /** Index of the sheet the name is local to, `null` for workbook-wide names. */
+  scope: number | null;
+  formula: string;
+};
+
+/** An arrow from an area to a formula that reads it. */
+export type TraceArrow = {
+  precedent: Area;
+  dependent: CellReference;
+};
+
+/** A value passed to or returned by a user defined function. */
+export type FunctionValue = number | string | boolean | null;
+
+/**
+ * A function that can be called from formulas. Ranges are passed as arrays of rows, an array
+ * returned by the function spills. An exception makes the formula evaluate to `#VALUE!`.
+ */
+export type UserFunction = (
+  ...args: (FunctionValue | FunctionValue[][])[]
+) => FunctionValue | FunctionValue[] | FunctionValue[][];
+
 type ForwardReferenceAction = {
   cell: CellReference;
   newValue: string | null;
@@ -129,6 +153,77 @@ export interface IWorkbook {
    * @param target - target cell where it was pasted.
    */
   forwardReferences(source_area: Area, target: CellReference): ForwardReferenceAction[];
+
+  /**
+   * Reverts the last change (or group of changes made in a transaction).
+   * @throws {@link CalcError} thrown if there is nothing to undo.
+   */
+  undo(): void;
+  /**
+   * Applies again the last undone change.
+   * @throws {@link CalcError} thrown if there is nothing to redo.
+   */
+  redo(): void;
+  canUndo(): boolean;
+  canRedo(): boolean;
+  /**
+   * Runs `callback` as a single transaction: all changes made in it are undone and redone together.
+   */
+  transaction<T>(callback: () => T): T;
+
+  /**
+   * @param transitive - when `true` the precedents of the precedents are traced too, and so on.
+   * @returns an arrow for every area read by the formula in the cell, including the areas reached
+   * through defined names and structured table references.
+   * @throws {@link CalcError} thrown if the sheet doesn't exist.
+   */
+  getCellPrecedents(cell: CellReference, transitive: boolean): TraceArrow[];
+  /**
+   * @param transitive - when `true` the dependents of the dependents are traced too, and so on.
+   * @returns an arrow to every formula that reads the cell.
+   * @throws {@link CalcError} thrown if the sheet doesn't exist.
+   */
+  getCellDependents(cell: CellReference, transitive: boolean): TraceArrow[];
+
+  /**
+   * Registers a function that can be called from formulas, replacing any function registered
+   * with the same name.
+   * @param volatile - when `true` the function is recomputed on every evaluation, like `RAND`.
+   * @throws {@link CalcError} thrown if the name isn't valid or it is the name of a built-in function.
+   */
+  registerFunction(name: string, callback: UserFunction, volatile?: boolean): void;
+  /**
+   * @throws {@link CalcError} thrown if the function isn't registered.
+   */
+  unregisterFunction(name: string): void;
+
+  /**
+   * @returns all defined names in the workbook.
+   */
+  getDefinedNames(): DefinedName[];
+  /**
+   * @param name - name to define, it must be unique within its scope.
+   * @param scope - sheet index the name is local to, `null` for a workbook-wide name.
+   * @param formula - formula the name refers to, example: `Sheet1!$A$1`.
+   * @throws {@link CalcError} thrown if the name or formula isn't valid.
+   */
+  addDefinedName(name: string, scope: number | null, formula: string): void;
+  /**
+   * Renames a defined name, changes its scope or its formula.
+   * Formulas using the old name are updated to use the new one.
+   * @throws {@link CalcError} thrown if the name doesn't exist or the new values aren't valid.
+   */
+  updateDefinedName(
+    name: string,
+    scope: number | null,
+    newName: string,
+    newScope: number | null,
+    newFormula: string,
+  ): void;
+  /**
+   * @throws {@link CalcError} thrown if the name doesn't exist.
+   */
+  deleteDefinedName(name: string, scope: number | null): void;
 }
 
 export class Workbook implements IWorkbook {
@@ -240,6 +335,126 @@ export class Workbook implements IWorkbook {
     return forwardReferenceActions;
   }
 
+  undo(): void {
+    try {
+      this._wasmWorkbook.undo();
+      this._wasmWorkbook.evaluate();
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+    this._sheets._refreshSheetLookups();
+  }
+
+  redo(): void {
+    try {
+      this._wasmWorkbook.redo();
+      this._wasmWorkbook.evaluate();
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+    this._sheets._refreshSheetLookups();
+  }
+
+  canUndo(): boolean {
+    return this._wasmWorkbook.canUndo();
+  }
+
+  canRedo(): boolean {
+    return this._wasmWorkbook.canRedo();
+  }
+
+  transaction<T>(callback: () => T): T {
+    this._wasmWorkbook.beginTransaction();
+    try {
+      return callback();
+    } finally {
+      this._wasmWorkbook.commitTransaction();
+    }
+  }
+
+  getCellPrecedents(cell: CellReference, transitive: boolean): TraceArrow[] {
+    try {
+      return JSON.parse(
+        this._wasmWorkbook.getCellPrecedents(cell.sheet, cell.row, cell.column, transitive),
+      );
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  getCellDependents(cell: CellReference, transitive: boolean): TraceArrow[] {
+    try {
+      return JSON.parse(
+        this._wasmWorkbook.getCellDependents(cell.sheet, cell.row, cell.column, transitive),
+      );
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  registerFunction(name: string, callback: UserFunction, volatile = false): void {
+    try {
+      this._wasmWorkbook.registerFunction(name, callback, volatile);
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  unregisterFunction(name: string): void {
+    try {
+      this._wasmWorkbook.unregisterFunction(name);
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  getDefinedNames(): DefinedName[] {
+    try {
+      const definedNames: [string, number | null, string][] = JSON.parse(
+        this._wasmWorkbook.getDefinedNameList(),
+      );
+      return definedNames.map(([name, scope, formula]) => ({ name, scope, formula }));
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  addDefinedName(name: string, scope: number | null, formula: string): void {
+    try {
+      this._wasmWorkbook.newDefinedName(name, scope ?? undefined, formula);
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  updateDefinedName(
+    name: string,
+    scope: number | null,
+    newName: string,
+    newScope: number | null,
+    newFormula: string,
+  ): void {
+    try {
+      this._wasmWorkbook.updateDefinedName(
+        name,
+        scope ?? undefined,
+        newName,
+        newScope ?? undefined,
+        newFormula,
+      );
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
+  deleteDefinedName(name: string, scope: number | null): void {
+    try {
+      this._wasmWorkbook.deleteDefinedName(name, scope ?? undefined);
+    } catch (e) {
+      throw wrapWebAssemblyError(e);
+    }
+  }
+
   private static cellReferenceToWasm(cell: CellReference) {
     return new WasmCellReferenceIndex(cell.sheet, cell.row, cell.column);
   }
@@ -39,12 +39,20 @@ export function parseCellReference(textReference: string): ParsedReference | nul
 
   return reference;
 }
+/**
+ * @param date1904 - `true` if dates are counted from 1 January 1904 (Mac workbooks).
+ * @returns first day of the date system, the day 0.
+ */
+function getSpreadsheetBaseDate(date1904: boolean): Dayjs {
+  return dayjs.utc(date1904 ? '1904-01-01' : '1899-12-30');
+}
+
 /**
  * This function is incompatible with Excel for dates before March 1900.
  * GSheets seem to behave consistently.
  */
-export function convertSpreadsheetDateToDayjsUTC(excelDate: number): Dayjs {
-  const baseDate = dayjs.utc('1899-12-30');
+export function convertSpreadsheetDateToDayjsUTC(excelDate: number, date1904 = false): Dayjs {
+  const baseDate = getSpreadsheetBaseDate(date1904);
 
   const fullDays = Math.floor(excelDate);
   const seconds = 24 * 60 * 60 * (excelDate - fullDays);
@@ -60,8 +68,8 @@ export function convertSpreadsheetDateToISOString(excelDate: number): string {
  * This function is incompatible with Excel for dates before March 1900.
  * GSheets seem to behave consistently.
  */
-export function convertDayjsUTCToSpreadsheetDate(date: Dayjs): number {
-  const baseDate = dayjs.utc('1899-12-30');
+export function convertDayjsUTCToSpreadsheetDate(date: Dayjs, date1904 = false): number {
+  const baseDate = getSpreadsheetBaseDate(date1904);
   const fullDays = date.diff(baseDate, 'day');
 
   const baseForDayFraction = baseDate.add(fullDays, 'day');